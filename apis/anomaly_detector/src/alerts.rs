@@ -0,0 +1,72 @@
+use std::str::FromStr;
+
+use features_notification_stream::message::NotificationMessage;
+use shared_shared_app::event_task::producer::{Producer, ProducerConfig, ProducerMessage};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::models::BlockEntry;
+
+/// Publishes block events to the notification topic, one message per admin to alert.
+/// Disabled when `ANOMALY_DETECTOR_KAFKA_BOOTSTRAP_SERVERS` is not set.
+pub struct Alerts {
+    producer: Option<Producer>,
+    admin_ids: Vec<Uuid>,
+}
+
+impl Alerts {
+    pub async fn from_env() -> Self {
+        let admin_ids = std::env::var("ANOMALY_DETECTOR_ALERT_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|id| !id.trim().is_empty())
+            .filter_map(|id| match Uuid::from_str(id.trim()) {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    warn!("Ignoring invalid alert user id {}", id);
+                    None
+                }
+            })
+            .collect();
+
+        if std::env::var("ANOMALY_DETECTOR_KAFKA_BOOTSTRAP_SERVERS").is_err() {
+            info!("Kafka not configured, block alerts are disabled");
+            return Self {
+                producer: None,
+                admin_ids,
+            };
+        }
+        let producer = Producer::from_config(ProducerConfig::from_env(
+            "ANOMALY_DETECTOR_KAFKA_BOOTSTRAP_SERVERS".to_string(),
+            "ANOMALY_DETECTOR_KAFKA_TOPIC".to_string(),
+        ))
+        .await;
+        Self {
+            producer: Some(producer),
+            admin_ids,
+        }
+    }
+
+    /// A failed alert is logged, the block is in place anyway.
+    pub async fn block_created(&self, entry: &BlockEntry) {
+        let Some(producer) = &self.producer else {
+            return;
+        };
+        let message = format!(
+            "Fingerprint {} blocked for {}s (offense {}): {}",
+            entry.fingerprint, entry.duration_seconds, entry.offense, entry.reason
+        );
+        for user_id in &self.admin_ids {
+            let alert = ProducerMessage {
+                key: Some(entry.fingerprint.clone()),
+                payload: NotificationMessage::SecurityAlert {
+                    user_id: *user_id,
+                    message: message.clone(),
+                },
+            };
+            if let Err(e) = producer.send(&alert).await {
+                warn!("Failed to publish block alert to {}: {}", user_id, e.reason);
+            }
+        }
+    }
+}
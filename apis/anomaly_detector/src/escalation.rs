@@ -0,0 +1,47 @@
+use chrono::{Duration, Utc};
+use features_anomaly_detector_entities::block_history;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+
+/// Repeat offenders get longer blocks: every earlier block of the fingerprint within
+/// `window_days` multiplies the requested duration by `factor`, up to `max_seconds`.
+pub struct Escalation {
+    pub window_days: u64,
+    pub factor: u64,
+    pub max_seconds: u64,
+}
+
+impl Escalation {
+    pub fn from_env() -> Self {
+        Self {
+            window_days: env_or("ANOMALY_DETECTOR_ESCALATION_WINDOW_DAYS", 30),
+            factor: env_or("ANOMALY_DETECTOR_ESCALATION_FACTOR", 2),
+            max_seconds: env_or("ANOMALY_DETECTOR_MAX_BLOCK_SECONDS", 30 * 24 * 3600),
+        }
+    }
+
+    /// Offense number of a new block of `fingerprint`, 1 for a first offense.
+    pub async fn offense(&self, db: &DatabaseConnection, fingerprint: &str) -> Result<u32, DbErr> {
+        let since = Utc::now().naive_utc() - Duration::days(self.window_days as i64);
+        let previous = block_history::Entity::find()
+            .filter(block_history::Column::Fingerprint.eq(fingerprint))
+            .filter(block_history::Column::BlockedAt.gte(since))
+            .count(db)
+            .await?;
+        Ok(previous as u32 + 1)
+    }
+
+    /// Block duration for the offense. A requested duration above the cap is kept as is.
+    pub fn duration(&self, requested: u64, offense: u32) -> u64 {
+        let multiplier = self.factor.saturating_pow(offense.saturating_sub(1));
+        requested
+            .saturating_mul(multiplier)
+            .min(self.max_seconds.max(requested))
+    }
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
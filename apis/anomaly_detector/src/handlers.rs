@@ -5,11 +5,20 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use features_anomaly_detector_entities::{block_history, ip_rule};
 use redis::Commands;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, SqlErr,
+};
+use shared_shared_data_core::cidr::Cidr;
 use std::net::SocketAddr;
+use std::str::FromStr;
+use tracing::warn;
+use uuid::Uuid;
 
 use crate::{
-    models::{BlockEntry, BlockListResponse, BlockRequest, FingerprintEntry, FingerprintListResponse, RegisterFingerprintRequest},
+    ip_rules,
+    models::{BlockEntry, BlockHistoryResponse, BlockListResponse, BlockRequest, FingerprintEntry, FingerprintListResponse, IpRuleListResponse, IpRuleRequest, RegisterFingerprintRequest},
     AppState,
 };
 
@@ -24,28 +33,68 @@ pub async fn create_block(
     State(state): State<Arc<AppState>>,
     Json(req): Json<BlockRequest>,
 ) -> Result<(StatusCode, Json<BlockEntry>), (StatusCode, String)> {
-    let mut conn = state.redis.get_connection()
+    // Repeat offenders within the escalation window get a longer block
+    let offense = state.escalation.offense(state.db.as_ref(), &req.fingerprint).await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let duration_seconds = state.escalation.duration(req.duration_seconds, offense);
 
     let now = chrono_now();
     let entry = BlockEntry {
         fingerprint: req.fingerprint.clone(),
         reason: req.reason.clone(),
-        duration_seconds: req.duration_seconds,
+        duration_seconds,
         blocked_by: req.blocked_by.clone(),
         blocked_at: now.clone(),
+        offense,
     };
 
     let value = serde_json::to_string(&entry)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    let key = block_key(&req.fingerprint);
-    let _: () = conn.set_ex(&key, &value, req.duration_seconds)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    {
+        let mut conn = state.redis.get_connection()
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let key = block_key(&req.fingerprint);
+        let _: () = conn.set_ex(&key, &value, duration_seconds)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    let history = block_history::ActiveModel {
+        fingerprint: ActiveValue::Set(req.fingerprint),
+        reason: ActiveValue::Set(req.reason),
+        duration_seconds: ActiveValue::Set(duration_seconds as i64),
+        offense: ActiveValue::Set(offense as i32),
+        blocked_by: ActiveValue::Set(req.blocked_by),
+        ..Default::default()
+    };
+    // The block is in place even when the history write fails
+    if let Err(e) = history.insert(state.db.as_ref()).await {
+        warn!(
+            "Failed to record block history of {}: {}",
+            entry.fingerprint, e
+        );
+    }
+
+    state.alerts.block_created(&entry).await;
 
     Ok((StatusCode::CREATED, Json(entry)))
 }
 
+pub async fn get_block_history(
+    State(state): State<Arc<AppState>>,
+    Path(fingerprint): Path<String>,
+) -> Result<Json<BlockHistoryResponse>, (StatusCode, String)> {
+    let history = block_history::Entity::find()
+        .filter(block_history::Column::Fingerprint.eq(fingerprint))
+        .order_by_desc(block_history::Column::BlockedAt)
+        .all(state.db.as_ref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let total = history.len();
+    Ok(Json(BlockHistoryResponse { history, total }))
+}
+
 pub async fn get_block(
     State(state): State<Arc<AppState>>,
     Path(fingerprint): Path<String>,
@@ -106,6 +155,81 @@ pub async fn list_blocks(
     Ok(Json(BlockListResponse { blocks, total }))
 }
 
+// --- IP rule handlers ---
+
+pub async fn create_ip_rule(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<IpRuleRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let cidr = Cidr::from_str(&req.cidr).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    // Stored normalized, so `10.0.0.1/8` and `10.0.0.0/8` are the same rule
+    let existing = ip_rule::Entity::find()
+        .filter(ip_rule::Column::Cidr.eq(cidr.to_string()))
+        .one(state.db.as_ref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if existing.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("A rule for {} already exists", cidr),
+        ));
+    }
+
+    let rule = ip_rule::ActiveModel {
+        cidr: ActiveValue::Set(cidr.to_string()),
+        kind: ActiveValue::Set(req.kind.as_str().to_string()),
+        reason: ActiveValue::Set(req.reason),
+        created_by: ActiveValue::Set(req.created_by),
+        ..Default::default()
+    };
+    // A concurrent request for the same range can pass the check above, the unique index
+    // still rejects it
+    rule.insert(state.db.as_ref()).await.map_err(|e| match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => (
+            StatusCode::CONFLICT,
+            format!("A rule for {} already exists", cidr),
+        ),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    ip_rules::publish(&state)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(StatusCode::CREATED)
+}
+
+pub async fn list_ip_rules(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<IpRuleListResponse>, (StatusCode, String)> {
+    let rules = ip_rules::list_rules(&state)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let total = rules.len();
+    Ok(Json(IpRuleListResponse { rules, total }))
+}
+
+pub async fn delete_ip_rule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let result = ip_rule::Entity::delete_by_id(id)
+        .exec(state.db.as_ref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if result.rows_affected == 0 {
+        return Err((StatusCode::NOT_FOUND, "IP rule not found".to_string()));
+    }
+
+    ip_rules::publish(&state)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 fn chrono_now() -> String {
     let duration = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use features_anomaly_detector_entities::ip_rule;
+use redis::Commands;
+use sea_orm::{EntityTrait, QueryOrder};
+use shared_shared_data_core::cidr::Cidr;
+use tracing::warn;
+
+use crate::{
+    models::{IpRuleEntry, IpRuleKind, IpRuleSet, IpRuleSource},
+    AppState,
+};
+
+/// Read by the gateway anomaly detector, which reloads it periodically.
+const IP_RULES_KEY: &str = "anomaly:ip_rules";
+
+/// Parses static ranges, one per line: `<cidr> <allow|deny> [label]`.
+/// Blank lines and lines starting with `#` are skipped.
+pub fn parse_ranges(content: &str) -> Result<Vec<IpRuleEntry>, String> {
+    let mut ranges = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(3, char::is_whitespace);
+        let cidr = parts.next().unwrap_or_default();
+        let kind = parts.next().unwrap_or_default().trim();
+        let label = parts.next().unwrap_or_default().trim();
+
+        let cidr = Cidr::from_str(cidr).map_err(|e| format!("line {}: {}", index + 1, e))?;
+        let kind = IpRuleKind::parse(kind)
+            .ok_or_else(|| format!("line {}: expected allow or deny", index + 1))?;
+        ranges.push(IpRuleEntry {
+            id: None,
+            cidr,
+            kind,
+            reason: label.to_string(),
+            created_by: None,
+            created_at: None,
+            source: IpRuleSource::File,
+        });
+    }
+    Ok(ranges)
+}
+
+pub fn load_ranges_file(path: &str) -> Result<Vec<IpRuleEntry>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    parse_ranges(&content).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Rules added through the API followed by the static ranges.
+pub async fn list_rules(state: &AppState) -> Result<Vec<IpRuleEntry>, String> {
+    let rows = ip_rule::Entity::find()
+        .order_by_asc(ip_rule::Column::CreatedAt)
+        .all(state.db.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut rules = Vec::with_capacity(rows.len() + state.ranges.len());
+    for row in rows {
+        let (Ok(cidr), Some(kind)) = (Cidr::from_str(&row.cidr), IpRuleKind::parse(&row.kind))
+        else {
+            warn!(
+                "Skipping invalid IP rule {}: {} {}",
+                row.id, row.cidr, row.kind
+            );
+            continue;
+        };
+        rules.push(IpRuleEntry {
+            id: Some(row.id),
+            cidr,
+            kind,
+            reason: row.reason,
+            created_by: row.created_by,
+            created_at: Some(row.created_at.and_utc().timestamp().to_string()),
+            source: IpRuleSource::Database,
+        });
+    }
+    rules.extend(state.ranges.iter().cloned());
+    Ok(rules)
+}
+
+/// Writes the merged allow and deny lists for the gateway, after every change and at start.
+pub async fn publish(state: &AppState) -> Result<(), String> {
+    let mut set = IpRuleSet::default();
+    for rule in list_rules(state).await? {
+        match rule.kind {
+            IpRuleKind::Allow => set.allow.push(rule.cidr),
+            IpRuleKind::Deny => set.deny.push(rule.cidr),
+        }
+    }
+    let value = serde_json::to_string(&set).map_err(|e| e.to_string())?;
+
+    let mut conn = state.redis.get_connection().map_err(|e| e.to_string())?;
+    let _: () = conn.set(IP_RULES_KEY, value).map_err(|e| e.to_string())?;
+    Ok(())
+}
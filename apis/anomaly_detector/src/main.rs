@@ -1,16 +1,29 @@
 use std::sync::Arc;
 
 use axum::Router;
+use features_anomaly_detector_migrations::{Migrator, MigratorTrait};
 use redis::Client;
+use sea_orm::DatabaseConnection;
+use shared_shared_config::db::Database;
 use tokio::net::TcpListener;
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::{alerts::Alerts, escalation::Escalation, models::IpRuleEntry};
+
+mod alerts;
+mod escalation;
 mod handlers;
+mod ip_rules;
 mod models;
 mod routes;
 
 pub struct AppState {
     pub redis: Client,
+    pub db: Arc<DatabaseConnection>,
+    /// Static ranges from `ANOMALY_DETECTOR_RANGES_FILE`
+    pub ranges: Vec<IpRuleEntry>,
+    pub escalation: Escalation,
+    pub alerts: Alerts,
 }
 
 #[tokio::main]
@@ -22,7 +35,33 @@ async fn main() {
         .unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
     let redis = Client::open(redis_url).expect("Failed to connect to Redis");
 
-    let state = Arc::new(AppState { redis });
+    let db_scheme = std::env::var("ANOMALY_DETECTOR_DATABASE_SCHEME")
+        .unwrap_or_else(|_| "anomaly_detector".to_string());
+    let mut db = Database::new(
+        Some("ANOMALY_DETECTOR_DATABASE_URL".to_string()),
+        Some(db_scheme),
+    );
+    db.connect().await;
+    let db = db.get_connection();
+    Migrator::up(db.as_ref(), None)
+        .await
+        .expect("Failed to run anomaly detector migrations");
+
+    let ranges = match std::env::var("ANOMALY_DETECTOR_RANGES_FILE") {
+        Ok(path) => ip_rules::load_ranges_file(&path).expect("Invalid IP ranges file"),
+        Err(_) => vec![],
+    };
+
+    let state = Arc::new(AppState {
+        redis,
+        db,
+        ranges,
+        escalation: Escalation::from_env(),
+        alerts: Alerts::from_env().await,
+    });
+    if let Err(e) = ip_rules::publish(&state).await {
+        warn!("Failed to publish IP rules: {}", e);
+    }
 
     let app = Router::new()
         .merge(routes::routes(state))
@@ -1,4 +1,7 @@
+use features_anomaly_detector_entities::block_history;
 use serde::{Deserialize, Serialize};
+use shared_shared_data_core::cidr::Cidr;
+use uuid::Uuid;
 
 // --- Fingerprint models ---
 
@@ -39,6 +42,7 @@ pub struct FingerprintListResponse {
 pub struct BlockRequest {
     pub fingerprint: String,
     pub reason: String,
+    /// Duration of a first offense, repeat offenders get longer blocks
     #[serde(default = "default_duration")]
     pub duration_seconds: u64,
     pub blocked_by: Option<String>,
@@ -55,6 +59,9 @@ pub struct BlockEntry {
     pub duration_seconds: u64,
     pub blocked_by: Option<String>,
     pub blocked_at: String,
+    /// Number of blocks of the fingerprint in the escalation window, this one included
+    #[serde(default)]
+    pub offense: u32,
 }
 
 #[derive(Serialize)]
@@ -62,3 +69,77 @@ pub struct BlockListResponse {
     pub blocks: Vec<BlockEntry>,
     pub total: usize,
 }
+
+#[derive(Serialize)]
+pub struct BlockHistoryResponse {
+    pub history: Vec<block_history::Model>,
+    pub total: usize,
+}
+
+// --- IP rule models ---
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IpRuleKind {
+    Allow,
+    Deny,
+}
+
+impl IpRuleKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IpRuleKind::Allow => "allow",
+            IpRuleKind::Deny => "deny",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "allow" => Some(IpRuleKind::Allow),
+            "deny" => Some(IpRuleKind::Deny),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum IpRuleSource {
+    /// Added through the API
+    Database,
+    /// Static range from the ranges file, read only
+    File,
+}
+
+#[derive(Deserialize)]
+pub struct IpRuleRequest {
+    pub cidr: String,
+    pub kind: IpRuleKind,
+    #[serde(default)]
+    pub reason: String,
+    pub created_by: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct IpRuleEntry {
+    pub id: Option<Uuid>,
+    pub cidr: Cidr,
+    pub kind: IpRuleKind,
+    pub reason: String,
+    pub created_by: Option<String>,
+    pub created_at: Option<String>,
+    pub source: IpRuleSource,
+}
+
+#[derive(Serialize)]
+pub struct IpRuleListResponse {
+    pub rules: Vec<IpRuleEntry>,
+    pub total: usize,
+}
+
+/// Lists read by the gateway anomaly detector, the allow list wins over the deny list.
+#[derive(Serialize, Default)]
+pub struct IpRuleSet {
+    pub allow: Vec<Cidr>,
+    pub deny: Vec<Cidr>,
+}
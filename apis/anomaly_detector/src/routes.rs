@@ -18,5 +18,10 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/blocks", get(handlers::list_blocks))
         .route("/blocks/{fingerprint}", get(handlers::get_block))
         .route("/blocks/{fingerprint}", delete(handlers::delete_block))
+        .route("/blocks/{fingerprint}/history", get(handlers::get_block_history))
+        // IP and CIDR allow/deny lists (called by admin)
+        .route("/ip-rules", post(handlers::create_ip_rule))
+        .route("/ip-rules", get(handlers::list_ip_rules))
+        .route("/ip-rules/{id}", delete(handlers::delete_ip_rule))
         .with_state(state)
 }
@@ -3,17 +3,23 @@ use std::time::Duration;
 use axum::Router;
 use features_auth_remote::PermissionService;
 use tokio::{spawn, time::interval};
-use tracing::debug;
+use tracing::{debug, error};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use shared_shared_app::{
     config::AppConfig,
     discovery::get_consul_client,
-    event_task::producer::{Producer, ProducerConfig},
+    dlq::{routes::routes as dlq_routes, DlqAdmin},
+    event_task::{
+        consumer::{consumer_task, ConsumerConfig},
+        outbox::{relay_task, OutboxConfig},
+        producer::{Producer, ProducerConfig},
+    },
     start_app::StartApp,
     state::AppState,
 };
+use shared_shared_audit::AuditPublisher;
 use shared_shared_config::db::Database;
 
 use features_auth_migrations::{Migrator, MigratorTrait};
@@ -21,15 +27,20 @@ use features_auth_model::state::{AuthAppState, AuthCacheState};
 use features_auth_stream::PRODUCER_KEY;
 
 use crate::{
+    consumers::audit_consumer::handler::handle_audit_message,
     doc::ApiDoc,
+    permission::{CanReadDlq, CanUpdateDlq},
     routes::{
         active_code::routes as active_code_routes,
+        audit_log::routes as audit_log_routes,
         auth_code::routes as auth_code_routes, authentication::routes as authentication_routes,
         client::routes as client_routes,
         field_permission::routes as field_permission_routes,
+        oidc::routes as oidc_routes,
         password::routes as password_routes,
         permission::routes as permission_routes,
         role::routes as role_routes, scope::routes as scope_routes,
+        session::routes as session_routes,
         signup::routes as signup_routes, token::routes as token_routes,
         user::routes as user_routes,
     },
@@ -53,7 +64,14 @@ impl<'a> StartApp<AuthAppState, AuthCacheState> for MyApp<'a> {
         app_state: &mut AppState<AuthAppState, AuthCacheState>,
     ) -> impl std::future::Future<Output = Result<(), Box<dyn std::error::Error>>> {
         let mut clone_app_state = app_state.clone();
+        let consumer_app_state = app_state.clone();
         let app_key = self.config.app_key.clone();
+        // One group for all instances, every audit event is stored once
+        let audit_consumer_config = ConsumerConfig::from_env(
+            format!("{}_CONSUMER_AUDIT_KAFKA_BOOTSTRAP_SERVERS", app_key),
+            format!("{}_CONSUMER_AUDIT_KAFKA_TOPIC", app_key),
+            "auth_for_audit".to_string(),
+        );
         async move {
             let kafka_server_env = format!("{}_KAFKA_BOOTSTRAP_SERVERS", app_key);
             let kafka_topic_env = format!("{}_KAFKA_TOPIC", app_key);
@@ -62,7 +80,35 @@ impl<'a> StartApp<AuthAppState, AuthCacheState> for MyApp<'a> {
                 ProducerConfig::from_env(kafka_server_env.clone(), kafka_topic_env.clone());
             debug!("Creating Kafka producer with config {:?}", producer_config);
             let producer = Producer::from_config(producer_config).await;
+            // Publishes the events stored in the outbox, e.g. SignUp
+            spawn(relay_task(
+                clone_app_state.write_db.clone(),
+                producer.clone(),
+                OutboxConfig::from_env(),
+            ));
             clone_app_state.set_producer(PRODUCER_KEY.to_string(), producer);
+            AuditPublisher::init_from_env(&app_key).await;
+            DlqAdmin::start(&app_key, &mut clone_app_state).await;
+
+            let dlq_producer = Producer::from_config(ProducerConfig::from_env(
+                "DLQ_KAFKA_BOOTSTRAP_SERVERS".to_string(),
+                "DLQ_KAFKA_TOPIC".to_string(),
+            ))
+            .await;
+            let dlq_app_key = app_key.clone();
+            spawn(async move {
+                if let Err(e) = consumer_task(
+                    audit_consumer_config,
+                    consumer_app_state,
+                    dlq_producer,
+                    dlq_app_key,
+                    handle_audit_message,
+                )
+                .await
+                {
+                    error!("Error in audit consumer task: {:?}", e);
+                }
+            });
 
             spawn(async move {
                 let service_key = "AUTH".to_string();
@@ -105,17 +151,21 @@ impl<'a> StartApp<AuthAppState, AuthCacheState> for MyApp<'a> {
     fn routes(&self, app_state: &AppState<AuthAppState, AuthCacheState>) -> Router {
         let all_routes = Router::new()
             .merge(active_code_routes(app_state))
+            .merge(audit_log_routes(app_state))
             .merge(auth_code_routes(app_state))
             .merge(authentication_routes(app_state))
             .merge(client_routes(app_state))
             .merge(field_permission_routes(app_state))
+            .merge(oidc_routes(app_state))
             .merge(password_routes(app_state))
             .merge(role_routes(app_state))
             .merge(scope_routes(app_state))
+            .merge(session_routes(app_state))
             .merge(signup_routes(app_state))
             .merge(token_routes(app_state))
             .merge(user_routes(app_state))
             .merge(permission_routes(app_state))
+            .merge(dlq_routes::<_, _, CanReadDlq, CanUpdateDlq>(app_state))
             .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()));
         all_routes
     }
@@ -0,0 +1,17 @@
+use std::collections::HashMap;
+
+use shared_shared_app::state::AppState;
+use shared_shared_audit::AuditEvent;
+
+use features_auth_model::state::{AuthAppState, AuthCacheState};
+use features_auth_service::AuditLogService;
+
+pub async fn handle_audit_message(
+    event: AuditEvent,
+    _state: AppState<AuthAppState, AuthCacheState>,
+    _headers: Option<HashMap<String, String>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    AuditLogService::store(event)
+        .await
+        .map_err(|e| format!("Failed to store audit event: {:?}", e).into())
+}
@@ -18,6 +18,8 @@ use shared_shared_app::doc::{ErrorResponse, JwtSecurityAddon, ServerAddon};
     paths(
         shared_shared_app::health::health_checker_handler,
         crate::routes::active_code::mark_as_sent,
+        crate::routes::audit_log::get_audit_log,
+        crate::routes::audit_log::filter_audit_logs,
         crate::routes::authentication::request_code,
         crate::routes::authentication::request_login,
         crate::routes::authentication::request_register,
@@ -36,9 +38,15 @@ use shared_shared_app::doc::{ErrorResponse, JwtSecurityAddon, ServerAddon};
         crate::routes::field_permission::delete_field_permission,
         crate::routes::field_permission::get_field_permission,
         crate::routes::field_permission::search_field_permissions,
+        crate::routes::oidc::oidc_authorize,
+        crate::routes::oidc::oidc_callback,
+        crate::routes::oidc::oidc_confirm_identity,
+        crate::routes::oidc::get_user_identities,
+        crate::routes::oidc::delete_user_identity,
         crate::routes::permission::create_permission,
         crate::routes::permission::update_permission,
         crate::routes::permission::delete_permission,
+        crate::routes::permission::explain_permission,
         crate::routes::permission::filter_permissions,
         crate::routes::permission::get_permission,
         crate::routes::role::assign_permissions,
@@ -63,11 +71,18 @@ use shared_shared_app::doc::{ErrorResponse, JwtSecurityAddon, ServerAddon};
         crate::routes::user::get_user,
         crate::routes::user::assign_roles,
         crate::routes::user::unassign_roles,
+        crate::routes::session::get_sessions,
+        crate::routes::session::revoke_session,
         crate::routes::signup::activate,
         crate::routes::password::request_change_password,
         crate::routes::password::change_password,
         crate::routes::password::request_reset,
         crate::routes::password::reset_password,
+        shared_shared_app::dlq::routes::filter_dlq_messages,
+        shared_shared_app::dlq::routes::get_dlq_message,
+        shared_shared_app::dlq::routes::update_dlq_message,
+        shared_shared_app::dlq::routes::replay_dlq_message,
+        shared_shared_app::dlq::routes::discard_dlq_message,
     ),
     tags(
         (name = "Rust REST API", description = "Authentication in Rust Endpoints")
@@ -1,6 +1,7 @@
 use app::start_app;
 
 mod app;
+mod consumers;
 mod doc;
 mod permission;
 mod routes;
@@ -78,3 +78,18 @@ define_resource_perms! {
     CanUpdateFieldPermission => (UPDATE, FIELD_PERMISSION_RESOURCE),
     CanDeleteFieldPermission => (DELETE, FIELD_PERMISSION_RESOURCE)
 }
+
+// AUDIT_LOG Permission
+const AUDIT_LOG_RESOURCE: &str = "AUTH:AUDIT_LOG";
+
+define_resource_perms! {
+    CanReadAuditLog => (READ, AUDIT_LOG_RESOURCE)
+}
+
+// DLQ Permission
+const DLQ_RESOURCE: &str = "AUTH:DLQ";
+
+define_resource_perms! {
+    CanReadDlq => (READ, DLQ_RESOURCE),
+    CanUpdateDlq => (UPDATE, DLQ_RESOURCE)
+}
@@ -0,0 +1,81 @@
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Router,
+};
+use tracing::{instrument, Level};
+use uuid::Uuid;
+
+use features_auth_model::{
+    audit_log::{AuditLogData, AuditLogDataFilterParams, AuditLogDataResponse},
+    state::{AuthAppState, AuthCacheState},
+};
+use features_auth_service::AuditLogService;
+use shared_shared_app::{doc::ErrorResponse, state::AppState};
+use shared_shared_auth::permission::Auth;
+use shared_shared_data_app::{json::ResponseJson, result::Result};
+use shared_shared_data_core::{
+    order::Order,
+    paging::{Pagination, QueryResult, QueryResultResponse},
+};
+
+use crate::permission::CanReadAuditLog;
+
+const TAG: &str = "audit_log";
+
+#[utoipa::path(
+    get,
+    path = "/audit-logs/{audit_log_id}",
+    tag = TAG,
+    summary = "Get audit log entry by ID",
+    responses(
+        (status = 200, description = "Audit log entry", body = AuditLogDataResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Audit log entry not found", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn get_audit_log(
+    _auth: Auth<CanReadAuditLog>,
+    Path(audit_log_id): Path<Uuid>,
+) -> Result<ResponseJson<AuditLogData>> {
+    let audit_log = AuditLogService::get_audit_log(audit_log_id).await?;
+    Ok(ResponseJson(audit_log))
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit-logs",
+    tag = TAG,
+    summary = "Filter audit log entries",
+    description = "Filter by service, action, actor_id, target_type, target_id or request_id",
+    params(
+        Order,
+        Pagination
+    ),
+    responses(
+        (status = 200, description = "Filtered audit log entries", body = QueryResultResponse<AuditLogData>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn filter_audit_logs(
+    _auth: Auth<CanReadAuditLog>,
+    query_pagination: Query<Pagination>,
+    query_order: Query<Order>,
+    filter: Query<AuditLogDataFilterParams>,
+) -> Result<ResponseJson<QueryResult<AuditLogData>>> {
+    let pagination = query_pagination.0;
+    let order = query_order.0;
+    let all_filters = filter.0.all_filters();
+
+    let result = AuditLogService::search(&pagination, &order, &all_filters).await?;
+    Ok(ResponseJson(result))
+}
+
+pub fn routes(app_state: &AppState<AuthAppState, AuthCacheState>) -> Router {
+    Router::new()
+        .route("/audit-logs", get(filter_audit_logs))
+        .route("/audit-logs/{audit_log_id}", get(get_audit_log))
+        .with_state(app_state.clone())
+}
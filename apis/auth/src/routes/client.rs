@@ -4,7 +4,8 @@ use axum::{
     Router,
 };
 
-use tracing::debug;
+use serde_json::{json, Value};
+use tracing::{debug, warn};
 use uuid::Uuid;
 
 use features_auth_model::{
@@ -16,6 +17,7 @@ use features_auth_model::{
 };
 
 use shared_shared_app::{doc::ErrorResponse, state::AppState};
+use shared_shared_audit::AuditContext;
 use shared_shared_auth::permission::Auth;
 use shared_shared_data_app::{
     json::{ResponseJson, ValidJson},
@@ -47,9 +49,29 @@ const TAG: &str = "client";
 )]
 async fn create_client(
     _auth: Auth<CanCreateClient>,
+    audit: AuditContext,
     ValidJson(register_request): ValidJson<ClientForCreateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
     let role_id = ClientMutation::create(register_request.into()).await?;
+    let after = ClientQuery::get(role_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "client.create of {} audited without its new state: {:?}",
+                role_id, e
+            )
+        })
+        .ok()
+        .map(|after| audit_snapshot(&after));
+    audit
+        .record(
+            "client.create",
+            "client",
+            role_id,
+            None::<&Value>,
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(role_id),
@@ -75,10 +97,31 @@ async fn create_client(
 )]
 async fn update_client(
     _auth: Auth<CanUpdateClient>,
+    audit: AuditContext,
     Path(client_id): Path<Uuid>,
     ValidJson(scope_request): ValidJson<ClientForUpdateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = audit_snapshot(&ClientQuery::get(client_id).await?);
     ClientMutation::update(client_id, scope_request.into()).await?;
+    let after = ClientQuery::get(client_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "client.update of {} audited without its new state: {:?}",
+                client_id, e
+            )
+        })
+        .ok()
+        .map(|after| audit_snapshot(&after));
+    audit
+        .record(
+            "client.update",
+            "client",
+            client_id,
+            Some(&before),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid { ok: true, id: None }))
 }
 
@@ -95,9 +138,20 @@ async fn update_client(
 )]
 async fn delete_client(
     _auth: Auth<CanDeleteClient>,
+    audit: AuditContext,
     Path(client_id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = audit_snapshot(&ClientQuery::get(client_id).await?);
     ClientMutation::delete(client_id).await?;
+    audit
+        .record(
+            "client.delete",
+            "client",
+            client_id,
+            Some(&before),
+            None::<&Value>,
+        )
+        .await;
     Ok(ResponseJson(OkUuid { ok: true, id: None }))
 }
 
@@ -149,6 +203,15 @@ async fn filter_clients(
     Ok(ResponseJson(result))
 }
 
+/// `ClientData` never serializes the secret, add it back so a rotation shows up in the diff.
+fn audit_snapshot(client: &ClientData) -> Value {
+    let mut snapshot = serde_json::to_value(client).unwrap_or_default();
+    if let Value::Object(map) = &mut snapshot {
+        map.insert("client_secret".to_string(), json!(client.client_secret));
+    }
+    snapshot
+}
+
 pub fn routes(app_state: &AppState<AuthAppState, AuthCacheState>) -> Router {
     Router::new()
         .route("/clients", post(create_client))
@@ -1,12 +1,15 @@
 pub mod active_code;
+pub mod audit_log;
 pub mod auth_code;
 pub mod authentication;
 pub mod client;
 pub mod field_permission;
+pub mod oidc;
 pub mod password;
 pub mod permission;
 pub mod role;
 pub mod scope;
+pub mod session;
 pub mod signup;
 pub mod token;
 pub mod user;
@@ -0,0 +1,250 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{AppendHeaders, IntoResponse},
+    routing::{delete, get, post},
+    Router,
+};
+use tracing::{instrument, Level};
+use uuid::Uuid;
+
+use features_auth_model::{
+    authentication::{AuthRegisterData, AuthRegisterDataResponse},
+    external_identity::ExternalIdentityData,
+    oidc::{
+        OidcAuthorizeData, OidcAuthorizeDataResponse, OidcAuthorizeQuery, OidcCallbackQuery,
+        OidcIdentityConfirmRequest,
+    },
+    state::{AuthAppState, AuthCacheState},
+};
+use features_auth_stream::PRODUCER_KEY;
+use shared_shared_app::{doc::ErrorResponse, state::AppState};
+use shared_shared_audit::AuditContext;
+use shared_shared_auth::permission::{Auth, PublicAccess};
+use shared_shared_data_app::{
+    json::{ResponseJson, ValidJson},
+    result::{OkUuid, OkUuidResponse, Result},
+};
+use shared_shared_data_core::{
+    filter::{FilterCondition, FilterEnum, FilterOperator, FilterParam},
+    order::Order,
+    paging::{Pagination, QueryResult, QueryResultResponse},
+};
+use shared_shared_data_error::app::AppError;
+
+use features_auth_repo::external_identity::{ExternalIdentityMutation, ExternalIdentityQuery};
+use features_auth_service::OidcService;
+
+use crate::permission::{CanReadUser, CanUpdateUser};
+
+const OIDC_AUTHORIZE: &str = "/public/oidc/{provider}/authorize";
+const OIDC_CALLBACK: &str = "/public/oidc/{provider}/callback";
+const OIDC_IDENTITY_CONFIRM: &str = "/public/oidc/identities/{identity_id}/confirm";
+
+/// Cookie binding a social login to the browser that started it.
+const BINDING_COOKIE: &str = "oidc_binding";
+/// Same lifetime as the login state it is checked against.
+const BINDING_COOKIE_MAX_AGE: u64 = 600;
+
+const TAG: &str = "oidc";
+
+#[utoipa::path(
+    get,
+    path = OIDC_AUTHORIZE,
+    tag = TAG,
+    summary = "Start social login",
+    description = "Returns the provider authorization URL for an authentication request created with /public/requests/code and sets the cookie the callback checks",
+    params(
+        ("provider" = String, Path, description = "Configured provider name, e.g. google"),
+        ("state" = String, Query, description = "Authentication request id"),
+    ),
+    responses(
+        (status = 200, description = "Authorization URL", body = OidcAuthorizeDataResponse),
+        (status = 403, description = "Authentication request not found", body = ErrorResponse),
+        (status = 404, description = "Unknown provider", body = ErrorResponse),
+        (status = 502, description = "Provider discovery failed", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn oidc_authorize(
+    _public: PublicAccess,
+    State(state): State<AppState<AuthAppState, AuthCacheState>>,
+    Path(provider): Path<String>,
+    Query(query): Query<OidcAuthorizeQuery>,
+) -> Result<impl IntoResponse> {
+    let (authorization_url, binding) =
+        OidcService::authorize(&state.cache, &provider, &query.state).await?;
+    let cookie = format!(
+        "{}={}; Path=/public/oidc; Max-Age={}; HttpOnly; Secure; SameSite=Lax",
+        BINDING_COOKIE, binding, BINDING_COOKIE_MAX_AGE
+    );
+    Ok((
+        AppendHeaders([(header::SET_COOKIE, cookie)]),
+        ResponseJson(OidcAuthorizeData { authorization_url }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = OIDC_CALLBACK,
+    tag = TAG,
+    summary = "Finish social login",
+    description = "Exchanges the provider code, links or creates the user and returns an auth code for the original request",
+    params(
+        ("provider" = String, Path, description = "Configured provider name, e.g. google"),
+        ("code" = String, Query, description = "Authorization code issued by the provider"),
+        ("state" = String, Query, description = "State sent to the provider by the authorize step"),
+    ),
+    responses(
+        (status = 200, description = "Login success", body = AuthRegisterDataResponse),
+        (status = 400, description = "State unknown, already used or started from another browser", body = ErrorResponse),
+        (status = 404, description = "Unknown provider", body = ErrorResponse),
+        (status = 409, description = "Provider email is missing or not verified, or the link to the existing account waits for the emailed code", body = ErrorResponse),
+        (status = 502, description = "Provider request failed", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn oidc_callback(
+    _public: PublicAccess,
+    State(state): State<AppState<AuthAppState, AuthCacheState>>,
+    headers: HeaderMap,
+    Path(provider): Path<String>,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<ResponseJson<AuthRegisterData>> {
+    let producer = state
+        .get_producer(PRODUCER_KEY.to_string())
+        .expect("Producer not found");
+    let binding = binding_cookie(&headers);
+    let data = OidcService::callback(
+        &state.cache,
+        producer.topic(),
+        &provider,
+        &query.code,
+        &query.state,
+        binding.as_deref(),
+    )
+    .await?;
+    Ok(ResponseJson(data))
+}
+
+#[utoipa::path(
+    post,
+    path = OIDC_IDENTITY_CONFIRM,
+    tag = TAG,
+    summary = "Confirm a pending social account link",
+    description = "Links the provider account with the code emailed to the owner of the existing account, which is confirmed and keeps its credentials. Only the last code sent for the identity is accepted, for a few wrong tries",
+    request_body = OidcIdentityConfirmRequest,
+    responses(
+        (status = 200, description = "Identity linked", body = OkUuidResponse),
+        (status = 403, description = "Identity not found", body = ErrorResponse),
+        (status = 404, description = "Link code not found", body = ErrorResponse),
+        (status = 410, description = "Link code expired", body = ErrorResponse),
+        (status = 429, description = "Too many wrong link codes", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn oidc_confirm_identity(
+    _public: PublicAccess,
+    Path(identity_id): Path<Uuid>,
+    ValidJson(request): ValidJson<OidcIdentityConfirmRequest>,
+) -> Result<ResponseJson<OkUuid>> {
+    OidcService::confirm_identity(identity_id, request.link_code).await?;
+    Ok(ResponseJson(OkUuid {
+        ok: true,
+        id: Some(identity_id),
+    }))
+}
+
+fn binding_cookie(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == BINDING_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/identities",
+    tag = TAG,
+    summary = "List linked social accounts of a user",
+    params(Order, Pagination),
+    responses(
+        (status = 200, description = "Linked identities", body = QueryResultResponse<ExternalIdentityData>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn get_user_identities(
+    _auth: Auth<CanReadUser>,
+    Path(user_id): Path<Uuid>,
+    Query(pagination): Query<Pagination>,
+    Query(order): Query<Order>,
+) -> Result<ResponseJson<QueryResult<ExternalIdentityData>>> {
+    let result = ExternalIdentityQuery::search(&pagination, &order, &user_filter(user_id)).await?;
+    Ok(ResponseJson(result))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/identities/{identity_id}",
+    tag = TAG,
+    summary = "Unlink a social account from a user",
+    responses(
+        (status = 200, description = "Identity unlinked", body = OkUuidResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Identity not found", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn delete_user_identity(
+    _auth: Auth<CanUpdateUser>,
+    audit: AuditContext,
+    Path((user_id, identity_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<OkUuid>> {
+    let identity = ExternalIdentityQuery::get(identity_id).await?;
+    if identity.user_id != Some(user_id) {
+        return Err(AppError::EntityNotFound {
+            entity: "external_identity".to_string(),
+        });
+    }
+    ExternalIdentityMutation::delete(identity_id).await?;
+    audit
+        .record(
+            "user.unlink_identity",
+            "external_identity",
+            identity_id,
+            Some(&identity),
+            None::<&ExternalIdentityData>,
+        )
+        .await;
+    Ok(ResponseJson(OkUuid {
+        ok: true,
+        id: Some(identity_id),
+    }))
+}
+
+fn user_filter(user_id: Uuid) -> FilterCondition {
+    FilterCondition::from(vec![FilterEnum::Uuid(FilterParam {
+        name: "user_id".to_string(),
+        operator: FilterOperator::Equal,
+        value: Some(user_id),
+        raw_value: user_id.to_string(),
+    })])
+}
+
+pub fn routes(app_state: &AppState<AuthAppState, AuthCacheState>) -> Router {
+    Router::new()
+        .route(OIDC_AUTHORIZE, get(oidc_authorize))
+        .route(OIDC_CALLBACK, get(oidc_callback))
+        .route(OIDC_IDENTITY_CONFIRM, post(oidc_confirm_identity))
+        .route("/users/{user_id}/identities", get(get_user_identities))
+        .route(
+            "/users/{user_id}/identities/{identity_id}",
+            delete(delete_user_identity),
+        )
+        .with_state(app_state.clone())
+}
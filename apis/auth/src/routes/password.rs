@@ -1,4 +1,6 @@
 use axum::{extract::State, routing::post, Router};
+use serde_json::{json, Value};
+use tracing::warn;
 
 use features_auth_model::{
     password::{
@@ -7,9 +9,11 @@ use features_auth_model::{
     },
     state::{AuthAppState, AuthCacheState},
 };
+use features_auth_repo::user::UserQuery;
 use features_auth_service::PasswordService;
 use features_auth_stream::PRODUCER_KEY;
 use shared_shared_app::{doc::ErrorResponse, state::AppState};
+use shared_shared_audit::AuditContext;
 use shared_shared_auth::permission::{Auth, PublicAccess};
 use shared_shared_data_app::{
     json::{ResponseJson, ValidJson},
@@ -66,6 +70,7 @@ async fn request_change_password(
 )]
 async fn change_password(
     auth: Auth<CanUpdateUser>,
+    audit: AuditContext,
     State(state): State<AppState<AuthAppState, AuthCacheState>>,
     ValidJson(request): ValidJson<ChangePasswordRequest>,
 ) -> Result<ResponseJson<PasswordResponse>> {
@@ -80,6 +85,15 @@ async fn change_password(
         request.new_password,
     )
     .await?;
+    audit
+        .record(
+            "user.password_change",
+            "user",
+            user_id,
+            None::<&Value>,
+            None::<&Value>,
+        )
+        .await;
     Ok(ResponseJson(response))
 }
 
@@ -122,15 +136,35 @@ async fn request_reset(
 )]
 async fn reset_password(
     _public: PublicAccess,
+    audit: AuditContext,
     State(state): State<AppState<AuthAppState, AuthCacheState>>,
     ValidJson(request): ValidJson<ResetPasswordRequest>,
 ) -> Result<ResponseJson<PasswordResponse>> {
     let producer = state
         .get_producer(PRODUCER_KEY.to_string())
         .expect("Producer not found");
+    let email = request.email.clone();
     let response =
         PasswordService::reset_password(&producer, request.email, request.reset_code, request.new_password)
             .await?;
+    // Public endpoint: there is no actor, the target is resolved from the email. The password
+    // is already reset, failing to resolve the target only skips the audit entry.
+    match UserQuery::get_user_by_email(email.clone()).await {
+        Ok(user) => {
+            if let Some(user_id) = user.id {
+                audit
+                    .record(
+                        "user.password_reset",
+                        "user",
+                        user_id,
+                        None::<&Value>,
+                        Some(&json!({ "email": email })),
+                    )
+                    .await;
+            }
+        }
+        Err(e) => warn!("Password reset of {} not audited: {:?}", email, e),
+    }
     Ok(ResponseJson(response))
 }
 
@@ -3,11 +3,15 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::debug;
+use tracing::{debug, warn};
 use uuid::Uuid;
 
 use shared_shared_app::{doc::ErrorResponse, state::AppState};
-use shared_shared_auth::permission::Auth;
+use shared_shared_audit::AuditContext;
+use shared_shared_auth::{
+    hierarchy::{PermissionExplanation, PermissionExplanationResponse},
+    permission::Auth,
+};
 use shared_shared_data_app::{
     json::{ResponseJson, ValidJson},
     result::{OkUuid, OkUuidResponse, Result},
@@ -21,11 +25,12 @@ use features_auth_entities::permission::PermissionForCreateDto;
 use features_auth_model::{
     permission::{
         PermissionData, PermissionDataFilterParams, PermissionDataResponse,
-        PermissionForCreateRequest, PermissionForUpdateRequest,
+        PermissionExplainQuery, PermissionForCreateRequest, PermissionForUpdateRequest,
     },
     state::{AuthAppState, AuthCacheState},
 };
 use features_auth_repo::permission::{PermissionMutation, PermissionQuery};
+use features_auth_service::PermissionService;
 
 use crate::permission as perm;
 
@@ -45,11 +50,30 @@ const TAG: &str = "permissions";
 )]
 async fn create_permission(
     _auth: Auth<perm::CanCreatePermission>,
+    audit: AuditContext,
     ValidJson(register_request): ValidJson<PermissionForCreateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
     let dto: PermissionForCreateDto = register_request.into();
     let permission_id = PermissionMutation::create(dto).await?;
     debug!("Created permission {:?}", permission_id);
+    let after = PermissionQuery::get(permission_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "permission.create of {} audited without its new state: {:?}",
+                permission_id, e
+            )
+        })
+        .ok();
+    audit
+        .record(
+            "permission.create",
+            "permission",
+            permission_id,
+            None::<&PermissionData>,
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(permission_id),
@@ -75,10 +99,30 @@ async fn create_permission(
 )]
 async fn update_permission(
     _auth: Auth<perm::CanUpdatePermission>,
+    audit: AuditContext,
     Path(permission_id): Path<Uuid>,
     ValidJson(scope_request): ValidJson<PermissionForUpdateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = PermissionQuery::get(permission_id).await?;
     PermissionMutation::update(permission_id, scope_request.into()).await?;
+    let after = PermissionQuery::get(permission_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "permission.update of {} audited without its new state: {:?}",
+                permission_id, e
+            )
+        })
+        .ok();
+    audit
+        .record(
+            "permission.update",
+            "permission",
+            permission_id,
+            Some(&before),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid { ok: true, id: None }))
 }
 
@@ -95,9 +139,20 @@ async fn update_permission(
 )]
 async fn delete_permission(
     _auth: Auth<perm::CanDeletePermission>,
+    audit: AuditContext,
     Path(permission_id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = PermissionQuery::get(permission_id).await?;
     PermissionMutation::delete(permission_id).await?;
+    audit
+        .record(
+            "permission.delete",
+            "permission",
+            permission_id,
+            Some(&before),
+            None::<&PermissionData>,
+        )
+        .await;
     Ok(ResponseJson(OkUuid { ok: true, id: None }))
 }
 
@@ -148,9 +203,36 @@ async fn filter_permissions(
     Ok(ResponseJson(result))
 }
 
+#[utoipa::path(
+    get,
+    path = "/permissions/explain",
+    tag = TAG,
+    summary = "Explain permission",
+    description = "Explain why a user can or cannot perform an action on a resource, including inherited roles and wildcard resources",
+    params  (
+        ("user_id" = Uuid, Query, description = "User Id"),
+        ("resource" = String, Query, description = "Resource, e.g. AUTH:USER"),
+        ("action" = u32, Query, description = "Action bitmask: READ=1, CREATE=2, UPDATE=4, DELETE=8, ADMIN=16"),
+    ),
+    responses(
+        (status = 200, description = "Permission explanation", body = PermissionExplanationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
+async fn explain_permission(
+    _auth: Auth<perm::CanReadPermission>,
+    Query(query): Query<PermissionExplainQuery>,
+) -> Result<ResponseJson<PermissionExplanation>> {
+    let explanation =
+        PermissionService::explain(query.user_id, query.resource, query.action).await?;
+    Ok(ResponseJson(explanation))
+}
+
 pub fn routes(app_state: &AppState<AuthAppState, AuthCacheState>) -> Router {
     Router::new()
         .route("/permissions", post(create_permission))
+        .route("/permissions/explain", get(explain_permission))
         .route("/permissions/{permission_id}", patch(update_permission))
         .route("/permissions/{permission_id}", delete(delete_permission))
         .route("/permissions/{permission_id}", get(get_permission))
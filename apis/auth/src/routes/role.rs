@@ -3,10 +3,12 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::debug;
+use serde_json::json;
+use tracing::{debug, warn};
 use uuid::Uuid;
 
 use shared_shared_app::{doc::ErrorResponse, state::AppState};
+use shared_shared_audit::AuditContext;
 use shared_shared_auth::permission::Auth;
 use shared_shared_data_app::{
     filter_param::FilterParams,
@@ -71,14 +73,21 @@ const TAG: &str = "role";
     summary = "Create role",
     responses(
         (status = 200, description = "Role is created", body = OkUuidResponse),
-        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 400, description = "Bad request or parent role of another client", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Parent role not found", body = ErrorResponse),
     )
 )]
 async fn create_role(
     _auth: Auth<CanCreateRole>,
     ValidJson(register_request): ValidJson<RoleForCreateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    RoleService::check_parent(
+        None,
+        register_request.client_id,
+        register_request.parent_role_id,
+    )
+    .await?;
     let dto: RoleForCreateDto = register_request.into();
     let role_id = RoleMutation::create(dto).await?;
     Ok(ResponseJson(OkUuid {
@@ -99,17 +108,43 @@ async fn create_role(
     description = "Change Role Data",
     responses(
         (status = 200, description = "Role is created", body = OkUuidResponse),
-        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 400, description = "Bad request, parent role of another client or inheriting from the role", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 404, description = "Role not found", body = ErrorResponse),
+        (status = 404, description = "Role or parent role not found", body = ErrorResponse),
     )
 )]
 async fn update_role(
     _auth: Auth<CanUpdateRole>,
+    audit: AuditContext,
     Path(role_id): Path<Uuid>,
     ValidJson(register_request): ValidJson<RoleForUpdateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = RoleService::get(role_id, &QueryParams::default()).await?;
+    RoleService::check_parent(
+        Some(role_id),
+        register_request.client_id,
+        register_request.parent_role_id,
+    )
+    .await?;
     let success = RoleMutation::update(role_id, register_request.into()).await?;
+    let after = RoleService::get(role_id, &QueryParams::default())
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "role.update of {} audited without its new state: {:?}",
+                role_id, e
+            )
+        })
+        .ok();
+    audit
+        .record(
+            "role.update",
+            "role",
+            role_id,
+            Some(&before),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: success,
         id: Some(role_id),
@@ -129,9 +164,20 @@ async fn update_role(
 )]
 async fn delete_role(
     _auth: Auth<CanDeleteRole>,
+    audit: AuditContext,
     Path(role_id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = RoleService::get(role_id, &QueryParams::default()).await?;
     RoleMutation::delete(role_id).await?;
+    audit
+        .record(
+            "role.delete",
+            "role",
+            role_id,
+            Some(&before),
+            None::<&RoleData>,
+        )
+        .await;
     Ok(ResponseJson(OkUuid { ok: true, id: None }))
 }
 
@@ -232,10 +278,31 @@ async fn filter_roles(
 )]
 async fn assign_permissions(
     _auth: Auth<CanUpdateRole>,
+    audit: AuditContext,
     Path(role_id): Path<Uuid>,
     ValidJson(request): ValidJson<AssignPermissionToRoleRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = RoleService::permission_ids(role_id).await?;
     let assign = RoleService::assign_permissions(role_id, request.permission_ids).await?;
+    let after = RoleService::permission_ids(role_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "role.assign_permissions of {} audited without its new state: {:?}",
+                role_id, e
+            )
+        })
+        .ok()
+        .map(|after| json!({ "permission_ids": after }));
+    audit
+        .record(
+            "role.assign_permissions",
+            "role",
+            role_id,
+            Some(&json!({ "permission_ids": before })),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: assign,
         id: None,
@@ -256,10 +323,31 @@ async fn assign_permissions(
 )]
 async fn unassign_permissions(
     _auth: Auth<CanUpdateRole>,
+    audit: AuditContext,
     Path(role_id): Path<Uuid>,
     ValidJson(request): ValidJson<AssignPermissionToRoleRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = RoleService::permission_ids(role_id).await?;
     let assign = RoleService::unassign_permissions(role_id, request.permission_ids).await?;
+    let after = RoleService::permission_ids(role_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "role.unassign_permissions of {} audited without its new state: {:?}",
+                role_id, e
+            )
+        })
+        .ok()
+        .map(|after| json!({ "permission_ids": after }));
+    audit
+        .record(
+            "role.unassign_permissions",
+            "role",
+            role_id,
+            Some(&json!({ "permission_ids": before })),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: assign,
         id: None,
@@ -0,0 +1,107 @@
+use std::net::IpAddr;
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    routing::{delete, get},
+    Router,
+};
+use tracing::{instrument, Level};
+use uuid::Uuid;
+
+use features_auth_model::{
+    state::{AuthAppState, AuthCacheState},
+    token::{SessionData, SessionDevice},
+};
+use features_auth_service::SessionService;
+use shared_shared_app::{doc::ErrorResponse, state::AppState};
+use shared_shared_audit::AuditContext;
+use shared_shared_auth::permission::SelfAccess;
+use shared_shared_data_app::{
+    json::ResponseJson,
+    result::{OkUuid, OkUuidResponse, Result},
+};
+
+const SESSIONS: &str = "/sessions";
+const SESSION: &str = "/sessions/{session_id}";
+
+const TAG: &str = "session";
+
+/// Device of the caller. The client address is the first hop of `X-Forwarded-For`, dropped when
+/// it is not an IP address.
+pub fn session_device(headers: &HeaderMap) -> SessionDevice {
+    let user_agent = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.chars().take(512).collect::<String>())
+        .filter(|s| !s.is_empty());
+
+    let ip_address = headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .and_then(|hop| hop.trim().parse::<IpAddr>().ok())
+        .map(|ip| ip.to_string());
+
+    SessionDevice {
+        user_agent,
+        ip_address,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = SESSIONS,
+    tag = TAG,
+    summary = "List active sessions of the current user",
+    responses(
+        (status = 200, description = "Active sessions, most recently used first", body = Vec<SessionData>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn get_sessions(auth: SelfAccess) -> Result<ResponseJson<Vec<SessionData>>> {
+    let sessions = SessionService::list_sessions(auth.user_id).await?;
+    Ok(ResponseJson(sessions))
+}
+
+#[utoipa::path(
+    delete,
+    path = SESSION,
+    tag = TAG,
+    summary = "Revoke a session of the current user",
+    responses(
+        (status = 200, description = "Session revoked", body = OkUuidResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+async fn revoke_session(
+    auth: SelfAccess,
+    audit: AuditContext,
+    State(state): State<AppState<AuthAppState, AuthCacheState>>,
+    Path(session_id): Path<Uuid>,
+) -> Result<ResponseJson<OkUuid>> {
+    let session = SessionService::revoke_session(&state.cache, auth.user_id, session_id).await?;
+    audit
+        .record(
+            "user.revoke_session",
+            "session",
+            session_id,
+            Some(&session),
+            None::<&SessionData>,
+        )
+        .await;
+    Ok(ResponseJson(OkUuid {
+        ok: true,
+        id: Some(session_id),
+    }))
+}
+
+pub fn routes(app_state: &AppState<AuthAppState, AuthCacheState>) -> Router {
+    Router::new()
+        .route(SESSIONS, get(get_sessions))
+        .route(SESSION, delete(revoke_session))
+        .with_state(app_state.clone())
+}
@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
+    http::HeaderMap,
     routing::{get, post},
     Router,
 };
@@ -31,8 +32,9 @@ use shared_shared_data_core::{
 
 use features_auth_repo::token::TokenQuery;
 use features_auth_service::TokenService;
+use features_auth_stream::PRODUCER_KEY;
 
-use crate::permission::CanReadToken;
+use crate::{permission::CanReadToken, routes::session::session_device};
 
 const TAG: &str = "token";
 
@@ -50,12 +52,16 @@ const TAG: &str = "token";
 async fn create_token(
     _public: PublicAccess,
     state: State<AppState<AuthAppState, AuthCacheState>>,
+    headers: HeaderMap,
     ValidJson(request): ValidJson<TokenForCreateRequest>,
 ) -> Result<ResponseJson<AuthorizationCodeData>> {
     debug!("Create token with request: {:?}", request);
     let cache = &state.cache;
+    let producer = state.get_producer(PRODUCER_KEY.to_string());
+    let device = session_device(&headers);
     // Create Logic Service to convert request to DTO
-    let authorization_code = TokenService::create_authorization_data(cache, &request).await?;
+    let authorization_code =
+        TokenService::create_authorization_data(cache, producer.as_ref(), &request, device).await?;
     let data = authorization_code.clone();
 
     Ok(ResponseJson(data))
@@ -3,7 +3,8 @@ use axum::{
     routing::{delete, get, post},
     Router,
 };
-use tracing::{instrument, Level};
+use serde_json::json;
+use tracing::{instrument, warn, Level};
 use uuid::Uuid;
 
 use features_auth_model::{
@@ -13,6 +14,7 @@ use features_auth_model::{
 };
 
 use shared_shared_app::{doc::ErrorResponse, state::AppState};
+use shared_shared_audit::AuditContext;
 use shared_shared_auth::permission::Auth;
 use shared_shared_data_app::{
     json::{ResponseJson, ValidJson},
@@ -44,9 +46,20 @@ const TAG: &str = "user";
 )]
 async fn delete_user(
     _auth: Auth<CanDeleteUser>,
+    audit: AuditContext,
     Path(user_id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = UserQuery::get(user_id, &QueryParams::default()).await?;
     UserMutation::delete_user(user_id).await?;
+    audit
+        .record(
+            "user.delete",
+            "user",
+            user_id,
+            Some(&before),
+            None::<&UserData>,
+        )
+        .await;
     Ok(ResponseJson(OkUuid { ok: true, id: None }))
 }
 
@@ -114,10 +127,31 @@ async fn filter_users(
 )]
 async fn assign_roles(
     _auth: Auth<CanUpdateUser>,
+    audit: AuditContext,
     Path(user_id): Path<Uuid>,
     ValidJson(request): ValidJson<AssignRoleToUserRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = UserService::role_ids(user_id).await?;
     let assign = UserService::assign_roles(user_id, request.role_ids, request.key).await?;
+    let after = UserService::role_ids(user_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "user.assign_roles of {} audited without its new state: {:?}",
+                user_id, e
+            )
+        })
+        .ok()
+        .map(|after| json!({ "role_ids": after }));
+    audit
+        .record(
+            "user.assign_roles",
+            "user",
+            user_id,
+            Some(&json!({ "role_ids": before })),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: assign,
         id: None,
@@ -138,10 +172,31 @@ async fn assign_roles(
 )]
 async fn unassign_roles(
     _auth: Auth<CanUpdateUser>,
+    audit: AuditContext,
     Path(user_id): Path<Uuid>,
     ValidJson(request): ValidJson<AssignRoleToUserRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = UserService::role_ids(user_id).await?;
     let unassign = UserService::unassign_roles(user_id, request.role_ids).await?;
+    let after = UserService::role_ids(user_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "user.unassign_roles of {} audited without its new state: {:?}",
+                user_id, e
+            )
+        })
+        .ok()
+        .map(|after| json!({ "role_ids": after }));
+    audit
+        .record(
+            "user.unassign_roles",
+            "user",
+            user_id,
+            Some(&json!({ "role_ids": before })),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: unassign,
         id: None,
@@ -0,0 +1,216 @@
+//! Links of provider accounts to existing users, on a schema of their own on the Postgres of
+//! `TEST_DATABASE_URL`, ignored unless run with `--ignored`.
+use uuid::Uuid;
+
+use shared_shared_config::db::with_db;
+use shared_shared_data_error::{app::AppError, auth::AuthError};
+use shared_shared_test_support::TestDatabase;
+
+use features_auth_entities::{client::ClientForCreateDto, user::UserForUpdateDto};
+use features_auth_migrations::Migrator;
+use features_auth_model::{oidc::OidcUserInfo, user::UserForCreateRequest};
+use features_auth_repo::{
+    active_code::query::ActiveCodeQuery,
+    client::ClientMutation,
+    external_identity::ExternalIdentityQuery,
+    user::{UserMutation, UserQuery},
+};
+use features_auth_service::OidcService;
+
+const TOPIC: &str = "auth";
+const EMAIL: &str = "social@example.com";
+
+async fn create_user(confirmed: bool) -> Uuid {
+    let user_id = UserMutation::create_user(
+        UserForCreateRequest {
+            email: EMAIL.to_string(),
+            password: "password".to_string(),
+            language: "en-US".to_string(),
+        }
+        .into(),
+    )
+    .await
+    .unwrap();
+    UserMutation::update(
+        user_id,
+        UserForUpdateDto {
+            email: None,
+            language: None,
+            password: None,
+            confirmed: Some(confirmed),
+            two_factor_enabled: None,
+            is_active: Some(true),
+        },
+    )
+    .await
+    .unwrap();
+    user_id
+}
+
+async fn create_client() -> Uuid {
+    ClientMutation::create(ClientForCreateDto {
+        client_secret: "secret".to_string(),
+        client_key: "SHOP".to_string(),
+        name: "Shop".to_string(),
+        email: "no-reply@example.com".to_string(),
+        description: String::new(),
+        redirect_uris: vec![],
+        allowed_grants: vec![],
+    })
+    .await
+    .unwrap()
+}
+
+fn user_info() -> OidcUserInfo {
+    OidcUserInfo {
+        subject: "mock-user-1".to_string(),
+        email: Some(EMAIL.to_string()),
+        email_verified: true,
+        name: None,
+    }
+}
+
+async fn link_code_of(identity_id: Uuid) -> String {
+    let identity = ExternalIdentityQuery::get_raw(identity_id).await.unwrap();
+    let code_id = identity.link_code_id.flatten().unwrap();
+    ActiveCodeQuery::get(code_id).await.unwrap().code.unwrap()
+}
+
+async fn pending_identity(client_id: Uuid) -> Uuid {
+    let resolved = OidcService::resolve_user(TOPIC, "mock", &user_info(), client_id).await;
+    assert!(matches!(
+        resolved,
+        Err(AppError::Auth(AuthError::LinkPending))
+    ));
+    ExternalIdentityQuery::find_by_provider_subject("mock", "mock-user-1")
+        .await
+        .unwrap()
+        .unwrap()
+        .id
+        .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_confirmed_user_is_linked() {
+    let db = TestDatabase::new::<Migrator>().await;
+    with_db(db.connection(), async {
+        let user_id = create_user(true).await;
+
+        let resolved = OidcService::resolve_user(TOPIC, "mock", &user_info(), Uuid::new_v4())
+            .await
+            .unwrap();
+        assert_eq!(resolved, user_id);
+
+        let identity = ExternalIdentityQuery::find_by_provider_subject("mock", "mock-user-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.user_id, Some(user_id));
+        assert_eq!(identity.confirmed, Some(true));
+    })
+    .await;
+    db.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_unconfirmed_user_link_waits_for_code() {
+    let db = TestDatabase::new::<Migrator>().await;
+    with_db(db.connection(), async {
+        let user_id = create_user(false).await;
+        let client_id = create_client().await;
+
+        let resolved = OidcService::resolve_user(TOPIC, "mock", &user_info(), client_id).await;
+        assert!(matches!(
+            resolved,
+            Err(AppError::Auth(AuthError::LinkPending))
+        ));
+        let identity = ExternalIdentityQuery::find_by_provider_subject("mock", "mock-user-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.confirmed, Some(false));
+
+        let identity_id = identity.id.unwrap();
+        let first_code = link_code_of(identity_id).await;
+
+        // A pending link does not log in until its code is confirmed, and each login sends
+        // a new code replacing the previous one
+        let resolved = OidcService::resolve_user(TOPIC, "mock", &user_info(), client_id).await;
+        assert!(matches!(
+            resolved,
+            Err(AppError::Auth(AuthError::LinkPending))
+        ));
+        let link_code = link_code_of(identity_id).await;
+        if first_code != link_code {
+            let replaced = OidcService::confirm_identity(identity_id, first_code).await;
+            assert!(matches!(
+                replaced,
+                Err(AppError::Auth(AuthError::LinkCodeNotFound))
+            ));
+        }
+
+        let wrong = OidcService::confirm_identity(identity_id, "wrong".to_string()).await;
+        assert!(matches!(
+            wrong,
+            Err(AppError::Auth(AuthError::LinkCodeNotFound))
+        ));
+
+        let confirmed = OidcService::confirm_identity(identity_id, link_code)
+            .await
+            .unwrap();
+        assert_eq!(confirmed, user_id);
+
+        let resolved = OidcService::resolve_user(TOPIC, "mock", &user_info(), client_id)
+            .await
+            .unwrap();
+        assert_eq!(resolved, user_id);
+        // The account keeps its password
+        let user = UserQuery::get_user_by_id_raw(user_id).await.unwrap();
+        assert_eq!(user.confirmed, Some(true));
+        assert!(UserQuery::get_user_by_email_and_password(
+            EMAIL.to_string(),
+            "password".to_string()
+        )
+        .await
+        .is_ok());
+    })
+    .await;
+    db.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_link_code_attempts_are_limited() {
+    let db = TestDatabase::new::<Migrator>().await;
+    with_db(db.connection(), async {
+        create_user(false).await;
+        let client_id = create_client().await;
+        let identity_id = pending_identity(client_id).await;
+        let link_code = link_code_of(identity_id).await;
+
+        for _ in 0..5 {
+            let wrong = OidcService::confirm_identity(identity_id, "wrong".to_string()).await;
+            assert!(matches!(
+                wrong,
+                Err(AppError::Auth(AuthError::LinkCodeNotFound))
+            ));
+        }
+        // Even the right code is refused once the attempts are used up
+        let locked = OidcService::confirm_identity(identity_id, link_code).await;
+        assert!(matches!(
+            locked,
+            Err(AppError::Auth(AuthError::TooManyLinkAttempts))
+        ));
+
+        // Logging in again sends a new code with fresh attempts
+        pending_identity(client_id).await;
+        let link_code = link_code_of(identity_id).await;
+        OidcService::confirm_identity(identity_id, link_code)
+            .await
+            .unwrap();
+    })
+    .await;
+    db.cleanup().await;
+}
@@ -0,0 +1,306 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, Method, Request, StatusCode},
+    middleware,
+    routing::{get, post},
+    Form, Json, Router,
+};
+use sea_orm::{DatabaseBackend, MockDatabase};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use shared_shared_app::{mapper::main_response_mapper, state::AppState};
+use shared_shared_data_cache::cache::Cache;
+
+use features_auth_model::{
+    oidc::{OidcLinkMode, OidcLoginState, OidcProviderConfig},
+    state::{AuthAppState, AuthCacheState},
+};
+use features_auth_service::OidcService;
+use shared_shared_data_error::{app::AppError, auth::AuthError};
+
+const MOCK_CODE: &str = "mock-code";
+const MOCK_ACCESS_TOKEN: &str = "mock-access-token";
+const MOCK_VERIFIER: &str = "mock-code-verifier";
+
+/// Minimal local OIDC issuer: discovery, token and userinfo endpoints.
+async fn start_mock_issuer(claims: Value) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+    let router = Router::new()
+        .route(
+            "/.well-known/openid-configuration",
+            get(|State((issuer, _)): State<(String, Value)>| async move {
+                Json(json!({
+                    "issuer": issuer,
+                    "authorization_endpoint": format!("{}/authorize", issuer),
+                    "token_endpoint": format!("{}/token", issuer),
+                    "userinfo_endpoint": format!("{}/userinfo", issuer),
+                    "jwks_uri": format!("{}/jwks", issuer),
+                }))
+            }),
+        )
+        .route(
+            "/token",
+            post(|Form(form): Form<HashMap<String, String>>| async move {
+                let valid = form.get("grant_type").map(String::as_str)
+                    == Some("authorization_code")
+                    && form.get("code").map(String::as_str) == Some(MOCK_CODE)
+                    && form.get("code_verifier").map(String::as_str) == Some(MOCK_VERIFIER)
+                    && form.get("client_secret").map(String::as_str) == Some("secret");
+                if !valid {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({ "error": "invalid_grant" })),
+                    );
+                }
+                (
+                    StatusCode::OK,
+                    Json(json!({ "access_token": MOCK_ACCESS_TOKEN, "token_type": "Bearer" })),
+                )
+            }),
+        )
+        .route(
+            "/userinfo",
+            get(
+                |State((_, claims)): State<(String, Value)>, headers: HeaderMap| async move {
+                    let expected = format!("Bearer {}", MOCK_ACCESS_TOKEN);
+                    match headers.get(header::AUTHORIZATION) {
+                        Some(value) if value.to_str().unwrap_or_default() == expected => {
+                            (StatusCode::OK, Json(claims))
+                        }
+                        _ => (StatusCode::UNAUTHORIZED, Json(json!({}))),
+                    }
+                },
+            ),
+        )
+        .with_state((issuer.clone(), claims));
+
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    issuer
+}
+
+fn provider(issuer: &str) -> OidcProviderConfig {
+    OidcProviderConfig {
+        name: "mock".to_string(),
+        issuer: issuer.to_string(),
+        client_id: "client".to_string(),
+        client_secret: "secret".to_string(),
+        redirect_uri: "http://localhost/public/oidc/mock/callback".to_string(),
+        scopes: vec!["openid".to_string(), "email".to_string()],
+        authorization_endpoint: None,
+        token_endpoint: None,
+        userinfo_endpoint: None,
+        subject_claim: "sub".to_string(),
+        trust_email: false,
+    }
+}
+
+/// Routes without a database, for the requests answered before any query.
+fn build_app() -> Router {
+    let db_conn = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    let cache = Cache::<String, AuthCacheState>::in_memory("test_oidc");
+    let app_state = AppState::new(&db_conn, cache, Some(AuthAppState::default()));
+
+    api_auth::routes::oidc::routes(&app_state).layer(middleware::map_response(main_response_mapper))
+}
+
+#[tokio::test]
+async fn test_discovery_and_authorization_url() {
+    let issuer = start_mock_issuer(json!({})).await;
+    let config = provider(&issuer);
+
+    let endpoints = OidcService::endpoints(&config).await.unwrap();
+    assert_eq!(endpoints.token_endpoint, format!("{}/token", issuer));
+    assert_eq!(endpoints.userinfo_endpoint, format!("{}/userinfo", issuer));
+
+    let state = Uuid::new_v4().to_string();
+    let challenge = OidcService::code_challenge(MOCK_VERIFIER);
+    let url = OidcService::authorization_url(&config, &endpoints, &state, &challenge).unwrap();
+    assert!(url.starts_with(&format!("{}/authorize?", issuer)));
+    assert!(url.contains("response_type=code"));
+    assert!(url.contains("client_id=client"));
+    assert!(url.contains("scope=openid+email"));
+    assert!(url.contains(&format!("state={}", state)));
+    assert!(url.contains(&format!("code_challenge={}", challenge)));
+    assert!(url.contains("code_challenge_method=S256"));
+}
+
+#[test]
+fn test_code_challenge_is_s256() {
+    // RFC 7636 appendix B
+    assert_eq!(
+        OidcService::code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+}
+
+#[tokio::test]
+async fn test_discovery_rejects_issuer_mismatch() {
+    let issuer = start_mock_issuer(json!({})).await;
+    let mut config = provider(&issuer);
+    config.issuer = format!("{}/", issuer.replace("127.0.0.1", "localhost"));
+    assert!(OidcService::discover(&config).await.is_err());
+}
+
+#[tokio::test]
+async fn test_code_exchange_and_user_info() {
+    let issuer = start_mock_issuer(json!({
+        "sub": "mock-user-1",
+        "email": "Social@Example.com",
+        "email_verified": true,
+        "name": "Social User"
+    }))
+    .await;
+    let config = provider(&issuer);
+    let endpoints = OidcService::endpoints(&config).await.unwrap();
+
+    let token = OidcService::exchange_code(&config, &endpoints, MOCK_CODE, MOCK_VERIFIER)
+        .await
+        .unwrap();
+    assert_eq!(token.access_token, MOCK_ACCESS_TOKEN);
+
+    let info = OidcService::fetch_user_info(&config, &endpoints, &token.access_token)
+        .await
+        .unwrap();
+    assert_eq!(info.subject, "mock-user-1");
+    assert_eq!(info.email.as_deref(), Some("social@example.com"));
+    assert!(info.email_verified);
+}
+
+#[tokio::test]
+async fn test_invalid_code_is_rejected() {
+    let issuer = start_mock_issuer(json!({ "sub": "mock-user-1" })).await;
+    let config = provider(&issuer);
+    let endpoints = OidcService::endpoints(&config).await.unwrap();
+    assert!(
+        OidcService::exchange_code(&config, &endpoints, "wrong", MOCK_VERIFIER)
+            .await
+            .is_err()
+    );
+    assert!(
+        OidcService::exchange_code(&config, &endpoints, MOCK_CODE, "wrong")
+            .await
+            .is_err()
+    );
+    assert!(OidcService::fetch_user_info(&config, &endpoints, "wrong")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_unknown_provider_is_public_and_not_found() {
+    let app = build_app();
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri(format!(
+            "/public/oidc/unknown/authorize?state={}",
+            Uuid::new_v4()
+        ))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_identities_require_auth() {
+    let app = build_app();
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{}/identities", Uuid::new_v4()))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+fn cache_login(cache: &Cache<String, AuthCacheState>, state: &str, binding: &str) {
+    let login = OidcLoginState {
+        request_id: Uuid::new_v4(),
+        provider: "mock".to_string(),
+        code_verifier: MOCK_VERIFIER.to_string(),
+        binding_hash: OidcService::binding_hash(binding),
+    };
+    cache
+        .insert(
+            format!("oidc_login:{}", state),
+            AuthCacheState::OidcLogin(login),
+            None,
+        )
+        .unwrap();
+}
+
+fn is_invalid_state<T>(result: Result<T, AppError>) -> bool {
+    matches!(result, Err(AppError::Auth(AuthError::InvalidOidcState)))
+}
+
+#[test]
+fn test_login_state_is_single_use() {
+    let cache = Cache::<String, AuthCacheState>::in_memory("test_oidc");
+    cache_login(&cache, "state", "browser");
+
+    let login = OidcService::take_login_state(&cache, "mock", "state", Some("browser")).unwrap();
+    assert_eq!(login.code_verifier, MOCK_VERIFIER);
+    assert!(is_invalid_state(OidcService::take_login_state(
+        &cache,
+        "mock",
+        "state",
+        Some("browser")
+    )));
+    assert!(is_invalid_state(OidcService::take_login_state(
+        &cache,
+        "mock",
+        "unknown",
+        Some("browser")
+    )));
+}
+
+#[test]
+fn test_login_state_is_bound_to_browser_and_provider() {
+    let cache = Cache::<String, AuthCacheState>::in_memory("test_oidc");
+    cache_login(&cache, "other-browser", "browser");
+    cache_login(&cache, "no-cookie", "browser");
+    cache_login(&cache, "other-provider", "browser");
+
+    assert!(is_invalid_state(OidcService::take_login_state(
+        &cache,
+        "mock",
+        "other-browser",
+        Some("attacker")
+    )));
+    assert!(is_invalid_state(OidcService::take_login_state(
+        &cache,
+        "mock",
+        "no-cookie",
+        None
+    )));
+    assert!(is_invalid_state(OidcService::take_login_state(
+        &cache,
+        "google",
+        "other-provider",
+        Some("browser")
+    )));
+    // A rejected state is consumed as well
+    assert!(cache.is_empty().unwrap());
+}
+
+#[test]
+fn test_only_confirmed_users_are_linked_right_away() {
+    assert_eq!(
+        OidcLinkMode::for_existing_user(Some(true)),
+        OidcLinkMode::Linked
+    );
+    assert_eq!(
+        OidcLinkMode::for_existing_user(Some(false)),
+        OidcLinkMode::Pending
+    );
+    assert_eq!(OidcLinkMode::for_existing_user(None), OidcLinkMode::Pending);
+}
@@ -0,0 +1,102 @@
+//! Parents of roles, on a schema of their own on the Postgres of `TEST_DATABASE_URL`,
+//! ignored unless run with `--ignored`.
+use uuid::Uuid;
+
+use shared_shared_config::db::with_db;
+use shared_shared_data_error::{app::AppError, auth::AuthError};
+use shared_shared_test_support::TestDatabase;
+
+use features_auth_entities::{client::ClientForCreateDto, role::RoleForCreateDto};
+use features_auth_migrations::Migrator;
+use features_auth_repo::{client::ClientMutation, role::RoleMutation};
+use features_auth_service::RoleService;
+
+async fn create_client(client_key: &str) -> Uuid {
+    ClientMutation::create(ClientForCreateDto {
+        client_secret: "secret".to_string(),
+        client_key: client_key.to_string(),
+        name: client_key.to_string(),
+        email: "no-reply@example.com".to_string(),
+        description: String::new(),
+        redirect_uris: vec![],
+        allowed_grants: vec![],
+    })
+    .await
+    .unwrap()
+}
+
+async fn create_role(client_id: Uuid, name: &str, parent_role_id: Option<Uuid>) -> Uuid {
+    RoleMutation::create(RoleForCreateDto {
+        name: name.to_string(),
+        description: String::new(),
+        client_id,
+        is_default: false,
+        parent_role_id,
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_role_cannot_inherit_from_itself_or_descendants() {
+    let db = TestDatabase::new::<Migrator>().await;
+    with_db(db.connection(), async {
+        let client_id = create_client("SHOP").await;
+        let client = Some(client_id);
+        let viewer = create_role(client_id, "VIEWER", None).await;
+        let editor = create_role(client_id, "EDITOR", Some(viewer)).await;
+        let admin = create_role(client_id, "ADMIN", Some(editor)).await;
+
+        assert!(RoleService::check_parent(Some(admin), client, Some(viewer))
+            .await
+            .is_ok());
+        assert!(matches!(
+            RoleService::check_parent(Some(viewer), client, Some(viewer)).await,
+            Err(AppError::Auth(AuthError::RoleCycle))
+        ));
+        assert!(matches!(
+            RoleService::check_parent(Some(viewer), client, Some(admin)).await,
+            Err(AppError::Auth(AuthError::RoleCycle))
+        ));
+        assert!(matches!(
+            RoleService::check_parent(None, client, Some(Uuid::new_v4())).await,
+            Err(AppError::Auth(AuthError::UnknowRole))
+        ));
+        assert!(matches!(
+            RoleService::get(Uuid::new_v4(), &Default::default()).await,
+            Err(AppError::Auth(AuthError::UnknowRole))
+        ));
+    })
+    .await;
+    db.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_role_cannot_inherit_from_a_role_of_another_client() {
+    let db = TestDatabase::new::<Migrator>().await;
+    with_db(db.connection(), async {
+        let shop = create_client("SHOP").await;
+        let pos = create_client("POS").await;
+        let shop_viewer = create_role(shop, "VIEWER", None).await;
+        let pos_editor = create_role(pos, "EDITOR", None).await;
+
+        assert!(matches!(
+            RoleService::check_parent(None, Some(pos), Some(shop_viewer)).await,
+            Err(AppError::Auth(AuthError::ParentRoleOfOtherClient))
+        ));
+        // The client of the role is the one it is updated to
+        assert!(matches!(
+            RoleService::check_parent(Some(pos_editor), Some(pos), Some(shop_viewer)).await,
+            Err(AppError::Auth(AuthError::ParentRoleOfOtherClient))
+        ));
+        assert!(
+            RoleService::check_parent(Some(pos_editor), Some(shop), Some(shop_viewer))
+                .await
+                .is_ok()
+        );
+    })
+    .await;
+    db.cleanup().await;
+}
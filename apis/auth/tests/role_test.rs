@@ -30,6 +30,7 @@ fn sample_model() -> RoleModel {
         description: "Administrator role".to_string(),
         client_id: Uuid::new_v4(),
         is_default: false,
+        parent_role_id: None,
         created_at: Utc::now().naive_utc(),
         updated_at: Utc::now().naive_utc(),
         permissions: vec![],
@@ -0,0 +1,243 @@
+//! Sessions of the signed-in user. The tests reading or revoking sessions run on a schema of
+//! their own on the Postgres of `TEST_DATABASE_URL`, ignored unless run with `--ignored`.
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware, Router,
+};
+use chrono::{Duration, Utc};
+use sea_orm::{DatabaseBackend, EntityTrait, MockDatabase};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use shared_shared_app::{mapper::main_response_mapper, state::AppState};
+use shared_shared_config::db::with_db;
+use shared_shared_data_cache::cache::Cache;
+use shared_shared_test_support::{TestApp, TestDatabase};
+
+use api_auth::routes::session::session_device;
+use features_auth_entities::{
+    client::ClientForCreateDto,
+    token::{ActiveModel as TokenActiveModel, Entity as TokenEntity, Model as TokenModel},
+};
+use features_auth_migrations::Migrator;
+use features_auth_model::{
+    state::{AuthAppState, AuthCacheState},
+    user::UserForCreateRequest,
+};
+use features_auth_repo::{client::ClientMutation, user::UserMutation};
+
+/// Routes without a database, for the requests rejected before any query.
+fn build_app() -> Router {
+    let db_conn = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    let cache = Cache::<String, AuthCacheState>::in_memory("test_session");
+    let app_state = AppState::new(&db_conn, cache, Some(AuthAppState::default()));
+
+    api_auth::routes::session::routes(&app_state)
+        .layer(middleware::map_response(main_response_mapper))
+}
+
+fn build_test_app(db: &TestDatabase) -> TestApp {
+    let app_state =
+        TestApp::app_state::<AuthAppState, AuthCacheState>(db, Some(AuthAppState::default()));
+    TestApp::new(db, api_auth::routes::session::routes(&app_state), &[])
+}
+
+/// Baggage of `user_id` signed in with a role that has no `AUTH:USER` permission.
+fn baggage(user_id: Uuid, client_id: Uuid) -> String {
+    format!(
+        "accesses=CUSTOMER*,user_id={},client_id={},tenant_id=test-tenant",
+        user_id, client_id
+    )
+}
+
+async fn create_user(email: &str) -> Uuid {
+    UserMutation::create_user(
+        UserForCreateRequest {
+            email: email.to_string(),
+            password: "password".to_string(),
+            language: "en-US".to_string(),
+        }
+        .into(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn create_client() -> Uuid {
+    ClientMutation::create(ClientForCreateDto {
+        client_secret: "secret".to_string(),
+        client_key: "SHOP".to_string(),
+        name: "Shop".to_string(),
+        email: "no-reply@example.com".to_string(),
+        description: String::new(),
+        redirect_uris: vec![],
+        allowed_grants: vec![],
+    })
+    .await
+    .unwrap()
+}
+
+async fn create_session(db: &TestDatabase, user_id: Uuid, client_id: Uuid) -> Uuid {
+    let id = Uuid::new_v4();
+    let now = Utc::now().naive_utc();
+    let token: TokenActiveModel = TokenModel {
+        id,
+        user_id,
+        client_id,
+        access_token: format!("access_token_{}", id),
+        refresh_token: format!("refresh_token_{}", id),
+        scopes: vec!["read:users".to_string()],
+        access_token_expires_at: now + Duration::hours(1),
+        refresh_token_expires_at: now + Duration::days(1),
+        revoked_at: None,
+        user_agent: Some("Mozilla/5.0".to_string()),
+        ip_address: Some("10.0.0.1".to_string()),
+        last_used_at: Some(now),
+        created_at: now,
+        updated_at: now,
+        code: None,
+    }
+    .into();
+    TokenEntity::insert(token)
+        .exec(db.connection())
+        .await
+        .unwrap();
+    id
+}
+
+#[test]
+fn test_session_device_from_headers() {
+    let mut headers = HeaderMap::new();
+    headers.insert("user-agent", HeaderValue::from_static("Mozilla/5.0"));
+    headers.insert(
+        "x-forwarded-for",
+        HeaderValue::from_static("203.0.113.7, 10.0.0.1"),
+    );
+    let device = session_device(&headers);
+    assert_eq!(device.user_agent.as_deref(), Some("Mozilla/5.0"));
+    assert_eq!(device.ip_address.as_deref(), Some("203.0.113.7"));
+
+    let device = session_device(&HeaderMap::new());
+    assert!(device.user_agent.is_none());
+    assert!(device.ip_address.is_none());
+}
+
+#[test]
+fn test_session_device_drops_invalid_address() {
+    let mut headers = HeaderMap::new();
+    let long = format!("{}, 10.0.0.1", "a".repeat(200));
+    headers.insert("x-forwarded-for", HeaderValue::from_str(&long).unwrap());
+    assert!(session_device(&headers).ip_address.is_none());
+
+    headers.insert("x-forwarded-for", HeaderValue::from_static("2001:db8::1"));
+    assert_eq!(
+        session_device(&headers).ip_address.as_deref(),
+        Some("2001:db8::1")
+    );
+}
+
+#[tokio::test]
+async fn test_get_sessions_requires_auth() {
+    let app = build_app();
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/sessions")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_revoke_session_requires_auth() {
+    let app = build_app();
+
+    let req = Request::builder()
+        .method(Method::DELETE)
+        .uri(format!("/sessions/{}", Uuid::new_v4()))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_get_sessions_lists_device_details() {
+    let db = TestDatabase::new::<Migrator>().await;
+    let (user_id, client_id) = with_db(db.connection(), async {
+        (create_user("user@example.com").await, create_client().await)
+    })
+    .await;
+    let session_id = create_session(&db, user_id, client_id).await;
+    let app = build_test_app(&db);
+
+    // Listing its own sessions needs no user permission
+    let (status, body) = app
+        .send_json(
+            Method::GET,
+            "/sessions",
+            Some(&baggage(user_id, client_id)),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let session = &body["data"][0];
+    assert_eq!(session["id"], session_id.to_string());
+    assert_eq!(session["user_agent"], "Mozilla/5.0");
+    assert_eq!(session["ip_address"], "10.0.0.1");
+    assert!(session.get("access_token").is_none());
+    assert!(session.get("refresh_token").is_none());
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_revoke_session_of_another_user_is_not_found() {
+    let db = TestDatabase::new::<Migrator>().await;
+    let (user_id, other_id, client_id) = with_db(db.connection(), async {
+        (
+            create_user("user@example.com").await,
+            create_user("other@example.com").await,
+            create_client().await,
+        )
+    })
+    .await;
+    let own_session = create_session(&db, user_id, client_id).await;
+    let other_session = create_session(&db, other_id, client_id).await;
+    let app = build_test_app(&db);
+    let baggage = baggage(user_id, client_id);
+
+    let (status, _) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/sessions/{}", other_session),
+            Some(&baggage),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/sessions/{}", own_session),
+            Some(&baggage),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app
+        .send_json(Method::GET, "/sessions", Some(&baggage), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], serde_json::json!([]));
+
+    db.cleanup().await;
+}
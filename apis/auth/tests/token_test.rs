@@ -34,6 +34,9 @@ fn sample_model() -> TokenModel {
         access_token_expires_at: Utc::now().naive_utc(),
         refresh_token_expires_at: Utc::now().naive_utc(),
         revoked_at: None,
+        user_agent: Some("Mozilla/5.0".to_string()),
+        ip_address: Some("127.0.0.1".to_string()),
+        last_used_at: Some(Utc::now().naive_utc()),
         created_at: Utc::now().naive_utc(),
         updated_at: Utc::now().naive_utc(),
         code: None,
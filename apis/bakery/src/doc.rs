@@ -17,6 +17,7 @@ use shared_shared_app::doc::JwtSecurityAddon;
         crate::routes::baker::get_by_id,
         crate::routes::bakery::create,
         crate::routes::bakery::delete_by_id,
+        crate::routes::bakery::restore_by_id,
         crate::routes::bakery::filter,
         crate::routes::bakery::get_by_id,
         crate::routes::cake_bakers::create,
@@ -32,6 +33,7 @@ use shared_shared_app::doc::JwtSecurityAddon;
         crate::routes::order::create,
         crate::routes::order::delete_by_id,
         crate::routes::order::filter,
+        crate::routes::order::aggregate,
         crate::routes::order::get_by_id,
         crate::routes::lineitem::create,
         crate::routes::lineitem::delete_by_id,
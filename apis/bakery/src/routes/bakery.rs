@@ -64,6 +64,26 @@ async fn delete_by_id(_auth: Auth<CanDeleteBakery>, Path(baker_id): Path<i32>) -
     Ok(ResponseJson(OkI32 { ok: true, id: None }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/bakeries/{baker_id}/restore",
+    tag = TAG,
+    operation_id = "restore-bakery-by-id",
+    responses(
+        (status = 200, description = "Deleted bakery is restored", body = OkI32Response),
+    )
+)]
+async fn restore_by_id(
+    _auth: Auth<CanDeleteBakery>,
+    Path(bakery_id): Path<i32>,
+) -> Result<ResponseJson<OkI32>> {
+    BakeryMutation::restore(bakery_id).await?;
+    Ok(ResponseJson(OkI32 {
+        ok: true,
+        id: Some(bakery_id),
+    }))
+}
+
 #[utoipa::path(
     get,
     path = "/bakeries/{baker_id}",
@@ -110,6 +130,7 @@ pub fn routes(app_state: &AppState<BakeryCacheState>) -> Router {
     Router::new()
         .route("/bakeries", post(create))
         .route("/bakeries/{baker_id}", delete(delete_by_id))
+        .route("/bakeries/{baker_id}/restore", post(restore_by_id))
         .route("/bakeries/{baker_id}", get(get_by_id))
         .route("/bakeries", get(filter))
         .with_state(app_state.clone())
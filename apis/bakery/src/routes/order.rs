@@ -12,6 +12,7 @@ use shared_shared_data_app::{
     result::{OkI32, OkI32Response, Result},
 };
 use shared_shared_data_core::{
+    aggregate::{AggregateRow, Aggregation},
     order::Order,
     paging::{Pagination, QueryResult, QueryResultResponse},
 };
@@ -103,11 +104,37 @@ async fn filter(
     Ok(ResponseJson(result))
 }
 
+#[utoipa::path(
+    get,
+    path = "/orders/aggregate",
+    tag = TAG,
+    operation_id = "aggregate-order",
+    params  (
+       Aggregation
+    ),
+    responses(
+        (status = 200, description = "Order count or sum by group", body = Vec<AggregateRow>),
+        (status = 400, description = "Column that orders can't be aggregated on"),
+    )
+)]
+async fn aggregate(
+    _public: PublicAccess,
+    query_aggregation: Query<Aggregation>,
+    filter: Query<OrderDataFilterParams>,
+) -> Result<ResponseJson<Vec<AggregateRow>>> {
+    let aggregation = query_aggregation.0;
+    let all_filters = filter.0.all_filters();
+
+    let result = OrderQuery::aggregate(&aggregation, &all_filters).await?;
+    Ok(ResponseJson(result))
+}
+
 pub fn routes(app_state: &AppState<BakeryCacheState>) -> Router {
     Router::new()
         .route("/orders", post(create))
         .route("/orders/{order_id}", delete(delete_by_id))
         .route("/orders/{order_id}", get(get_by_id))
         .route("/orders", get(filter))
+        .route("/orders/aggregate", get(aggregate))
         .with_state(app_state.clone())
 }
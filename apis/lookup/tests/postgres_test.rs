@@ -0,0 +1,103 @@
+//! Runs against a schema of its own on the Postgres of `TEST_DATABASE_URL`, ignored unless
+//! run with `--ignored`.
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+use shared_shared_test_support::{TestApp, TestDatabase};
+
+use features_lookup_migrations::Migrator;
+use features_lookup_model::state::{LookupAppState, LookupCacheState};
+
+const BAGGAGE_ADMIN: &str = "accesses=ADMIN_ALL*,user_id=00000000-0000-0000-0000-000000000000,client_id=00000000-0000-0000-0000-000000000000,tenant_id=test-tenant";
+
+const PUBLIC_PATHS: &[&str] = &["/lookup-types"];
+
+fn build_app(db: &TestDatabase) -> TestApp {
+    let app_state =
+        TestApp::app_state::<LookupAppState, LookupCacheState>(db, Some(LookupAppState::default()));
+    TestApp::new(
+        db,
+        api_lookup::routes::lookup_type::routes(&app_state),
+        PUBLIC_PATHS,
+    )
+}
+
+async fn create_lookup_type(app: &TestApp, code: &str) -> String {
+    let (status, body) = app
+        .send_json(
+            Method::POST,
+            "/lookup-types",
+            Some(BAGGAGE_ADMIN),
+            Some(json!({ "code": code, "name": "Currency Types" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    body["data"]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_create_then_get_lookup_type() {
+    let db = TestDatabase::new::<Migrator>().await;
+    let app = build_app(&db);
+
+    let id = create_lookup_type(&app, "CURRENCY").await;
+    let (status, body) = app
+        .send_json(
+            Method::GET,
+            &format!("/lookup-types/{}", id),
+            Some(BAGGAGE_ADMIN),
+            None,
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["id"], id.as_str());
+    assert_eq!(body["data"]["code"], "CURRENCY");
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_delete_lookup_type() {
+    let db = TestDatabase::new::<Migrator>().await;
+    let app = build_app(&db);
+
+    let id = create_lookup_type(&app, "CURRENCY").await;
+    let uri = format!("/lookup-types/{}", id);
+    let (status, _) = app
+        .send_json(Method::DELETE, &uri, Some(BAGGAGE_ADMIN), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app
+        .send_json(Method::GET, &uri, Some(BAGGAGE_ADMIN), None)
+        .await;
+    assert_ne!(status, StatusCode::OK);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn test_tests_do_not_share_rows() {
+    let first_db = TestDatabase::new::<Migrator>().await;
+    let second_db = TestDatabase::new::<Migrator>().await;
+    let first_app = build_app(&first_db);
+    let second_app = build_app(&second_db);
+
+    let id = create_lookup_type(&first_app, "CURRENCY").await;
+    let (status, _) = second_app
+        .send_json(
+            Method::GET,
+            &format!("/lookup-types/{}", id),
+            Some(BAGGAGE_ADMIN),
+            None,
+        )
+        .await;
+    assert_ne!(status, StatusCode::OK);
+
+    first_db.cleanup().await;
+    second_db.cleanup().await;
+}
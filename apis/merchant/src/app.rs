@@ -14,6 +14,7 @@ use shared_shared_app::{
     start_app::StartApp,
     state::AppState,
 };
+use shared_shared_audit::AuditPublisher;
 use shared_shared_config::db::Database;
 
 use features_merchant_migrations::{Migrator, MigratorTrait};
@@ -52,6 +53,7 @@ impl<'a> StartApp<MerchantAppState, MerchantCacheState> for MyApp<'a> {
             debug!("Creating Kafka producer with config {:?}", producer_config);
             let producer = Producer::from_config(producer_config).await;
             clone_app_state.set_producer(PRODUCER_KEY.to_string(), producer);
+            AuditPublisher::init_from_env(&app_key).await;
 
             spawn(async move {
                 let service_key = "MERCHANT".to_string();
@@ -3,7 +3,7 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 use features_merchant_model::api_key::{
     ApiKeyData, ApiKeyDataFilterParams, ApiKeyForCreateRequest, ApiKeyForUpdateRequest,
@@ -11,6 +11,7 @@ use features_merchant_model::api_key::{
 use features_merchant_model::state::{MerchantAppState, MerchantCacheState};
 use features_merchant_service::ApiKeyService;
 
+use shared_shared_audit::AuditContext;
 use shared_shared_auth::permission::Auth;
 
 use crate::permission::{CanCreateApiKey, CanDeleteApiKey, CanReadApiKey, CanUpdateApiKey};
@@ -40,9 +41,22 @@ const TAG: &str = "api_key";
 #[instrument(skip_all)]
 async fn create_api_key(
     _auth: Auth<CanCreateApiKey>,
+    audit: AuditContext,
     ValidJson(req): ValidJson<ApiKeyForCreateRequest>,
 ) -> Result<ResponseJson<OkI32>> {
     let id = ApiKeyService::create_api_key(req).await?;
+    let after = ApiKeyService::get_api_key_by_id(id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "api_key.create of {} audited without its new state: {:?}",
+                id, e
+            )
+        })
+        .ok();
+    audit
+        .record("api_key.create", "api_key", id, None::<&ApiKeyData>, after.as_ref())
+        .await;
     Ok(ResponseJson(OkI32 {
         ok: true,
         id: Some(id),
@@ -100,10 +114,24 @@ async fn filter_api_keys(
 #[instrument(skip_all)]
 async fn update_api_key(
     _auth: Auth<CanUpdateApiKey>,
+    audit: AuditContext,
     Path(api_key_id): Path<i32>,
     ValidJson(req): ValidJson<ApiKeyForUpdateRequest>,
 ) -> Result<ResponseJson<OkI32>> {
+    let before = ApiKeyService::get_api_key_by_id(api_key_id).await?;
     ApiKeyService::update_api_key(api_key_id, req).await?;
+    let after = ApiKeyService::get_api_key_by_id(api_key_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "api_key.update of {} audited without its new state: {:?}",
+                api_key_id, e
+            )
+        })
+        .ok();
+    audit
+        .record("api_key.update", "api_key", api_key_id, Some(&before), after.as_ref())
+        .await;
     Ok(ResponseJson(OkI32 {
         ok: true,
         id: Some(api_key_id),
@@ -119,8 +147,16 @@ async fn update_api_key(
     )
 )]
 #[instrument(skip_all)]
-async fn delete_api_key(_auth: Auth<CanDeleteApiKey>, Path(api_key_id): Path<i32>) -> Result<ResponseJson<OkI32>> {
+async fn delete_api_key(
+    _auth: Auth<CanDeleteApiKey>,
+    audit: AuditContext,
+    Path(api_key_id): Path<i32>,
+) -> Result<ResponseJson<OkI32>> {
+    let before = ApiKeyService::get_api_key_by_id(api_key_id).await?;
     ApiKeyService::delete_api_key(api_key_id).await?;
+    audit
+        .record("api_key.delete", "api_key", api_key_id, Some(&before), None::<&ApiKeyData>)
+        .await;
     Ok(ResponseJson(OkI32 {
         ok: true,
         id: Some(api_key_id),
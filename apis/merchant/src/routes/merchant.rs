@@ -3,7 +3,7 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::{instrument, Level};
+use tracing::{instrument, warn, Level};
 
 use features_merchant_model::{
     api_key::ApiKeyData,
@@ -13,6 +13,7 @@ use features_merchant_model::{
     state::{MerchantAppState, MerchantCacheState},
 };
 
+use shared_shared_audit::AuditContext;
 use shared_shared_auth::permission::Auth;
 
 use crate::permission::{
@@ -122,10 +123,24 @@ async fn get_api_keys_by_merchant(
 #[instrument(level = Level::INFO, skip_all)]
 async fn update_merchant(
     _auth: Auth<CanUpdateMerchant>,
+    audit: AuditContext,
     Path(merchant_id): Path<String>,
     ValidJson(req): ValidJson<MerchantForUpdateRequest>,
 ) -> Result<ResponseJson<OkStr>> {
+    let before = MerchantService::get_merchant_by_id(merchant_id.to_string()).await?;
     MerchantService::update_merchant(merchant_id.to_string(), req).await?;
+    let after = MerchantService::get_merchant_by_id(merchant_id.to_string())
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "merchant.update of {} audited without its new state: {:?}",
+                merchant_id, e
+            )
+        })
+        .ok();
+    audit
+        .record("merchant.update", "merchant", &merchant_id, Some(&before), after.as_ref())
+        .await;
     Ok(ResponseJson(OkStr {
         ok: true,
         id: Some(merchant_id.to_string()),
@@ -141,8 +156,16 @@ async fn update_merchant(
     )
 )]
 #[instrument(level = Level::INFO, skip_all)]
-async fn delete_merchant(_auth: Auth<CanDeleteMerchant>, Path(merchant_id): Path<String>) -> Result<ResponseJson<OkStr>> {
+async fn delete_merchant(
+    _auth: Auth<CanDeleteMerchant>,
+    audit: AuditContext,
+    Path(merchant_id): Path<String>,
+) -> Result<ResponseJson<OkStr>> {
+    let before = MerchantService::get_merchant_by_id(merchant_id.to_string()).await?;
     MerchantService::delete_merchant(merchant_id.to_string()).await?;
+    audit
+        .record("merchant.delete", "merchant", &merchant_id, Some(&before), None::<&MerchantData>)
+        .await;
     Ok(ResponseJson(OkStr {
         ok: true,
         id: Some(merchant_id.to_string()),
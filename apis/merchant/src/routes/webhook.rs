@@ -3,7 +3,7 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::instrument;
+use tracing::{instrument, warn};
 use uuid::Uuid;
 
 use features_merchant_model::state::{MerchantAppState, MerchantCacheState};
@@ -12,6 +12,7 @@ use features_merchant_model::webhook::{
 };
 use features_merchant_service::WebhookService;
 
+use shared_shared_audit::AuditContext;
 use shared_shared_auth::permission::Auth;
 
 use crate::permission::{CanCreateWebhook, CanDeleteWebhook, CanReadWebhook, CanUpdateWebhook};
@@ -41,9 +42,22 @@ const TAG: &str = "webhook";
 #[instrument(skip_all)]
 async fn create_webhook(
     _auth: Auth<CanCreateWebhook>,
+    audit: AuditContext,
     ValidJson(req): ValidJson<WebhookForCreateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
     let id = WebhookService::create_webhook(req).await?;
+    let after = WebhookService::get_webhook_by_id(id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "webhook.create of {} audited without its new state: {:?}",
+                id, e
+            )
+        })
+        .ok();
+    audit
+        .record("webhook.create", "webhook", id, None::<&WebhookData>, after.as_ref())
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(id),
@@ -100,10 +114,24 @@ async fn filter_webhooks(
 #[instrument(skip_all)]
 async fn update_webhook(
     _auth: Auth<CanUpdateWebhook>,
+    audit: AuditContext,
     Path(webhook_id): Path<Uuid>,
     ValidJson(req): ValidJson<WebhookForUpdateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = WebhookService::get_webhook_by_id(webhook_id).await?;
     WebhookService::update_webhook(webhook_id, req).await?;
+    let after = WebhookService::get_webhook_by_id(webhook_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "webhook.update of {} audited without its new state: {:?}",
+                webhook_id, e
+            )
+        })
+        .ok();
+    audit
+        .record("webhook.update", "webhook", webhook_id, Some(&before), after.as_ref())
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(webhook_id),
@@ -119,8 +147,16 @@ async fn update_webhook(
     )
 )]
 #[instrument(skip_all)]
-async fn delete_webhook(_auth: Auth<CanDeleteWebhook>, Path(webhook_id): Path<Uuid>) -> Result<ResponseJson<OkUuid>> {
+async fn delete_webhook(
+    _auth: Auth<CanDeleteWebhook>,
+    audit: AuditContext,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<ResponseJson<OkUuid>> {
+    let before = WebhookService::get_webhook_by_id(webhook_id).await?;
     WebhookService::delete_webhook(webhook_id).await?;
+    audit
+        .record("webhook.delete", "webhook", webhook_id, Some(&before), None::<&WebhookData>)
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(webhook_id),
@@ -10,8 +10,10 @@ use utoipa_swagger_ui::SwaggerUi;
 use shared_shared_app::{
     config::AppConfig,
     discovery::get_consul_client,
+    dlq::{routes::routes as dlq_routes, DlqAdmin},
     event_task::{
         consumer::{consumer_task, ConsumerConfig},
+        outbox::{relay_task, OutboxConfig},
         producer::{Producer, ProducerConfig},
     },
     start_app::StartApp,
@@ -25,6 +27,7 @@ use features_payments_core_model::state::{PaymentsCoreAppState, PaymentsCoreCach
 use crate::{
     consumers::event_consumer::handler::handle_event_consumer_message,
     doc::ApiDoc,
+    permission::{CanReadDlq, CanUpdateDlq},
     routes::{
         payment::routes as payment_routes, payment_attempt::routes as payment_attempt_routes,
         payment_method::routes as payment_method_routes,
@@ -69,7 +72,14 @@ impl<'a> StartApp<PaymentsCoreAppState, PaymentsCoreCacheState> for MyApp<'a> {
                 format!("{}_KAFKA_TOPIC", producer_app_key),
             );
             let producer = Producer::from_config(producer_config).await;
+            // Publishes the events stored in the outbox, e.g. payment succeeded
+            spawn(relay_task(
+                producer_app_state.write_db.clone(),
+                producer.clone(),
+                OutboxConfig::from_env(),
+            ));
             producer_app_state.set_producer(features_payments_core_stream::PRODUCER_KEY.to_string(), producer);
+            DlqAdmin::start(&producer_app_key, &mut producer_app_state).await;
 
             let dlq_producer = Producer::from_config(ProducerConfig::from_env(
                 "DLQ_KAFKA_BOOTSTRAP_SERVERS".to_string(),
@@ -134,6 +144,7 @@ impl<'a> StartApp<PaymentsCoreAppState, PaymentsCoreCacheState> for MyApp<'a> {
             .merge(payment_attempt_routes(app_state))
             .merge(payment_method_routes(app_state))
             .merge(payment_method_limit_routes(app_state))
+            .merge(dlq_routes::<_, _, CanReadDlq, CanUpdateDlq>(app_state))
             .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()));
         all_routes
     }
@@ -31,9 +31,15 @@ use shared_shared_app::doc::JwtSecurityAddon;
         crate::routes::payment_method_limit::filter_payment_method_limits,
         crate::routes::payment_method_limit::update_payment_method_limit,
         crate::routes::payment_method_limit::delete_payment_method_limit,
+        shared_shared_app::dlq::routes::filter_dlq_messages,
+        shared_shared_app::dlq::routes::get_dlq_message,
+        shared_shared_app::dlq::routes::update_dlq_message,
+        shared_shared_app::dlq::routes::replay_dlq_message,
+        shared_shared_app::dlq::routes::discard_dlq_message,
     ),
     tags(
         (name = "payment-core", description = "Payment Core management endpoints"),
+        (name = "dlq", description = "Dead-lettered message endpoints"),
     ),
     modifiers(&JwtSecurityAddon),
 )]
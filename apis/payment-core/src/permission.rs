@@ -43,3 +43,11 @@ define_resource_perms! {
     CanUpdateMethodLimit => (UPDATE, METHOD_LIMIT_RESOURCE),
     CanDeleteMethodLimit => (DELETE, METHOD_LIMIT_RESOURCE)
 }
+
+// DLQ Permission
+const DLQ_RESOURCE: &str = "PAYMENT_CORE:DLQ";
+
+define_resource_perms! {
+    CanReadDlq => (READ, DLQ_RESOURCE),
+    CanUpdateDlq => (UPDATE, DLQ_RESOURCE)
+}
@@ -3,7 +3,7 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::{debug, instrument, Level};
+use tracing::{instrument, Level};
 use uuid::Uuid;
 
 use shared_shared_auth::permission::Auth;
@@ -17,10 +17,7 @@ use features_payments_core_model::{
     state::{PaymentsCoreAppState, PaymentsCoreCacheState},
 };
 
-use shared_shared_app::{
-    event_task::producer::ProducerMessage,
-    state::AppState,
-};
+use shared_shared_app::state::AppState;
 use shared_shared_data_app::{
     filter_param::FilterParams,
     json::{ResponseJson, ValidJson},
@@ -32,9 +29,7 @@ use shared_shared_data_core::{
 };
 
 use features_payments_core_service::PaymentService;
-use features_payments_core_stream::{
-    PaymentCoreEventMessage, PaymentSucceededMessage, PRODUCER_KEY,
-};
+use features_payments_core_stream::PRODUCER_KEY;
 
 const TAG: &str = "payment";
 
@@ -114,36 +109,10 @@ pub async fn update_payment(
     Path(payment_id): Path<Uuid>,
     ValidJson(req): ValidJson<PaymentForUpdateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
-    let is_succeeded = req.status.as_deref() == Some("succeeded");
-    PaymentService::update_payment(payment_id, req).await?;
-
-    if is_succeeded {
-        let payment = PaymentService::get_payment_by_id(payment_id).await?;
-        let wallet_id = payment
-            .metadata
-            .as_ref()
-            .and_then(|m| m.get("wallet_id"))
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse::<Uuid>().ok());
-        let producer = state
-            .get_producer(PRODUCER_KEY.to_string())
-            .expect("Producer not found");
-        let message = ProducerMessage {
-            key: None,
-            payload: PaymentCoreEventMessage::Succeeded {
-                message: PaymentSucceededMessage {
-                    payment_id,
-                    user_id: payment.user_id.unwrap_or_default(),
-                    wallet_id,
-                    amount: payment.amount.unwrap_or_default(),
-                    currency: payment.currency.unwrap_or_default(),
-                },
-            },
-        };
-        if let Err(e) = producer.send(&message).await {
-            debug!("Error sending payment success event to Kafka: {:?}", e.reason);
-        }
-    }
+    let producer = state
+        .get_producer(PRODUCER_KEY.to_string())
+        .expect("Producer not found");
+    PaymentService::update_payment_with_event(payment_id, req, producer.topic()).await?;
 
     Ok(ResponseJson(OkUuid {
         ok: true,
@@ -7,7 +7,7 @@ use tracing::{debug, instrument, Level};
 use uuid::Uuid;
 
 use shared_shared_app::state::AppState;
-use shared_shared_auth::permission::Auth;
+use shared_shared_auth::{permission::Auth, policy::PolicySubject};
 use shared_shared_data_app::json::{ResponseJson, ValidJson};
 use shared_shared_data_app::result::{OkUuid, OkUuidResponse, Result};
 use shared_shared_data_core::{
@@ -73,15 +73,16 @@ pub async fn get_api_keys(
     tag = TAG,
     responses(
         (status = 200, description = "API key deleted", body = OkUuidResponse),
+        (status = 404, description = "API key not found or not owned by the caller"),
     )
 )]
 #[instrument(level = Level::INFO, skip_all)]
 pub async fn delete_api_key(
-    auth: Auth<CanDeleteApiKey>,
+    _auth: Auth<CanDeleteApiKey>,
+    subject: PolicySubject,
     Path(id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
-    let user_id = auth.user_id;
-    ApiKeyService::revoke_api_key(id, user_id).await?;
+    ApiKeyService::revoke_api_key(id, &subject).await?;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(id),
@@ -7,7 +7,7 @@ use tracing::{debug, instrument, Level};
 use uuid::Uuid;
 
 use shared_shared_app::state::AppState;
-use shared_shared_auth::permission::Auth;
+use shared_shared_auth::{permission::Auth, policy::PolicySubject};
 use shared_shared_data_app::result::{OkUuid, OkUuidResponse, Result};
 use shared_shared_data_app::{
     filter_param::FilterParams,
@@ -66,17 +66,17 @@ pub async fn create_url(
 )]
 #[instrument(level = Level::INFO, skip_all)]
 pub async fn get_urls(
-    auth: Auth<CanCreateUrl>,
+    _auth: Auth<CanCreateUrl>,
+    subject: PolicySubject,
     query_pagination: Query<Pagination>,
     query_order: Query<Order>,
     filter_params: FilterParams<ShortenedUrlDataFilterParams>,
 ) -> Result<ResponseJson<QueryResult<ShortenedUrlData>>> {
-    let user_id = auth.user_id;
     let pagination = query_pagination.0;
     let order = query_order.0;
     let filters = filter_params.0.all_filters();
     let result =
-        ShortenedUrlService::list_user_urls(&user_id, &pagination, &order, &filters).await?;
+        ShortenedUrlService::list_user_urls(&subject, &pagination, &order, &filters).await?;
     Ok(ResponseJson(result))
 }
 
@@ -86,15 +86,16 @@ pub async fn get_urls(
     tag = TAG,
     responses(
         (status = 200, description = "URL details", body = ShortenedUrlData),
+        (status = 404, description = "URL not found or not owned by the caller"),
     )
 )]
 #[instrument(level = Level::INFO, skip_all)]
 pub async fn get_url(
     _auth: Auth<CanCreateUrl>,
+    subject: PolicySubject,
     Path(id): Path<Uuid>,
 ) -> Result<ResponseJson<ShortenedUrlData>> {
-    let result =
-        features_url_shortener_repo::ShortenedUrlQuery::get_by_id(id, &Default::default()).await?;
+    let result = ShortenedUrlService::get_url(id, &subject).await?;
     Ok(ResponseJson(result))
 }
 
@@ -105,16 +106,17 @@ pub async fn get_url(
     request_body = UpdateShortenedUrlRequest,
     responses(
         (status = 200, description = "URL updated", body = OkUuidResponse),
+        (status = 404, description = "URL not found or not owned by the caller"),
     )
 )]
 #[instrument(level = Level::INFO, skip_all)]
 pub async fn update_url(
-    auth: Auth<CanUpdateUrl>,
+    _auth: Auth<CanUpdateUrl>,
+    subject: PolicySubject,
     Path(id): Path<Uuid>,
     ValidJson(req): ValidJson<UpdateShortenedUrlRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
-    let user_id = auth.user_id;
-    ShortenedUrlService::update_short_url(id, user_id, req).await?;
+    ShortenedUrlService::update_short_url(id, &subject, req).await?;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(id),
@@ -127,15 +129,16 @@ pub async fn update_url(
     tag = TAG,
     responses(
         (status = 200, description = "URL deleted", body = OkUuidResponse),
+        (status = 404, description = "URL not found or not owned by the caller"),
     )
 )]
 #[instrument(level = Level::INFO, skip_all)]
 pub async fn delete_url(
-    auth: Auth<CanDeleteUrl>,
+    _auth: Auth<CanDeleteUrl>,
+    subject: PolicySubject,
     Path(id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
-    let user_id = auth.user_id;
-    ShortenedUrlService::delete_short_url(id, user_id).await?;
+    ShortenedUrlService::delete_short_url(id, &subject).await?;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(id),
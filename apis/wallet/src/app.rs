@@ -10,22 +10,27 @@ use utoipa_swagger_ui::SwaggerUi;
 use shared_shared_app::{
     config::AppConfig,
     discovery::get_consul_client,
+    dlq::{routes::routes as dlq_routes, DlqAdmin},
     event_task::{
         consumer::{consumer_task, ConsumerConfig},
+        outbox::{relay_task, OutboxConfig},
         producer::{Producer, ProducerConfig},
     },
     start_app::StartApp,
     state::AppState,
 };
+use shared_shared_audit::AuditPublisher;
 use shared_shared_config::db::Database;
 
 use features_wallet_migrations::{Migrator, MigratorTrait};
 use features_wallet_model::state::{WalletAppState, WalletCacheState};
+use features_wallet_stream::PRODUCER_KEY;
 
 use crate::{
     consumers::payment_core_consumer::handler::handle_payment_core_message,
     doc::ApiDoc,
     middleware::idempotency_tracking_middleware,
+    permission::{CanReadDlq, CanUpdateDlq},
     routes::{
         idempotency::routes as idempotency_routes, p2p_transfer::routes as p2p_transfer_routes,
         top_up_transaction::routes as top_up_transaction_routes,
@@ -64,6 +69,23 @@ impl<'a> StartApp<WalletAppState, WalletCacheState> for MyApp<'a> {
         );
 
         async move {
+            AuditPublisher::init_from_env(&app_key).await;
+
+            // Wallet events are stored in the outbox and published by the relay
+            let producer = Producer::from_config(ProducerConfig::from_env(
+                format!("{}_KAFKA_BOOTSTRAP_SERVERS", app_key),
+                format!("{}_KAFKA_TOPIC", app_key),
+            ))
+            .await;
+            spawn(relay_task(
+                clone_app_state.write_db.clone(),
+                producer.clone(),
+                OutboxConfig::from_env(),
+            ));
+            clone_app_state.set_producer(PRODUCER_KEY.to_string(), producer);
+
+            DlqAdmin::start(&app_key, &mut clone_app_state).await;
+
             // Spawn payment-core consumer
             let dlq_producer = Producer::from_config(ProducerConfig::from_env(
                 "DLQ_KAFKA_BOOTSTRAP_SERVERS".to_string(),
@@ -131,6 +153,7 @@ impl<'a> StartApp<WalletAppState, WalletCacheState> for MyApp<'a> {
             .merge(p2p_transfer_routes(app_state))
             .merge(withdrawal_routes(app_state))
             .merge(idempotency_routes(app_state))
+            .merge(dlq_routes::<_, _, CanReadDlq, CanUpdateDlq>(app_state))
             .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
             .layer(from_fn(idempotency_tracking_middleware));
 
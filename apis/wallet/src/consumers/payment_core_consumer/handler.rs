@@ -15,23 +15,25 @@ use features_wallet_model::{
     transaction::TransactionForCreateRequest,
 };
 use features_wallet_service::{TransactionService, WalletService};
+use features_wallet_stream::PRODUCER_KEY;
 
 use features_wallet_entities::transaction::Column as TxColumn;
 
 pub async fn handle_payment_core_message(
     message: PaymentCoreEventMessage,
-    _state: AppState<WalletAppState, WalletCacheState>,
+    state: AppState<WalletAppState, WalletCacheState>,
     _headers: Option<HashMap<String, String>>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     match message {
         PaymentCoreEventMessage::Succeeded { message } => {
-            handle_payment_succeeded(message).await
+            handle_payment_succeeded(message, state).await
         }
     }
 }
 
 async fn handle_payment_succeeded(
     msg: PaymentSucceededMessage,
+    state: AppState<WalletAppState, WalletCacheState>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let wallet_id = match msg.wallet_id {
         Some(id) => id,
@@ -52,7 +54,10 @@ async fn handle_payment_succeeded(
     })]
     .into();
     let existing = TransactionService::get_transactions(
-        &Pagination { page: Some(1), page_size: Some(1) },
+        &Pagination {
+            with_total: Some(false),
+            ..Pagination::new(1, 1)
+        },
         &Order::default(),
         &filters,
     )
@@ -62,14 +67,8 @@ async fn handle_payment_succeeded(
         return Ok(());
     }
 
-    // Credit wallet
+    // Credit wallet and create DEPOSIT transaction record, in one database transaction
     let amount = msg.amount as f32;
-    WalletService::credit_wallet(wallet_id, amount).await.map_err(|e| {
-        error!("Failed to credit wallet {}: {:?}", wallet_id, e);
-        Box::new(e) as Box<dyn std::error::Error + Send + Sync>
-    })?;
-
-    // Create DEPOSIT transaction record
     let tx_request = TransactionForCreateRequest {
         wallet_id,
         transaction_type: "DEPOSIT".to_string(),
@@ -78,8 +77,11 @@ async fn handle_payment_succeeded(
         reference_id: Some(reference_id.clone()),
         description: Some(format!("Payment credit from payment_id={}", msg.payment_id)),
     };
-    TransactionService::create_transaction(tx_request).await.map_err(|e| {
-        error!("Failed to create transaction for reference_id={}: {:?}", reference_id, e);
+    let producer = state
+        .get_producer(PRODUCER_KEY.to_string())
+        .expect("Producer not found");
+    WalletService::deposit(tx_request, producer.topic()).await.map_err(|e| {
+        error!("Failed to credit wallet {} for reference_id={}: {:?}", wallet_id, reference_id, e);
         Box::new(e) as Box<dyn std::error::Error + Send + Sync>
     })?;
 
@@ -45,11 +45,17 @@ use shared_shared_app::doc::JwtSecurityAddon;
         crate::routes::idempotency::update_idempotency_key,
         crate::routes::idempotency::delete_idempotency_key,
         crate::routes::idempotency::get_idempotency_key_by_key,
+        shared_shared_app::dlq::routes::filter_dlq_messages,
+        shared_shared_app::dlq::routes::get_dlq_message,
+        shared_shared_app::dlq::routes::update_dlq_message,
+        shared_shared_app::dlq::routes::replay_dlq_message,
+        shared_shared_app::dlq::routes::discard_dlq_message,
     ),
     tags(
         (name = "wallet", description = "Wallet management endpoints"),
         (name = "transaction", description = "Transaction management endpoints"),
         (name = "top_up", description = "Top-up transaction endpoints"),
+        (name = "dlq", description = "Dead-lettered message endpoints"),
     ),
     modifiers(&JwtSecurityAddon),
 )]
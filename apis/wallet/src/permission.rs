@@ -55,3 +55,11 @@ define_resource_perms! {
     CanReadIdempotency => (READ, IDEMPOTENCY_RESOURCE),
     CanUpdateIdempotency => (UPDATE, IDEMPOTENCY_RESOURCE)
 }
+
+// DLQ Permission
+const DLQ_RESOURCE: &str = "WALLET:DLQ";
+
+define_resource_perms! {
+    CanReadDlq => (READ, DLQ_RESOURCE),
+    CanUpdateDlq => (UPDATE, DLQ_RESOURCE)
+}
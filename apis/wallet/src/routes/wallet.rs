@@ -3,7 +3,7 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::{instrument, Level};
+use tracing::{instrument, warn, Level};
 use uuid::Uuid;
 
 use features_wallet_model::{
@@ -13,6 +13,7 @@ use features_wallet_model::{
 };
 
 use shared_shared_app::state::AppState;
+use shared_shared_audit::AuditContext;
 use shared_shared_data_app::{
     filter_param::FilterParams,
     json::{ResponseJson, ValidJson},
@@ -124,10 +125,30 @@ async fn filter_wallets(
 #[instrument(level = Level::INFO, skip_all)]
 async fn update_wallet(
     _auth: Auth<CanUpdateWallet>,
+    audit: AuditContext,
     Path(wallet_id): Path<Uuid>,
     ValidJson(req): ValidJson<WalletForUpdateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = WalletService::get_wallet_by_id(wallet_id).await?;
     WalletService::update_wallet(wallet_id, req).await?;
+    let after = WalletService::get_wallet_by_id(wallet_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "wallet.update of {} audited without its new state: {:?}",
+                wallet_id, e
+            )
+        })
+        .ok();
+    audit
+        .record(
+            "wallet.update",
+            "wallet",
+            wallet_id,
+            Some(&before),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(wallet_id),
@@ -145,9 +166,20 @@ async fn update_wallet(
 #[instrument(level = Level::INFO, skip_all)]
 async fn delete_wallet(
     _auth: Auth<CanDeleteWallet>,
+    audit: AuditContext,
     Path(wallet_id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = WalletService::get_wallet_by_id(wallet_id).await?;
     WalletService::delete_wallet(wallet_id).await?;
+    audit
+        .record(
+            "wallet.delete",
+            "wallet",
+            wallet_id,
+            Some(&before),
+            None::<&WalletData>,
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(wallet_id),
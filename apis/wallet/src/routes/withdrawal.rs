@@ -4,10 +4,11 @@ use axum::{
     routing::{delete, get, patch, post},
     Router,
 };
-use tracing::{debug, instrument, Level};
+use tracing::{debug, instrument, warn, Level};
 use uuid::Uuid;
 
 use shared_shared_app::state::AppState;
+use shared_shared_audit::AuditContext;
 use shared_shared_data_app::{
     filter_param::FilterParams,
     json::{ResponseJson, ValidJson},
@@ -46,6 +47,7 @@ const TAG: &str = "withdrawal";
 #[instrument(level = Level::INFO, skip_all)]
 async fn create_withdrawal(
     _auth: Auth<CanCreateWithdrawal>,
+    audit: AuditContext,
     idempotency_key: IdempotencyKey,
     Path(wallet_id): Path<Uuid>,
     ValidJson(mut req): ValidJson<WithdrawalForCreateRequest>,
@@ -57,6 +59,24 @@ async fn create_withdrawal(
 
     req.wallet_id = wallet_id;
     let withdrawal_id = WithdrawalService::create_withdrawal(req).await?;
+    let after = WithdrawalService::get_withdrawal_by_id(withdrawal_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "withdrawal.create of {} audited without its new state: {:?}",
+                withdrawal_id, e
+            )
+        })
+        .ok();
+    audit
+        .record(
+            "withdrawal.create",
+            "withdrawal",
+            withdrawal_id,
+            None::<&WithdrawalData>,
+            after.as_ref(),
+        )
+        .await;
 
     Ok(ResponseJson(OkUuid {
         ok: true,
@@ -138,11 +158,31 @@ async fn get_wallet_withdrawals(
 #[instrument(level = Level::INFO, skip_all)]
 async fn update_withdrawal(
     _auth: Auth<CanUpdateWithdrawal>,
+    audit: AuditContext,
     _idempotency_key: IdempotencyKey,
     Path(withdrawal_id): Path<Uuid>,
     ValidJson(req): ValidJson<WithdrawalForUpdateRequest>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = WithdrawalService::get_withdrawal_by_id(withdrawal_id).await?;
     WithdrawalService::update_withdrawal(withdrawal_id, req).await?;
+    let after = WithdrawalService::get_withdrawal_by_id(withdrawal_id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "withdrawal.update of {} audited without its new state: {:?}",
+                withdrawal_id, e
+            )
+        })
+        .ok();
+    audit
+        .record(
+            "withdrawal.update",
+            "withdrawal",
+            withdrawal_id,
+            Some(&before),
+            after.as_ref(),
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(withdrawal_id),
@@ -160,9 +200,20 @@ async fn update_withdrawal(
 #[instrument(level = Level::INFO, skip_all)]
 async fn delete_withdrawal(
     _auth: Auth<CanCreateWithdrawal>,
+    audit: AuditContext,
     Path(withdrawal_id): Path<Uuid>,
 ) -> Result<ResponseJson<OkUuid>> {
+    let before = WithdrawalService::get_withdrawal_by_id(withdrawal_id).await?;
     WithdrawalService::delete_withdrawal(withdrawal_id).await?;
+    audit
+        .record(
+            "withdrawal.delete",
+            "withdrawal",
+            withdrawal_id,
+            Some(&before),
+            None::<&WithdrawalData>,
+        )
+        .await;
     Ok(ResponseJson(OkUuid {
         ok: true,
         id: Some(withdrawal_id),
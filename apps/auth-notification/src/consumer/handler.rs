@@ -3,7 +3,9 @@ use tracing::{debug, error};
 
 use features_auth_model::state::AuthAppState;
 use features_auth_remote::ActiveCodeRemoteService;
-use features_auth_stream::{password::PasswordMessage, signup::SignUpMessage, AuthMessage};
+use features_auth_stream::{
+    password::PasswordMessage, signin::SignInMessage, signup::SignUpMessage, AuthMessage,
+};
 use features_email_template_remote::{
     EmailTemplateService, TemplatePlaceholderService, TemplateTranslationService,
 };
@@ -19,7 +21,7 @@ pub async fn handle_consumer_message(
     let result = match message {
         AuthMessage::SignIn { message } => {
             debug!("Handling sign-in message: {:?}", message);
-            Ok(())
+            handle_signin_message(message).await
         }
         AuthMessage::SignUp { message } => {
             debug!("Handling sign-up message");
@@ -70,30 +72,136 @@ async fn handle_signup_message<'a>(
                 return Ok(());
             }
 
-            // 1. Fetch email template
-            let key = format!("{}_ACTIVE_CODE", app_key);
-            let template =
-                EmailTemplateService::get_email_template_by_key(key.clone())
-                    .await
-                    .map_err(|e| {
-                        error!("Failed to fetch email template '{}': {}", key, e);
-                        ConsumerError::NotFound {
-                            message: format!("Email template '{}': {}", key, e),
-                        }
-                    })?;
-
-            let template_id = template.get_id().ok_or_else(|| {
-                error!("Email template '{}' has no ID", key);
-                ConsumerError::NotFound {
-                    message: format!("Email template '{}' has no ID", key),
-                }
-            })?;
-
-            // 2. Fetch translation for user's language
-            let translation = TemplateTranslationService::get_template_translations(
-                template_id,
-                language_code.clone(),
+            let values = HashMap::from([("ACTIVE_CODE".to_string(), active_code.clone())]);
+            send_template_email(
+                format!("{}_ACTIVE_CODE", app_key),
+                language_code,
+                client_email,
+                email.clone(),
+                values,
             )
+            .await?;
+
+            debug!("Activation email sent successfully to {}", email);
+        }
+    }
+
+    Ok(())
+}
+
+/// Email of a sign-in message, None for the ones only logged.
+#[derive(Debug, PartialEq)]
+struct SignInEmail {
+    template_key: String,
+    language_code: String,
+    from: String,
+    to: String,
+    values: HashMap<String, String>,
+}
+
+fn signin_email(message: SignInMessage) -> Option<SignInEmail> {
+    match message {
+        SignInMessage::NewDevice {
+            email,
+            user_agent,
+            ip_address,
+            app_key,
+            language_code,
+            client_email,
+            ..
+        } => Some(SignInEmail {
+            template_key: format!("{}_NEW_DEVICE", app_key),
+            language_code,
+            from: client_email,
+            to: email,
+            values: HashMap::from([
+                ("USER_AGENT".to_string(), user_agent),
+                ("IP_ADDRESS".to_string(), ip_address),
+            ]),
+        }),
+        SignInMessage::LinkRequest {
+            email,
+            provider,
+            identity_id,
+            link_code,
+            app_key,
+            language_code,
+            client_email,
+            ..
+        } => Some(SignInEmail {
+            template_key: format!("{}_LINK_CODE", app_key),
+            language_code,
+            from: client_email,
+            to: email,
+            values: HashMap::from([
+                ("PROVIDER".to_string(), provider),
+                ("IDENTITY_ID".to_string(), identity_id),
+                ("LINK_CODE".to_string(), link_code),
+            ]),
+        }),
+        _ => None,
+    }
+}
+
+#[tracing::instrument(name = "handle_signin_message", skip(message))]
+async fn handle_signin_message(
+    message: SignInMessage,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(signin_email) = signin_email(message) else {
+        return Ok(());
+    };
+    let to = signin_email.to.clone();
+    send_template_email(
+        signin_email.template_key,
+        signin_email.language_code,
+        signin_email.from,
+        signin_email.to,
+        signin_email.values,
+    )
+    .await?;
+    debug!("Sign-in email sent successfully to {}", to);
+    Ok(())
+}
+
+/// Placeholders of a template with their example values, overridden by `values`.
+fn placeholder_values(
+    examples: impl IntoIterator<Item = (String, String)>,
+    values: HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut placeholders: HashMap<String, String> = examples.into_iter().collect();
+    placeholders.extend(values);
+    placeholders
+}
+
+/// Sends the translation of the template `template_key` in `language_code`, with `values`
+/// for its placeholders.
+async fn send_template_email(
+    template_key: String,
+    language_code: String,
+    from: String,
+    to: String,
+    values: HashMap<String, String>,
+) -> Result<(), ConsumerError> {
+    // 1. Fetch email template
+    let template = EmailTemplateService::get_email_template_by_key(template_key.clone())
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch email template '{}': {}", template_key, e);
+            ConsumerError::NotFound {
+                message: format!("Email template '{}': {}", template_key, e),
+            }
+        })?;
+
+    let template_id = template.get_id().ok_or_else(|| {
+        error!("Email template '{}' has no ID", template_key);
+        ConsumerError::NotFound {
+            message: format!("Email template '{}' has no ID", template_key),
+        }
+    })?;
+
+    // 2. Fetch translation for user's language
+    let translation =
+        TemplateTranslationService::get_template_translations(template_id, language_code.clone())
             .await
             .map_err(|e| {
                 error!(
@@ -101,54 +209,51 @@ async fn handle_signup_message<'a>(
                     template_id, language_code, e
                 );
                 ConsumerError::NotFound {
-                    message: format!("Translation (template={}, lang={}): {}", template_id, language_code, e),
+                    message: format!(
+                        "Translation (template={}, lang={}): {}",
+                        template_id, language_code, e
+                    ),
                 }
             })?;
 
-            // 3. Fetch placeholders
-            let placeholders =
-                TemplatePlaceholderService::get_template_holder_by_template_id(template_id)
-                    .await
-                    .map_err(|e| {
-                        error!(
-                            "Failed to fetch placeholders for template_id={}: {}",
-                            template_id, e
-                        );
-                        ConsumerError::NotFound {
-                            message: format!("Placeholders (template={}): {}", template_id, e),
-                        }
-                    })?;
-
-            // 4. Build placeholder map
-            let mut placeholder_maps: HashMap<String, String> = HashMap::new();
-            for placeholder in placeholders.iter() {
-                placeholder_maps.insert(
-                    placeholder.get_placeholder_key(),
-                    placeholder.get_example_value(),
-                );
+    // 3. Fetch placeholders
+    let placeholders = TemplatePlaceholderService::get_template_holder_by_template_id(template_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to fetch placeholders for template_id={}: {}",
+                template_id, e
+            );
+            ConsumerError::NotFound {
+                message: format!("Placeholders (template={}): {}", template_id, e),
             }
-            placeholder_maps.insert("ACTIVE_CODE".to_string(), active_code.clone());
+        })?;
 
-            // 5. Send email
-            let send_mail = SendMail::new(
-                client_email,
-                email.clone(),
-                translation.get_subject(),
-                translation.get_body(),
-                Some(placeholder_maps),
-            );
-            send_email(&send_mail).await.map_err(|e| {
-                error!("Failed to send activation email to {}: {}", email, e);
-                ConsumerError::SendEmailError {
-                    message: format!("To {}: {}", email, e),
-                }
-            })?;
+    // 4. Build placeholder map
+    let placeholder_maps = placeholder_values(
+        placeholders.iter().map(|placeholder| {
+            (
+                placeholder.get_placeholder_key(),
+                placeholder.get_example_value(),
+            )
+        }),
+        values,
+    );
 
-            debug!("Activation email sent successfully to {}", email);
+    // 5. Send email
+    let send_mail = SendMail::new(
+        from,
+        to.clone(),
+        translation.get_subject(),
+        translation.get_body(),
+        Some(placeholder_maps),
+    );
+    send_email(&send_mail).await.map_err(|e| {
+        error!("Failed to send email to {}: {}", to, e);
+        ConsumerError::SendEmailError {
+            message: format!("To {}: {}", to, e),
         }
-    }
-
-    Ok(())
+    })
 }
 
 #[tracing::instrument(name = "handle_password_message", skip(message))]
@@ -191,3 +296,76 @@ async fn handle_password_message(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_device() -> SignInMessage {
+        SignInMessage::NewDevice {
+            user_id: "user".to_string(),
+            email: "user@example.com".to_string(),
+            client_id: "client".to_string(),
+            user_agent: "Firefox".to_string(),
+            ip_address: "203.0.113.7".to_string(),
+            app_key: "SHOP".to_string(),
+            language_code: "en-US".to_string(),
+            client_email: "no-reply@example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_new_device_email() {
+        let email = signin_email(new_device()).unwrap();
+
+        assert_eq!(email.template_key, "SHOP_NEW_DEVICE");
+        assert_eq!(email.language_code, "en-US");
+        assert_eq!(email.from, "no-reply@example.com");
+        assert_eq!(email.to, "user@example.com");
+        assert_eq!(email.values["USER_AGENT"], "Firefox");
+        assert_eq!(email.values["IP_ADDRESS"], "203.0.113.7");
+    }
+
+    #[test]
+    fn test_link_request_email() {
+        let message = SignInMessage::LinkRequest {
+            user_id: "user".to_string(),
+            email: "user@example.com".to_string(),
+            provider: "google".to_string(),
+            identity_id: "identity".to_string(),
+            link_code: "123456".to_string(),
+            app_key: "SHOP".to_string(),
+            language_code: "en-US".to_string(),
+            client_email: "no-reply@example.com".to_string(),
+        };
+        let email = signin_email(message).unwrap();
+
+        assert_eq!(email.template_key, "SHOP_LINK_CODE");
+        assert_eq!(email.to, "user@example.com");
+        assert_eq!(email.values["LINK_CODE"], "123456");
+        assert_eq!(email.values["IDENTITY_ID"], "identity");
+    }
+
+    #[test]
+    fn test_other_signin_messages_send_no_email() {
+        let message = SignInMessage::Success {
+            user_id: "user".to_string(),
+            ip_address: "203.0.113.7".to_string(),
+        };
+        assert_eq!(signin_email(message), None);
+    }
+
+    #[test]
+    fn test_message_values_override_examples() {
+        let placeholders = placeholder_values(
+            [
+                ("USER_AGENT".to_string(), "Example browser".to_string()),
+                ("APP_NAME".to_string(), "Shop".to_string()),
+            ],
+            HashMap::from([("USER_AGENT".to_string(), "Firefox".to_string())]),
+        );
+
+        assert_eq!(placeholders["USER_AGENT"], "Firefox");
+        assert_eq!(placeholders["APP_NAME"], "Shop");
+    }
+}
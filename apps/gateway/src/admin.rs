@@ -8,65 +8,326 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use serde::Deserialize;
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use tracing::{debug, error, info};
 
 use crate::{
-    config::dn_config::DnConfig,
-    gateway::state::{build_gateway_state_async, GatewayStateStore},
+    config::{
+        dn_config::DnConfig,
+        source_config::GatewayConfig,
+        validation::{validate_config, ConfigIssue},
+    },
+    gateway::{
+        interceptor::{InterceptorType, Phase},
+        registry::GatewayRegistry,
+    },
 };
 
 pub struct AdminState {
     pub dp: String,
-    pub gateway_stores: Vec<Arc<GatewayStateStore>>,
+    pub gateway_registry: Arc<GatewayRegistry>,
     pub admin_api_key: Option<String>,
 }
 
+/// Parts of interceptor config keys whose values are replaced in the config dump.
+const SECRET_KEY_PARTS: [&str; 5] = ["secret", "password", "token", "credential", "api_key"];
+
 async fn health() -> impl IntoResponse {
     Json(json!({ "status": "ok" }))
 }
 
+fn invalid_config(
+    status: StatusCode,
+    issues: Vec<ConfigIssue>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        status,
+        Json(json!({
+            "error": "invalid config",
+            "issues": issues,
+        })),
+    )
+}
+
+/// Apply config.yaml to the running gateways: gateways are matched by name, new ones are started
+/// and missing ones removed. Nothing is applied when the config is invalid.
 async fn reload(AxumState(state): AxumState<Arc<AdminState>>) -> impl IntoResponse {
     debug!("Admin reload requested");
 
-    let new_dn_config = DnConfig::load_from_path(&state.dp);
+    let new_dn_config = match DnConfig::try_load_from_path(&state.dp) {
+        Ok(dn_config) => dn_config,
+        Err(message) => {
+            error!("Reload aborted: {}", message);
+            let issue = ConfigIssue {
+                gateway: None,
+                field: String::new(),
+                message,
+            };
+            return invalid_config(StatusCode::BAD_REQUEST, vec![issue]);
+        }
+    };
 
-    if new_dn_config.gateways.len() != state.gateway_stores.len() {
+    let issues = validate_config(&new_dn_config);
+    if !issues.is_empty() {
         error!(
-            "Gateway count mismatch: config has {}, running has {}. Full restart required.",
-            new_dn_config.gateways.len(),
-            state.gateway_stores.len()
-        );
-        return (
-            StatusCode::CONFLICT,
-            Json(json!({
-                "error": "gateway count mismatch",
-                "message": format!(
-                    "config has {} gateways, running has {}. A full restart is required to add/remove gateways.",
-                    new_dn_config.gateways.len(),
-                    state.gateway_stores.len()
-                ),
-            })),
+            "Reload aborted, {} config issues: {:?}",
+            issues.len(),
+            issues
         );
+        return invalid_config(StatusCode::UNPROCESSABLE_ENTITY, issues);
     }
 
-    let mut reloaded = 0usize;
-    for (i, gateway_config) in new_dn_config.gateways.iter().enumerate() {
-        let new_state = build_gateway_state_async(gateway_config.clone()).await;
-        state.gateway_stores[i].update_state(new_state).await;
-        reloaded += 1;
-        info!("Reloaded gateway config: {}", gateway_config.name);
-    }
+    let report = match state.gateway_registry.apply(&new_dn_config).await {
+        Ok(report) => report,
+        Err(issue) => {
+            error!("Reload aborted, gateway state not built: {:?}", issue);
+            return invalid_config(StatusCode::UNPROCESSABLE_ENTITY, vec![issue]);
+        }
+    };
+    info!("Reloaded gateway config: {:?}", report);
 
     (
         StatusCode::OK,
         Json(json!({
             "status": "reloaded",
-            "gateways": reloaded,
+            "gateways": report,
         })),
     )
 }
 
+/// Dry-run validation of the config.yaml sent as body, or of the one on disk when the body is
+/// empty. Nothing is applied.
+async fn validate(AxumState(state): AxumState<Arc<AdminState>>, body: String) -> impl IntoResponse {
+    let (source, parsed) = if body.trim().is_empty() {
+        let source = DnConfig::config_path(&state.dp).display().to_string();
+        (source, DnConfig::try_load_from_path(&state.dp))
+    } else {
+        ("request".to_string(), DnConfig::parse(&body))
+    };
+
+    let issues = match parsed {
+        Ok(dn_config) => validate_config(&dn_config),
+        Err(message) => vec![ConfigIssue {
+            gateway: None,
+            field: String::new(),
+            message,
+        }],
+    };
+
+    Json(json!({
+        "source": source,
+        "valid": issues.is_empty(),
+        "issues": issues,
+    }))
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+/// Config of a running gateway with secret interceptor values and URL passwords masked.
+fn redacted_gateway_config(gateway_config: &GatewayConfig) -> serde_json::Value {
+    let mut gateway_config = gateway_config.clone();
+    for interceptor_config in &mut gateway_config.interceptors {
+        for (key, value) in interceptor_config.config.iter_mut().flatten() {
+            if is_secret_key(key) {
+                *value = "***".to_string();
+            } else if let Ok(mut url) = url::Url::parse(value) {
+                if url.password().is_some() && url.set_password(Some("***")).is_ok() {
+                    *value = url.to_string();
+                }
+            }
+        }
+    }
+    serde_json::to_value(&gateway_config).unwrap_or_default()
+}
+
+/// Effective config of every running gateway, as applied by the last reload.
+async fn config(AxumState(state): AxumState<Arc<AdminState>>) -> impl IntoResponse {
+    let gateways: Vec<serde_json::Value> = state
+        .gateway_registry
+        .stores()
+        .iter()
+        .map(|gateway_store| redacted_gateway_config(gateway_store.get_state().gateway_config()))
+        .collect();
+
+    Json(json!({
+        "dp": state.dp,
+        "gateways": gateways,
+    }))
+}
+
+/// Enabled interceptors per filter and phase, in execution order.
+async fn interceptors(AxumState(state): AxumState<Arc<AdminState>>) -> impl IntoResponse {
+    let gateways: Vec<serde_json::Value> = state
+        .gateway_registry
+        .stores()
+        .iter()
+        .map(|gateway_store| {
+            let gateway_state = gateway_store.get_state();
+            let gateway_config = gateway_state.gateway_config();
+            let interceptors = gateway_state.interceptors();
+
+            let filters: Vec<serde_json::Value> = gateway_config
+                .filters
+                .iter()
+                .map(|filter| {
+                    let phases: Vec<serde_json::Value> = Phase::all()
+                        .iter()
+                        .filter_map(|phase| {
+                            let names: Vec<serde_json::Value> = interceptors
+                                .iter()
+                                .filter(|named| {
+                                    named.interceptor.filter().as_ref() == Some(&filter.name)
+                                        && named.interceptor.phase_mask() & phase.mask() != 0
+                                })
+                                .map(|named| {
+                                    json!({
+                                        "name": named.name,
+                                        "type": named.interceptor.interceptor_type(),
+                                    })
+                                })
+                                .collect();
+                            (!names.is_empty()).then(
+                                || json!({ "phase": phase.to_string(), "interceptors": names }),
+                            )
+                        })
+                        .collect();
+                    json!({ "name": filter.name, "phases": phases })
+                })
+                .collect();
+
+            // Interceptors without a known filter never run
+            let unattached: Vec<&String> = interceptors
+                .iter()
+                .filter(|named| {
+                    named.interceptor.filter().as_ref().map_or(true, |name| {
+                        !gateway_config
+                            .filters
+                            .iter()
+                            .any(|filter| filter.name == *name)
+                    })
+                })
+                .map(|named| &named.name)
+                .collect();
+
+            json!({
+                "name": gateway_config.name,
+                "filters": filters,
+                "unattached": unattached,
+            })
+        })
+        .collect();
+
+    Json(json!({ "gateways": gateways }))
+}
+
+/// Circuit breaker state of every upstream node, per gateway.
+async fn circuit_breakers(AxumState(state): AxumState<Arc<AdminState>>) -> impl IntoResponse {
+    let gateways: Vec<serde_json::Value> = state
+        .gateway_registry
+        .stores()
+        .iter()
+        .map(|gateway_store| {
+            let gateway_state = gateway_store.get_state();
+            let upstreams: Vec<serde_json::Value> = gateway_state
+                .upstream_load_balancers()
+                .iter()
+                .filter(|us_balance| !us_balance.circuit_breakers.is_empty())
+                .map(|us_balance| {
+                    let mut nodes: Vec<_> = us_balance
+                        .circuit_breakers
+                        .values()
+                        .map(|circuit_breaker| circuit_breaker.snapshot())
+                        .collect();
+                    nodes.sort_by(|a, b| a.node.cmp(&b.node));
+                    json!({ "name": us_balance.name, "nodes": nodes })
+                })
+                .collect();
+            json!({
+                "name": gateway_state.gateway_config().name,
+                "upstreams": upstreams,
+            })
+        })
+        .collect();
+
+    Json(json!({ "gateways": gateways }))
+}
+
+#[derive(Debug, Deserialize)]
+struct PurgeRequest {
+    /// Path prefix, as sent upstream
+    prefix: String,
+    /// Only purge the cache of this filter
+    filter: Option<String>,
+}
+
+/// Remove cached responses by path prefix from every `response_cache` interceptor.
+async fn purge_cache(
+    AxumState(state): AxumState<Arc<AdminState>>,
+    Json(request): Json<PurgeRequest>,
+) -> impl IntoResponse {
+    debug!("Admin cache purge requested: {:?}", request);
+
+    let mut removed = 0usize;
+    for gateway_store in state.gateway_registry.stores() {
+        let gateway_state = gateway_store.get_state();
+        let caches = gateway_state
+            .interceptors()
+            .iter()
+            .map(|named| &named.interceptor)
+            .filter(|interceptor| {
+                interceptor.interceptor_type() == InterceptorType::ResponseCache
+                    && request
+                        .filter
+                        .as_ref()
+                        .map_or(true, |filter| interceptor.filter().as_ref() == Some(filter))
+            });
+        for cache in caches {
+            match cache.purge(&request.prefix).await {
+                Ok(count) => removed += count,
+                Err(e) => {
+                    error!("Failed to purge response cache: {}", e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({
+                            "error": "purge failed",
+                            "message": e.to_string(),
+                        })),
+                    );
+                }
+            }
+        }
+    }
+
+    info!(
+        "Purged {} cached responses for prefix {}",
+        removed, request.prefix
+    );
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "purged",
+            "removed": removed,
+        })),
+    )
+}
+
+/// Compare SHA-256 digests so the time taken depends neither on where the keys differ nor on
+/// their length.
+fn keys_match(provided: &str, expected: &str) -> bool {
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    provided
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
 /// Middleware: verify X-Admin-Key header against the configured admin API key.
 /// Skips check if no key is configured (open mode).
 async fn verify_admin_key(
@@ -82,7 +343,7 @@ async fn verify_admin_key(
             .and_then(|v| v.to_str().ok())
             .unwrap_or("");
 
-        if provided_key != expected_key {
+        if !keys_match(provided_key, expected_key) {
             return Err((
                 StatusCode::UNAUTHORIZED,
                 Json(json!({
@@ -100,6 +361,11 @@ pub fn admin_router(admin_state: Arc<AdminState>) -> Router {
     Router::new()
         .route("/admin/health", get(health))
         .route("/admin/reload", post(reload))
+        .route("/admin/config", get(config))
+        .route("/admin/config/validate", post(validate))
+        .route("/admin/interceptors", get(interceptors))
+        .route("/admin/circuit-breakers", get(circuit_breakers))
+        .route("/admin/cache/purge", post(purge_cache))
         .layer(axum::middleware::from_fn_with_state(
             admin_state.clone(),
             verify_admin_key,
@@ -1,7 +1,10 @@
 use config::{Config, File, FileFormat};
 use pingora::{prelude::Opt, server::configuration::ServerConf};
 use serde::Deserialize;
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use tracing::debug;
 
 use super::{app_config::AppConfig, source_config::GatewayConfig};
@@ -39,32 +42,43 @@ impl DnConfig {
 
     /// Re-read and re-parse config.yaml from disk. Used by the admin reload endpoint.
     pub fn load_from_path(dp: &str) -> Self {
-        let directory_path = dp;
-        let config_path = Path::new(directory_path).join("config/config.yaml");
+        Self::try_load_from_path(dp).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as `load_from_path`, returning read and parse errors instead of panicking.
+    pub fn try_load_from_path(dp: &str) -> Result<Self, String> {
+        let config_path = Self::config_path(dp);
         debug!("Loading config from path: {:?}", config_path);
-        let raw_config = fs::read_to_string(&config_path).unwrap_or_else(|e| {
-            panic!(
+        let raw_config = fs::read_to_string(&config_path).map_err(|e| {
+            format!(
                 "Failed to read config from {:?}: {}. Set GATEWAY_DP to the directory containing config/config.yaml",
                 config_path, e
-            );
-        });
-        let config = Config::builder()
-            .add_source(File::from_str(raw_config.as_str(), FileFormat::Yaml))
-            .build();
-        let config = match config {
-            Ok(c) => c,
-            Err(e) => {
-                panic!(
-                    "Failed to load config from path {:?}, error: {}",
-                    config_path, e
-                );
-            }
-        };
-        let mut dn_config: DnConfig = config.try_deserialize().unwrap();
-        dn_config.version = 0;
+            )
+        })?;
+        let mut dn_config = Self::parse(&raw_config).map_err(|e| {
+            format!(
+                "Failed to load config from path {:?}, error: {}",
+                config_path, e
+            )
+        })?;
         dn_config.dp = dp.to_string();
         debug!("\n{:#?}", dn_config);
-        dn_config
+        Ok(dn_config)
+    }
+
+    /// Parse the content of a config.yaml.
+    pub fn parse(raw_config: &str) -> Result<Self, String> {
+        let config = Config::builder()
+            .add_source(File::from_str(raw_config, FileFormat::Yaml))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let mut dn_config: DnConfig = config.try_deserialize().map_err(|e| e.to_string())?;
+        dn_config.version = 0;
+        Ok(dn_config)
+    }
+
+    pub fn config_path(dp: &str) -> PathBuf {
+        Path::new(dp).join("config/config.yaml")
     }
 
     pub fn to_pingore_opt(&self, args: &AppConfig) -> Opt {
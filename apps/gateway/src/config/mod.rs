@@ -3,3 +3,4 @@ pub mod dn_config;
 pub mod proxy;
 
 pub mod source_config;
+pub mod validation;
@@ -1,8 +1,12 @@
+use bytes::{Bytes, BytesMut};
+use http::Extensions;
 use opentelemetry::context::Context;
 use pingora::Error;
+use std::{collections::HashMap, time::Instant};
+use tokio::sync::oneshot;
 
 use crate::config::{
-    proxy::http::HeaderBuffer,
+    proxy::http::{mirror::MirrorRequest, HeaderBuffer},
     source_config::{Filter, RouterConfig},
 };
 
@@ -10,17 +14,55 @@ use crate::config::{
 pub struct HttpGatewayCtx {
     pub span_context: Option<Context>,
     pub filter: Option<Filter>,
+    /// Parameters captured by the filter path template or regex
+    pub path_params: HashMap<String, String>,
     pub ds_res_header_buffer: HeaderBuffer,
     pub us_req_header_buffer: HeaderBuffer,
+    pub started_at: Instant,
+    /// Attempts made to reach the upstream, retries included
+    pub attempts: u32,
+    /// Router variant serving the request, selected once so retries stay on it
+    pub variant: Option<String>,
+    /// WebSocket handshake, set with the variant
+    pub upgrade: bool,
+    pub upstream: Option<String>,
+    /// Address of the node of the current attempt
+    pub upstream_node: Option<String>,
+    /// Per request state of interceptors, shared between phases
+    pub extensions: Extensions,
+    /// Replaces the upstream response body when set
+    pub ds_res_body: Option<Bytes>,
+    pub ds_res_body_capture: Option<BodyCapture>,
+    pub mirror: Option<MirrorRequest>,
+}
+
+/// Copy of the downstream response body, sent once the body is complete.
+/// Dropped without sending when the body exceeds `max_size`.
+#[derive(Debug)]
+pub struct BodyCapture {
+    pub max_size: usize,
+    pub buffer: BytesMut,
+    pub sender: oneshot::Sender<Bytes>,
 }
 
 impl HttpGatewayCtx {
     pub fn new() -> Self {
         Self {
             filter: None,
+            path_params: HashMap::new(),
             span_context: None,
             ds_res_header_buffer: HeaderBuffer::new(),
             us_req_header_buffer: HeaderBuffer::new(),
+            started_at: Instant::now(),
+            attempts: 0,
+            variant: None,
+            upgrade: false,
+            upstream: None,
+            upstream_node: None,
+            extensions: Extensions::new(),
+            ds_res_body: None,
+            ds_res_body_capture: None,
+            mirror: None,
         }
     }
 }
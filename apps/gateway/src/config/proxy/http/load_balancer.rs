@@ -5,8 +5,20 @@ use pingora::lb::{
 };
 use std::collections::{BTreeSet, HashMap};
 use std::fmt::Debug;
+use std::sync::Arc;
 
-use crate::config::source_config::{LoadBalancerAlgorithm, UpstreamConfig};
+use crate::config::{
+    proxy::http::resilience::CircuitBreaker,
+    source_config::{LoadBalancerAlgorithm, UpstreamConfig, UpstreamProtocol},
+};
+
+/// Connection settings of a node, kept in the `ext` of its backend.
+#[derive(Clone, Debug)]
+pub struct UpstreamNode {
+    pub tls: bool,
+    pub sni: String,
+    pub protocol: UpstreamProtocol,
+}
 
 pub enum LoadBalancerEnum {
     RoundRobin { lb: LoadBalancer<RoundRobin> },
@@ -26,6 +38,8 @@ impl Debug for LoadBalancerEnum {
 pub struct UpStreamLoadBalaner {
     pub name: String,
     pub load_balancer: LoadBalancerEnum,
+    /// Breaker per node address, empty when the upstream has no circuit breaker
+    pub circuit_breakers: HashMap<String, Arc<CircuitBreaker>>,
 }
 
 impl UpStreamLoadBalaner {
@@ -33,15 +47,32 @@ impl UpStreamLoadBalaner {
         let mut upstream_load_balancers: Vec<UpStreamLoadBalaner> = vec![];
         for upstream in upstreams {
             let mut backends: BTreeSet<Backend> = BTreeSet::new();
+            let mut circuit_breakers = HashMap::new();
             for upstream_node in upstream.upstream_nodes {
                 let mut back_end = Backend::new_with_weight(
                     upstream_node.address.get_formatted_address().as_str(),
                     upstream_node.weight.unwrap_or(1) as usize,
                 )
                 .unwrap();
-                let mut ext_data: HashMap<String, bool> = std::collections::HashMap::new();
-                ext_data.insert("tls".to_string(), upstream_node.tls);
-                back_end.ext.insert(ext_data);
+                back_end.ext.insert(UpstreamNode {
+                    tls: upstream_node.tls,
+                    sni: upstream_node
+                        .sni
+                        .clone()
+                        .unwrap_or_else(|| upstream_node.address.host.clone()),
+                    protocol: upstream_node.protocol,
+                });
+                if let Some(circuit_breaker) = &upstream.circuit_breaker {
+                    let node = back_end.addr.to_string();
+                    circuit_breakers.insert(
+                        node.clone(),
+                        Arc::new(CircuitBreaker::new(
+                            upstream.name.clone(),
+                            node,
+                            circuit_breaker.clone(),
+                        )),
+                    );
+                }
                 backends.insert(back_end);
             }
 
@@ -56,6 +87,7 @@ impl UpStreamLoadBalaner {
                 upstream_load_balancers.push(UpStreamLoadBalaner {
                     name: upstream.name,
                     load_balancer: LoadBalancerEnum::RoundRobin { lb },
+                    circuit_breakers,
                 });
             } else if upstream.traffic_distribution_policy == LoadBalancerAlgorithm::Random {
                 let lb = LoadBalancer::from_backends(backends);
@@ -65,6 +97,7 @@ impl UpStreamLoadBalaner {
                 upstream_load_balancers.push(UpStreamLoadBalaner {
                     name: upstream.name,
                     load_balancer: LoadBalancerEnum::Random { lb },
+                    circuit_breakers,
                 });
             }
         }
@@ -85,11 +118,19 @@ impl UpStreamLoadBalaner {
         Self::build_from_upstreams(upstreams).await
     }
 
-    pub fn get_backend(&self) -> Backend {
-        let back_end: Backend = match &self.load_balancer {
-            LoadBalancerEnum::RoundRobin { lb } => lb.select(b"", 256).unwrap(),
-            LoadBalancerEnum::Random { lb } => lb.select(b"", 256).unwrap(),
+    /// Next backend whose circuit breaker lets the request through, None when all are open.
+    pub fn get_backend(&self) -> Option<Backend> {
+        let accept = |back_end: &Backend, _healthy: bool| {
+            self.circuit_breaker(&back_end.addr.to_string())
+                .map_or(true, |circuit_breaker| circuit_breaker.allow_request())
         };
-        back_end
+        match &self.load_balancer {
+            LoadBalancerEnum::RoundRobin { lb } => lb.select_with(b"", 256, accept),
+            LoadBalancerEnum::Random { lb } => lb.select_with(b"", 256, accept),
+        }
+    }
+
+    pub fn circuit_breaker(&self, node: &str) -> Option<&Arc<CircuitBreaker>> {
+        self.circuit_breakers.get(node)
     }
 }
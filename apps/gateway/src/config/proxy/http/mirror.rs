@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use bytes::BytesMut;
+use http::{HeaderMap, Method};
+use once_cell::sync::Lazy;
+use opentelemetry::{global, KeyValue};
+use tracing::debug;
+
+use crate::config::source_config::MirrorConfig;
+
+static MIRROR_CLIENT: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
+
+/// Hop-by-hop headers, not copied to the mirrored request.
+const HOP_HEADERS: [&str; 7] = [
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Copy of an upstream request, sent to the mirror upstream once the original request completed.
+#[derive(Debug)]
+pub struct MirrorRequest {
+    pub filter: String,
+    pub upstream: String,
+    pub method: Method,
+    pub path_and_query: String,
+    pub headers: HeaderMap,
+    pub body: BytesMut,
+    pub max_body_size: usize,
+    pub timeout: Duration,
+    /// The body outgrew `max_body_size`, the request is not mirrored
+    pub truncated: bool,
+    /// The whole body was read, chunks replayed by a retry are ignored
+    pub body_complete: bool,
+}
+
+impl MirrorRequest {
+    pub fn new(
+        filter: String,
+        mirror_config: &MirrorConfig,
+        method: Method,
+        path_and_query: String,
+        headers: &HeaderMap,
+    ) -> Self {
+        let mut headers = headers.clone();
+        for header in HOP_HEADERS {
+            headers.remove(header);
+        }
+        // The body is buffered, reqwest sets the length
+        headers.remove(http::header::CONTENT_LENGTH);
+        Self {
+            filter,
+            upstream: mirror_config.upstream.clone(),
+            method,
+            path_and_query,
+            headers,
+            body: BytesMut::new(),
+            max_body_size: mirror_config.max_body_size,
+            timeout: Duration::from_millis(mirror_config.timeout),
+            truncated: false,
+            body_complete: false,
+        }
+    }
+
+    pub fn append_body(&mut self, chunk: &[u8], end_of_stream: bool) {
+        if self.truncated || self.body_complete {
+            return;
+        }
+        self.body_complete = end_of_stream;
+        if self.body.len() + chunk.len() > self.max_body_size {
+            debug!(
+                "Request body larger than {} bytes, not mirrored",
+                self.max_body_size
+            );
+            self.truncated = true;
+            self.body.clear();
+            return;
+        }
+        self.body.extend_from_slice(chunk);
+    }
+
+    /// Send the copy to `node` in the background, the response is only counted in
+    /// `gateway.router.mirror.requests`.
+    pub fn send(self, node: &str, tls: bool) {
+        if self.truncated {
+            return;
+        }
+        let scheme = if tls { "https" } else { "http" };
+        let url = format!("{}://{}{}", scheme, node, self.path_and_query);
+        let request = MIRROR_CLIENT
+            .request(self.method, url)
+            .headers(self.headers)
+            .body(self.body.freeze())
+            .timeout(self.timeout);
+        let filter = self.filter;
+        let upstream = self.upstream;
+
+        tokio::spawn(async move {
+            let outcome = match request.send().await {
+                Ok(response) => format!("{}xx", response.status().as_u16() / 100),
+                Err(e) => {
+                    debug!("Mirrored request to {} failed: {}", upstream, e);
+                    "error".to_string()
+                }
+            };
+            let meter = global::meter("gateway_router");
+            let counter = meter.u64_counter("gateway.router.mirror.requests").build();
+            counter.add(
+                1,
+                &[
+                    KeyValue::new("filter", filter),
+                    KeyValue::new("upstream", upstream),
+                    KeyValue::new("outcome", outcome),
+                ],
+            );
+        });
+    }
+}
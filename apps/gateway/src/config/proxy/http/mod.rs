@@ -1,7 +1,9 @@
 mod ctx;
 mod helpers;
 pub mod load_balancer;
+mod mirror;
 mod proxy;
+pub mod resilience;
 mod session;
 mod tracing;
 
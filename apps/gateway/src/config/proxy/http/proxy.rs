@@ -1,8 +1,9 @@
 use async_trait::async_trait;
+use bytes::Bytes;
 use opentelemetry::propagation::TextMapPropagator;
-use opentelemetry::{baggage::BaggageExt, global};
+use opentelemetry::{baggage::BaggageExt, global, KeyValue};
 use opentelemetry_sdk::propagation::BaggagePropagator;
-use pingora::{prelude::HttpPeer, upstreams::peer::Peer, Error};
+use pingora::{prelude::HttpPeer, protocols::ALPN, upstreams::peer::Peer, Error};
 use pingora_http::{RequestHeader, ResponseHeader};
 use pingora_proxy::{ProxyHttp, Session};
 use std::{collections::HashMap, sync::Arc, time::Duration};
@@ -15,7 +16,7 @@ use crate::{
             session,
             tracing::{PingoraHeaderExtractor, PingoraHeaderInjector},
         },
-        source_config::{find_filter_config, find_router_config},
+        source_config::{find_filter_config, find_router_config, sampled, UpstreamProtocol},
     },
     gateway::{
         interceptor::{execute_interceptors, Phase},
@@ -23,7 +24,7 @@ use crate::{
     },
 };
 
-use super::ctx::HttpGatewayCtx;
+use super::{ctx::HttpGatewayCtx, load_balancer::UpstreamNode, mirror::MirrorRequest};
 
 #[derive(Clone)]
 pub struct Proxy {
@@ -36,6 +37,61 @@ impl Proxy {
             gateway_state_store,
         }
     }
+
+    /// Feed the circuit breaker of the node the current attempt was sent to.
+    fn report_upstream_result(&self, ctx: &HttpGatewayCtx, success: bool) {
+        let (Some(upstream), Some(node)) = (&ctx.upstream, &ctx.upstream_node) else {
+            return;
+        };
+        let state = self.gateway_state_store.get_state();
+        let upstream_load_balancers = state.upstream_load_balancers();
+        let circuit_breaker = upstream_load_balancers
+            .iter()
+            .find(|us_balance| us_balance.name == *upstream)
+            .and_then(|us_balance| us_balance.circuit_breaker(node));
+        match circuit_breaker {
+            Some(circuit_breaker) if success => circuit_breaker.record_success(),
+            Some(circuit_breaker) => circuit_breaker.record_failure(),
+            None => {}
+        }
+    }
+
+    /// Whether the router policy allows another attempt. `method` is None when the request was
+    /// not sent, which is safe to retry for any method.
+    fn can_retry(&self, ctx: &HttpGatewayCtx, method: Option<&str>) -> bool {
+        let Some(filter) = &ctx.filter else {
+            return false;
+        };
+        let state = self.gateway_state_store.get_state();
+        let Ok(router_config) = find_router_config(state.gateway_config(), filter) else {
+            return false;
+        };
+        let Some(retry) = &router_config.retry else {
+            return false;
+        };
+        if ctx.attempts > retry.attempts {
+            return false;
+        }
+        if method.is_some_and(|method| !retry.allows_method(method)) {
+            return false;
+        }
+        if let Some(total) = router_config.timeouts.as_ref().and_then(|t| t.total) {
+            if ctx.started_at.elapsed() >= Duration::from_millis(total) {
+                return false;
+            }
+        }
+        let allowed = state
+            .retry_budget(&filter.name)
+            .is_none_or(|retry_budget| retry_budget.try_retry());
+        if allowed {
+            let meter = global::meter("gateway_retry");
+            let counter = meter.u64_counter("gateway.upstream.retries").build();
+            counter.add(1, &[KeyValue::new("filter", filter.name.clone())]);
+        } else {
+            debug!("Retry budget exhausted for {}", filter.name);
+        }
+        allowed
+    }
 }
 
 #[async_trait]
@@ -71,13 +127,15 @@ impl ProxyHttp for Proxy {
 
         let state = self.gateway_state_store.get_state();
         let gateway_config = state.gateway_config();
-        let filter = match find_filter_config(gateway_config, session.ds_req_path()) {
+        let filter_match = match find_filter_config(gateway_config, &session.route_request()) {
             Ok(f) => f,
             Err(_) => {
                 error!("Not found filter for path {}", session.ds_req_path());
                 return Err(Error::new_str("Not found filter for path"));
             }
         };
+        let filter = filter_match.filter;
+        session.set_path_params(filter_match.params);
         session.flush_path_and_query(&filter);
         let filter_name = filter.name.clone();
         session.set_filter(filter);
@@ -110,9 +168,15 @@ impl ProxyHttp for Proxy {
     ) -> Result<bool, Box<Error>> {
         let filter = ctx.filter.clone().unwrap();
         debug!("request_filter - Filter Name: {}", filter.name);
+        let state = self.gateway_state_store.get_state();
+        // Keep the request body so that a retry can send it again
+        if find_router_config(state.gateway_config(), &filter)
+            .is_ok_and(|router_config| router_config.retry.is_some())
+        {
+            _session.enable_retry_buffering();
+        }
         let mut session = session::Session::build(Phase::RequestFilter, _session, ctx);
 
-        let state = self.gateway_state_store.get_state();
         let filter_interceptors = state.get_interceptors(Phase::RequestFilter, filter.name.clone());
         debug!(
             "Executing request_filter interceptors with length {}",
@@ -126,6 +190,10 @@ impl ProxyHttp for Proxy {
                     success
                 );
                 if success {
+                    // The interceptor already answered the request, e.g. a short circuit response
+                    if session.get_psession().response_written().is_some() {
+                        return Ok(true);
+                    }
                     let err = Error::new_str("Terminated by request_filter interceptor");
                     return Err(err.into());
                 }
@@ -146,33 +214,141 @@ impl ProxyHttp for Proxy {
         let _session = session::Session::build(Phase::UpstreamPeerSelection, psession, ctx);
         let state = self.gateway_state_store.get_state();
         let gateway_config = state.gateway_config();
-        let filter = ctx.filter.as_ref().unwrap();
-        let router_config = find_router_config(gateway_config, filter).unwrap();
-        let upstream_name = router_config.upstream;
+        let filter = ctx.filter.clone().unwrap();
+        let router_config = find_router_config(gateway_config, &filter).unwrap();
+        // The variant is selected once, retries go to the same upstream
+        if ctx.variant.is_none() {
+            let (variant, upstream) = router_config.select_upstream(&psession.req_header().headers);
+            debug!("Variant {} of {} selected", variant, filter.name);
+            ctx.variant = Some(variant);
+            ctx.upstream = Some(upstream);
+            ctx.upgrade = psession.is_upgrade_req();
+        }
+        if ctx.upgrade && router_config.websocket.is_none() {
+            return Err(Error::explain(
+                pingora_core::ErrorType::HTTPStatus(400),
+                "WebSocket is not enabled on this route",
+            ));
+        }
+        let upstream_name = ctx.upstream.clone().unwrap();
+
+        if ctx.attempts == 0 {
+            if let Some(retry_budget) = state.retry_budget(&filter.name) {
+                retry_budget.record_request();
+            }
+        } else if let Some(retry) = &router_config.retry {
+            let backoff = retry.backoff(ctx.attempts);
+            debug!(
+                "Retry {} for {} after {:?}",
+                ctx.attempts, filter.name, backoff
+            );
+            tokio::time::sleep(backoff).await;
+        }
+        ctx.attempts += 1;
+
+        // gRPC streams may stay open for long, their deadline is the client `grpc-timeout`
+        let grpc = session::is_grpc(psession.req_header());
+        let timeouts = router_config.timeouts.clone().unwrap_or_default();
+        let remaining = timeouts
+            .total
+            .filter(|_| !grpc)
+            .map(|total| Duration::from_millis(total).saturating_sub(ctx.started_at.elapsed()));
+        if remaining == Some(Duration::ZERO) {
+            return Err(Error::explain(
+                pingora_core::ErrorType::HTTPStatus(504),
+                "Upstream total timeout exceeded",
+            ));
+        }
 
         let upstream_load_balancers = state.upstream_load_balancers();
         let upstream_load_balancer = upstream_load_balancers
             .iter()
             .find(|us_balance| us_balance.name == upstream_name)
             .unwrap();
-        let back_end = upstream_load_balancer.get_backend();
+        let back_end = match upstream_load_balancer.get_backend() {
+            Some(back_end) => back_end,
+            None => {
+                error!(
+                    "No upstream node available for {}, all circuit breakers are open",
+                    upstream_name
+                );
+                return Err(Error::explain(
+                    pingora_core::ErrorType::HTTPStatus(503),
+                    "No upstream node available",
+                ));
+            }
+        };
         debug!("back_end {:?}", back_end);
-        let ext = back_end.ext.get::<HashMap<String, bool>>().unwrap();
-
-        let tls = ext.get("tls").unwrap();
-        let mut peer = HttpPeer::new(&back_end.addr, *tls, upstream_name);
+        ctx.upstream = Some(upstream_name.clone());
+        ctx.upstream_node = Some(back_end.addr.to_string());
+        let node = back_end.ext.get::<UpstreamNode>().unwrap();
+        let mut peer = HttpPeer::new(&back_end.addr, node.tls, node.sni.clone());
 
-        if filter.timeout.is_some() {
-            let timeout = filter.timeout.unwrap();
-            debug!("Set timeout for peer: {} seconds", timeout);
-            let option = peer.get_mut_peer_options().unwrap();
-            option.read_timeout = Some(Duration::from_secs(timeout));
-            option.write_timeout = Some(Duration::from_secs(timeout));
+        let option = peer.get_mut_peer_options().unwrap();
+        option.alpn = match node.protocol {
+            UpstreamProtocol::Http1 => ALPN::H1,
+            UpstreamProtocol::Http2 => ALPN::H2,
+            UpstreamProtocol::Auto => ALPN::H2H1,
+        };
+        if let Some(connect) = timeouts.connect {
+            option.connection_timeout = Some(Duration::from_millis(connect));
+        }
+        // Router timeouts take precedence over the filter timeout, in seconds
+        let read_timeout = timeouts
+            .read
+            .map(Duration::from_millis)
+            .or(filter.timeout.map(Duration::from_secs));
+        let read_timeout = match (read_timeout, remaining) {
+            (Some(read_timeout), Some(remaining)) => Some(read_timeout.min(remaining)),
+            (read_timeout, remaining) => read_timeout.or(remaining),
+        };
+        // An upgraded connection stays open as long as data flows
+        let read_timeout = match &router_config.websocket {
+            Some(websocket) if ctx.upgrade => Some(Duration::from_millis(websocket.idle_timeout)),
+            _ if grpc => None,
+            _ => read_timeout,
+        };
+        if let Some(timeout) = read_timeout {
+            debug!("Set timeout for peer: {:?}", timeout);
+            option.read_timeout = Some(timeout);
+            option.write_timeout = Some(timeout);
         }
 
         Ok(Box::new(peer))
     }
 
+    fn fail_to_connect(
+        &self,
+        _session: &mut Session,
+        _peer: &HttpPeer,
+        ctx: &mut Self::CTX,
+        mut e: Box<Error>,
+    ) -> Box<Error> {
+        self.report_upstream_result(ctx, false);
+        // Nothing was sent yet, any method can be retried
+        if self.can_retry(ctx, None) {
+            e.set_retry(true);
+        }
+        e
+    }
+
+    fn error_while_proxy(
+        &self,
+        peer: &HttpPeer,
+        session: &mut Session,
+        e: Box<Error>,
+        ctx: &mut Self::CTX,
+        _client_reused: bool,
+    ) -> Box<Error> {
+        let mut e = e.more_context(format!("Peer: {}", peer));
+        self.report_upstream_result(ctx, false);
+        let method = session.req_header().method.clone();
+        let retry = !session.as_ref().retry_buffer_truncated()
+            && self.can_retry(ctx, Some(method.as_str()));
+        e.set_retry(retry);
+        e
+    }
+
     async fn response_filter(
         &self,
         psession: &mut Session,
@@ -181,6 +357,7 @@ impl ProxyHttp for Proxy {
     ) -> Result<(), Box<Error>> {
         let filter = ctx.filter.clone().unwrap();
         debug!("response_filter - Filter Name: {}", filter.name);
+        self.report_upstream_result(ctx, !upstream_response.status.is_server_error());
         let mut session = session::Session::build(Phase::PostUpstreamResponse, psession, ctx);
         session.upstream_response(upstream_response);
 
@@ -209,6 +386,44 @@ impl ProxyHttp for Proxy {
         Ok(())
     }
 
+    fn response_body_filter(
+        &self,
+        _session: &mut Session,
+        body: &mut Option<Bytes>,
+        end_of_stream: bool,
+        ctx: &mut Self::CTX,
+    ) -> Result<Option<Duration>, Box<Error>>
+    where
+        Self::CTX: Send + Sync,
+    {
+        if ctx.ds_res_body.is_some() {
+            *body = if end_of_stream {
+                ctx.ds_res_body.take()
+            } else {
+                None
+            };
+        }
+
+        if let Some(capture) = ctx.ds_res_body_capture.as_mut() {
+            if let Some(chunk) = body.as_ref() {
+                capture.buffer.extend_from_slice(chunk);
+            }
+            if capture.buffer.len() > capture.max_size {
+                debug!(
+                    "Response body larger than {} bytes, not captured",
+                    capture.max_size
+                );
+                ctx.ds_res_body_capture = None;
+            } else if end_of_stream {
+                if let Some(capture) = ctx.ds_res_body_capture.take() {
+                    let _ = capture.sender.send(capture.buffer.freeze());
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
     async fn upstream_request_filter(
         &self,
         _session: &mut Session,
@@ -245,8 +460,114 @@ impl ProxyHttp for Proxy {
         }
 
         let _up = session.upstream_request(upstream_request);
+
+        let filter = session.get_filter().unwrap();
+        let state = self.gateway_state_store.get_state();
+        let filter_interceptors =
+            state.get_interceptors(Phase::PreUpstreamRequest, filter.name.clone());
+        let invalid_execute =
+            execute_interceptors(&filter_interceptors, &mut session, &Phase::PreUpstreamRequest)
+                .await;
+        match invalid_execute {
+            Ok(success) => {
+                if success {
+                    let err = Error::new_str("Terminated by upstream_request_filter interceptor");
+                    return Err(err.into());
+                }
+            }
+            Err(e) => {
+                error!("Error executing upstream_request_filter interceptors: {:?}", e);
+                return Err(Error::new_str("Error in upstream_request_filter interceptor"));
+            }
+        }
+
         let _plush = session.flush_us_req_header();
 
+        let router_config = find_router_config(state.gateway_config(), &filter).unwrap();
+        if let Some(mirror_config) = &router_config.mirror {
+            // Upgraded and gRPC streams are not replayable as a single request
+            let streaming = ctx.upgrade || session::is_grpc(upstream_request);
+            if ctx.attempts == 1 && !streaming && sampled(mirror_config.percentage) {
+                let path_and_query = upstream_request
+                    .uri
+                    .path_and_query()
+                    .map(|path_and_query| path_and_query.to_string())
+                    .unwrap_or_else(|| "/".to_string());
+                ctx.mirror = Some(MirrorRequest::new(
+                    filter.name.clone(),
+                    mirror_config,
+                    upstream_request.method.clone(),
+                    path_and_query,
+                    &upstream_request.headers,
+                ));
+            }
+        }
+
         Ok(())
     }
+
+    async fn request_body_filter(
+        &self,
+        _session: &mut Session,
+        body: &mut Option<Bytes>,
+        end_of_stream: bool,
+        ctx: &mut Self::CTX,
+    ) -> Result<(), Box<Error>>
+    where
+        Self::CTX: Send + Sync,
+    {
+        if let Some(mirror) = ctx.mirror.as_mut() {
+            mirror.append_body(body.as_deref().unwrap_or_default(), end_of_stream);
+        }
+        Ok(())
+    }
+
+    async fn logging(&self, psession: &mut Session, _e: Option<&Error>, ctx: &mut Self::CTX)
+    where
+        Self::CTX: Send + Sync,
+    {
+        let (Some(filter), Some(variant), Some(upstream)) =
+            (ctx.get_filter(), &ctx.variant, &ctx.upstream)
+        else {
+            return;
+        };
+        let status = psession
+            .response_written()
+            .map(|response| response.status.as_u16().to_string())
+            .unwrap_or_else(|| "none".to_string());
+        let attributes = [
+            KeyValue::new("filter", filter.name.clone()),
+            KeyValue::new("variant", variant.clone()),
+            KeyValue::new("upstream", upstream.clone()),
+            KeyValue::new("status", status),
+        ];
+        let meter = global::meter("gateway_router");
+        let counter = meter.u64_counter("gateway.router.requests").build();
+        counter.add(1, &attributes);
+        let duration = meter
+            .f64_histogram("gateway.router.duration")
+            .with_unit("ms")
+            .build();
+        duration.record(ctx.started_at.elapsed().as_secs_f64() * 1000.0, &attributes);
+
+        let Some(mirror) = ctx.mirror.take() else {
+            return;
+        };
+        let state = self.gateway_state_store.get_state();
+        let upstream_load_balancers = state.upstream_load_balancers();
+        let back_end = upstream_load_balancers
+            .iter()
+            .find(|us_balance| us_balance.name == mirror.upstream)
+            .and_then(|us_balance| us_balance.get_backend());
+        match back_end {
+            Some(back_end) => {
+                let tls = back_end
+                    .ext
+                    .get::<UpstreamNode>()
+                    .is_some_and(|node| node.tls);
+                mirror.send(&back_end.addr.to_string(), tls);
+            }
+            None => debug!("No node available for mirror upstream {}", mirror.upstream),
+        }
+    }
 }
@@ -0,0 +1,386 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use opentelemetry::{global, metrics::ObservableGauge, KeyValue};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{config::source_config::CircuitBreakerConfig, gateway::registry::GatewayRegistry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+
+    /// Value reported by the `gateway.circuit_breaker.state` gauge.
+    pub fn as_metric(&self) -> i64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitBreakerSnapshot {
+    pub node: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Milliseconds before an open breaker lets a probe through
+    pub retry_in_ms: Option<u64>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Instant,
+    probes: u32,
+    probe_successes: u32,
+    probe_started_at: Instant,
+}
+
+/// Breaker of one upstream node.
+///
+/// Closed: requests pass, `failure_threshold` consecutive failures open it.
+/// Open: requests are refused for `open_duration`, then it turns half-open.
+/// Half-open: `half_open_requests` probes pass, one failure reopens it, all succeeding close it.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    upstream: String,
+    node: String,
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(upstream: String, node: String, config: CircuitBreakerConfig) -> Self {
+        let now = Instant::now();
+        Self {
+            upstream,
+            node,
+            config,
+            inner: Mutex::new(BreakerInner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: now,
+                probes: 0,
+                probe_successes: 0,
+                probe_started_at: now,
+            }),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap().state
+    }
+
+    /// Whether a request may be sent to the node. In half-open state this takes a probe slot.
+    pub fn allow_request(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let open_duration = self.config.open_duration();
+        match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                if inner.opened_at.elapsed() < open_duration {
+                    return false;
+                }
+                self.transition(&mut inner, CircuitState::HalfOpen);
+                inner.probes = 1;
+                inner.probe_successes = 0;
+                inner.probe_started_at = Instant::now();
+                true
+            }
+            CircuitState::HalfOpen => {
+                // A probe that never reported back must not keep the node out forever
+                let stale_probe = inner.probe_started_at.elapsed() >= open_duration;
+                if inner.probes < self.config.half_open_requests || stale_probe {
+                    inner.probes += 1;
+                    inner.probe_started_at = Instant::now();
+                    return true;
+                }
+                false
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            CircuitState::Closed => inner.consecutive_failures = 0,
+            CircuitState::HalfOpen => {
+                inner.probe_successes += 1;
+                if inner.probe_successes >= self.config.half_open_requests {
+                    inner.consecutive_failures = 0;
+                    self.transition(&mut inner, CircuitState::Closed);
+                }
+            }
+            CircuitState::Open => {}
+        }
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            CircuitState::Closed => {
+                inner.consecutive_failures += 1;
+                if inner.consecutive_failures >= self.config.failure_threshold {
+                    inner.opened_at = Instant::now();
+                    self.transition(&mut inner, CircuitState::Open);
+                }
+            }
+            CircuitState::HalfOpen => {
+                inner.consecutive_failures += 1;
+                inner.opened_at = Instant::now();
+                self.transition(&mut inner, CircuitState::Open);
+            }
+            CircuitState::Open => {}
+        }
+    }
+
+    pub fn snapshot(&self) -> CircuitBreakerSnapshot {
+        let inner = self.inner.lock().unwrap();
+        let retry_in_ms = (inner.state == CircuitState::Open).then(|| {
+            self.config
+                .open_duration()
+                .saturating_sub(inner.opened_at.elapsed())
+                .as_millis() as u64
+        });
+        CircuitBreakerSnapshot {
+            node: self.node.clone(),
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            retry_in_ms,
+        }
+    }
+
+    fn transition(&self, inner: &mut BreakerInner, state: CircuitState) {
+        if state == CircuitState::Open {
+            warn!(
+                "Circuit breaker opened for upstream {} node {} after {} failures",
+                self.upstream, self.node, inner.consecutive_failures
+            );
+        } else {
+            info!(
+                "Circuit breaker {} for upstream {} node {}",
+                state.as_str(),
+                self.upstream,
+                self.node
+            );
+        }
+        inner.state = state;
+
+        let meter = global::meter("gateway_circuit_breaker");
+        let counter = meter
+            .u64_counter("gateway.circuit_breaker.transitions")
+            .build();
+        counter.add(
+            1,
+            &[
+                KeyValue::new("upstream", self.upstream.clone()),
+                KeyValue::new("node", self.node.clone()),
+                KeyValue::new("state", state.as_str()),
+            ],
+        );
+    }
+}
+
+/// Retry budget of a router: retries are allowed up to a ratio of the requests of the current
+/// window, with a floor of `min_retries`.
+#[derive(Debug)]
+pub struct RetryBudget {
+    ratio: f64,
+    min_retries: u64,
+    window: Mutex<BudgetWindow>,
+}
+
+#[derive(Debug)]
+struct BudgetWindow {
+    started_at: Instant,
+    requests: u64,
+    retries: u64,
+}
+
+const RETRY_BUDGET_WINDOW: Duration = Duration::from_secs(10);
+
+impl RetryBudget {
+    pub fn new(ratio: f64, min_retries: u64) -> Self {
+        Self {
+            ratio,
+            min_retries,
+            window: Mutex::new(BudgetWindow {
+                started_at: Instant::now(),
+                requests: 0,
+                retries: 0,
+            }),
+        }
+    }
+
+    pub fn record_request(&self) {
+        let mut window = self.window.lock().unwrap();
+        Self::roll(&mut window);
+        window.requests += 1;
+    }
+
+    /// Take a retry from the budget, false when it is exhausted.
+    pub fn try_retry(&self) -> bool {
+        let mut window = self.window.lock().unwrap();
+        Self::roll(&mut window);
+        let allowed = ((window.requests as f64 * self.ratio) as u64).max(self.min_retries);
+        if window.retries >= allowed {
+            return false;
+        }
+        window.retries += 1;
+        true
+    }
+
+    fn roll(window: &mut BudgetWindow) {
+        if window.started_at.elapsed() >= RETRY_BUDGET_WINDOW {
+            window.started_at = Instant::now();
+            window.requests = 0;
+            window.retries = 0;
+        }
+    }
+}
+
+/// Report the state of every circuit breaker as the `gateway.circuit_breaker.state` gauge:
+/// 0 closed, 1 half-open, 2 open. Breakers are read from the current state of each gateway,
+/// so reloaded configs are picked up.
+pub fn register_circuit_breaker_gauge(
+    gateway_registry: Arc<GatewayRegistry>,
+) -> ObservableGauge<i64> {
+    let meter = global::meter("gateway_circuit_breaker");
+    meter
+        .i64_observable_gauge("gateway.circuit_breaker.state")
+        .with_description("Circuit breaker state per upstream node")
+        .with_callback(move |observer| {
+            for gateway_store in gateway_registry.stores() {
+                let state = gateway_store.get_state();
+                for us_balance in state.upstream_load_balancers().iter() {
+                    for circuit_breaker in us_balance.circuit_breakers.values() {
+                        observer.observe(
+                            circuit_breaker.state().as_metric(),
+                            &[
+                                KeyValue::new("upstream", us_balance.name.clone()),
+                                KeyValue::new("node", circuit_breaker.node.clone()),
+                            ],
+                        );
+                    }
+                }
+            }
+        })
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(failure_threshold: u32, half_open_requests: u32) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "upstream".to_string(),
+            "127.0.0.1:8080".to_string(),
+            CircuitBreakerConfig {
+                failure_threshold,
+                open_duration: 1,
+                half_open_requests,
+            },
+        )
+    }
+
+    /// Moves an open breaker past its open duration.
+    fn expire(breaker: &CircuitBreaker) {
+        breaker.inner.lock().unwrap().opened_at =
+            Instant::now().checked_sub(Duration::from_secs(2)).unwrap();
+    }
+
+    #[test]
+    fn test_consecutive_failures_open_the_breaker() {
+        let breaker = breaker(3, 1);
+        breaker.record_failure();
+        breaker.record_failure();
+        // A success in between resets the count
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.allow_request());
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.allow_request());
+        let snapshot = breaker.snapshot();
+        assert_eq!(snapshot.consecutive_failures, 3);
+        assert!(snapshot.retry_in_ms.is_some());
+    }
+
+    #[test]
+    fn test_half_open_probes_close_the_breaker() {
+        let breaker = breaker(1, 2);
+        breaker.record_failure();
+        expire(&breaker);
+
+        assert!(breaker.allow_request());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.allow_request());
+        // Both probe slots are taken
+        assert!(!breaker.allow_request());
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.snapshot().consecutive_failures, 0);
+        assert_eq!(breaker.snapshot().retry_in_ms, None);
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_the_breaker() {
+        let breaker = breaker(1, 1);
+        breaker.record_failure();
+        expire(&breaker);
+
+        assert!(breaker.allow_request());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.allow_request());
+    }
+
+    #[test]
+    fn test_retry_budget_floor() {
+        let budget = RetryBudget::new(0.2, 2);
+        budget.record_request();
+        assert!(budget.try_retry());
+        assert!(budget.try_retry());
+        assert!(!budget.try_retry());
+    }
+
+    #[test]
+    fn test_retry_budget_ratio() {
+        let budget = RetryBudget::new(0.2, 0);
+        for _ in 0..20 {
+            budget.record_request();
+        }
+        assert_eq!((0..10).filter(|_| budget.try_retry()).count(), 4);
+
+        // A new window starts with an empty budget
+        budget.window.lock().unwrap().started_at =
+            Instant::now().checked_sub(RETRY_BUDGET_WINDOW).unwrap();
+        assert!(!budget.try_retry());
+    }
+}
@@ -1,17 +1,19 @@
-use http::Uri;
+use bytes::{Bytes, BytesMut};
+use http::{Extensions, StatusCode, Uri};
 use opentelemetry::{trace::TraceContextExt, Context};
 use pingora_http::{RequestHeader as PRequestHeader, ResponseHeader as PResponseHeader};
 use pingora_proxy::Session as PSession;
-use std::{mem::take, str::FromStr};
+use std::{collections::HashMap, mem::take, str::FromStr};
+use tokio::sync::oneshot;
 use tracing::debug;
 
 use crate::{
-    config::source_config::{Filter, PathFilter},
-    error::Error,
+    config::source_config::{Filter, PathFilter, RouteRequest},
+    error::{Error, GatewayResult},
     gateway::interceptor::{Phase, PhaseResult},
 };
 
-use super::ctx::HttpGatewayCtx;
+use super::ctx::{BodyCapture, HttpGatewayCtx};
 
 pub struct Session<'a> {
     ctx: &'a mut HttpGatewayCtx,
@@ -68,6 +70,32 @@ impl<'a> Session<'a> {
     pub fn get_filter(&self) -> Option<Filter> {
         self.ctx.get_filter().cloned()
     }
+
+    pub fn set_path_params(&mut self, params: HashMap<String, String>) {
+        self.ctx.path_params = params;
+    }
+
+    pub fn path_params(&self) -> &HashMap<String, String> {
+        &self.ctx.path_params
+    }
+
+    /// Downstream request attributes used to select a filter.
+    pub fn route_request(&self) -> RouteRequest<'_> {
+        let req_header = self.psession.as_downstream().req_header();
+        let host = req_header.uri.host().or_else(|| {
+            req_header
+                .headers
+                .get(http::header::HOST)
+                .and_then(|v| v.to_str().ok())
+        });
+        RouteRequest {
+            method: req_header.method.as_str(),
+            host,
+            path: req_header.uri.path(),
+            query: req_header.uri.query(),
+            headers: &req_header.headers,
+        }
+    }
 }
 
 impl<'a> Session<'a> {
@@ -181,6 +209,184 @@ impl<'a> Session<'a> {
     }
 }
 
+/// Header changes applied directly to the upstream request and response.
+/// Values buffered with `set_us_req_header`/`set_ds_res_header` are still flushed afterwards.
+impl<'a> Session<'a> {
+    pub fn append_us_req_header(&mut self, header_name: String, header_value: Vec<u8>) {
+        if let Some(upstream_request) = self.upstream_request.as_mut() {
+            let _append = upstream_request.append_header(header_name, header_value);
+        }
+    }
+
+    pub fn remove_us_req_header(&mut self, header_name: &str) {
+        self.ctx.us_req_header_buffer.remove(header_name);
+        if let Some(upstream_request) = self.upstream_request.as_mut() {
+            upstream_request.remove_header(header_name);
+        }
+    }
+
+    pub fn append_ds_res_header(&mut self, header_name: String, header_value: Vec<u8>) {
+        if let Some(upstream_response) = self.upstream_response.as_mut() {
+            let _append = upstream_response.append_header(header_name, header_value);
+        }
+    }
+
+    pub fn remove_ds_res_header(&mut self, header_name: &str) {
+        self.ctx.ds_res_header_buffer.remove(header_name);
+        if let Some(upstream_response) = self.upstream_response.as_mut() {
+            upstream_response.remove_header(header_name);
+        }
+    }
+
+    pub fn us_req_path_and_query(&self) -> Option<&str> {
+        self.upstream_request
+            .as_ref()
+            .and_then(|upstream_request| upstream_request.uri.path_and_query())
+            .map(|pq| pq.as_str())
+    }
+
+    pub fn set_us_req_uri(&mut self, uri: Uri) -> GatewayResult<()> {
+        match self.upstream_request.as_mut() {
+            Some(upstream_request) => {
+                upstream_request.set_uri(uri);
+                Ok(())
+            }
+            None => Err(Error::from_str(
+                "Something went wrong! Upstream headers are not present",
+            )),
+        }
+    }
+}
+
+/// Downstream response status and body
+impl<'a> Session<'a> {
+    pub fn extensions(&self) -> &Extensions {
+        &self.ctx.extensions
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.ctx.extensions
+    }
+
+    pub fn ds_res_status(&self) -> Option<StatusCode> {
+        self.upstream_response
+            .as_ref()
+            .map(|upstream_response| upstream_response.status)
+    }
+
+    pub fn ds_res_header(&self, header_name: &str) -> Option<String> {
+        self.upstream_response
+            .as_ref()?
+            .headers
+            .get(header_name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    }
+
+    /// Headers of the upstream response, with the buffered downstream headers applied.
+    pub fn ds_res_headers(&self) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = match self.upstream_response.as_ref() {
+            Some(upstream_response) => upstream_response
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            None => vec![],
+        };
+        for (header_name, header_value) in &self.ctx.ds_res_header_buffer {
+            let Ok(value) = String::from_utf8(header_value.clone()) else {
+                continue;
+            };
+            let header_name = header_name.to_lowercase();
+            headers.retain(|(name, _)| *name != header_name);
+            headers.push((header_name, value));
+        }
+        headers
+    }
+
+    pub fn set_ds_res_status(&mut self, status: StatusCode) -> GatewayResult<()> {
+        match self.upstream_response.as_mut() {
+            Some(upstream_response) => {
+                let _set = upstream_response.set_status(status);
+                Ok(())
+            }
+            None => Err(Error::from_str(
+                "Something went wrong! Upstream headers are not present",
+            )),
+        }
+    }
+
+    /// Send `body` downstream instead of the upstream response body.
+    pub fn set_ds_res_body(&mut self, body: Bytes) {
+        self.ctx.ds_res_body = Some(body);
+    }
+
+    /// Receive a copy of the downstream response body once complete.
+    /// The sender is dropped when the body is larger than `max_size`.
+    pub fn capture_ds_res_body(&mut self, max_size: usize) -> oneshot::Receiver<Bytes> {
+        let (sender, receiver) = oneshot::channel();
+        self.ctx.ds_res_body_capture = Some(BodyCapture {
+            max_size,
+            buffer: BytesMut::new(),
+            sender,
+        });
+        receiver
+    }
+}
+
+/// gRPC request, whatever the `+proto`/`+json` suffix of its content type.
+pub fn is_grpc(req_header: &PRequestHeader) -> bool {
+    req_header
+        .headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("application/grpc"))
+}
+
+/// Downstream request protocol
+impl<'a> Session<'a> {
+    /// WebSocket handshake, the connection is upgraded once the upstream answers `101`
+    pub fn is_upgrade_req(&self) -> bool {
+        self.psession.is_upgrade_req()
+    }
+
+    pub fn is_grpc_req(&self) -> bool {
+        is_grpc(self.psession.req_header())
+    }
+
+    /// Remove a query parameter from the downstream request, so it is not forwarded, and return
+    /// its raw value.
+    pub fn take_ds_req_query_param(&mut self, name: &str) -> Option<String> {
+        let uri = &self.psession.req_header().uri;
+        let mut value = None;
+        let remaining: Vec<&str> = uri
+            .query()?
+            .split('&')
+            .filter(|pair| match pair.split_once('=') {
+                Some((key, param)) if key == name && value.is_none() => {
+                    value = Some(param.to_string());
+                    false
+                }
+                _ => true,
+            })
+            .collect();
+        let value = value?;
+
+        let path_and_query = if remaining.is_empty() {
+            uri.path().to_string()
+        } else {
+            format!("{}?{}", uri.path(), remaining.join("&"))
+        };
+        match path_and_query.parse::<Uri>() {
+            Ok(uri) => self.psession.req_header_mut().set_uri(uri),
+            Err(e) => debug!("Failed to remove query parameter {}: {:?}", name, e),
+        }
+        Some(value)
+    }
+}
+
 /// Override request path
 impl<'a> Session<'a> {
     pub fn ds_req_path(&self) -> &str {
@@ -190,12 +396,11 @@ impl<'a> Session<'a> {
     pub fn flush_path_and_query(&mut self, filter: &Filter) -> () {
         let current_path_and_query = self.path_and_query();
         let mut new_path_str = current_path_and_query.to_string();
-        let filter_path = filter.path.clone().unwrap();
-        match filter_path {
-            PathFilter::StartWith { value } => {
-                new_path_str.replace_range(0..value.len(), "");
+        if let Some(PathFilter::StartWith { value }) = &filter.path {
+            new_path_str.replace_range(0..value.len(), "");
+            if !new_path_str.starts_with('/') {
+                new_path_str.insert(0, '/');
             }
-            PathFilter::EndWith { value } => {}
         }
 
         let uri = new_path_str.parse::<Uri>().unwrap();
@@ -213,3 +418,75 @@ impl<'a> Session<'a> {
         path_and_query
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio_test::io::Builder;
+
+    use super::*;
+
+    async fn downstream(request: &str) -> PSession {
+        let stream = Builder::new().read(request.as_bytes()).build();
+        let mut psession = PSession::new_h1(Box::new(stream));
+        assert!(psession.read_request().await.unwrap());
+        psession
+    }
+
+    fn request_header(content_type: Option<&str>) -> PRequestHeader {
+        let mut req_header = PRequestHeader::build("POST", b"/search.Search/Query", None).unwrap();
+        if let Some(content_type) = content_type {
+            req_header
+                .insert_header(http::header::CONTENT_TYPE, content_type)
+                .unwrap();
+        }
+        req_header
+    }
+
+    #[test]
+    fn test_grpc_requests_are_detected() {
+        assert!(is_grpc(&request_header(Some("application/grpc"))));
+        assert!(is_grpc(&request_header(Some("application/grpc+proto"))));
+        assert!(is_grpc(&request_header(Some("application/grpc-web+json"))));
+        assert!(!is_grpc(&request_header(Some("application/json"))));
+        assert!(!is_grpc(&request_header(None)));
+    }
+
+    #[tokio::test]
+    async fn test_query_param_is_taken_from_the_request() {
+        let mut psession =
+            downstream("GET /ws?room=1&access_token=abc&lang=en HTTP/1.1\r\nHost: a\r\n\r\n").await;
+        let mut ctx = HttpGatewayCtx::new();
+        let mut session = Session::build(Phase::RequestFilter, &mut psession, &mut ctx);
+
+        assert_eq!(
+            session.take_ds_req_query_param("access_token"),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            session.get_psession().req_header().uri,
+            "/ws?room=1&lang=en"
+        );
+        assert_eq!(session.take_ds_req_query_param("access_token"), None);
+    }
+
+    #[tokio::test]
+    async fn test_last_query_param_leaves_the_path() {
+        let mut psession =
+            downstream("GET /ws?access_token=abc&access_token=def HTTP/1.1\r\nHost: a\r\n\r\n")
+                .await;
+        let mut ctx = HttpGatewayCtx::new();
+        let mut session = Session::build(Phase::RequestFilter, &mut psession, &mut ctx);
+
+        // Only the first occurrence is taken
+        assert_eq!(
+            session.take_ds_req_query_param("access_token"),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            session.take_ds_req_query_param("access_token"),
+            Some("def".to_string())
+        );
+        assert_eq!(session.get_psession().req_header().uri, "/ws");
+        assert_eq!(session.take_ds_req_query_param("room"), None);
+    }
+}
@@ -1,17 +1,528 @@
-use serde::Deserialize;
+use http::{uri::Authority, HeaderMap};
+use pcre2::bytes::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::HashMap, fmt, sync::Arc};
+use wildmatch::WildMatch;
 
-#[derive(Deserialize, Debug, Clone)]
+/// Attributes of a downstream request that filters are matched against.
+pub struct RouteRequest<'a> {
+    pub method: &'a str,
+    pub host: Option<&'a str>,
+    pub path: &'a str,
+    pub query: Option<&'a str>,
+    pub headers: &'a HeaderMap,
+}
+
+/// Selects the requests handled by a router and its interceptors.
+///
+/// Every predicate that is set must match. Among matching filters the highest `priority` wins,
+/// then the longest path match, then the filter with the most predicates, then the first declared.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Filter {
     pub name: String,
     pub path: Option<PathFilter>,
+    #[serde(default)]
+    pub priority: i32,
+    /// HTTP methods, any method when empty
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Host patterns with `*` wildcards, matched against the host without port. IPv6 hosts
+    /// keep their brackets, `[::1]`
+    #[serde(default)]
+    pub hosts: Vec<HostPattern>,
+    #[serde(default)]
+    pub headers: Vec<ValueFilter>,
+    #[serde(default)]
+    pub query: Vec<ValueFilter>,
     pub timeout: Option<u64>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "operator")]
 pub enum PathFilter {
     #[serde(rename = "start_with")]
     StartWith { value: String },
     #[serde(rename = "end_with")]
     EndWith { value: String },
+    #[serde(rename = "exact")]
+    Exact { value: String },
+    /// `/users/{user_id}/orders/{*rest}`: `{name}` captures one segment, `{*name}` the remaining path
+    #[serde(rename = "template")]
+    Template { value: String },
+    /// PCRE pattern, named groups are captured
+    #[serde(rename = "regex")]
+    Regex { value: Pattern },
+}
+
+/// Condition on a header or query parameter value.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValueFilter {
+    pub name: String,
+    #[serde(flatten)]
+    pub condition: ValueCondition,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "operator", rename_all = "snake_case")]
+pub enum ValueCondition {
+    Exists,
+    Absent,
+    Equal { value: String },
+    NotEqual { value: String },
+    In { values: Vec<String> },
+    StartWith { value: String },
+    EndWith { value: String },
+    Contains { value: String },
+    Regex { value: Pattern },
+}
+
+/// Compiled PCRE pattern, deserialized from its source string.
+#[derive(Clone)]
+pub struct Pattern {
+    source: String,
+    regex: Arc<Regex>,
+}
+
+/// Host pattern with `*` wildcards, compiled and lowercased once when the filter is loaded.
+#[derive(Clone)]
+pub struct HostPattern {
+    source: String,
+    wildcard: Arc<WildMatch>,
+}
+
+/// The filter selected for a request, with the parameters captured from the path.
+#[derive(Debug, Clone)]
+pub struct FilterMatch {
+    pub filter: Filter,
+    pub params: HashMap<String, String>,
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self, pcre2::Error> {
+        Ok(Self {
+            source: source.to_string(),
+            regex: Arc::new(Regex::new(source)?),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    pub fn is_match(&self, subject: &str) -> bool {
+        self.regex.is_match(subject.as_bytes()).unwrap_or(false)
+    }
+
+    /// Named groups of the first match, None when the subject does not match.
+    pub fn captures(&self, subject: &str) -> Option<HashMap<String, String>> {
+        let captures = self.regex.captures(subject.as_bytes()).ok()??;
+        let params = self
+            .regex
+            .capture_names()
+            .iter()
+            .flatten()
+            .filter_map(|name| {
+                let value = captures.name(name)?;
+                Some((
+                    name.clone(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                ))
+            })
+            .collect();
+        Some(params)
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pattern({:?})", self.source)
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        Pattern::new(&source).map_err(|e| de::Error::custom(format!("{}: {}", source, e)))
+    }
+}
+
+impl HostPattern {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            wildcard: Arc::new(WildMatch::new(&source.to_lowercase())),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// `host` is expected lowercased, as returned by `host_without_port`.
+    pub fn matches(&self, host: &str) -> bool {
+        self.wildcard.matches(host)
+    }
+}
+
+impl fmt::Debug for HostPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HostPattern({:?})", self.source)
+    }
+}
+
+impl Serialize for HostPattern {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for HostPattern {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        Ok(HostPattern::new(&source))
+    }
+}
+
+impl PathFilter {
+    /// Captured parameters and the number of literal characters matched, used for longest-match.
+    pub fn matches(&self, path: &str) -> Option<(HashMap<String, String>, usize)> {
+        match self {
+            PathFilter::StartWith { value } => path
+                .starts_with(value.as_str())
+                .then(|| (HashMap::new(), value.len())),
+            PathFilter::EndWith { value } => path
+                .ends_with(value.as_str())
+                .then(|| (HashMap::new(), value.len())),
+            // An exact match beats a prefix of the same length
+            PathFilter::Exact { value } => {
+                (path == value).then(|| (HashMap::new(), value.len() + 1))
+            }
+            PathFilter::Template { value } => match_template(value, path),
+            PathFilter::Regex { value } => value.captures(path).map(|params| (params, 0)),
+        }
+    }
+}
+
+fn match_template(template: &str, path: &str) -> Option<(HashMap<String, String>, usize)> {
+    let mut params = HashMap::new();
+    let mut specificity = 0;
+    let mut path_segments = path.trim_start_matches('/').split('/');
+    let mut template_segments = template.trim_start_matches('/').split('/').peekable();
+
+    while let Some(segment) = template_segments.next() {
+        if let Some(name) = segment.strip_prefix("{*").and_then(|s| s.strip_suffix('}')) {
+            // Catch-all must be the last segment
+            if template_segments.peek().is_some() {
+                return None;
+            }
+            let rest: Vec<&str> = path_segments.by_ref().collect();
+            params.insert(name.to_string(), rest.join("/"));
+            return Some((params, specificity));
+        }
+
+        let path_segment = path_segments.next()?;
+        match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) if !path_segment.is_empty() => {
+                params.insert(name.to_string(), path_segment.to_string());
+            }
+            Some(_) => return None,
+            None if segment == path_segment => specificity += segment.len() + 1,
+            None => return None,
+        }
+    }
+
+    path_segments
+        .next()
+        .is_none()
+        .then_some((params, specificity))
+}
+
+impl ValueCondition {
+    pub fn matches(&self, value: Option<&str>) -> bool {
+        match (self, value) {
+            (ValueCondition::Exists, value) => value.is_some(),
+            (ValueCondition::Absent, value) => value.is_none(),
+            (ValueCondition::NotEqual { value: expected }, value) => {
+                value != Some(expected.as_str())
+            }
+            (_, None) => false,
+            (ValueCondition::Equal { value: expected }, Some(value)) => value == expected.as_str(),
+            (ValueCondition::In { values }, Some(value)) => values.iter().any(|v| v == value),
+            (ValueCondition::StartWith { value: prefix }, Some(value)) => value.starts_with(prefix),
+            (ValueCondition::EndWith { value: suffix }, Some(value)) => value.ends_with(suffix),
+            (ValueCondition::Contains { value: part }, Some(value)) => value.contains(part),
+            (ValueCondition::Regex { value: pattern }, Some(value)) => pattern.is_match(value),
+        }
+    }
+}
+
+impl Filter {
+    /// Captured path parameters and path specificity when every predicate matches.
+    pub fn matches(&self, request: &RouteRequest) -> Option<(HashMap<String, String>, usize)> {
+        if !self.methods.is_empty()
+            && !self
+                .methods
+                .iter()
+                .any(|method| method.eq_ignore_ascii_case(request.method))
+        {
+            return None;
+        }
+
+        if !self.hosts.is_empty() {
+            let host = request.host.and_then(host_without_port)?;
+            if !self.hosts.iter().any(|pattern| pattern.matches(&host)) {
+                return None;
+            }
+        }
+
+        let headers_match = self.headers.iter().all(|header| {
+            let value = request
+                .headers
+                .get(header.name.as_str())
+                .and_then(|v| v.to_str().ok());
+            header.condition.matches(value)
+        });
+        if !headers_match {
+            return None;
+        }
+
+        if !self.query.is_empty() {
+            let query: HashMap<String, String> = request
+                .query
+                .map(|q| {
+                    url::form_urlencoded::parse(q.as_bytes())
+                        .into_owned()
+                        .collect()
+                })
+                .unwrap_or_default();
+            let query_match = self.query.iter().all(|param| {
+                param
+                    .condition
+                    .matches(query.get(&param.name).map(String::as_str))
+            });
+            if !query_match {
+                return None;
+            }
+        }
+
+        match &self.path {
+            Some(path_filter) => path_filter.matches(request.path),
+            None => Some((HashMap::new(), 0)),
+        }
+    }
+
+    fn predicate_count(&self) -> usize {
+        (!self.methods.is_empty()) as usize
+            + (!self.hosts.is_empty()) as usize
+            + self.headers.len()
+            + self.query.len()
+    }
+}
+
+/// Host of a `Host` header or URI authority, lowercased and without its port.
+fn host_without_port(host: &str) -> Option<String> {
+    let authority: Authority = host.parse().ok()?;
+    Some(authority.host().to_lowercase())
+}
+
+/// Priority, path specificity and predicate count of a matching filter, compared in that order.
+type Rank = (i32, usize, usize);
+
+/// Best filter for a request: priority, then longest path match, then most predicates, then declaration order.
+pub fn select_filter(filters: &[Filter], request: &RouteRequest) -> Option<FilterMatch> {
+    let mut best: Option<(&Filter, HashMap<String, String>, Rank)> = None;
+    for filter in filters {
+        let Some((params, specificity)) = filter.matches(request) else {
+            continue;
+        };
+        let rank = (filter.priority, specificity, filter.predicate_count());
+        // Strictly greater, so the first declared filter wins a tie
+        if best
+            .as_ref()
+            .is_none_or(|(_, _, best_rank)| rank > *best_rank)
+        {
+            best = Some((filter, params, rank));
+        }
+    }
+    best.map(|(filter, params, _)| FilterMatch {
+        filter: filter.clone(),
+        params,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filters(yaml: &str) -> Vec<Filter> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn request<'a>(method: &'a str, host: Option<&'a str>, path: &'a str) -> RouteRequest<'a> {
+        static HEADERS: std::sync::OnceLock<HeaderMap> = std::sync::OnceLock::new();
+        RouteRequest {
+            method,
+            host,
+            path,
+            query: None,
+            headers: HEADERS.get_or_init(HeaderMap::new),
+        }
+    }
+
+    fn selected(filters: &[Filter], request: &RouteRequest) -> Option<String> {
+        select_filter(filters, request).map(|filter_match| filter_match.filter.name)
+    }
+
+    #[test]
+    fn test_template_captures_segments_and_rest() {
+        let (params, specificity) =
+            match_template("/users/{user_id}/files/{*rest}", "/users/42/files/a/b.txt").unwrap();
+        assert_eq!(params["user_id"], "42");
+        assert_eq!(params["rest"], "a/b.txt");
+        assert_eq!(specificity, "users".len() + "files".len() + 2);
+    }
+
+    #[test]
+    fn test_template_requires_every_segment() {
+        assert!(match_template("/users/{user_id}", "/users/").is_none());
+        assert!(match_template("/users/{user_id}", "/users/42/orders").is_none());
+        assert!(match_template("/users/{user_id}", "/accounts/42").is_none());
+        // A catch-all is only allowed last
+        assert!(match_template("/{*rest}/users", "/a/users").is_none());
+    }
+
+    #[test]
+    fn test_longest_path_then_priority_wins() {
+        let filters = filters(
+            r#"
+            - name: api
+              path: { operator: start_with, value: /api }
+            - name: users
+              path: { operator: start_with, value: /api/users }
+            - name: user
+              path: { operator: template, value: "/api/users/{id}" }
+            "#,
+        );
+        assert_eq!(
+            selected(&filters, &request("GET", None, "/api/users/1")),
+            Some("users".to_string())
+        );
+        assert_eq!(
+            selected(&filters, &request("GET", None, "/api/orders")),
+            Some("api".to_string())
+        );
+
+        let mut filters = filters;
+        filters[2].priority = 1;
+        let filter_match = select_filter(&filters, &request("GET", None, "/api/users/1")).unwrap();
+        assert_eq!(filter_match.filter.name, "user");
+        assert_eq!(filter_match.params["id"], "1");
+    }
+
+    #[test]
+    fn test_more_predicates_then_first_declared_wins() {
+        let filters = filters(
+            r#"
+            - name: first
+              path: { operator: start_with, value: /api }
+            - name: second
+              path: { operator: start_with, value: /api }
+            - name: post
+              path: { operator: start_with, value: /api }
+              methods: [post]
+            "#,
+        );
+        assert_eq!(
+            selected(&filters, &request("POST", None, "/api")),
+            Some("post".to_string())
+        );
+        assert_eq!(
+            selected(&filters, &request("GET", None, "/api")),
+            Some("first".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hosts_match_without_port() {
+        let filters = filters(
+            r#"
+            - name: tenant
+              hosts: ["*.example.com"]
+            - name: local
+              hosts: ["[::1]", "localhost"]
+            "#,
+        );
+        assert_eq!(
+            selected(
+                &filters,
+                &request("GET", Some("Shop.Example.com:8443"), "/")
+            ),
+            Some("tenant".to_string())
+        );
+        assert_eq!(
+            selected(&filters, &request("GET", Some("[::1]:8080"), "/")),
+            Some("local".to_string())
+        );
+        assert_eq!(
+            selected(&filters, &request("GET", Some("[::1]"), "/")),
+            Some("local".to_string())
+        );
+        assert_eq!(
+            selected(&filters, &request("GET", Some("localhost:80"), "/")),
+            Some("local".to_string())
+        );
+        assert_eq!(selected(&filters, &request("GET", None, "/")), None);
+        assert_eq!(
+            selected(&filters, &request("GET", Some("[::2]:80"), "/")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_header_and_query_conditions() {
+        let filters = filters(
+            r#"
+            - name: beta
+              headers:
+                - { name: x-beta, operator: equal, value: "1" }
+              query:
+                - { name: debug, operator: absent }
+            "#,
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert("x-beta", "1".parse().unwrap());
+        let mut beta = RouteRequest {
+            method: "GET",
+            host: None,
+            path: "/",
+            query: Some("page=2"),
+            headers: &headers,
+        };
+        assert_eq!(selected(&filters, &beta), Some("beta".to_string()));
+        beta.query = Some("debug=true");
+        assert_eq!(selected(&filters, &beta), None);
+        assert_eq!(selected(&filters, &request("GET", None, "/")), None);
+    }
 }
@@ -1,9 +1,12 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use super::{
-    filter::PathFilter, inet_address::InetAddress, router_config::RouterConfig,
-    upstream_config::UpstreamConfig, Filter,
+    filter::{select_filter, FilterMatch, RouteRequest},
+    inet_address::InetAddress,
+    router_config::RouterConfig,
+    upstream_config::UpstreamConfig,
+    Filter,
 };
 
 use crate::{
@@ -11,14 +14,15 @@ use crate::{
     error::{Error, GatewayResult},
 };
 
-// super::filter::PathFilter;
-
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GatewayConfig {
     // TODO: use auto generated name
     pub name: String,
     // TODO: add type = HTTP, TCP, SMTP, etc
     pub bind_addresses: Vec<InetAddress>,
+    /// Accept HTTP/2 without TLS (h2c prior knowledge) next to HTTP/1.1, for gRPC clients
+    #[serde(default)]
+    pub h2c: bool,
     // pub downstreams: Vec<DownstreamConfig>,
     pub upstreams: Vec<UpstreamConfig>,
     pub routers: Vec<RouterConfig>,
@@ -31,22 +35,18 @@ pub struct GatewayConfig {
 
 pub fn find_filter_config<'a>(
     gateway_config: &'a GatewayConfig,
-    path: &'a str,
-) -> GatewayResult<Filter> {
-    let filter = gateway_config.filters.iter().find(|filter| {
-        let path_filter = filter.path.as_ref().unwrap();
-
-        match path_filter {
-            PathFilter::StartWith { value } => path.starts_with(value),
-            PathFilter::EndWith { value } => path.ends_with(value),
-        }
-    });
-    if filter.is_none() {
+    request: &RouteRequest<'a>,
+) -> GatewayResult<FilterMatch> {
+    let filter_match = select_filter(&gateway_config.filters, request);
+    if filter_match.is_none() {
         return Err(Error::from_str("Not found filter"));
     }
-    debug!("Found filter for path {}: {:?}", path, filter);
-    let filter = filter.unwrap();
-    Ok(filter.clone())
+    let filter_match = filter_match.unwrap();
+    debug!(
+        "Found filter for {} {}: {:?}",
+        request.method, request.path, filter_match
+    );
+    Ok(filter_match)
 }
 
 pub fn find_router_config<'a>(
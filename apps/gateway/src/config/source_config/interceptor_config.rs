@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::gateway::interceptor::InterceptorType;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterceptorConfig {
     pub name: String,
-    #[serde(rename(deserialize = "type"))]
+    #[serde(rename = "type")]
     pub interceptor_type: InterceptorType,
     pub enabled: bool,
     pub filter: Option<String>,
@@ -3,14 +3,22 @@ mod filter;
 mod gateway_config;
 mod inet_address;
 mod interceptor_config;
+mod resilience_config;
 mod router_config;
+mod traffic_config;
 mod upstream_config;
+mod websocket_config;
 
 pub use downstream_config::DownstreamConfig;
-pub use filter::{Filter, PathFilter};
+pub use filter::{
+    Filter, FilterMatch, PathFilter, Pattern, RouteRequest, ValueCondition, ValueFilter,
+};
 pub use gateway_config::*;
 pub use inet_address::InetAddress;
 pub use interceptor_config::*;
 pub use interceptor_config::*;
+pub use resilience_config::{CircuitBreakerConfig, RetryConfig, TimeoutConfig};
 pub use router_config::RouterConfig;
+pub use traffic_config::{sampled, MirrorConfig, PRIMARY_VARIANT};
 pub use upstream_config::*;
+pub use websocket_config::WebSocketConfig;
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Upstream timeouts of a router, in milliseconds.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TimeoutConfig {
+    pub connect: Option<u64>,
+    /// Per read/write on the upstream connection
+    pub read: Option<u64>,
+    /// Whole request across retries, each attempt's read timeout is capped by what remains
+    pub total: Option<u64>,
+}
+
+/// Retries of a router. Connection failures are retried for every method, errors after the
+/// request was sent only for `methods`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Retries after the first attempt
+    #[serde(default = "default_retry_attempts")]
+    pub attempts: u32,
+    #[serde(default = "default_retry_methods")]
+    pub methods: Vec<String>,
+    /// Base delay in milliseconds, doubled on every retry
+    #[serde(default = "default_retry_backoff")]
+    pub backoff: u64,
+    #[serde(default = "default_retry_max_backoff")]
+    pub max_backoff: u64,
+    /// Retries allowed as a ratio of the requests of the router
+    #[serde(default = "default_retry_budget_ratio")]
+    pub budget_ratio: f64,
+    /// Retries always allowed per budget window, so low traffic routers can still retry
+    #[serde(default = "default_retry_budget_min")]
+    pub budget_min: u64,
+}
+
+/// Circuit breaker applied to every node of an upstream.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures opening the breaker
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Seconds before an open breaker lets probe requests through
+    #[serde(default = "default_open_duration")]
+    pub open_duration: u64,
+    /// Probe requests in half-open state, all must succeed to close the breaker
+    #[serde(default = "default_half_open_requests")]
+    pub half_open_requests: u32,
+}
+
+fn default_retry_attempts() -> u32 {
+    2
+}
+
+fn default_retry_methods() -> Vec<String> {
+    ["GET", "HEAD", "OPTIONS", "PUT", "DELETE"]
+        .iter()
+        .map(|method| method.to_string())
+        .collect()
+}
+
+fn default_retry_backoff() -> u64 {
+    50
+}
+
+fn default_retry_max_backoff() -> u64 {
+    1000
+}
+
+fn default_retry_budget_ratio() -> f64 {
+    0.2
+}
+
+fn default_retry_budget_min() -> u64 {
+    10
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_open_duration() -> u64 {
+    30
+}
+
+fn default_half_open_requests() -> u32 {
+    1
+}
+
+impl RetryConfig {
+    pub fn allows_method(&self, method: &str) -> bool {
+        self.methods
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(method))
+    }
+
+    /// Exponential backoff with jitter for the `retry`-th retry, starting at 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(16);
+        let delay = self
+            .backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        let jitter = rand::thread_rng().gen_range(0..=delay / 2);
+        Duration::from_millis(delay - delay / 2 + jitter)
+    }
+}
+
+impl CircuitBreakerConfig {
+    pub fn open_duration(&self) -> Duration {
+        Duration::from_secs(self.open_duration)
+    }
+}
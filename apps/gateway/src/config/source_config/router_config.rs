@@ -1,7 +1,33 @@
+use http::HeaderMap;
 use serde::{Deserialize, Serialize};
 
+use super::{
+    resilience_config::{RetryConfig, TimeoutConfig},
+    traffic_config::{select_variant, MirrorConfig, RequestValue, VariantConfig, PRIMARY_VARIANT},
+    websocket_config::WebSocketConfig,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouterConfig {
     pub filter: Option<String>,
     pub upstream: String,
+    pub timeouts: Option<TimeoutConfig>,
+    pub retry: Option<RetryConfig>,
+    /// Weighted or pinned alternatives to `upstream`, e.g. a canary release
+    #[serde(default)]
+    pub variants: Vec<VariantConfig>,
+    /// Value keeping a client on the same variant, e.g. a user id header
+    pub sticky: Option<RequestValue>,
+    pub mirror: Option<MirrorConfig>,
+    pub websocket: Option<WebSocketConfig>,
+}
+
+impl RouterConfig {
+    /// Variant name and upstream serving a request.
+    pub fn select_upstream(&self, headers: &HeaderMap) -> (String, String) {
+        match select_variant(&self.variants, self.sticky.as_ref(), headers) {
+            Some(variant) => (variant.name.clone(), variant.upstream.clone()),
+            None => (PRIMARY_VARIANT.to_string(), self.upstream.clone()),
+        }
+    }
 }
@@ -0,0 +1,247 @@
+use http::HeaderMap;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A value read from the downstream request, from a header or a cookie.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RequestValue {
+    pub header: Option<String>,
+    pub cookie: Option<String>,
+}
+
+/// Sends the requests carrying `value` to a variant whatever its weight, e.g. `X-Canary: true`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PinConfig {
+    #[serde(flatten)]
+    pub source: RequestValue,
+    pub value: String,
+}
+
+/// Upstream receiving part of the traffic of a router instead of its `upstream`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VariantConfig {
+    /// Reported in the `variant` metric attribute
+    pub name: String,
+    pub upstream: String,
+    /// Percentage of the requests, the remainder goes to the router upstream
+    #[serde(default)]
+    pub weight: f64,
+    #[serde(default)]
+    pub pins: Vec<PinConfig>,
+}
+
+/// Shadow traffic: a copy of the requests is sent to `upstream` once the original completed,
+/// its response is discarded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MirrorConfig {
+    pub upstream: String,
+    /// Percentage of the requests mirrored
+    #[serde(default = "default_mirror_percentage")]
+    pub percentage: f64,
+    /// Requests with a larger body are not mirrored
+    #[serde(default = "default_mirror_max_body_size")]
+    pub max_body_size: usize,
+    /// Milliseconds
+    #[serde(default = "default_mirror_timeout")]
+    pub timeout: u64,
+}
+
+fn default_mirror_percentage() -> f64 {
+    100.0
+}
+
+fn default_mirror_max_body_size() -> usize {
+    1024 * 1024
+}
+
+fn default_mirror_timeout() -> u64 {
+    5000
+}
+
+/// Variant name of the router upstream in metrics.
+pub const PRIMARY_VARIANT: &str = "primary";
+
+impl RequestValue {
+    pub fn read(&self, headers: &HeaderMap) -> Option<String> {
+        if let Some(header) = &self.header {
+            if let Some(value) = headers.get(header).and_then(|v| v.to_str().ok()) {
+                return Some(value.to_string());
+            }
+        }
+        let cookie = self.cookie.as_ref()?;
+        headers
+            .get_all(http::header::COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|cookies| cookies.split(';'))
+            .find_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                (name == cookie).then(|| value.to_string())
+            })
+    }
+}
+
+impl PinConfig {
+    pub fn matches(&self, headers: &HeaderMap) -> bool {
+        self.source.read(headers).as_deref() == Some(self.value.as_str())
+    }
+}
+
+/// Variant serving a request: a pinned variant first, then a weighted pick. The position in
+/// `[0, 100)` is derived from the sticky value when there is one, so a client stays on the same
+/// variant while the weights do not change.
+pub fn select_variant<'a>(
+    variants: &'a [VariantConfig],
+    sticky: Option<&RequestValue>,
+    headers: &HeaderMap,
+) -> Option<&'a VariantConfig> {
+    if let Some(variant) = variants
+        .iter()
+        .find(|variant| variant.pins.iter().any(|pin| pin.matches(headers)))
+    {
+        return Some(variant);
+    }
+
+    let position = match sticky.and_then(|sticky| sticky.read(headers)) {
+        Some(value) => bucket(&value),
+        None => rand::thread_rng().gen_range(0.0..100.0),
+    };
+    let mut upper = 0.0;
+    for variant in variants {
+        upper += variant.weight;
+        if position < upper {
+            return Some(variant);
+        }
+    }
+    None
+}
+
+/// Stable position of a value in `[0, 100)`, FNV-1a so it does not change between releases.
+fn bucket(value: &str) -> f64 {
+    let hash = value.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    (hash % 10_000) as f64 / 100.0
+}
+
+/// Whether a request is sampled for a percentage.
+pub fn sampled(percentage: f64) -> bool {
+    percentage >= 100.0 || rand::thread_rng().gen_range(0.0..100.0) < percentage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variants(yaml: &str) -> Vec<VariantConfig> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn sticky_header() -> RequestValue {
+        RequestValue {
+            header: Some("x-user-id".to_string()),
+            cookie: None,
+        }
+    }
+
+    fn selected(variants: &[VariantConfig], headers: &HeaderMap) -> Option<String> {
+        select_variant(variants, Some(&sticky_header()), headers)
+            .map(|variant| variant.name.clone())
+    }
+
+    #[test]
+    fn test_bucket_is_stable() {
+        assert_eq!(bucket(""), 60.37);
+        assert_eq!(bucket("a"), 19.96);
+        assert_eq!(bucket("user-1"), bucket("user-1"));
+    }
+
+    #[test]
+    fn test_pins_win_over_weights() {
+        let variants = variants(
+            r#"
+            - name: canary
+              upstream: canary
+              weight: 0
+              pins:
+                - { header: x-canary, value: "true" }
+                - { cookie: canary, value: "1" }
+            "#,
+        );
+        let canary = Some("canary".to_string());
+        assert_eq!(
+            selected(&variants, &headers(&[("x-canary", "true")])),
+            canary
+        );
+        assert_eq!(
+            selected(&variants, &headers(&[("cookie", "theme=dark; canary=1")])),
+            canary
+        );
+        assert_eq!(
+            selected(&variants, &headers(&[("x-canary", "false")])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sticky_value_keeps_the_variant() {
+        let variants = variants(
+            r#"
+            - name: v2
+              upstream: v2
+              weight: 50
+            "#,
+        );
+        for user in ["alice", "bob", "carol"] {
+            let headers = headers(&[("x-user-id", user)]);
+            let first = selected(&variants, &headers);
+            assert_eq!(first.is_some(), bucket(user) < 50.0);
+            for _ in 0..10 {
+                assert_eq!(selected(&variants, &headers), first);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weights_split_the_traffic() {
+        let variants = variants(
+            r#"
+            - name: a
+              upstream: a
+              weight: 20
+            - name: b
+              upstream: b
+              weight: 30
+            "#,
+        );
+        let mut counts: std::collections::HashMap<Option<String>, usize> = Default::default();
+        for user in 0..1000 {
+            let user = format!("user-{}", user);
+            *counts
+                .entry(selected(
+                    &variants,
+                    &headers(&[("x-user-id", user.as_str())]),
+                ))
+                .or_default() += 1;
+        }
+        let share = |name: Option<&str>| counts[&name.map(str::to_string)] as f64 / 10.0;
+        // Percentages of 1000 sticky users, the remainder stays on the router upstream
+        assert!((share(Some("a")) - 20.0).abs() < 5.0);
+        assert!((share(Some("b")) - 30.0).abs() < 5.0);
+        assert!((share(None) - 50.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_sampled() {
+        assert!(sampled(100.0));
+        assert!(!sampled(0.0));
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::inet_address::InetAddress;
+use super::{inet_address::InetAddress, resilience_config::CircuitBreakerConfig};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -9,12 +9,28 @@ pub enum LoadBalancerAlgorithm {
     Random,
 }
 
+/// Protocol spoken to the nodes of an upstream.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamProtocol {
+    #[default]
+    Http1,
+    /// HTTP/2 only, as gRPC needs: ALPN h2 over TLS, h2c with prior knowledge otherwise
+    Http2,
+    /// HTTP/2 when the node offers it through ALPN, HTTP/1.1 otherwise. Plain text nodes get
+    /// HTTP/1.1.
+    Auto,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UpstreamNodeConfig {
     pub address: InetAddress,
     pub tls: bool,
+    /// TLS server name, the node host when not set
     pub sni: Option<String>,
     pub weight: Option<u16>,
+    #[serde(default)]
+    pub protocol: UpstreamProtocol,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -23,4 +39,5 @@ pub struct UpstreamConfig {
     pub default: bool,
     pub upstream_nodes: Vec<UpstreamNodeConfig>,
     pub traffic_distribution_policy: LoadBalancerAlgorithm,
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
 }
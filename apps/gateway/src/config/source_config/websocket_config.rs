@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// WebSocket upgrades of a router, upgrade requests are refused on routers without it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebSocketConfig {
+    /// Milliseconds without data from the upstream before the connection is closed. Replaces the
+    /// router `read` and `total` timeouts, which are meant for request/response traffic.
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: u64,
+}
+
+fn default_idle_timeout() -> u64 {
+    300_000
+}
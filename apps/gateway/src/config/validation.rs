@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::{
+    config::{
+        dn_config::DnConfig,
+        source_config::{GatewayConfig, RouterConfig, UpstreamProtocol, PRIMARY_VARIANT},
+    },
+    error::Error,
+    gateway::interceptor_builder::{utils::check_interceptor_order, InterceptorBuilderRegistry},
+};
+
+/// A problem found in a config, `field` is the path of the offending value, e.g.
+/// `routers[1].upstream`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    pub gateway: Option<String>,
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(gateway: Option<&str>, field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            gateway: gateway.map(str::to_string),
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Everything that would make the config fail or misbehave once applied, empty when valid.
+///
+/// Interceptors are built the same way a reload builds them, so their config errors are reported too.
+pub fn validate_config(dn_config: &DnConfig) -> Vec<ConfigIssue> {
+    let mut issues = vec![];
+    if dn_config.gateways.is_empty() {
+        issues.push(ConfigIssue::new(None, "gateways", "no gateway configured"));
+    }
+
+    let mut names = HashSet::new();
+    let mut addresses = HashSet::new();
+    for (i, gateway_config) in dn_config.gateways.iter().enumerate() {
+        if !names.insert(gateway_config.name.as_str()) {
+            issues.push(ConfigIssue::new(
+                None,
+                format!("gateways[{}].name", i),
+                format!("duplicate gateway name {}", gateway_config.name),
+            ));
+        }
+        for (j, inet_address) in gateway_config.bind_addresses.iter().enumerate() {
+            let address = inet_address.get_formatted_address();
+            if !addresses.insert(address.clone()) {
+                issues.push(ConfigIssue::new(
+                    Some(&gateway_config.name),
+                    format!("bind_addresses[{}]", j),
+                    format!("address {} is bound by another gateway", address),
+                ));
+            }
+        }
+        issues.extend(validate_gateway(gateway_config));
+    }
+    issues
+}
+
+fn validate_gateway(gateway_config: &GatewayConfig) -> Vec<ConfigIssue> {
+    let gateway = Some(gateway_config.name.as_str());
+    let mut issues = vec![];
+
+    if gateway_config.bind_addresses.is_empty() {
+        issues.push(ConfigIssue::new(
+            gateway,
+            "bind_addresses",
+            "no bind address",
+        ));
+    }
+
+    let mut upstreams = HashSet::new();
+    for (i, upstream_config) in gateway_config.upstreams.iter().enumerate() {
+        if !upstreams.insert(upstream_config.name.as_str()) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("upstreams[{}].name", i),
+                format!("duplicate upstream name {}", upstream_config.name),
+            ));
+        }
+        if upstream_config.upstream_nodes.is_empty() {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("upstreams[{}].upstream_nodes", i),
+                "no upstream node",
+            ));
+        }
+    }
+
+    let mut filters = HashSet::new();
+    for (i, filter) in gateway_config.filters.iter().enumerate() {
+        if !filters.insert(filter.name.as_str()) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("filters[{}].name", i),
+                format!("duplicate filter name {}", filter.name),
+            ));
+        }
+    }
+
+    let mut routed_filters = HashSet::new();
+    for (i, router_config) in gateway_config.routers.iter().enumerate() {
+        match &router_config.filter {
+            Some(filter) if !filters.contains(filter.as_str()) => {
+                issues.push(ConfigIssue::new(
+                    gateway,
+                    format!("routers[{}].filter", i),
+                    format!("unknown filter {}", filter),
+                ));
+            }
+            Some(filter) if !routed_filters.insert(filter.as_str()) => {
+                issues.push(ConfigIssue::new(
+                    gateway,
+                    format!("routers[{}].filter", i),
+                    format!("filter {} is already routed by another router", filter),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                issues.push(ConfigIssue::new(
+                    gateway,
+                    format!("routers[{}].filter", i),
+                    "router without filter is never selected",
+                ));
+            }
+        }
+        if !upstreams.contains(router_config.upstream.as_str()) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("routers[{}].upstream", i),
+                format!("unknown upstream {}", router_config.upstream),
+            ));
+        }
+        issues.extend(validate_traffic(gateway, i, router_config, &upstreams));
+        if router_config.websocket.is_some() {
+            // Upgrades only exist in HTTP/1.1, `auto` may negotiate HTTP/2 with TLS nodes
+            let http2_node = gateway_config
+                .upstreams
+                .iter()
+                .filter(|upstream_config| {
+                    upstream_config.name == router_config.upstream
+                        || router_config
+                            .variants
+                            .iter()
+                            .any(|variant| variant.upstream == upstream_config.name)
+                })
+                .flat_map(|upstream_config| &upstream_config.upstream_nodes)
+                .any(|node| match node.protocol {
+                    UpstreamProtocol::Http1 => false,
+                    UpstreamProtocol::Http2 => true,
+                    UpstreamProtocol::Auto => node.tls,
+                });
+            if http2_node {
+                issues.push(ConfigIssue::new(
+                    gateway,
+                    format!("routers[{}].websocket", i),
+                    "WebSocket upstream nodes cannot use the http2 protocol, nor auto with TLS",
+                ));
+            }
+        }
+    }
+
+    let interceptor_builder_registry = InterceptorBuilderRegistry::build();
+    let mut interceptors = HashSet::new();
+    for (i, interceptor_config) in gateway_config.interceptors.iter().enumerate() {
+        if !interceptors.insert(interceptor_config.name.as_str()) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("interceptors[{}].name", i),
+                format!("duplicate interceptor name {}", interceptor_config.name),
+            ));
+        }
+        if let Some(filter) = &interceptor_config.filter {
+            if !filters.contains(filter.as_str()) {
+                issues.push(ConfigIssue::new(
+                    gateway,
+                    format!("interceptors[{}].filter", i),
+                    format!("unknown filter {}", filter),
+                ));
+            }
+        }
+        if !interceptor_config.enabled {
+            continue;
+        }
+        let builder = interceptor_builder_registry
+            .registry
+            .get(&interceptor_config.interceptor_type);
+        let built = match builder {
+            Some(builder) => builder.build(interceptor_config.clone()).map(|_| ()),
+            None => Err(Error::from_str("No builder for interceptor type")),
+        };
+        if let Err(e) = built {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("interceptors[{}]", i),
+                format!("{}: {}", interceptor_config.name, e),
+            ));
+        }
+    }
+    if let Err(e) = check_interceptor_order(gateway_config) {
+        issues.push(ConfigIssue::new(gateway, "interceptors", e.to_string()));
+    }
+
+    issues
+}
+
+/// Variants and mirror of a router.
+fn validate_traffic(
+    gateway: Option<&str>,
+    i: usize,
+    router_config: &RouterConfig,
+    upstreams: &HashSet<&str>,
+) -> Vec<ConfigIssue> {
+    let mut issues = vec![];
+
+    let mut names = HashSet::new();
+    let mut total_weight = 0.0;
+    for (j, variant) in router_config.variants.iter().enumerate() {
+        let field = format!("routers[{}].variants[{}]", i, j);
+        if variant.name == PRIMARY_VARIANT || !names.insert(variant.name.as_str()) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("{}.name", field),
+                format!("variant name {} is already used", variant.name),
+            ));
+        }
+        if !upstreams.contains(variant.upstream.as_str()) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("{}.upstream", field),
+                format!("unknown upstream {}", variant.upstream),
+            ));
+        }
+        if !(0.0..=100.0).contains(&variant.weight) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("{}.weight", field),
+                "weight must be a percentage",
+            ));
+        }
+        for (k, pin) in variant.pins.iter().enumerate() {
+            if pin.source.header.is_none() && pin.source.cookie.is_none() {
+                issues.push(ConfigIssue::new(
+                    gateway,
+                    format!("{}.pins[{}]", field, k),
+                    "pin needs a header or a cookie",
+                ));
+            }
+        }
+        total_weight += variant.weight;
+    }
+    if total_weight > 100.0 {
+        issues.push(ConfigIssue::new(
+            gateway,
+            format!("routers[{}].variants", i),
+            format!("variant weights add up to {}, more than 100", total_weight),
+        ));
+    }
+
+    if let Some(mirror) = &router_config.mirror {
+        if !upstreams.contains(mirror.upstream.as_str()) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("routers[{}].mirror.upstream", i),
+                format!("unknown upstream {}", mirror.upstream),
+            ));
+        }
+        if !(0.0..=100.0).contains(&mirror.percentage) {
+            issues.push(ConfigIssue::new(
+                gateway,
+                format!("routers[{}].mirror.percentage", i),
+                "percentage must be between 0 and 100",
+            ));
+        }
+    }
+    issues
+}
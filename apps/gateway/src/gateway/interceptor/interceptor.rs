@@ -56,6 +56,11 @@ pub trait Interceptor: Send + Sync {
     async fn pre_downstream_response_hook(&self, _session: &mut Session) -> PhaseResult {
         Ok(false)
     }
+
+    /// Drop cached responses whose path starts with `path_prefix`, returns the number removed.
+    async fn purge(&self, _path_prefix: &str) -> GatewayResult<usize> {
+        Ok(0)
+    }
 }
 
 pub async fn execute_interceptors<'a>(
@@ -95,6 +100,13 @@ async fn execute_interceptor<'a>(
             );
             interceptor.request_filter(session).await
         }
+        Phase::PreUpstreamRequest => {
+            debug!(
+                "Executing PreUpstreamRequest for interceptor: {:?}",
+                interceptor.interceptor_type()
+            );
+            interceptor.pre_upstream_request(session).await
+        }
         Phase::PostUpstreamResponse => {
             debug!(
                 "Executing PostUpstreamResponse for interceptor: {:?}",
@@ -8,6 +8,10 @@ pub enum InterceptorType {
     RateLimiter,
     TokenAuth,
     AnomalyDetector,
+    RequestRewrite,
+    ResponseRewrite,
+    ShortCircuit,
+    ResponseCache,
 }
 
 impl InterceptorType {
@@ -18,6 +22,10 @@ impl InterceptorType {
             InterceptorType::RateLimiter => "rate_limiter",
             InterceptorType::TokenAuth => "token_auth",
             InterceptorType::AnomalyDetector => "anomaly_detector",
+            InterceptorType::RequestRewrite => "request_rewrite",
+            InterceptorType::ResponseRewrite => "response_rewrite",
+            InterceptorType::ShortCircuit => "short_circuit",
+            InterceptorType::ResponseCache => "response_cache",
         }
     }
 }
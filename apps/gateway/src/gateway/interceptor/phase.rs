@@ -18,6 +18,19 @@ impl Phase {
         self.clone() as PhaseMask
     }
 
+    /// Every phase, in execution order.
+    pub fn all() -> [Phase; 7] {
+        [
+            Phase::Init,
+            Phase::RequestFilter,
+            Phase::UpstreamProxyFilter,
+            Phase::UpstreamPeerSelection,
+            Phase::PreUpstreamRequest,
+            Phase::PostUpstreamResponse,
+            Phase::PreDownstreamResponse,
+        ]
+    }
+
     pub fn all_hook_mask() -> PhaseMask {
         let bits = Phase::Init.mask()
             | Phase::RequestFilter.mask()
@@ -8,7 +8,11 @@ use crate::{
         interceptors::{
             anomaly_detector::AnomalyDetectorInterceptorBuilder,
             cors::CorsInterceptorBuilder, rate_limiter::RateLimiterInterceptorBuilder,
-            request_id::RequestIdInterceptorBuilder, token_auth::TokenAuthInterceptorBuilder,
+            request_id::RequestIdInterceptorBuilder,
+            request_rewrite::RequestRewriteInterceptorBuilder,
+            response_cache::ResponseCacheInterceptorBuilder,
+            response_rewrite::ResponseRewriteInterceptorBuilder,
+            short_circuit::ShortCircuitInterceptorBuilder, token_auth::TokenAuthInterceptorBuilder,
         },
     },
 };
@@ -47,6 +51,22 @@ impl InterceptorBuilderRegistry {
             InterceptorType::AnomalyDetector,
             Arc::new(AnomalyDetectorInterceptorBuilder::default()),
         );
+        registry.insert(
+            InterceptorType::RequestRewrite,
+            Arc::new(RequestRewriteInterceptorBuilder::default()),
+        );
+        registry.insert(
+            InterceptorType::ResponseRewrite,
+            Arc::new(ResponseRewriteInterceptorBuilder::default()),
+        );
+        registry.insert(
+            InterceptorType::ShortCircuit,
+            Arc::new(ShortCircuitInterceptorBuilder::default()),
+        );
+        registry.insert(
+            InterceptorType::ResponseCache,
+            Arc::new(ResponseCacheInterceptorBuilder::default()),
+        );
 
         Self { registry }
     }
@@ -5,7 +5,10 @@ use tracing::debug;
 use crate::{
     config::source_config::{GatewayConfig, InterceptorConfig},
     error::{Error, GatewayResult},
-    gateway::{interceptor::Interceptor, interceptor_builder::InterceptorBuilderRegistry},
+    gateway::{
+        interceptor::{Interceptor, InterceptorType},
+        interceptor_builder::InterceptorBuilderRegistry,
+    },
 };
 
 fn build_interceptor(
@@ -26,11 +29,20 @@ fn build_interceptor(
     Ok(interceptor)
 }
 
+/// A built interceptor with the name it has in the gateway config.
+#[derive(Clone)]
+pub struct NamedInterceptor {
+    pub name: String,
+    pub interceptor: Arc<dyn Interceptor>,
+}
+
+/// Build the enabled interceptors in config order, failing on the first one that cannot be built.
 pub fn build_interceptors(
     gateway_config: &GatewayConfig,
     interceptor_builder_registry: &InterceptorBuilderRegistry,
-) -> GatewayResult<Vec<Arc<dyn Interceptor>>> {
-    let mut interceptors: Vec<Arc<dyn Interceptor>> = vec![];
+) -> GatewayResult<Vec<NamedInterceptor>> {
+    check_interceptor_order(gateway_config)?;
+    let mut interceptors: Vec<NamedInterceptor> = vec![];
 
     for interceptor_config in &gateway_config.interceptors {
         debug!(
@@ -43,8 +55,40 @@ pub fn build_interceptors(
         }
 
         let interceptor = build_interceptor(interceptor_config, interceptor_builder_registry)?;
-        interceptors.push(interceptor);
+        interceptors.push(NamedInterceptor {
+            name: interceptor_config.name.clone(),
+            interceptor,
+        });
     }
 
     Ok(interceptors)
 }
+
+/// Interceptors of a filter run in config order: `response_cache` keys responses on the
+/// `user_id` baggage set by `token_auth`, so it must come after the `token_auth` of its filter.
+pub fn check_interceptor_order(gateway_config: &GatewayConfig) -> GatewayResult<()> {
+    let enabled: Vec<&InterceptorConfig> = gateway_config
+        .interceptors
+        .iter()
+        .filter(|interceptor_config| interceptor_config.enabled)
+        .collect();
+    for (i, response_cache) in enabled.iter().enumerate() {
+        if response_cache.interceptor_type != InterceptorType::ResponseCache {
+            continue;
+        }
+        let token_auth = enabled[i + 1..].iter().find(|interceptor_config| {
+            interceptor_config.interceptor_type == InterceptorType::TokenAuth
+                && interceptor_config.filter == response_cache.filter
+        });
+        if let Some(token_auth) = token_auth {
+            debug!(
+                "Interceptor {} is listed before {}",
+                response_cache.name, token_auth.name
+            );
+            return Err(Error::from_str(
+                "response_cache must be listed after the token_auth of its filter",
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use http::StatusCode;
+use once_cell::sync::Lazy;
+use pingora_http::ResponseHeader;
+use serde_json::json;
+use tracing::{debug, warn};
+
+use crate::{
+    config::proxy::http::Session,
+    error::{Error, GatewayResult},
+    gateway::interceptor::PhaseResult,
+};
+
+use super::rules::DetectionRule;
+
+static SERVICE_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_or_default()
+});
+
+/// Set on the upstream request and the response when a rule asks for a challenge, e.g. a CAPTCHA
+/// shown by the frontend.
+pub const CHALLENGE_HEADER: &str = "X-Anomaly-Challenge";
+
+/// What happens when a rule is violated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Logged and counted, the request goes through
+    Log,
+    /// The request goes through with `X-Anomaly-Challenge` set to the rule name
+    Challenge,
+    /// The request is refused with `429`
+    Throttle,
+    /// Every request of the client is refused with `403` for the duration
+    Block(Duration),
+}
+
+impl Action {
+    pub fn parse(value: &str, block_duration: Duration) -> GatewayResult<Self> {
+        match value {
+            "log" => Ok(Action::Log),
+            "challenge" => Ok(Action::Challenge),
+            "throttle" => Ok(Action::Throttle),
+            "block" => Ok(Action::Block(block_duration)),
+            _ => Err(Error::from_str("Unknown anomaly rule action")),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Log => "log",
+            Action::Challenge => "challenge",
+            Action::Throttle => "throttle",
+            Action::Block(_) => "block",
+        }
+    }
+}
+
+/// A rule with its configured action. Without action the rule answers with its own response.
+pub struct ConfiguredRule {
+    pub rule: Arc<dyn DetectionRule>,
+    pub action: Option<Action>,
+    /// Violations are logged and counted, never enforced
+    pub shadow: bool,
+}
+
+impl ConfiguredRule {
+    pub fn action_name(&self) -> &'static str {
+        self.action.as_ref().map_or("respond", Action::name)
+    }
+}
+
+/// Refuse the request with a plain text reason, the connection is not reused.
+pub async fn write_refusal(
+    session: &mut Session<'_>,
+    status: StatusCode,
+    reason: &str,
+    retry_after: Option<Duration>,
+) -> PhaseResult {
+    let psession = session.get_psession();
+    let mut resp = ResponseHeader::build(status, None).unwrap();
+    let _ = resp.insert_header("Content-Type", "text/plain");
+    if let Some(retry_after) = retry_after {
+        let _ = resp.insert_header("Retry-After", retry_after.as_secs().to_string());
+    }
+    psession.set_keepalive(None);
+    let _ = psession.write_response_header(Box::new(resp), false).await;
+    let _ = psession
+        .write_response_body(Some(Bytes::from(reason.to_string())), true)
+        .await;
+    Ok(true)
+}
+
+/// Report a block set by a rule to the anomaly detector API at `service_url`, which records it
+/// in the block history, escalates repeat offenders and alerts the admins. Sent in the
+/// background: the rule block is already in place.
+pub fn report_block(
+    service_url: &str,
+    client_id: &str,
+    rule_name: &str,
+    reason: &str,
+    duration: Duration,
+) {
+    let url = format!("{}/blocks", service_url.trim_end_matches('/'));
+    let body = json!({
+        "fingerprint": client_id,
+        "reason": reason,
+        "duration_seconds": duration.as_secs(),
+        "blocked_by": format!("rule:{}", rule_name),
+    });
+    let client_id = client_id.to_string();
+    tokio::spawn(async move {
+        let reported = SERVICE_CLIENT
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match reported {
+            Ok(_) => debug!("Block of {} reported to {}", client_id, url),
+            Err(e) => warn!("Failed to report block of {} to {}: {}", client_id, url, e),
+        }
+    });
+}
@@ -1,11 +1,13 @@
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
 
 use shared_shared_data_cache::cache::Cache;
+use tracing::debug;
 
 use crate::{
-    config::source_config::InterceptorConfig,
-    error::GatewayResult,
+    config::source_config::{InterceptorConfig, Pattern, ValueCondition},
+    error::{Error, GatewayResult},
     gateway::{
         interceptor::Interceptor,
         interceptor_builder::InterceptorBuilder,
@@ -13,9 +15,12 @@ use crate::{
 };
 
 use super::{
+    action::{Action, ConfiguredRule},
     interceptor::{AnomalyDetectorConfig, AnomalyDetectorInterceptor},
+    ip_rules::{IpRuleSet, IpRules},
     rules::{
         auth_brute_force::AuthBruteForceRule,
+        custom::{CounterScope, CustomRule, RuleMatch},
         duplicate_payload::DuplicatePayloadRule,
         endpoint_scan::EndpointScanRule,
         payload_size::PayloadSizeRule,
@@ -49,10 +54,16 @@ impl InterceptorBuilder for AnomalyDetectorInterceptorBuilder {
             block_duration: parse_or(&config_map, "block_duration", 600),
             max_distinct_paths: parse_or(&config_map, "max_distinct_paths", 50),
             path_window: parse_or(&config_map, "path_window", 60),
+            shadow: config_map.get("shadow").is_some_and(|v| v == "true"),
+            ip_rules_refresh: parse_or(&config_map, "ip_rules_refresh", 30),
+            service_url: config_map.get("service_url").cloned(),
         };
 
         let cache = Cache::<String, String>::new(&redis_url, "anomaly")
             .map_err(|_| crate::error::Error::from_str("Failed to connect to Redis"))?;
+        let ip_rules_cache = Cache::<String, IpRuleSet>::new(&redis_url, "anomaly")
+            .map_err(|_| Error::from_str("Failed to connect to Redis"))?;
+        let ip_rules = IpRules::new(ip_rules_cache, Duration::from_secs(cfg.ip_rules_refresh));
 
         let rules: Vec<Arc<dyn DetectionRule>> = vec![
             Arc::new(PayloadSizeRule { max_size: cfg.max_payload_size }),
@@ -78,8 +89,42 @@ impl InterceptorBuilder for AnomalyDetectorInterceptorBuilder {
             }),
         ];
 
+        let block_duration = Duration::from_secs(cfg.block_duration);
+        let mut rule_fields = parse_rule_fields(&config_map)?;
+        let mut configured_rules = vec![];
+        for rule in rules {
+            let fields = rule_fields.remove(rule.name()).unwrap_or_default();
+            if let Some(field) = fields.keys().find(|field| !POLICY_FIELDS.contains(&field.as_str())) {
+                debug!(
+                    "Built-in anomaly rule {} does not take {}",
+                    rule.name(),
+                    field
+                );
+                return Err(Error::from_str(
+                    "Built-in anomaly rules only take action, shadow and block_duration",
+                ));
+            }
+            let (action, shadow) = parse_policy(&fields, block_duration)?;
+            configured_rules.push(ConfiguredRule {
+                rule,
+                action,
+                shadow,
+            });
+        }
+        // Custom rules run after the built-in ones, in name order
+        for (name, fields) in rule_fields {
+            let (action, shadow) = parse_policy(&fields, block_duration)?;
+            let rule = parse_custom_rule(name, &fields)?;
+            configured_rules.push(ConfiguredRule {
+                rule: Arc::new(rule),
+                action: Some(action.unwrap_or(Action::Log)),
+                shadow,
+            });
+        }
+
         let filter = interceptor_config.filter;
-        let interceptor = AnomalyDetectorInterceptor::build(filter, cfg, cache, rules);
+        let interceptor =
+            AnomalyDetectorInterceptor::build(filter, cfg, cache, configured_rules, ip_rules);
 
         Ok(Arc::new(interceptor))
     }
@@ -88,3 +133,147 @@ impl InterceptorBuilder for AnomalyDetectorInterceptorBuilder {
 fn parse_or(map: &std::collections::HashMap<String, String>, key: &str, default: u64) -> u64 {
     map.get(key).and_then(|v| v.parse().ok()).unwrap_or(default)
 }
+
+/// Fields every rule takes, built-in or custom.
+const POLICY_FIELDS: [&str; 3] = ["action", "shadow", "block_duration"];
+
+/// `match.*` fields of custom rules, besides `match.header.<name>`.
+const MATCH_FIELDS: [&str; 4] = [
+    "match.method",
+    "match.path",
+    "match.status",
+    "match.min_content_length",
+];
+
+/// `rule.<name>.<field>` entries grouped by rule name.
+fn parse_rule_fields(
+    map: &HashMap<String, String>,
+) -> GatewayResult<BTreeMap<String, HashMap<String, String>>> {
+    let mut rules: BTreeMap<String, HashMap<String, String>> = BTreeMap::new();
+    for (key, value) in map {
+        let Some(rule_key) = key.strip_prefix("rule.") else {
+            continue;
+        };
+        let (name, field) = rule_key
+            .split_once('.')
+            .ok_or_else(|| Error::from_str("Anomaly rule keys are rule.<name>.<field>"))?;
+        rules
+            .entry(name.to_string())
+            .or_default()
+            .insert(field.to_string(), value.clone());
+    }
+    Ok(rules)
+}
+
+fn parse_policy(
+    fields: &HashMap<String, String>,
+    block_duration: Duration,
+) -> GatewayResult<(Option<Action>, bool)> {
+    let block_duration = match fields.get("block_duration") {
+        Some(v) => Duration::from_secs(
+            v.parse()
+                .map_err(|_| Error::from_str("Invalid anomaly rule block_duration"))?,
+        ),
+        None => block_duration,
+    };
+    let action = fields
+        .get("action")
+        .map(|v| Action::parse(v, block_duration))
+        .transpose()?;
+    let shadow = fields.get("shadow").is_some_and(|v| v == "true");
+    Ok((action, shadow))
+}
+
+pub(super) fn parse_custom_rule(name: String, fields: &HashMap<String, String>) -> GatewayResult<CustomRule> {
+    let list = |field: &str| -> Vec<String> {
+        fields
+            .get(field)
+            .map(|v| {
+                v.split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut matcher = RuleMatch {
+        methods: list("match.method")
+            .iter()
+            .map(|method| method.to_uppercase())
+            .collect(),
+        ..Default::default()
+    };
+    if let Some(path) = fields.get("match.path") {
+        matcher.path = Some(Pattern::new(path).map_err(|e| {
+            debug!("Invalid path pattern of anomaly rule {}: {}", name, e);
+            Error::from_str("Invalid anomaly rule match.path")
+        })?);
+    }
+    for status in list("match.status") {
+        let status = status
+            .parse()
+            .map_err(|_| Error::from_str("Invalid anomaly rule match.status"))?;
+        matcher.statuses.push(status);
+    }
+    if let Some(min_content_length) = fields.get("match.min_content_length") {
+        matcher.min_content_length = Some(
+            min_content_length
+                .parse()
+                .map_err(|_| Error::from_str("Invalid anomaly rule match.min_content_length"))?,
+        );
+    }
+
+    for (field, value) in fields {
+        if let Some(header_name) = field.strip_prefix("match.header.") {
+            // `*` only asks for the header, anything else is a pattern of its value
+            let condition = match value.as_str() {
+                "*" => ValueCondition::Exists,
+                pattern => ValueCondition::Regex {
+                    value: Pattern::new(pattern).map_err(|e| {
+                        debug!("Invalid header pattern of anomaly rule {}: {}", name, e);
+                        Error::from_str("Invalid anomaly rule match.header")
+                    })?,
+                },
+            };
+            matcher.headers.push((header_name.to_string(), condition));
+        } else if field.starts_with("match.") {
+            // A misspelled condition would leave the rule matching every request
+            if !MATCH_FIELDS.contains(&field.as_str()) {
+                debug!("Unknown match field {} of anomaly rule {}", field, name);
+                return Err(Error::from_str("Unknown anomaly rule match field"));
+            }
+        } else if !POLICY_FIELDS.contains(&field.as_str())
+            && !["scope", "window", "threshold"].contains(&field.as_str())
+        {
+            debug!("Unknown field {} of anomaly rule {}", field, name);
+            return Err(Error::from_str("Unknown anomaly rule field"));
+        }
+    }
+
+    let scope = match fields.get("scope").map(String::as_str) {
+        None | Some("client") => CounterScope::Client,
+        Some("client_path") => CounterScope::ClientPath,
+        Some("path") => CounterScope::Path,
+        Some(_) => return Err(Error::from_str("Invalid anomaly rule scope")),
+    };
+    let threshold = fields
+        .get("threshold")
+        .ok_or_else(|| Error::from_str("Anomaly rule threshold is required"))?
+        .parse()
+        .map_err(|_| Error::from_str("Invalid anomaly rule threshold"))?;
+    let window = fields
+        .get("window")
+        .map(|v| v.parse::<u64>())
+        .transpose()
+        .map_err(|_| Error::from_str("Invalid anomaly rule window"))?
+        .unwrap_or(60);
+
+    Ok(CustomRule {
+        name,
+        matcher,
+        scope,
+        window: Duration::from_secs(window),
+        threshold,
+    })
+}
@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use async_trait::async_trait;
 use opentelemetry::{global, KeyValue};
 use sha2::{Digest, Sha256};
@@ -11,7 +9,11 @@ use crate::{
     gateway::interceptor::{Interceptor, InterceptorType, Phase, PhaseMask, PhaseResult},
 };
 
-use super::rules::{DetectionRule, RequestContext};
+use super::{
+    action::{report_block, write_refusal, Action, ConfiguredRule, CHALLENGE_HEADER},
+    ip_rules::{IpDecision, IpRules},
+    rules::RequestContext,
+};
 
 pub struct AnomalyDetectorConfig {
     pub redis_url: String,
@@ -26,13 +28,21 @@ pub struct AnomalyDetectorConfig {
     pub block_duration: u64,
     pub max_distinct_paths: u64,
     pub path_window: u64,
+    /// Every rule only logs its violations, to try new rules without enforcing them
+    pub shadow: bool,
+    /// Seconds between reloads of the IP allow and deny lists
+    pub ip_rules_refresh: u64,
+    /// Anomaly detector API that records the blocks set by rules, for their history,
+    /// escalation and alerts
+    pub service_url: Option<String>,
 }
 
 pub struct AnomalyDetectorInterceptor {
     pub(crate) filter: Option<String>,
     pub(crate) config: AnomalyDetectorConfig,
     pub(crate) cache: Cache<String, String>,
-    pub(crate) rules: Vec<Arc<dyn DetectionRule>>,
+    pub(crate) rules: Vec<ConfiguredRule>,
+    pub(crate) ip_rules: IpRules,
 }
 
 impl AnomalyDetectorInterceptor {
@@ -40,9 +50,28 @@ impl AnomalyDetectorInterceptor {
         filter: Option<String>,
         config: AnomalyDetectorConfig,
         cache: Cache<String, String>,
-        rules: Vec<Arc<dyn DetectionRule>>,
+        rules: Vec<ConfiguredRule>,
+        ip_rules: IpRules,
     ) -> Self {
-        Self { filter, config, cache, rules }
+        Self {
+            filter,
+            config,
+            cache,
+            rules,
+            ip_rules,
+        }
+    }
+
+    fn ip_decision(&self, session: &mut Session) -> IpDecision {
+        let ip = session
+            .get_psession()
+            .client_addr()
+            .and_then(|addr| addr.as_inet())
+            .map(|addr| addr.ip());
+        match ip {
+            Some(ip) => self.ip_rules.current().decide(&ip),
+            None => IpDecision::Unlisted,
+        }
     }
 
     /// Extracts a client identity using a priority-based strategy:
@@ -86,6 +115,22 @@ impl AnomalyDetectorInterceptor {
         // Priority 4: Fallback to IP
         format!("ip:{}", ip)
     }
+
+    fn request_context(&self, session: &mut Session, client_id: String) -> RequestContext {
+        let content_length = session
+            .get_req_header("content-length")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        let req_header = session.get_psession().req_header();
+
+        RequestContext {
+            client_ip: client_id,
+            path: req_header.uri.path().to_string(),
+            method: req_header.method.to_string(),
+            content_length,
+            headers: req_header.headers.clone(),
+        }
+    }
 }
 
 #[async_trait]
@@ -106,6 +151,26 @@ impl Interceptor for AnomalyDetectorInterceptor {
         let client_id = self.extract_client_identity(session);
         let path = session.ds_req_path().to_string();
 
+        match self.ip_decision(session) {
+            IpDecision::Allow => return Ok(false),
+            IpDecision::Deny => {
+                warn!(
+                    client_id = %client_id,
+                    rule_name = "ip_deny",
+                    path = %path,
+                    action = "block",
+                    "Client address on the deny list"
+                );
+                global::meter("gateway_anomaly_detector")
+                    .u64_counter("gateway.anomaly.blocked")
+                    .build()
+                    .add(1, &[KeyValue::new("rule", "ip_deny")]);
+                return write_refusal(session, http::StatusCode::FORBIDDEN, "Forbidden", None)
+                    .await;
+            }
+            IpDecision::Unlisted => {}
+        }
+
         // Check manual block first (set by anomaly_detector API)
         let manual_block_key = format!("{}:blocked:manual", client_id);
         if let Ok(Some(_)) = self.cache.get(&manual_block_key) {
@@ -125,37 +190,98 @@ impl Interceptor for AnomalyDetectorInterceptor {
             return Ok(true);
         }
 
-        let method = session
-            .get_req_header(":method")
-            .unwrap_or_default();
-        let content_length = session
-            .get_req_header("content-length")
-            .and_then(|v| v.parse::<u64>().ok())
-            .unwrap_or(0);
+        // Blocked by a rule with the block action
+        let rule_block_key = format!("{}:blocked:rule", client_id);
+        if let Ok(Some(rule)) = self.cache.get(&rule_block_key) {
+            warn!(
+                client_id = %client_id,
+                rule_name = %rule,
+                path = %path,
+                action = "block",
+                "Blocked client"
+            );
+            return write_refusal(session, http::StatusCode::FORBIDDEN, "Forbidden", None).await;
+        }
 
-        let ctx = RequestContext {
-            client_ip: client_id.clone(),
-            path: path.clone(),
-            method,
-            content_length,
-        };
+        let ctx = self.request_context(session, client_id.clone());
 
         let meter = global::meter("gateway_anomaly_detector");
         let counter = meter.u64_counter("gateway.anomaly.blocked").build();
+        let detected = meter.u64_counter("gateway.anomaly.detected").build();
 
-        for rule in &self.rules {
-            if let Some(violation) = rule.check(&ctx, &self.cache).await {
-                warn!(
-                    client_id = %client_id,
-                    rule_name = violation.rule,
-                    violation_type = violation.rule,
-                    path = %path,
-                    action = "block",
-                    reason = %violation.reason,
-                    "Anomaly detected"
-                );
-                counter.add(1, &[KeyValue::new("rule", violation.rule)]);
-                return rule.respond(session, &violation).await;
+        for configured in &self.rules {
+            let Some(violation) = configured.rule.check(&ctx, &self.cache).await else {
+                continue;
+            };
+            let shadow = self.config.shadow || configured.shadow;
+            warn!(
+                client_id = %client_id,
+                rule_name = configured.rule.name(),
+                violation_type = %violation.rule,
+                path = %path,
+                action = configured.action_name(),
+                shadow = shadow,
+                reason = %violation.reason,
+                "Anomaly detected"
+            );
+            detected.add(
+                1,
+                &[
+                    KeyValue::new("rule", violation.rule.clone()),
+                    KeyValue::new("action", configured.action_name()),
+                    KeyValue::new("shadow", shadow),
+                ],
+            );
+            if shadow {
+                continue;
+            }
+
+            let rule_name = configured.rule.name().to_string();
+            match &configured.action {
+                Some(Action::Log) => {}
+                Some(Action::Challenge) => {
+                    session.set_us_req_header(
+                        CHALLENGE_HEADER.to_string(),
+                        rule_name.clone().into_bytes(),
+                    );
+                    session.set_ds_res_header(CHALLENGE_HEADER.to_string(), rule_name.into_bytes());
+                }
+                Some(Action::Throttle) => {
+                    counter.add(1, &[KeyValue::new("rule", violation.rule.clone())]);
+                    return write_refusal(
+                        session,
+                        http::StatusCode::TOO_MANY_REQUESTS,
+                        &violation.reason,
+                        None,
+                    )
+                    .await;
+                }
+                Some(Action::Block(duration)) => {
+                    if let Some(service_url) = &self.config.service_url {
+                        report_block(
+                            service_url,
+                            &client_id,
+                            &rule_name,
+                            &violation.reason,
+                            *duration,
+                        );
+                    }
+                    let _ = self
+                        .cache
+                        .insert(rule_block_key.clone(), rule_name, Some(*duration));
+                    counter.add(1, &[KeyValue::new("rule", violation.rule.clone())]);
+                    return write_refusal(
+                        session,
+                        http::StatusCode::FORBIDDEN,
+                        &violation.reason,
+                        Some(*duration),
+                    )
+                    .await;
+                }
+                None => {
+                    counter.add(1, &[KeyValue::new("rule", violation.rule.clone())]);
+                    return configured.rule.respond(session, &violation).await;
+                }
             }
         }
 
@@ -163,18 +289,17 @@ impl Interceptor for AnomalyDetectorInterceptor {
     }
 
     async fn post_upstream_response(&self, session: &mut Session) -> PhaseResult {
+        if self.ip_decision(session) == IpDecision::Allow {
+            return Ok(false);
+        }
         let client_id = self.extract_client_identity(session);
-        let path = session.ds_req_path().to_string();
-
-        let ctx = RequestContext {
-            client_ip: client_id,
-            path,
-            method: String::new(),
-            content_length: 0,
-        };
+        let ctx = self.request_context(session, client_id);
 
-        for rule in &self.rules {
-            rule.post_response(&ctx, session, &self.cache).await;
+        for configured in &self.rules {
+            configured
+                .rule
+                .post_response(&ctx, session, &self.cache)
+                .await;
         }
 
         Ok(false)
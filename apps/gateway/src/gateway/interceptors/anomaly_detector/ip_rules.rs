@@ -0,0 +1,85 @@
+use std::{
+    net::IpAddr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use shared_shared_data_cache::cache::Cache;
+use shared_shared_data_core::cidr::Cidr;
+use tracing::warn;
+
+/// Key, under the `anomaly` prefix, the anomaly detector API publishes the IP lists to.
+const IP_RULES_KEY: &str = "ip_rules";
+
+/// Allow and deny lists published by the anomaly detector API, database and file ranges merged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpRuleSet {
+    #[serde(default)]
+    pub allow: Vec<Cidr>,
+    #[serde(default)]
+    pub deny: Vec<Cidr>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IpDecision {
+    /// Trusted, the client skips detection
+    Allow,
+    Deny,
+    Unlisted,
+}
+
+impl IpRuleSet {
+    /// The allow list wins, so a trusted host can be carved out of a denied range.
+    pub fn decide(&self, ip: &IpAddr) -> IpDecision {
+        if self.allow.iter().any(|cidr| cidr.contains(ip)) {
+            IpDecision::Allow
+        } else if self.deny.iter().any(|cidr| cidr.contains(ip)) {
+            IpDecision::Deny
+        } else {
+            IpDecision::Unlisted
+        }
+    }
+}
+
+struct Loaded {
+    at: Option<Instant>,
+    rules: Arc<IpRuleSet>,
+}
+
+/// The published lists, read from Redis again once `refresh` has passed.
+pub struct IpRules {
+    cache: Cache<String, IpRuleSet>,
+    refresh: Duration,
+    loaded: RwLock<Loaded>,
+}
+
+impl IpRules {
+    pub fn new(cache: Cache<String, IpRuleSet>, refresh: Duration) -> Self {
+        Self {
+            cache,
+            refresh,
+            loaded: RwLock::new(Loaded {
+                at: None,
+                rules: Arc::new(IpRuleSet::default()),
+            }),
+        }
+    }
+
+    pub fn current(&self) -> Arc<IpRuleSet> {
+        {
+            let loaded = self.loaded.read().unwrap();
+            if loaded.at.is_some_and(|at| at.elapsed() < self.refresh) {
+                return loaded.rules.clone();
+            }
+        }
+        let mut loaded = self.loaded.write().unwrap();
+        // The last lists are kept when Redis is unavailable
+        match self.cache.get(&IP_RULES_KEY.to_string()) {
+            Ok(rules) => loaded.rules = Arc::new(rules.unwrap_or_default()),
+            Err(e) => warn!("Failed to load anomaly IP rules: {}", e),
+        }
+        loaded.at = Some(Instant::now());
+        loaded.rules.clone()
+    }
+}
@@ -1,5 +1,7 @@
+mod action;
 mod builder;
 mod interceptor;
+mod ip_rules;
 pub mod rules;
 
 pub use builder::AnomalyDetectorInterceptorBuilder;
@@ -16,11 +16,15 @@ pub struct AuthBruteForceRule {
 
 #[async_trait]
 impl DetectionRule for AuthBruteForceRule {
+    fn name(&self) -> &str {
+        "auth_brute_force"
+    }
+
     async fn check(&self, ctx: &RequestContext, cache: &Cache<String, String>) -> Option<Violation> {
         let block_key = format!("{}:blocked:auth", ctx.client_ip);
         if let Ok(Some(_)) = cache.get(&block_key) {
             return Some(Violation {
-                rule: "auth_brute_force",
+                rule: "auth_brute_force".to_string(),
                 reason: "IP blocked due to repeated auth failures".to_string(),
                 status_code: 403,
             });
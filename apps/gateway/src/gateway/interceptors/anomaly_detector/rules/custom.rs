@@ -0,0 +1,265 @@
+use async_trait::async_trait;
+use http::StatusCode;
+use shared_shared_data_cache::cache::Cache;
+use std::time::Duration;
+
+use crate::{
+    config::{
+        proxy::http::Session,
+        source_config::{Pattern, ValueCondition},
+    },
+    gateway::{interceptor::PhaseResult, interceptors::anomaly_detector::action::write_refusal},
+};
+
+use super::{DetectionRule, RequestContext, Violation};
+
+/// What a custom rule counter is shared by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CounterScope {
+    Client,
+    /// One counter per client and path
+    ClientPath,
+    /// One counter per path for all clients, e.g. a credential stuffing wave
+    Path,
+}
+
+/// Conditions a request must meet to be counted, all of them.
+#[derive(Debug, Clone, Default)]
+pub struct RuleMatch {
+    pub methods: Vec<String>,
+    pub path: Option<Pattern>,
+    pub headers: Vec<(String, ValueCondition)>,
+    pub min_content_length: Option<u64>,
+    /// Counted on responses with one of these statuses instead of on requests
+    pub statuses: Vec<u16>,
+}
+
+impl RuleMatch {
+    fn matches_request(&self, ctx: &RequestContext) -> bool {
+        if !self.methods.is_empty() && !self.methods.contains(&ctx.method) {
+            return false;
+        }
+        if let Some(path) = &self.path {
+            if !path.is_match(&ctx.path) {
+                return false;
+            }
+        }
+        if let Some(min_content_length) = self.min_content_length {
+            if ctx.content_length < min_content_length {
+                return false;
+            }
+        }
+        self.headers.iter().all(|(name, condition)| {
+            let value = ctx.headers.get(name).and_then(|value| value.to_str().ok());
+            condition.matches(value)
+        })
+    }
+}
+
+/// Rule defined in config: counts the matching requests, or responses, in a window and is
+/// violated once the count goes past `threshold`.
+pub struct CustomRule {
+    pub name: String,
+    pub matcher: RuleMatch,
+    pub scope: CounterScope,
+    pub window: Duration,
+    pub threshold: u64,
+}
+
+impl CustomRule {
+    fn counter_key(&self, ctx: &RequestContext) -> String {
+        match self.scope {
+            CounterScope::Client => format!("{}:rule:{}", ctx.client_ip, self.name),
+            CounterScope::ClientPath => {
+                format!("{}:rule:{}:{}", ctx.client_ip, self.name, ctx.path)
+            }
+            CounterScope::Path => format!("rule:{}:{}", self.name, ctx.path),
+        }
+    }
+
+    fn count(&self, key: &str, cache: &Cache<String, String>) -> u64 {
+        match cache.get(&key.to_string()) {
+            Ok(Some(v)) => v.parse::<u64>().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn increment(&self, key: String, cache: &Cache<String, String>) -> u64 {
+        let new_count = self.count(&key, cache) + 1;
+        let _ = cache.insert(key, new_count.to_string(), Some(self.window));
+        new_count
+    }
+}
+
+#[async_trait]
+impl DetectionRule for CustomRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(
+        &self,
+        ctx: &RequestContext,
+        cache: &Cache<String, String>,
+    ) -> Option<Violation> {
+        if !self.matcher.matches_request(ctx) {
+            return None;
+        }
+        let key = self.counter_key(ctx);
+        // Response counted rules refuse the request after the `threshold`-th matching response
+        let (violated, counted) = if self.matcher.statuses.is_empty() {
+            (self.increment(key, cache) > self.threshold, "requests")
+        } else {
+            (self.count(&key, cache) >= self.threshold, "responses")
+        };
+        if !violated {
+            return None;
+        }
+        Some(Violation {
+            rule: self.name.clone(),
+            reason: format!(
+                "Too many matching {}, {} allowed in {}s",
+                counted,
+                self.threshold,
+                self.window.as_secs()
+            ),
+            status_code: StatusCode::TOO_MANY_REQUESTS.as_u16(),
+        })
+    }
+
+    async fn post_response(
+        &self,
+        ctx: &RequestContext,
+        session: &mut Session,
+        cache: &Cache<String, String>,
+    ) {
+        if self.matcher.statuses.is_empty() || !self.matcher.matches_request(ctx) {
+            return;
+        }
+        let status = session.ds_res_status().map(|s| s.as_u16()).unwrap_or(0);
+        if self.matcher.statuses.contains(&status) {
+            self.increment(self.counter_key(ctx), cache);
+        }
+    }
+
+    async fn respond(&self, session: &mut Session, violation: &Violation) -> PhaseResult {
+        write_refusal(
+            session,
+            StatusCode::TOO_MANY_REQUESTS,
+            &violation.reason,
+            Some(self.window),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use http::HeaderMap;
+
+    use super::*;
+    use crate::{
+        error::GatewayResult, gateway::interceptors::anomaly_detector::builder::parse_custom_rule,
+    };
+
+    fn rule(fields: &[(&str, &str)]) -> GatewayResult<CustomRule> {
+        let fields: HashMap<String, String> = fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), value.to_string()))
+            .collect();
+        parse_custom_rule("login".to_string(), &fields)
+    }
+
+    fn request(client_ip: &str, method: &str, path: &str) -> RequestContext {
+        RequestContext {
+            client_ip: client_ip.to_string(),
+            path: path.to_string(),
+            method: method.to_string(),
+            content_length: 0,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_rule_is_parsed() {
+        let rule = rule(&[
+            ("match.method", "post, put"),
+            ("match.path", "^/login"),
+            ("match.header.x-api-key", "*"),
+            ("match.min_content_length", "10"),
+            ("scope", "client_path"),
+            ("threshold", "3"),
+            ("window", "30"),
+            ("action", "block"),
+        ])
+        .unwrap();
+        assert_eq!(rule.matcher.methods, vec!["POST", "PUT"]);
+        assert_eq!(rule.matcher.path.unwrap().as_str(), "^/login");
+        assert_eq!(rule.matcher.headers.len(), 1);
+        assert_eq!(rule.matcher.min_content_length, Some(10));
+        assert_eq!(rule.scope, CounterScope::ClientPath);
+        assert_eq!(rule.threshold, 3);
+        assert_eq!(rule.window, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        // A misspelled match field must not leave the rule matching everything
+        assert!(rule(&[("match.methods", "POST"), ("threshold", "3")]).is_err());
+        assert!(rule(&[("match.status", "abc"), ("threshold", "3")]).is_err());
+        assert!(rule(&[("match.path", "("), ("threshold", "3")]).is_err());
+        assert!(rule(&[("scope", "user"), ("threshold", "3")]).is_err());
+        assert!(rule(&[("threshold", "3"), ("limit", "5")]).is_err());
+        assert!(rule(&[("match.method", "POST")]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_counted_per_scope() {
+        let cache = Cache::in_memory("anomaly-test");
+        let rule = rule(&[
+            ("match.method", "POST"),
+            ("match.path", "^/login"),
+            ("threshold", "2"),
+        ])
+        .unwrap();
+
+        let login = request("10.0.0.1", "POST", "/login");
+        assert!(rule.check(&login, &cache).await.is_none());
+        // Requests the rule does not match are not counted
+        assert!(rule
+            .check(&request("10.0.0.1", "GET", "/login"), &cache)
+            .await
+            .is_none());
+        assert!(rule.check(&login, &cache).await.is_none());
+        let violation = rule.check(&login, &cache).await.unwrap();
+        assert_eq!(violation.rule, "login");
+        assert_eq!(violation.status_code, 429);
+
+        // Another client has a counter of its own
+        assert!(rule
+            .check(&request("10.0.0.2", "POST", "/login"), &cache)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_path_scope_is_shared_by_clients() {
+        let cache = Cache::in_memory("anomaly-test");
+        let rule = rule(&[("scope", "path"), ("threshold", "1")]).unwrap();
+
+        assert!(rule
+            .check(&request("10.0.0.1", "POST", "/login"), &cache)
+            .await
+            .is_none());
+        assert!(rule
+            .check(&request("10.0.0.2", "POST", "/login"), &cache)
+            .await
+            .is_some());
+        assert!(rule
+            .check(&request("10.0.0.2", "POST", "/register"), &cache)
+            .await
+            .is_none());
+    }
+}
@@ -17,6 +17,10 @@ pub struct DuplicatePayloadRule {
 
 #[async_trait]
 impl DetectionRule for DuplicatePayloadRule {
+    fn name(&self) -> &str {
+        "duplicate_payload"
+    }
+
     async fn check(&self, ctx: &RequestContext, cache: &Cache<String, String>) -> Option<Violation> {
         let mut hasher = Sha256::new();
         hasher.update(format!("{}:{}:{}", ctx.method, ctx.path, ctx.content_length));
@@ -33,7 +37,7 @@ impl DetectionRule for DuplicatePayloadRule {
 
         if new_count > self.threshold {
             return Some(Violation {
-                rule: "duplicate_payload",
+                rule: "duplicate_payload".to_string(),
                 reason: format!("Duplicate payload detected {} times from same client", new_count),
                 status_code: 429,
             });
@@ -57,7 +61,7 @@ impl DetectionRule for DuplicatePayloadRule {
 
         if ip_list.len() as u64 > self.multi_ip_threshold {
             return Some(Violation {
-                rule: "duplicate_payload_multi_ip",
+                rule: "duplicate_payload_multi_ip".to_string(),
                 reason: format!("Same payload from {} distinct IPs", ip_list.len()),
                 status_code: 429,
             });
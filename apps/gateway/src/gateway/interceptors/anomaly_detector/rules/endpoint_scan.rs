@@ -16,12 +16,16 @@ pub struct EndpointScanRule {
 
 #[async_trait]
 impl DetectionRule for EndpointScanRule {
+    fn name(&self) -> &str {
+        "endpoint_scan"
+    }
+
     async fn check(&self, ctx: &RequestContext, cache: &Cache<String, String>) -> Option<Violation> {
         // Check if IP is already blocked
         let block_key = format!("{}:blocked:scan", ctx.client_ip);
         if let Ok(Some(_)) = cache.get(&block_key) {
             return Some(Violation {
-                rule: "endpoint_scan",
+                rule: "endpoint_scan".to_string(),
                 reason: "IP blocked due to endpoint scanning".to_string(),
                 status_code: 403,
             });
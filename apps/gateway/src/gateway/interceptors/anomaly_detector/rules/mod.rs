@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use http::HeaderMap;
 use shared_shared_data_cache::cache::Cache;
 
 use crate::{config::proxy::http::Session, gateway::interceptor::PhaseResult};
@@ -8,22 +9,27 @@ pub mod duplicate_payload;
 pub mod endpoint_scan;
 pub mod auth_brute_force;
 pub mod rapid_path_switch;
+pub mod custom;
 
 pub struct RequestContext {
     pub client_ip: String,
     pub path: String,
     pub method: String,
     pub content_length: u64,
+    pub headers: HeaderMap,
 }
 
 pub struct Violation {
-    pub rule: &'static str,
+    pub rule: String,
     pub reason: String,
     pub status_code: u16,
 }
 
 #[async_trait]
 pub trait DetectionRule: Send + Sync {
+    /// Name the rule is configured under, `rule.<name>.*`
+    fn name(&self) -> &str;
+
     async fn check(&self, ctx: &RequestContext, cache: &Cache<String, String>) -> Option<Violation>;
 
     async fn post_response(
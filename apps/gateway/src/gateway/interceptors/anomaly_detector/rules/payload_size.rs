@@ -13,10 +13,14 @@ pub struct PayloadSizeRule {
 
 #[async_trait]
 impl DetectionRule for PayloadSizeRule {
+    fn name(&self) -> &str {
+        "payload_size"
+    }
+
     async fn check(&self, ctx: &RequestContext, _cache: &Cache<String, String>) -> Option<Violation> {
         if ctx.content_length > self.max_size {
             return Some(Violation {
-                rule: "payload_size",
+                rule: "payload_size".to_string(),
                 reason: format!("Payload size {} exceeds max {}", ctx.content_length, self.max_size),
                 status_code: 413,
             });
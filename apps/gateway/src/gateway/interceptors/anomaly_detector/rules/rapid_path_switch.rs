@@ -16,12 +16,16 @@ pub struct RapidPathSwitchRule {
 
 #[async_trait]
 impl DetectionRule for RapidPathSwitchRule {
+    fn name(&self) -> &str {
+        "rapid_path_switch"
+    }
+
     async fn check(&self, ctx: &RequestContext, cache: &Cache<String, String>) -> Option<Violation> {
         // Check if already blocked
         let block_key = format!("{}:blocked:paths", ctx.client_ip);
         if let Ok(Some(_)) = cache.get(&block_key) {
             return Some(Violation {
-                rule: "rapid_path_switch",
+                rule: "rapid_path_switch".to_string(),
                 reason: "IP blocked due to rapid path switching".to_string(),
                 status_code: 403,
             });
@@ -46,7 +50,7 @@ impl DetectionRule for RapidPathSwitchRule {
         if path_list.len() as u64 > self.max_distinct_paths {
             let _ = cache.insert(block_key, "1".to_string(), Some(self.block_duration));
             return Some(Violation {
-                rule: "rapid_path_switch",
+                rule: "rapid_path_switch".to_string(),
                 reason: format!("Accessed {} distinct paths in window", path_list.len()),
                 status_code: 403,
             });
@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+/// Header changes read from interceptor config keys:
+/// `header.set.<Name>`, `header.add.<Name>` and `header.remove` (comma separated names).
+#[derive(Debug, Default, Clone)]
+pub struct HeaderRewrite {
+    pub set: Vec<(String, Vec<u8>)>,
+    pub add: Vec<(String, Vec<u8>)>,
+    pub remove: Vec<String>,
+}
+
+impl HeaderRewrite {
+    pub fn from_config(config: &HashMap<String, String>) -> Self {
+        let mut rewrite = HeaderRewrite::default();
+        for (key, value) in config {
+            if let Some(name) = key.strip_prefix("header.set.") {
+                rewrite
+                    .set
+                    .push((name.to_string(), value.clone().into_bytes()));
+            } else if let Some(name) = key.strip_prefix("header.add.") {
+                rewrite
+                    .add
+                    .push((name.to_string(), value.clone().into_bytes()));
+            } else if key == "header.remove" {
+                rewrite.remove = value
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+            }
+        }
+        rewrite
+    }
+}
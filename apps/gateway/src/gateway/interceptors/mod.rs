@@ -1,5 +1,10 @@
 pub mod anomaly_detector;
 pub mod cors;
+pub mod header_rewrite;
 pub mod rate_limiter;
 pub mod request_id;
+pub mod request_rewrite;
+pub mod response_cache;
+pub mod response_rewrite;
+pub mod short_circuit;
 pub mod token_auth;
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use tracing::debug;
+use uuid::Uuid;
 
 use crate::{
     config::proxy::http::Session,
@@ -24,23 +25,26 @@ impl Interceptor for CorsInterceptor {
     }
 
     fn phase_mask(&self) -> PhaseMask {
-        Phase::PostUpstreamResponse.mask()
+        Phase::PreUpstreamRequest.mask()
     }
 
     fn filter(&self) -> &Option<String> {
         &self.filter
     }
 
-    async fn post_upstream_response(&self, session: &mut Session) -> PhaseResult {
-        let trace_id = session.get_trace_id();
+    /// Sets the id on the upstream request, replacing any sent by the client, and on the
+    /// response. The trace id when tracing is on, a random one otherwise.
+    async fn pre_upstream_request(&self, session: &mut Session) -> PhaseResult {
+        let mut request_id = session.get_trace_id();
+        if request_id.is_empty() {
+            request_id = Uuid::new_v4().to_string();
+        }
         debug!(
-            "RequestIdInterceptor setting X-Request-Id header with trace_id: {}",
-            trace_id
-        );
-        session.set_ds_res_header(
-            "X-Request-Id".to_string(),
-            trace_id.clone().into_bytes().to_vec(),
+            "RequestIdInterceptor setting X-Request-Id header to {}",
+            request_id
         );
+        session.set_us_req_header("X-Request-Id".to_string(), request_id.clone().into_bytes());
+        session.set_ds_res_header("X-Request-Id".to_string(), request_id.into_bytes());
         Ok(false)
     }
 }
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use crate::{
+    config::source_config::InterceptorConfig,
+    error::GatewayResult,
+    gateway::{interceptor::Interceptor, interceptor_builder::InterceptorBuilder},
+};
+
+use super::{interceptor::RequestRewriteInterceptor, rewrite_parts::RewriteParts};
+
+pub struct RequestRewriteInterceptorBuilder {}
+
+impl Default for RequestRewriteInterceptorBuilder {
+    fn default() -> Self {
+        Self {}
+    }
+}
+
+impl InterceptorBuilder for RequestRewriteInterceptorBuilder {
+    fn build(&self, interceptor_config: InterceptorConfig) -> GatewayResult<Arc<dyn Interceptor>> {
+        let rewrite_parts = RewriteParts::build(&interceptor_config)?;
+        let interceptor =
+            RequestRewriteInterceptor::build(interceptor_config.filter, rewrite_parts);
+        Ok(Arc::new(interceptor))
+    }
+}
@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use http::Uri;
+use tracing::debug;
+
+use crate::{
+    config::proxy::http::Session,
+    error::Error,
+    gateway::interceptor::{Interceptor, InterceptorType, Phase, PhaseMask, PhaseResult},
+};
+
+use super::rewrite_parts::RewriteParts;
+
+pub struct RequestRewriteInterceptor {
+    filter: Option<String>,
+    rewrite_parts: RewriteParts,
+}
+
+impl RequestRewriteInterceptor {
+    pub fn build(filter: Option<String>, rewrite_parts: RewriteParts) -> Self {
+        Self {
+            filter,
+            rewrite_parts,
+        }
+    }
+}
+
+#[async_trait]
+impl Interceptor for RequestRewriteInterceptor {
+    fn interceptor_type(&self) -> InterceptorType {
+        InterceptorType::RequestRewrite
+    }
+
+    fn phase_mask(&self) -> PhaseMask {
+        Phase::PreUpstreamRequest.mask()
+    }
+
+    fn filter(&self) -> &Option<String> {
+        &self.filter
+    }
+
+    async fn pre_upstream_request(&self, session: &mut Session) -> PhaseResult {
+        let headers = &self.rewrite_parts.headers;
+        for header_name in &headers.remove {
+            session.remove_us_req_header(header_name);
+        }
+        for (header_name, header_value) in &headers.set {
+            session.set_us_req_header(header_name.clone(), header_value.clone());
+        }
+        for (header_name, header_value) in &headers.add {
+            session.append_us_req_header(header_name.clone(), header_value.clone());
+        }
+
+        let Some(path_rewrite) = &self.rewrite_parts.path else {
+            return Ok(false);
+        };
+        let path_and_query = session.us_req_path_and_query().unwrap_or("/").to_string();
+        let (path, query) = match path_and_query.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (path_and_query.as_str(), None),
+        };
+        let Some(mut new_path) = path_rewrite.rewrite(path, session.path_params()) else {
+            return Ok(false);
+        };
+        // The original query is kept unless the replacement sets one
+        if let Some(query) = query.filter(|_| !new_path.contains('?')) {
+            new_path.push('?');
+            new_path.push_str(query);
+        }
+
+        debug!(
+            "RequestRewriteInterceptor: {} -> {}",
+            path_and_query, new_path
+        );
+        let uri = new_path.parse::<Uri>().map_err(|e| {
+            debug!(
+                "RequestRewriteInterceptor: invalid path {}: {}",
+                new_path, e
+            );
+            Error::from_str("Invalid rewritten path")
+        })?;
+        session.set_us_req_uri(uri)?;
+        Ok(false)
+    }
+}
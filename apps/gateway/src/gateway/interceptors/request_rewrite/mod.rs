@@ -0,0 +1,5 @@
+mod builder;
+mod interceptor;
+mod rewrite_parts;
+
+pub use builder::RequestRewriteInterceptorBuilder;
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use tracing::debug;
+
+use crate::{
+    config::source_config::{InterceptorConfig, Pattern},
+    error::{Error, GatewayResult},
+    gateway::interceptors::header_rewrite::HeaderRewrite,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    Group(usize),
+    Named(String),
+}
+
+/// Replaces the first match of `pattern` in the upstream path.
+///
+/// The replacement refers to capture groups with `$1`/`${1}` and `${name}`; a name that is not a
+/// group of the pattern is looked up in the parameters captured by the filter path. `$$` is a `$`.
+#[derive(Debug, Clone)]
+pub struct PathRewrite {
+    pattern: Pattern,
+    replacement: Vec<Token>,
+}
+
+impl PathRewrite {
+    pub fn build(pattern: &str, replacement: &str) -> GatewayResult<Self> {
+        let pattern = Pattern::new(pattern).map_err(|e| {
+            debug!("Invalid rewrite pattern {}: {}", pattern, e);
+            Error::from_str("Invalid rewrite pattern")
+        })?;
+        Ok(Self {
+            pattern,
+            replacement: parse_replacement(replacement),
+        })
+    }
+
+    /// The rewritten path, None when the pattern does not match.
+    pub fn rewrite(&self, path: &str, params: &HashMap<String, String>) -> Option<String> {
+        let captures = self.pattern.regex().captures(path.as_bytes()).ok()??;
+        let matched = captures.get(0)?;
+
+        let mut rewritten = String::with_capacity(path.len());
+        rewritten.push_str(&path[..matched.start()]);
+        for token in &self.replacement {
+            match token {
+                Token::Literal(literal) => rewritten.push_str(literal),
+                Token::Group(index) => {
+                    if let Some(group) = captures.get(*index) {
+                        rewritten.push_str(&String::from_utf8_lossy(group.as_bytes()));
+                    }
+                }
+                Token::Named(name) => {
+                    let is_group = self
+                        .pattern
+                        .regex()
+                        .capture_names()
+                        .iter()
+                        .any(|group| group.as_deref() == Some(name.as_str()));
+                    if is_group {
+                        if let Some(group) = captures.name(name) {
+                            rewritten.push_str(&String::from_utf8_lossy(group.as_bytes()));
+                        }
+                    } else if let Some(value) = params.get(name) {
+                        rewritten.push_str(value);
+                    }
+                }
+            }
+        }
+        rewritten.push_str(&path[matched.end()..]);
+        Some(rewritten)
+    }
+}
+
+fn parse_replacement(replacement: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut literal = String::new();
+    let mut chars = replacement.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            literal.push(c);
+            continue;
+        }
+
+        let token = match chars.peek() {
+            Some('$') => {
+                chars.next();
+                literal.push('$');
+                continue;
+            }
+            Some('{') => {
+                chars.next();
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                match name.parse::<usize>() {
+                    Ok(index) => Token::Group(index),
+                    Err(_) => Token::Named(name),
+                }
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    digits.push(*c);
+                    chars.next();
+                }
+                Token::Group(digits.parse().unwrap_or_default())
+            }
+            _ => {
+                literal.push('$');
+                continue;
+            }
+        };
+
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(token);
+    }
+
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    tokens
+}
+
+pub struct RewriteParts {
+    pub path: Option<PathRewrite>,
+    pub headers: HeaderRewrite,
+}
+
+impl RewriteParts {
+    pub fn build(interceptor_config: &InterceptorConfig) -> GatewayResult<Self> {
+        let config = interceptor_config.config.clone().unwrap_or_default();
+
+        let path = match (config.get("path.pattern"), config.get("path.replacement")) {
+            (Some(pattern), Some(replacement)) => Some(PathRewrite::build(pattern, replacement)?),
+            (None, None) => None,
+            _ => {
+                debug!(
+                    "path.pattern and path.replacement must be set together for {:?}",
+                    interceptor_config.name
+                );
+                return Err(Error::from_str("Incomplete path rewrite config"));
+            }
+        };
+
+        Ok(Self {
+            path,
+            headers: HeaderRewrite::from_config(&config),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(value: &str) -> Token {
+        Token::Literal(value.to_string())
+    }
+
+    #[test]
+    fn test_parse_replacement() {
+        assert_eq!(
+            parse_replacement("/v2/$1/${2}/${name}"),
+            vec![
+                literal("/v2/"),
+                Token::Group(1),
+                literal("/"),
+                Token::Group(2),
+                literal("/"),
+                Token::Named("name".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_replacement("$12x"),
+            vec![Token::Group(12), literal("x")]
+        );
+    }
+
+    #[test]
+    fn test_parse_replacement_keeps_lone_dollars() {
+        assert_eq!(parse_replacement("cost$$5"), vec![literal("cost$5")]);
+        assert_eq!(parse_replacement("a$-b$"), vec![literal("a$-b$")]);
+        assert_eq!(parse_replacement(""), vec![]);
+    }
+
+    #[test]
+    fn test_rewrite_groups_and_keeps_the_rest_of_the_path() {
+        let rewrite = PathRewrite::build(r"^/api/v1/(\w+)", "/$1/v2").unwrap();
+        assert_eq!(
+            rewrite.rewrite("/api/v1/users/42", &HashMap::new()),
+            Some("/users/v2/42".to_string())
+        );
+        assert_eq!(rewrite.rewrite("/other", &HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_rewrite_named_groups_before_filter_params() {
+        let rewrite =
+            PathRewrite::build(r"^/shop/(?<item>\w+)", "/${tenant}/items/${item}").unwrap();
+        let params = HashMap::from([
+            ("tenant".to_string(), "acme".to_string()),
+            ("item".to_string(), "from-filter".to_string()),
+        ]);
+        assert_eq!(
+            rewrite.rewrite("/shop/book", &params),
+            Some("/acme/items/book".to_string())
+        );
+        // Unknown names and groups that did not participate are left empty
+        let rewrite = PathRewrite::build(r"^/a(/b)?", "/x${missing}$1").unwrap();
+        assert_eq!(
+            rewrite.rewrite("/a", &HashMap::new()),
+            Some("/x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        assert!(PathRewrite::build("(", "/").is_err());
+    }
+}
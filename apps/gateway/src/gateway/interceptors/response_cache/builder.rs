@@ -0,0 +1,82 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tracing::debug;
+
+use shared_shared_data_cache::cache::Cache;
+
+use crate::{
+    config::source_config::InterceptorConfig,
+    error::{Error, GatewayResult},
+    gateway::{interceptor::Interceptor, interceptor_builder::InterceptorBuilder},
+};
+
+use super::interceptor::{ResponseCacheConfig, ResponseCacheInterceptor};
+
+pub struct ResponseCacheInterceptorBuilder {}
+
+impl Default for ResponseCacheInterceptorBuilder {
+    fn default() -> Self {
+        Self {}
+    }
+}
+
+fn parse_or<T: std::str::FromStr>(config: &HashMap<String, String>, key: &str, default: T) -> T {
+    config
+        .get(key)
+        .and_then(|v| v.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+fn parse_list(config: &HashMap<String, String>, key: &str, default: &str) -> Vec<String> {
+    config
+        .get(key)
+        .map(String::as_str)
+        .unwrap_or(default)
+        .split(',')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+impl InterceptorBuilder for ResponseCacheInterceptorBuilder {
+    fn build(&self, interceptor_config: InterceptorConfig) -> GatewayResult<Arc<dyn Interceptor>> {
+        let config = interceptor_config.config.unwrap_or_default();
+
+        let redis_url = config
+            .get("redis_url")
+            .cloned()
+            .unwrap_or_else(|| "redis://127.0.0.1/".to_string());
+
+        let cache_config = ResponseCacheConfig {
+            default_ttl: parse_or(&config, "default_ttl", 0),
+            max_ttl: parse_or(&config, "max_ttl", 3600),
+            stale_ttl: parse_or(&config, "stale_ttl", 300),
+            max_body_size: parse_or(&config, "max_body_size", 1_048_576),
+            methods: parse_list(&config, "methods", "GET")
+                .into_iter()
+                .map(|method| method.to_uppercase())
+                .collect(),
+            status_codes: parse_list(&config, "status_codes", "200,203,204,301,404,410")
+                .iter()
+                .filter_map(|status| status.parse::<u16>().ok())
+                .collect(),
+            key_query: parse_or(&config, "key.query", true),
+            key_headers: parse_list(&config, "key.headers", ""),
+            key_baggage: parse_list(&config, "key.baggage", ""),
+        };
+        debug!("Response cache config: {:?}", cache_config);
+
+        let responses = Cache::<String, _>::new(&redis_url, "http_cache")
+            .map_err(|_| Error::from_str("Failed to connect to Redis"))?;
+        let vary = Cache::<String, _>::new(&redis_url, "http_cache_vary")
+            .map_err(|_| Error::from_str("Failed to connect to Redis"))?;
+
+        let interceptor = ResponseCacheInterceptor::build(
+            interceptor_config.filter,
+            cache_config,
+            responses,
+            vary,
+        );
+        Ok(Arc::new(interceptor))
+    }
+}
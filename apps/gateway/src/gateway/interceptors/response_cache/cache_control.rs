@@ -0,0 +1,40 @@
+/// Directives of a `Cache-Control` header used by the response cache.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CacheControl {
+    pub no_store: bool,
+    pub no_cache: bool,
+    pub private: bool,
+    pub public: bool,
+    pub max_age: Option<u64>,
+    pub s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    pub fn parse(value: Option<&str>) -> Self {
+        let mut cache_control = CacheControl::default();
+        let Some(value) = value else {
+            return cache_control;
+        };
+        for directive in value.split(',') {
+            let (name, argument) = match directive.split_once('=') {
+                Some((name, argument)) => (name, Some(argument.trim().trim_matches('"'))),
+                None => (directive, None),
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "no-store" => cache_control.no_store = true,
+                "no-cache" => cache_control.no_cache = true,
+                "private" => cache_control.private = true,
+                "public" => cache_control.public = true,
+                "max-age" => cache_control.max_age = argument.and_then(|a| a.parse().ok()),
+                "s-maxage" => cache_control.s_maxage = argument.and_then(|a| a.parse().ok()),
+                _ => {}
+            }
+        }
+        cache_control
+    }
+
+    /// Freshness lifetime for a shared cache, `s-maxage` first.
+    pub fn ttl(&self) -> Option<u64> {
+        self.s_maxage.or(self.max_age)
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// Headers that describe the connection or the cache itself, never stored.
+const SKIPPED_HEADERS: [&str; 7] = [
+    "connection",
+    "keep-alive",
+    "transfer-encoding",
+    "content-length",
+    "set-cookie",
+    "age",
+    "x-cache",
+];
+
+/// Response stored in Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// Base64 encoded
+    pub body: String,
+    pub stored_at: u64,
+    pub fresh_until: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl CachedResponse {
+    pub fn new(status: u16, headers: Vec<(String, String)>, body: &[u8], ttl: u64) -> Self {
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+        };
+        let etag = header("etag");
+        let last_modified = header("last-modified");
+        let headers = headers
+            .into_iter()
+            .filter(|(name, _)| {
+                !SKIPPED_HEADERS
+                    .iter()
+                    .any(|skipped| name.eq_ignore_ascii_case(skipped))
+            })
+            .collect();
+        let stored_at = now();
+        Self {
+            status,
+            headers,
+            body: STANDARD.encode(body),
+            stored_at,
+            fresh_until: stored_at + ttl,
+            etag,
+            last_modified,
+        }
+    }
+
+    pub fn body(&self) -> Bytes {
+        STANDARD
+            .decode(&self.body)
+            .map(Bytes::from)
+            .unwrap_or_default()
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        now() < self.fresh_until
+    }
+
+    pub fn age(&self) -> u64 {
+        now().saturating_sub(self.stored_at)
+    }
+
+    pub fn has_validator(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Whether an `If-None-Match` request header matches the stored `ETag` (weak comparison).
+    pub fn matches_etag(&self, if_none_match: &str) -> bool {
+        let Some(etag) = &self.etag else {
+            return false;
+        };
+        let etag = etag.trim_start_matches("W/");
+        if_none_match
+            .split(',')
+            .map(|candidate| candidate.trim())
+            .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+    }
+
+    /// Start a new freshness lifetime after a successful revalidation.
+    pub fn refresh(&mut self, ttl: u64) {
+        self.stored_at = now();
+        self.fresh_until = self.stored_at + ttl;
+    }
+}
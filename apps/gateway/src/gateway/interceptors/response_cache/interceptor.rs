@@ -0,0 +1,460 @@
+use std::{fmt::Display, time::Duration};
+
+use async_trait::async_trait;
+use http::StatusCode;
+use opentelemetry::baggage::BaggageExt;
+use pingora_http::ResponseHeader;
+use sha2::{Digest, Sha256};
+use tracing::{debug, error};
+
+use shared_shared_data_cache::cache::{escape_pattern, Cache};
+
+use crate::{
+    config::proxy::http::Session,
+    error::{Error, GatewayResult},
+    gateway::interceptor::{Interceptor, InterceptorType, Phase, PhaseMask, PhaseResult},
+};
+
+use super::{cache_control::CacheControl, entry::CachedResponse};
+
+#[derive(Debug)]
+pub struct ResponseCacheConfig {
+    /// Seconds a response without `max-age`/`s-maxage` stays fresh, 0 to not cache it
+    pub default_ttl: u64,
+    pub max_ttl: u64,
+    /// Seconds an expired response with a validator is kept for revalidation
+    pub stale_ttl: u64,
+    pub max_body_size: usize,
+    pub methods: Vec<String>,
+    pub status_codes: Vec<u16>,
+    pub key_query: bool,
+    /// Request headers added to the cache key
+    pub key_headers: Vec<String>,
+    /// Baggage entries added to the cache key, e.g. `user_id` set by `token_auth`
+    pub key_baggage: Vec<String>,
+}
+
+/// Baggage entry of `token_auth` separating the responses of each user.
+const USER_ID_BAGGAGE: &str = "user_id";
+
+/// Cache state of a request, kept in the session between phases.
+#[derive(Clone)]
+struct CacheLookup {
+    base_key: String,
+    /// Expired entry being revalidated with the upstream
+    stale: Option<CachedResponse>,
+}
+
+pub struct ResponseCacheInterceptor {
+    filter: Option<String>,
+    config: ResponseCacheConfig,
+    responses: Cache<String, CachedResponse>,
+    /// Header names of the `Vary` of the last stored response, per base key
+    vary: Cache<String, Vec<String>>,
+}
+
+impl ResponseCacheInterceptor {
+    pub fn build(
+        filter: Option<String>,
+        config: ResponseCacheConfig,
+        responses: Cache<String, CachedResponse>,
+        vary: Cache<String, Vec<String>>,
+    ) -> Self {
+        Self {
+            filter,
+            config,
+            responses,
+            vary,
+        }
+    }
+
+    /// `{filter}:{path}?{query}|{method}|{host}|{key headers}|{key baggage}`, the filter and path
+    /// come first so entries can be purged by path prefix.
+    fn base_key(&self, session: &Session) -> String {
+        let filter_name = session.get_filter().map(|f| f.name).unwrap_or_default();
+        let request = session.route_request();
+
+        let mut query: Vec<&str> = match request.query {
+            Some(query) if self.config.key_query => {
+                query.split('&').filter(|p| !p.is_empty()).collect()
+            }
+            _ => vec![],
+        };
+        query.sort_unstable();
+
+        let headers: Vec<String> = self
+            .config
+            .key_headers
+            .iter()
+            .map(|name| session.get_req_header(name).unwrap_or_default())
+            .collect();
+
+        let baggage: Vec<String> = match session.get_span_context() {
+            Some(context) => self
+                .config
+                .key_baggage
+                .iter()
+                .map(|name| {
+                    context
+                        .baggage()
+                        .get(name.as_str())
+                        .map(|value| value.to_string())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        format!(
+            "{}:{}?{}|{}|{}|{}|{}",
+            filter_name,
+            request.path,
+            query.join("&"),
+            request.method,
+            request.host.unwrap_or_default(),
+            headers.join(","),
+            baggage.join(",")
+        )
+    }
+
+    /// Key of the variant selected by the request headers listed in `Vary`.
+    fn variant_key(&self, session: &Session, base_key: &str, vary: &[String]) -> String {
+        if vary.is_empty() {
+            return base_key.to_string();
+        }
+        let mut hasher = Sha256::new();
+        for name in vary {
+            hasher.update(name.as_bytes());
+            hasher.update(b"=");
+            hasher.update(session.get_req_header(name).unwrap_or_default());
+            hasher.update(b"\n");
+        }
+        format!("{}#{:x}", base_key, hasher.finalize())
+    }
+
+    async fn lookup(&self, session: &Session<'_>, base_key: &str) -> Option<CachedResponse> {
+        let vary_cache = self.vary.clone();
+        let vary_key = base_key.to_string();
+        let vary = match blocking(move || vary_cache.get(&vary_key)).await {
+            Ok(vary) => vary.unwrap_or_default(),
+            Err(e) => {
+                error!("ResponseCacheInterceptor: Redis error: {}", e);
+                return None;
+            }
+        };
+        let key = self.variant_key(session, base_key, &vary);
+        let responses = self.responses.clone();
+        match blocking(move || responses.get(&key)).await {
+            Ok(entry) => entry,
+            Err(e) => {
+                error!("ResponseCacheInterceptor: Redis error: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Freshness lifetime of the upstream response, None when it must not be stored.
+    fn response_ttl(&self, session: &Session) -> Option<u64> {
+        let status = session.ds_res_status()?;
+        if !self.config.status_codes.contains(&status.as_u16()) {
+            return None;
+        }
+        if session.ds_res_header("set-cookie").is_some() {
+            return None;
+        }
+        let vary = session.ds_res_header("vary").unwrap_or_default();
+        if vary.split(',').any(|name| name.trim() == "*") {
+            return None;
+        }
+
+        let cache_control = CacheControl::parse(session.ds_res_header("cache-control").as_deref());
+        if cache_control.no_store || cache_control.private {
+            return None;
+        }
+        // Shared caches only store authorized responses that are explicitly public, or when
+        // the key separates them per user. Other baggage entries, such as a tenant, are not
+        // set for every request and would share the entry between users.
+        let authorized = session.get_req_header("authorization").is_some();
+        let keyed_per_user = self
+            .config
+            .key_baggage
+            .iter()
+            .any(|name| name == USER_ID_BAGGAGE);
+        if authorized
+            && !keyed_per_user
+            && !cache_control.public
+            && cache_control.s_maxage.is_none()
+        {
+            return None;
+        }
+
+        let ttl = if cache_control.no_cache {
+            0
+        } else {
+            cache_control
+                .ttl()
+                .unwrap_or(self.config.default_ttl)
+                .min(self.config.max_ttl)
+        };
+        let has_validator = session.ds_res_header("etag").is_some()
+            || session.ds_res_header("last-modified").is_some();
+        if ttl == 0 && !has_validator {
+            return None;
+        }
+        Some(ttl)
+    }
+
+    /// Lowercase names of the request headers listed in the response `Vary`.
+    fn response_vary(&self, session: &Session) -> Vec<String> {
+        session
+            .ds_res_header("vary")
+            .unwrap_or_default()
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    async fn write_cached(&self, session: &mut Session<'_>, entry: &CachedResponse) -> PhaseResult {
+        let not_modified = session
+            .get_req_header("if-none-match")
+            .is_some_and(|if_none_match| entry.matches_etag(&if_none_match));
+        let (status, body) = if not_modified {
+            (StatusCode::NOT_MODIFIED, None)
+        } else {
+            (
+                StatusCode::from_u16(entry.status).unwrap_or(StatusCode::OK),
+                Some(entry.body()),
+            )
+        };
+
+        let mut resp = ResponseHeader::build(status, None).unwrap();
+        for (header_name, header_value) in &entry.headers {
+            let _ = resp.append_header(header_name.clone(), header_value.as_str());
+        }
+        let _ = resp.insert_header("Age", entry.age().to_string());
+        let _ = resp.insert_header("X-Cache", "HIT");
+        let _ = resp.insert_header(
+            "Content-Length",
+            body.as_ref().map_or(0, |body| body.len()).to_string(),
+        );
+
+        let psession = session.get_psession();
+        let _ = psession
+            .write_response_header(Box::new(resp), body.is_none())
+            .await;
+        if body.is_some() {
+            let _ = psession.write_response_body(body, true).await;
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl Interceptor for ResponseCacheInterceptor {
+    fn interceptor_type(&self) -> InterceptorType {
+        InterceptorType::ResponseCache
+    }
+
+    fn phase_mask(&self) -> PhaseMask {
+        Phase::RequestFilter.mask() | Phase::PostUpstreamResponse.mask()
+    }
+
+    fn filter(&self) -> &Option<String> {
+        &self.filter
+    }
+
+    async fn request_filter(&self, session: &mut Session) -> PhaseResult {
+        let method = session.route_request().method.to_string();
+        let cache_control = CacheControl::parse(session.get_req_header("cache-control").as_deref());
+        if !self.config.methods.contains(&method) || cache_control.no_store {
+            session.set_ds_res_header("X-Cache".to_string(), b"BYPASS".to_vec());
+            return Ok(false);
+        }
+
+        let base_key = self.base_key(session);
+        // `no-cache` asks for a response from the origin, it is still stored
+        let entry = if cache_control.no_cache {
+            None
+        } else {
+            self.lookup(session, &base_key).await
+        };
+
+        let stale = match entry {
+            Some(entry) if entry.is_fresh() => {
+                debug!("ResponseCacheInterceptor: HIT {}", base_key);
+                return self.write_cached(session, &entry).await;
+            }
+            Some(entry) if entry.has_validator() => {
+                debug!("ResponseCacheInterceptor: revalidating {}", base_key);
+                if let Some(etag) = &entry.etag {
+                    session
+                        .set_us_req_header("If-None-Match".to_string(), etag.clone().into_bytes());
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    session.set_us_req_header(
+                        "If-Modified-Since".to_string(),
+                        last_modified.clone().into_bytes(),
+                    );
+                }
+                Some(entry)
+            }
+            _ => None,
+        };
+
+        session
+            .extensions_mut()
+            .insert(CacheLookup { base_key, stale });
+        Ok(false)
+    }
+
+    async fn post_upstream_response(&self, session: &mut Session) -> PhaseResult {
+        let Some(lookup) = session.extensions_mut().remove::<CacheLookup>() else {
+            return Ok(false);
+        };
+
+        if let (Some(StatusCode::NOT_MODIFIED), Some(mut entry)) =
+            (session.ds_res_status(), lookup.stale)
+        {
+            debug!("ResponseCacheInterceptor: REVALIDATED {}", lookup.base_key);
+            let cache_control =
+                CacheControl::parse(session.ds_res_header("cache-control").as_deref());
+            let ttl = cache_control
+                .ttl()
+                .unwrap_or(self.config.default_ttl)
+                .min(self.config.max_ttl);
+            entry.refresh(ttl);
+
+            let body = entry.body();
+            let status = StatusCode::from_u16(entry.status).unwrap_or(StatusCode::OK);
+            session.set_ds_res_status(status)?;
+            for (header_name, header_value) in &entry.headers {
+                session.set_ds_res_header(header_name.clone(), header_value.clone().into_bytes());
+            }
+            session.remove_ds_res_header("transfer-encoding");
+            session.set_ds_res_header(
+                "Content-Length".to_string(),
+                body.len().to_string().into_bytes(),
+            );
+            session.set_ds_res_header("X-Cache".to_string(), b"REVALIDATED".to_vec());
+            session.set_ds_res_body(body);
+
+            let vary = self.response_vary(session);
+            let key = self.variant_key(session, &lookup.base_key, &vary);
+            let redis_ttl = redis_ttl(&entry, ttl, self.config.stale_ttl);
+            let responses = self.responses.clone();
+            let vary_cache = self.vary.clone();
+            tokio::task::spawn_blocking(move || {
+                save(
+                    &responses,
+                    &vary_cache,
+                    lookup.base_key,
+                    vary,
+                    key,
+                    entry,
+                    redis_ttl,
+                );
+            });
+            return Ok(false);
+        }
+
+        session.set_ds_res_header("X-Cache".to_string(), b"MISS".to_vec());
+        let Some(ttl) = self.response_ttl(session) else {
+            return Ok(false);
+        };
+
+        let status = session.ds_res_status().map(|s| s.as_u16()).unwrap_or(200);
+        let headers = session.ds_res_headers();
+        let vary = self.response_vary(session);
+        let key = self.variant_key(session, &lookup.base_key, &vary);
+        let body = session.capture_ds_res_body(self.config.max_body_size);
+        let stale_ttl = self.config.stale_ttl;
+        let responses = self.responses.clone();
+        let vary_cache = self.vary.clone();
+        tokio::spawn(async move {
+            // The sender is dropped when the body is too large or the response is aborted
+            let Ok(body) = body.await else {
+                return;
+            };
+            let entry = CachedResponse::new(status, headers, &body, ttl);
+            let redis_ttl = redis_ttl(&entry, ttl, stale_ttl);
+            tokio::task::spawn_blocking(move || {
+                save(
+                    &responses,
+                    &vary_cache,
+                    lookup.base_key,
+                    vary,
+                    key,
+                    entry,
+                    redis_ttl,
+                );
+            });
+        });
+        Ok(false)
+    }
+
+    async fn purge(&self, path_prefix: &str) -> GatewayResult<usize> {
+        let filter = match &self.filter {
+            Some(filter) => escape_pattern(filter),
+            None => "*".to_string(),
+        };
+        let pattern = format!("{}:{}*", filter, escape_pattern(path_prefix));
+        let responses = self.responses.clone();
+        let vary_cache = self.vary.clone();
+        let purged_pattern = pattern.clone();
+        let removed = blocking(move || {
+            responses
+                .remove_matching(&purged_pattern)
+                .and_then(|removed| {
+                    vary_cache.remove_matching(&purged_pattern)?;
+                    Ok(removed)
+                })
+        })
+        .await
+        .map_err(|e| {
+            error!(
+                "ResponseCacheInterceptor: failed to purge {}: {}",
+                pattern, e
+            );
+            Error::from_str("Failed to purge response cache")
+        })?;
+        Ok(removed)
+    }
+}
+
+/// Runs a call of the synchronous Redis client on the blocking pool, off the async workers.
+async fn blocking<T, E, F>(call: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+    T: Send + 'static,
+    E: Display + Send + 'static,
+{
+    match tokio::task::spawn_blocking(call).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Entries with a validator outlive their freshness by `stale_ttl` so they can be revalidated.
+fn redis_ttl(entry: &CachedResponse, ttl: u64, stale_ttl: u64) -> Duration {
+    let stale_ttl = if entry.has_validator() { stale_ttl } else { 0 };
+    Duration::from_secs((ttl + stale_ttl).max(1))
+}
+
+fn save(
+    responses: &Cache<String, CachedResponse>,
+    vary_cache: &Cache<String, Vec<String>>,
+    base_key: String,
+    vary: Vec<String>,
+    key: String,
+    entry: CachedResponse,
+    redis_ttl: Duration,
+) {
+    let stored = vary_cache
+        .insert(base_key.clone(), vary, Some(redis_ttl))
+        .and_then(|_| responses.insert(key, entry, Some(redis_ttl)));
+    match stored {
+        Ok(_) => debug!("ResponseCacheInterceptor: stored {}", base_key),
+        Err(e) => error!("ResponseCacheInterceptor: failed to store response: {}", e),
+    }
+}
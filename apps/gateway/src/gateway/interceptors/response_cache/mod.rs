@@ -0,0 +1,6 @@
+mod builder;
+mod cache_control;
+mod entry;
+mod interceptor;
+
+pub use builder::ResponseCacheInterceptorBuilder;
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::{
+    config::source_config::InterceptorConfig,
+    error::GatewayResult,
+    gateway::{
+        interceptor::Interceptor, interceptor_builder::InterceptorBuilder,
+        interceptors::header_rewrite::HeaderRewrite,
+    },
+};
+
+use super::interceptor::ResponseRewriteInterceptor;
+
+pub struct ResponseRewriteInterceptorBuilder {}
+
+impl Default for ResponseRewriteInterceptorBuilder {
+    fn default() -> Self {
+        Self {}
+    }
+}
+
+impl InterceptorBuilder for ResponseRewriteInterceptorBuilder {
+    fn build(&self, interceptor_config: InterceptorConfig) -> GatewayResult<Arc<dyn Interceptor>> {
+        let config = interceptor_config.config.unwrap_or_default();
+        let headers = HeaderRewrite::from_config(&config);
+        let interceptor = ResponseRewriteInterceptor::build(interceptor_config.filter, headers);
+        Ok(Arc::new(interceptor))
+    }
+}
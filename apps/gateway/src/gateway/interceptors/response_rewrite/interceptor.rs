@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+
+use crate::{
+    config::proxy::http::Session,
+    gateway::{
+        interceptor::{Interceptor, InterceptorType, Phase, PhaseMask, PhaseResult},
+        interceptors::header_rewrite::HeaderRewrite,
+    },
+};
+
+pub struct ResponseRewriteInterceptor {
+    filter: Option<String>,
+    headers: HeaderRewrite,
+}
+
+impl ResponseRewriteInterceptor {
+    pub fn build(filter: Option<String>, headers: HeaderRewrite) -> Self {
+        Self { filter, headers }
+    }
+}
+
+#[async_trait]
+impl Interceptor for ResponseRewriteInterceptor {
+    fn interceptor_type(&self) -> InterceptorType {
+        InterceptorType::ResponseRewrite
+    }
+
+    fn phase_mask(&self) -> PhaseMask {
+        Phase::PostUpstreamResponse.mask()
+    }
+
+    fn filter(&self) -> &Option<String> {
+        &self.filter
+    }
+
+    async fn post_upstream_response(&self, session: &mut Session) -> PhaseResult {
+        for header_name in &self.headers.remove {
+            session.remove_ds_res_header(header_name);
+        }
+        for (header_name, header_value) in &self.headers.set {
+            session.set_ds_res_header(header_name.clone(), header_value.clone());
+        }
+        for (header_name, header_value) in &self.headers.add {
+            session.append_ds_res_header(header_name.clone(), header_value.clone());
+        }
+        Ok(false)
+    }
+}
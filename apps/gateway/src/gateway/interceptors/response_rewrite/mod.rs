@@ -0,0 +1,4 @@
+mod builder;
+mod interceptor;
+
+pub use builder::ResponseRewriteInterceptorBuilder;
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use crate::{
+    config::source_config::InterceptorConfig,
+    error::GatewayResult,
+    gateway::{interceptor::Interceptor, interceptor_builder::InterceptorBuilder},
+};
+
+use super::{interceptor::ShortCircuitInterceptor, response_parts::ResponseParts};
+
+pub struct ShortCircuitInterceptorBuilder {}
+
+impl Default for ShortCircuitInterceptorBuilder {
+    fn default() -> Self {
+        Self {}
+    }
+}
+
+impl InterceptorBuilder for ShortCircuitInterceptorBuilder {
+    fn build(&self, interceptor_config: InterceptorConfig) -> GatewayResult<Arc<dyn Interceptor>> {
+        let response_parts = ResponseParts::build(&interceptor_config)?;
+        let interceptor = ShortCircuitInterceptor::build(interceptor_config.filter, response_parts);
+        Ok(Arc::new(interceptor))
+    }
+}
@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use pingora_http::ResponseHeader;
+use tracing::debug;
+
+use crate::{
+    config::proxy::http::Session,
+    gateway::interceptor::{Interceptor, InterceptorType, Phase, PhaseMask, PhaseResult},
+};
+
+use super::response_parts::ResponseParts;
+
+/// Answers every request of its filter with a static response, the upstream is never called.
+pub struct ShortCircuitInterceptor {
+    filter: Option<String>,
+    response_parts: ResponseParts,
+}
+
+impl ShortCircuitInterceptor {
+    pub fn build(filter: Option<String>, response_parts: ResponseParts) -> Self {
+        Self {
+            filter,
+            response_parts,
+        }
+    }
+}
+
+#[async_trait]
+impl Interceptor for ShortCircuitInterceptor {
+    fn interceptor_type(&self) -> InterceptorType {
+        InterceptorType::ShortCircuit
+    }
+
+    fn phase_mask(&self) -> PhaseMask {
+        Phase::RequestFilter.mask()
+    }
+
+    fn filter(&self) -> &Option<String> {
+        &self.filter
+    }
+
+    async fn request_filter(&self, session: &mut Session) -> PhaseResult {
+        debug!(
+            "ShortCircuitInterceptor: responding {} for {}",
+            self.response_parts.status_code,
+            session.ds_req_path()
+        );
+        let mut resp = ResponseHeader::build(self.response_parts.status_code, None).unwrap();
+        for (header_name, header_value) in &self.response_parts.headers {
+            let _ = resp.insert_header(header_name.clone(), header_value.as_str());
+        }
+        let body = self.response_parts.body.clone();
+        let _ = resp.insert_header(
+            "Content-Length",
+            body.as_ref().map_or(0, |body| body.len()).to_string(),
+        );
+
+        let psession = session.get_psession();
+        let _ = psession
+            .write_response_header(Box::new(resp), body.is_none())
+            .await;
+        if body.is_some() {
+            let _ = psession.write_response_body(body, true).await;
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,5 @@
+mod builder;
+mod interceptor;
+mod response_parts;
+
+pub use builder::ShortCircuitInterceptorBuilder;
@@ -0,0 +1,53 @@
+use bytes::Bytes;
+use http::StatusCode;
+use tracing::debug;
+
+use crate::{
+    config::source_config::InterceptorConfig,
+    error::{Error, GatewayResult},
+};
+
+/// Static response read from interceptor config keys: `status` (default 200), `body` and
+/// `header.<Name>`.
+pub struct ResponseParts {
+    pub status_code: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Bytes>,
+}
+
+impl ResponseParts {
+    pub fn build(interceptor_config: &InterceptorConfig) -> GatewayResult<Self> {
+        let config = interceptor_config.config.clone().unwrap_or_default();
+
+        let status_code = match config.get("status") {
+            Some(status) => status
+                .parse::<u16>()
+                .ok()
+                .and_then(|status| StatusCode::from_u16(status).ok())
+                .ok_or_else(|| {
+                    debug!(
+                        "Invalid status {} for {:?}",
+                        status, interceptor_config.name
+                    );
+                    Error::from_str("Invalid short circuit status")
+                })?,
+            None => StatusCode::OK,
+        };
+
+        let headers = config
+            .iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix("header.")
+                    .map(|name| (name.to_string(), value.clone()))
+            })
+            .collect();
+
+        let body = config.get("body").map(|body| Bytes::from(body.clone()));
+
+        Ok(Self {
+            status_code,
+            headers,
+            body,
+        })
+    }
+}
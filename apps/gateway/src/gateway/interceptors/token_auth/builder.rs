@@ -1,14 +1,18 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use tracing::debug;
+use uuid::Uuid;
 
 use crate::{
     config::source_config::InterceptorConfig,
-    error::GatewayResult,
+    error::{Error, GatewayResult},
     gateway::{
         interceptor::Interceptor,
         interceptor_builder::InterceptorBuilder,
-        interceptors::token_auth::interceptor::{TokenAuthConfig, TokenAuthInterceptor},
+        interceptors::token_auth::{
+            claims::{AccessRules, Claim},
+            interceptor::{TokenAuthConfig, TokenAuthInterceptor, Verification},
+        },
     },
 };
 
@@ -20,6 +24,29 @@ impl Default for TokenAuthInterceptorBuilder {
     }
 }
 
+fn parse_list(value: Option<&String>) -> Vec<String> {
+    value
+        .map(|value| {
+            value
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `env:NAME` reads the secret from the environment, anything else is the secret itself.
+fn resolve_secret(value: &str) -> GatewayResult<String> {
+    match value.strip_prefix("env:") {
+        Some(name) => std::env::var(name).map_err(|_| {
+            debug!("Client secret variable {} is not set", name);
+            Error::from_str("Client secret variable not set")
+        }),
+        None => Ok(value.to_string()),
+    }
+}
+
 impl InterceptorBuilder for TokenAuthInterceptorBuilder {
     fn build(&self, interceptor_config: InterceptorConfig) -> GatewayResult<Arc<dyn Interceptor>> {
         debug!("TokenAuthInterceptorBuilder");
@@ -29,9 +56,74 @@ impl InterceptorBuilder for TokenAuthInterceptorBuilder {
             .get("use_auth_service")
             .map(|v| v == "true")
             .unwrap_or(false);
+        let verification = match config.get("verification").map(String::as_str) {
+            Some("local") => Verification::Local,
+            Some("auth_service") => Verification::AuthService,
+            Some("none") => Verification::None,
+            Some(_) => return Err(Error::from_str("Invalid token verification mode")),
+            None if use_auth_service => Verification::AuthService,
+            None => Verification::None,
+        };
+
+        let mut client_secrets = HashMap::new();
+        let mut claim_headers = vec![];
+        for (key, value) in &config {
+            if let Some(client_id) = key.strip_prefix("client_secret.") {
+                let client_id = Uuid::parse_str(client_id)
+                    .map_err(|_| Error::from_str("Invalid client id in client_secret"))?;
+                client_secrets.insert(client_id, resolve_secret(value)?);
+            } else if let Some(header_name) = key.strip_prefix("claims.header.") {
+                claim_headers.push((header_name.to_string(), Claim::parse(value)?));
+            }
+        }
+        if verification == Verification::Local && client_secrets.is_empty() {
+            return Err(Error::from_str(
+                "Local token verification needs a client_secret",
+            ));
+        }
+
+        let claim_baggage = match config.get("claims.baggage") {
+            Some(claims) => parse_list(Some(claims))
+                .iter()
+                .map(|claim| Claim::parse(claim))
+                .collect::<GatewayResult<Vec<_>>>()?,
+            // Same baggage as `AccessTokenStruct::to_baggage`, read back by the services
+            None => vec![Claim::UserId, Claim::ClientId, Claim::Accesses],
+        };
+
+        let rules = AccessRules {
+            roles: parse_list(config.get("require.roles")),
+            scopes: parse_list(config.get("require.scopes")),
+        };
+        if verification == Verification::None && (!rules.is_empty() || !claim_headers.is_empty()) {
+            return Err(Error::from_str(
+                "Token auth rules and claim headers need token verification",
+            ));
+        }
+
+        let cache_size = config
+            .get("cache_size")
+            .map(|v| v.parse::<usize>())
+            .transpose()
+            .map_err(|_| Error::from_str("Invalid token auth cache_size"))?
+            .unwrap_or(10_000);
+        let cache_ttl = config
+            .get("cache_ttl")
+            .map(|v| v.parse::<u64>())
+            .transpose()
+            .map_err(|_| Error::from_str("Invalid token auth cache_ttl"))?
+            .unwrap_or(60);
+
         let token_auth_config = TokenAuthConfig {
             verify_endpoint: verify_endpoint,
-            use_auth_service,
+            verification,
+            client_secrets,
+            cache_size,
+            cache_ttl: Duration::from_secs(cache_ttl),
+            rules,
+            claim_headers,
+            claim_baggage,
+            websocket_token_param: config.get("websocket.token_param").cloned(),
         };
         debug!("Token auth config: {:?}", debug(&token_auth_config));
         let interceptor = TokenAuthInterceptor::build(token_auth_config, interceptor_config.filter);
@@ -0,0 +1,168 @@
+use shared_shared_auth::claim::AccessTokenStruct;
+
+use crate::error::{Error, GatewayResult};
+
+/// Claim of an access token that can be forwarded upstream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Claim {
+    UserId,
+    ClientId,
+    /// Role names, comma separated
+    Roles,
+    /// Access keys, comma separated
+    Scopes,
+    /// `ROLE*KEY` entries separated by `|`, as read by `AccessTokenStruct::from_string`
+    Accesses,
+}
+
+impl Claim {
+    pub fn parse(name: &str) -> GatewayResult<Self> {
+        match name.trim() {
+            "user_id" => Ok(Claim::UserId),
+            "client_id" => Ok(Claim::ClientId),
+            "roles" => Ok(Claim::Roles),
+            "scopes" => Ok(Claim::Scopes),
+            "accesses" => Ok(Claim::Accesses),
+            _ => Err(Error::from_str("Unknown token claim")),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Claim::UserId => "user_id",
+            Claim::ClientId => "client_id",
+            Claim::Roles => "roles",
+            Claim::Scopes => "scopes",
+            Claim::Accesses => "accesses",
+        }
+    }
+
+    pub fn value(&self, access_token: &AccessTokenStruct) -> String {
+        match self {
+            Claim::UserId => access_token.user_id.to_string(),
+            Claim::ClientId => access_token.client_id.to_string(),
+            Claim::Roles => access_token
+                .accesses
+                .iter()
+                .map(|access| access.role_name.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            Claim::Scopes => access_token
+                .accesses
+                .iter()
+                .filter_map(|access| access.key.as_deref())
+                .collect::<Vec<_>>()
+                .join(","),
+            Claim::Accesses => access_token.access_to_string(),
+        }
+    }
+}
+
+/// Authorization rules of a filter. A token needs one of `roles` and every one of `scopes`,
+/// scopes being the access keys the roles are granted on.
+#[derive(Debug, Default)]
+pub struct AccessRules {
+    pub roles: Vec<String>,
+    pub scopes: Vec<String>,
+}
+
+impl AccessRules {
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty() && self.scopes.is_empty()
+    }
+
+    /// The reason the token is refused, None when it is allowed.
+    pub fn check(&self, access_token: &AccessTokenStruct) -> Option<String> {
+        let has_role = self.roles.is_empty()
+            || access_token
+                .accesses
+                .iter()
+                .any(|access| self.roles.contains(&access.role_name));
+        if !has_role {
+            return Some(format!(
+                "one of the roles {} is required",
+                self.roles.join(", ")
+            ));
+        }
+
+        let missing: Vec<&str> = self
+            .scopes
+            .iter()
+            .filter(|scope| {
+                !access_token
+                    .accesses
+                    .iter()
+                    .any(|access| access.key.as_ref() == Some(*scope))
+            })
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Some(format!("missing scopes {}", missing.join(", ")));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access_token(accesses: &str) -> AccessTokenStruct {
+        AccessTokenStruct::from_string(&format!(
+            "accesses={},user_id=066df7b0-dcd1-4e7c-94a1-9b5f68794ca7,client_id=123e4567-e89b-12d3-a456-426614174000",
+            accesses
+        ))
+        .unwrap()
+    }
+
+    fn rules(roles: &[&str], scopes: &[&str]) -> AccessRules {
+        AccessRules {
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_one_of_the_roles_is_required() {
+        let token = access_token("EDITOR*SHOP|SUPPORT*");
+        assert_eq!(rules(&["ADMIN", "SUPPORT"], &[]).check(&token), None);
+        assert_eq!(
+            rules(&["ADMIN"], &[]).check(&token),
+            Some("one of the roles ADMIN is required".to_string())
+        );
+        assert!(rules(&[], &[]).is_empty());
+        assert_eq!(rules(&[], &[]).check(&token), None);
+    }
+
+    #[test]
+    fn test_every_scope_is_required() {
+        let token = access_token("EDITOR*SHOP|VIEWER*BLOG");
+        assert_eq!(rules(&[], &["SHOP", "BLOG"]).check(&token), None);
+        assert_eq!(
+            rules(&["EDITOR"], &["SHOP", "WIKI", "MAIL"]).check(&token),
+            Some("missing scopes WIKI, MAIL".to_string())
+        );
+    }
+
+    #[test]
+    fn test_claim_values() {
+        let token = access_token("EDITOR*SHOP|SUPPORT*");
+        assert_eq!(Claim::parse(" roles ").unwrap(), Claim::Roles);
+        assert!(Claim::parse("email").is_err());
+        assert_eq!(Claim::Roles.value(&token), "EDITOR,SUPPORT");
+        assert_eq!(Claim::Scopes.value(&token), "SHOP");
+        assert_eq!(
+            Claim::UserId.value(&token),
+            "066df7b0-dcd1-4e7c-94a1-9b5f68794ca7"
+        );
+        for claim in [
+            Claim::UserId,
+            Claim::ClientId,
+            Claim::Roles,
+            Claim::Scopes,
+            Claim::Accesses,
+        ] {
+            assert_eq!(Claim::parse(claim.name()).unwrap(), claim);
+        }
+    }
+}
@@ -1,7 +1,17 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
 use async_trait::async_trait;
-use opentelemetry::baggage::{Baggage, BaggageExt};
-use tracing::debug;
+use bytes::Bytes;
+use opentelemetry::{baggage::BaggageExt, Context, KeyValue};
+use pingora_http::ResponseHeader;
+use serde_json::json;
+use tracing::{debug, Instrument};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
 
 use crate::{
     config::proxy::http::Session,
@@ -9,26 +19,177 @@ use crate::{
 };
 
 use features_auth_remote::TokenService;
+use shared_shared_auth::{
+    claim::AccessTokenStruct,
+    token::{decode_access_token, insecured_decode_access_token, insecured_decode_exp},
+};
+
+use super::{
+    claims::{AccessRules, Claim},
+    token_cache::TokenCache,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verification {
+    /// Only the presence of a token is checked
+    None,
+    /// `TokenService::validate_token`, which also rejects revoked tokens
+    AuthService,
+    /// Signature and expiry checked with the secret of the token client, revocations are not
+    /// seen before the token expires
+    Local,
+}
 
-#[derive(Debug)]
 pub struct TokenAuthConfig {
     pub verify_endpoint: Option<String>,
-    pub use_auth_service: bool,
+    pub verification: Verification,
+    /// HS256 secret per client id, for local verification
+    pub client_secrets: HashMap<Uuid, String>,
+    pub cache_size: usize,
+    pub cache_ttl: Duration,
+    pub rules: AccessRules,
+    /// Upstream request header and the claim it carries
+    pub claim_headers: Vec<(String, Claim)>,
+    pub claim_baggage: Vec<Claim>,
+    /// Query parameter carrying the token of WebSocket handshakes, browsers cannot set headers
+    pub websocket_token_param: Option<String>,
+}
+
+impl std::fmt::Debug for TokenAuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenAuthConfig")
+            .field("verify_endpoint", &self.verify_endpoint)
+            .field("verification", &self.verification)
+            .field("clients", &self.client_secrets.keys().collect::<Vec<_>>())
+            .field("cache_size", &self.cache_size)
+            .field("cache_ttl", &self.cache_ttl)
+            .field("rules", &self.rules)
+            .field("claim_headers", &self.claim_headers)
+            .field("claim_baggage", &self.claim_baggage)
+            .field("websocket_token_param", &self.websocket_token_param)
+            .finish()
+    }
 }
 
-#[derive(Debug)]
 pub struct TokenAuthInterceptor {
     filter: Option<String>,
     token_auth_config: TokenAuthConfig,
+    token_cache: TokenCache,
+}
+
+/// Instant at which a token expires, from its `exp` claim.
+fn token_expires_at(token: &str) -> Option<Instant> {
+    let exp = insecured_decode_exp(token).ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Instant::now() + Duration::from_secs(exp.checked_sub(now)?))
+}
+
+/// Answer with a JSON error, `WWW-Authenticate` tells the client why per RFC 6750. gRPC clients
+/// get a trailers-only response instead, they only read the `grpc-status`.
+async fn write_auth_error(
+    session: &mut Session<'_>,
+    status: u16,
+    error: &str,
+    message: &str,
+) -> PhaseResult {
+    if session.is_grpc_req() {
+        // PERMISSION_DENIED or UNAUTHENTICATED
+        let grpc_status = if status == 403 { "7" } else { "16" };
+        let mut resp = ResponseHeader::build(200, None).unwrap();
+        let _ = resp.insert_header("Content-Type", "application/grpc");
+        let _ = resp.insert_header("grpc-status", grpc_status);
+        let _ = resp.insert_header("grpc-message", message);
+        let psession = session.get_psession();
+        let _ = psession.write_response_header(Box::new(resp), true).await;
+        return Ok(true);
+    }
+
+    let (title, bearer_error) = match status {
+        403 => ("forbidden", "insufficient_scope"),
+        _ => ("unauthorized", "invalid_token"),
+    };
+    let body = Bytes::from(
+        json!({
+            "error": title,
+            "code": error,
+            "message": message,
+        })
+        .to_string(),
+    );
+
+    let mut resp = ResponseHeader::build(status, None).unwrap();
+    let _ = resp.insert_header("Content-Type", "application/json");
+    let _ = resp.insert_header("Content-Length", body.len().to_string());
+    let _ = resp.insert_header(
+        "WWW-Authenticate",
+        format!("Bearer error=\"{}\"", bearer_error),
+    );
+
+    let psession = session.get_psession();
+    let _ = psession.write_response_header(Box::new(resp), false).await;
+    let _ = psession.write_response_body(Some(body), true).await;
+    Ok(true)
 }
 
 impl TokenAuthInterceptor {
     pub fn build(token_auth_config: TokenAuthConfig, filter: Option<String>) -> Self {
+        let token_cache =
+            TokenCache::new(token_auth_config.cache_size, token_auth_config.cache_ttl);
         Self {
             filter,
             token_auth_config,
+            token_cache,
         }
     }
+
+    fn verify_locally(&self, token: &str) -> Result<AccessTokenStruct, String> {
+        let unverified = insecured_decode_access_token(token).map_err(|e| e.to_string())?;
+        let client_secret = self
+            .token_auth_config
+            .client_secrets
+            .get(&unverified.client_id)
+            .ok_or_else(|| format!("Unknown client {}", unverified.client_id))?;
+        decode_access_token(token, client_secret).map_err(|e| e.to_string())
+    }
+
+    async fn verify(
+        &self,
+        span_context: Context,
+        token: &str,
+    ) -> Result<Arc<AccessTokenStruct>, String> {
+        if let Some(access_token) = self.token_cache.get(token) {
+            debug!("Token found in verified token cache");
+            return Ok(access_token);
+        }
+
+        let access_token = match self.token_auth_config.verification {
+            Verification::Local => self.verify_locally(token)?,
+            _ => {
+                debug!("Using auth service to verify token");
+                let verify_token_span = tracing::info_span!("verify_token");
+                let _ = verify_token_span.set_parent(span_context);
+                TokenService::validate_token(token.to_string())
+                    .instrument(verify_token_span)
+                    .await?
+            }
+        };
+
+        let access_token = Arc::new(access_token);
+        if let Some(expires_at) = token_expires_at(token) {
+            self.token_cache
+                .insert(token, access_token.clone(), expires_at);
+        }
+        Ok(access_token)
+    }
+
+    /// Baggage with the configured claims.
+    fn claim_baggage(&self, access_token: &AccessTokenStruct) -> Vec<KeyValue> {
+        self.token_auth_config
+            .claim_baggage
+            .iter()
+            .map(|claim| KeyValue::new(claim.name(), claim.value(access_token)))
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -46,46 +207,68 @@ impl Interceptor for TokenAuthInterceptor {
     }
 
     async fn request_filter(&self, session: &mut Session) -> PhaseResult {
+        // Claim headers only ever come from a verified token
+        for (header_name, _) in &self.token_auth_config.claim_headers {
+            session.remove_us_req_header(header_name);
+        }
+
         let request_path = session.ds_req_path();
         debug!("Request path: {}", request_path);
         if request_path.starts_with("/public/") {
             debug!("Public path, skipping token auth");
             return Ok(false);
         }
-        let token = session.ds_req_header("Authorization");
+        // Taken even when the header is set, so the token never reaches the upstream URL
+        let query_token = match &self.token_auth_config.websocket_token_param {
+            Some(param) if session.is_upgrade_req() => session.take_ds_req_query_param(param),
+            _ => None,
+        };
+        let token = session.ds_req_header("Authorization").or(query_token);
         if token.is_none() {
             debug!("No Authorization header found");
-            return Ok(true);
+            return write_auth_error(
+                session,
+                401,
+                "missing_token",
+                "Missing Authorization header",
+            )
+            .await;
         }
         let token = token.unwrap();
         // Remove Bearer prefix if present
         let token = token.trim_start_matches("Bearer ").to_string();
 
-        let is_use_auth_service = self.token_auth_config.use_auth_service;
-        if is_use_auth_service {
-            debug!("Using auth service to verify token");
-            let span_context = session.get_span_context().as_ref().unwrap().clone();
-            debug!("Span context in session: {:?}", span_context);
-            let verify_token_span = tracing::info_span!("verify_token");
-            let _ = verify_token_span.set_parent(span_context.clone());
-            let _entered_span = verify_token_span.enter();
-            let verify_data = TokenService::validate_token(token).await;
-            let access_token = match verify_data {
-                Ok(claim_subject) => claim_subject,
-                Err(e) => {
-                    debug!("Token validation failed: {:?}", e);
-                    return Ok(true);
-                }
-            };
-            debug!("Access token result: {:?}", access_token);
-            let updated_span_context = span_context.with_baggage(access_token.to_baggage());
-            session.set_us_req_header("Authorization".to_string(), vec![]);
-            session.set_span_context(updated_span_context.clone());
-            debug!(
-                "Updated span context baggage: {:?}",
-                updated_span_context.baggage()
-            );
+        if self.token_auth_config.verification == Verification::None {
+            return Ok(false);
+        }
+
+        let span_context = session.get_span_context().clone().unwrap_or_default();
+        let access_token = match self.verify(span_context.clone(), &token).await {
+            Ok(access_token) => access_token,
+            Err(e) => {
+                debug!("Token validation failed: {:?}", e);
+                return write_auth_error(session, 401, "invalid_token", "Invalid or expired token")
+                    .await;
+            }
+        };
+        debug!("Access token result: {:?}", access_token);
+
+        if let Some(reason) = self.token_auth_config.rules.check(&access_token) {
+            debug!("Token of user {} refused: {}", access_token.user_id, reason);
+            return write_auth_error(session, 403, "insufficient_scope", &reason).await;
+        }
+
+        // Baggage is replaced, never merged with what the client sent
+        let updated_span_context = span_context.with_baggage(self.claim_baggage(&access_token));
+        session.set_us_req_header("Authorization".to_string(), vec![]);
+        for (header_name, claim) in &self.token_auth_config.claim_headers {
+            session.set_us_req_header(header_name.clone(), claim.value(&access_token).into_bytes());
         }
+        session.set_span_context(updated_span_context.clone());
+        debug!(
+            "Updated span context baggage: {:?}",
+            updated_span_context.baggage()
+        );
 
         Ok(false)
     }
@@ -1,4 +1,6 @@
 mod builder;
+mod claims;
 mod interceptor;
+mod token_cache;
 
 pub use builder::TokenAuthInterceptorBuilder;
@@ -0,0 +1,173 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use sha2::{Digest, Sha256};
+
+use shared_shared_auth::claim::AccessTokenStruct;
+
+struct CacheEntry {
+    access_token: Arc<AccessTokenStruct>,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<[u8; 32], CacheEntry>,
+    /// Keys by last use, the first one is evicted
+    recency: BTreeMap<u64, [u8; 32]>,
+    clock: u64,
+}
+
+/// Verified tokens, least recently used evicted first. Keys are SHA-256 digests so raw tokens
+/// are not kept in memory.
+pub struct TokenCache {
+    capacity: usize,
+    ttl: Duration,
+    state: Mutex<LruState>,
+}
+
+fn cache_key(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+impl TokenCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.capacity > 0 && !self.ttl.is_zero()
+    }
+
+    pub fn get(&self, token: &str) -> Option<Arc<AccessTokenStruct>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let key = cache_key(token);
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let entry = state.entries.get_mut(&key)?;
+        if entry.expires_at <= Instant::now() {
+            let last_used = entry.last_used;
+            state.entries.remove(&key);
+            state.recency.remove(&last_used);
+            return None;
+        }
+        let previous = std::mem::replace(&mut entry.last_used, clock);
+        let access_token = entry.access_token.clone();
+        state.recency.remove(&previous);
+        state.recency.insert(clock, key);
+        Some(access_token)
+    }
+
+    /// Cache a verified token for the cache TTL, or until `token_expires_at` when sooner.
+    pub fn insert(
+        &self,
+        token: &str,
+        access_token: Arc<AccessTokenStruct>,
+        token_expires_at: Instant,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let key = cache_key(token);
+        let expires_at = token_expires_at.min(Instant::now() + self.ttl);
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let previous = state.entries.insert(
+            key,
+            CacheEntry {
+                access_token,
+                expires_at,
+                last_used: clock,
+            },
+        );
+        if let Some(previous) = previous {
+            state.recency.remove(&previous.last_used);
+        }
+        state.recency.insert(clock, key);
+
+        while state.entries.len() > self.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_ID: &str = "066df7b0-dcd1-4e7c-94a1-9b5f68794ca7";
+
+    fn access_token() -> Arc<AccessTokenStruct> {
+        let baggage = format!(
+            "accesses=EDITOR*SHOP,user_id={},client_id={}",
+            USER_ID, USER_ID
+        );
+        Arc::new(AccessTokenStruct::from_string(&baggage).unwrap())
+    }
+
+    fn in_an_hour() -> Instant {
+        Instant::now() + Duration::from_secs(3600)
+    }
+
+    #[test]
+    fn test_cached_until_the_token_expires() {
+        let cache = TokenCache::new(10, Duration::from_secs(60));
+        cache.insert("valid", access_token(), in_an_hour());
+        assert_eq!(cache.get("valid").unwrap().user_id.to_string(), USER_ID);
+        assert!(cache.get("other").is_none());
+
+        // The token expiry wins over a longer cache TTL
+        cache.insert("expired", access_token(), Instant::now());
+        assert!(cache.get("expired").is_none());
+        assert_eq!(cache.state.lock().unwrap().entries.len(), 1);
+    }
+
+    #[test]
+    fn test_cached_until_the_ttl() {
+        let cache = TokenCache::new(10, Duration::from_millis(1));
+        cache.insert("valid", access_token(), in_an_hour());
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("valid").is_none());
+    }
+
+    #[test]
+    fn test_least_recently_used_is_evicted() {
+        let cache = TokenCache::new(2, Duration::from_secs(60));
+        cache.insert("a", access_token(), in_an_hour());
+        cache.insert("b", access_token(), in_an_hour());
+        assert!(cache.get("a").is_some());
+        cache.insert("c", access_token(), in_an_hour());
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+    }
+
+    #[test]
+    fn test_disabled_without_capacity_or_ttl() {
+        for cache in [
+            TokenCache::new(0, Duration::from_secs(60)),
+            TokenCache::new(10, Duration::ZERO),
+        ] {
+            cache.insert("valid", access_token(), in_an_hour());
+            assert!(cache.get("valid").is_none());
+        }
+    }
+}
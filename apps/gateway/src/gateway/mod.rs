@@ -1,12 +1,15 @@
 use std::sync::Arc;
 
-use pingora::{server::configuration::ServerConf, services::listening::Service};
+use pingora::{
+    apps::HttpServerOptions, server::configuration::ServerConf, services::listening::Service,
+};
 use pingora_proxy::{http_proxy_service_with_name, HttpProxy};
 use state::GatewayStateStore;
 
 pub mod interceptor;
 pub mod interceptor_builder;
 pub mod interceptors;
+pub mod registry;
 pub mod state;
 
 use super::Proxy;
@@ -23,6 +26,13 @@ pub fn build_http(
 
     let mut http_proxy_service =
         http_proxy_service_with_name(&server_conf, proxy, gateway_config.name.as_str());
+    if gateway_config.h2c {
+        if let Some(http_proxy) = http_proxy_service.app_logic_mut() {
+            let mut server_options = HttpServerOptions::default();
+            server_options.h2c = true;
+            http_proxy.server_options = Some(server_options);
+        }
+    }
     let binding_address = &gateway_config.bind_addresses;
 
     for inet_address in binding_address {
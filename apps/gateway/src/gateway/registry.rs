@@ -0,0 +1,356 @@
+use std::{
+    io::ErrorKind,
+    net::TcpListener,
+    os::fd::IntoRawFd,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use pingora::{
+    server::{configuration::ServerConf, Fds},
+    services::Service,
+};
+use serde::Serialize;
+use tokio::sync::{watch, Mutex};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{dn_config::DnConfig, source_config::GatewayConfig, validation::ConfigIssue},
+    gateway::{
+        build_http,
+        state::{build_gateway_state_async, GatewayState, GatewayStateStore},
+    },
+};
+
+struct GatewayHandle {
+    name: String,
+    bind_addresses: Vec<String>,
+    store: Arc<GatewayStateStore>,
+    /// Stops the listeners of a gateway added at runtime, None for gateways started with the server
+    shutdown: Option<watch::Sender<bool>>,
+    /// Removed from the config while its listeners belong to the server: it keeps listening
+    /// without any filter until restart, and comes back if re-added with the same addresses.
+    retired: bool,
+}
+
+/// Outcome of applying a config to the running gateways, by gateway name.
+#[derive(Debug, Default, Serialize)]
+pub struct ReloadReport {
+    pub updated: Vec<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Listeners that cannot change without a restart: a gateway started with the server whose
+    /// bind addresses changed keeps its old addresses, a new gateway on a still bound address
+    /// is not started
+    pub restart_required: Vec<String>,
+    /// Gateways whose listeners could not be bound, e.g. an address used by another process.
+    /// A runtime gateway restarted for new addresses is stopped.
+    pub failed: Vec<ConfigIssue>,
+}
+
+/// A gateway to start once the registry lock is released, binding may wait.
+struct PendingStart {
+    state: GatewayState,
+    addresses: Vec<String>,
+    /// Restart of a runtime gateway, reported as updated
+    restart: bool,
+}
+
+/// Attempts to bind an address still held by listeners being stopped, as Pingora does.
+const BIND_ATTEMPTS: u32 = 5;
+const BIND_RETRY_STEP: Duration = Duration::from_millis(200);
+
+/// Running gateways, so the admin API can add and remove them without a restart.
+///
+/// Gateways started with the server are Pingora services and their listeners cannot be closed,
+/// gateways added at runtime run their service on the admin runtime and are stopped on removal.
+pub struct GatewayRegistry {
+    server_conf: Arc<ServerConf>,
+    gateways: RwLock<Vec<GatewayHandle>>,
+}
+
+fn bind_addresses(gateway_config: &GatewayConfig) -> Vec<String> {
+    gateway_config
+        .bind_addresses
+        .iter()
+        .map(|inet_address| inet_address.get_formatted_address())
+        .collect()
+}
+
+async fn build_state(gateway_config: GatewayConfig) -> Result<GatewayState, ConfigIssue> {
+    let name = gateway_config.name.clone();
+    build_gateway_state_async(gateway_config)
+        .await
+        .map_err(|e| ConfigIssue {
+            gateway: Some(name),
+            field: "interceptors".to_string(),
+            message: e.to_string(),
+        })
+}
+
+async fn bind_listener(address: &str) -> std::io::Result<TcpListener> {
+    let mut attempts = 1;
+    loop {
+        match TcpListener::bind(address) {
+            Ok(listener) => {
+                listener.set_nonblocking(true)?;
+                return Ok(listener);
+            }
+            Err(e) if e.kind() == ErrorKind::AddrInUse && attempts < BIND_ATTEMPTS => {
+                attempts += 1;
+                tokio::time::sleep(BIND_RETRY_STEP).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Bind every address up front: the service would otherwise panic in its task on a bind error.
+/// Pingora takes the listeners from the returned table instead of binding them.
+async fn bind_listeners(addresses: &[String]) -> std::io::Result<Fds> {
+    let mut listeners = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        let listener = bind_listener(address)
+            .await
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", address, e)))?;
+        listeners.push((address.clone(), listener));
+    }
+    let mut fds = Fds::new();
+    for (address, listener) in listeners {
+        fds.add(address, listener.into_raw_fd());
+    }
+    Ok(fds)
+}
+
+impl GatewayRegistry {
+    pub fn new(server_conf: Arc<ServerConf>) -> Self {
+        Self {
+            server_conf,
+            gateways: RwLock::new(vec![]),
+        }
+    }
+
+    /// Track a gateway whose service was added to the server at startup.
+    pub fn register(&self, store: Arc<GatewayStateStore>) {
+        let gateway_config = store.get_state().gateway_config().clone();
+        self.gateways.write().unwrap().push(GatewayHandle {
+            name: gateway_config.name.clone(),
+            bind_addresses: bind_addresses(&gateway_config),
+            store,
+            shutdown: None,
+            retired: false,
+        });
+    }
+
+    /// Stores of the gateways serving the current config, in config order.
+    pub fn stores(&self) -> Vec<Arc<GatewayStateStore>> {
+        self.gateways
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|handle| !handle.retired)
+            .map(|handle| handle.store.clone())
+            .collect()
+    }
+
+    async fn start(
+        &self,
+        store: Arc<GatewayStateStore>,
+        addresses: &[String],
+    ) -> std::io::Result<watch::Sender<bool>> {
+        let fds = bind_listeners(addresses).await?;
+        let (shutdown, shutdown_watch) = watch::channel(false);
+        let mut service = build_http(store, self.server_conf.clone());
+        let listeners_per_fd = self.server_conf.listener_tasks_per_fd;
+        tokio::spawn(async move {
+            service
+                .start_service(
+                    Some(Arc::new(Mutex::new(fds))),
+                    shutdown_watch,
+                    listeners_per_fd,
+                )
+                .await;
+        });
+        Ok(shutdown)
+    }
+
+    /// Update gateways matched by name, start the new ones and remove the ones missing from
+    /// `dn_config`. The config is expected to be validated; when a gateway state cannot be built
+    /// nothing is applied and the running gateways keep their state.
+    pub async fn apply(&self, dn_config: &DnConfig) -> Result<ReloadReport, ConfigIssue> {
+        // States are built before taking the lock, building is async
+        let mut new_states = Vec::with_capacity(dn_config.gateways.len());
+        for gateway_config in &dn_config.gateways {
+            new_states.push(build_state(gateway_config.clone()).await?);
+        }
+
+        let retiring: Vec<GatewayConfig> = {
+            let gateways = self.gateways.read().unwrap();
+            gateways
+                .iter()
+                .filter(|handle| handle.shutdown.is_none() && !handle.retired)
+                .filter(|handle| {
+                    !dn_config
+                        .gateways
+                        .iter()
+                        .any(|gateway_config| gateway_config.name == handle.name)
+                })
+                .map(|handle| handle.store.get_state().gateway_config().clone())
+                .collect()
+        };
+        let mut retired_states = Vec::with_capacity(retiring.len());
+        for gateway_config in retiring {
+            let retired_config = GatewayConfig {
+                upstreams: vec![],
+                routers: vec![],
+                interceptors: vec![],
+                filters: vec![],
+                ..gateway_config
+            };
+            retired_states.push(build_state(retired_config).await?);
+        }
+
+        let mut report = ReloadReport::default();
+        let mut updates: Vec<(Arc<GatewayStateStore>, GatewayState)> = vec![];
+        let mut starts: Vec<PendingStart> = vec![];
+        {
+            let mut gateways = self.gateways.write().unwrap();
+
+            for state in retired_states {
+                let name = state.gateway_config().name.clone();
+                if let Some(handle) = gateways.iter_mut().find(|handle| handle.name == name) {
+                    handle.retired = true;
+                    updates.push((handle.store.clone(), state));
+                    warn!(
+                        "Gateway {} removed, it keeps listening on {:?} until restart",
+                        name, handle.bind_addresses
+                    );
+                    report.removed.push(name);
+                }
+            }
+
+            gateways.retain_mut(|handle| {
+                let keep = handle.shutdown.is_none()
+                    || dn_config
+                        .gateways
+                        .iter()
+                        .any(|gateway_config| gateway_config.name == handle.name);
+                if !keep {
+                    if let Some(shutdown) = handle.shutdown.take() {
+                        let _ = shutdown.send(true);
+                    }
+                    info!("Gateway {} removed", handle.name);
+                    report.removed.push(handle.name.clone());
+                }
+                keep
+            });
+
+            for state in new_states {
+                let gateway_config = state.gateway_config().clone();
+                let addresses = bind_addresses(&gateway_config);
+                let address_taken = gateways.iter().any(|handle| {
+                    handle.name != gateway_config.name
+                        && handle
+                            .bind_addresses
+                            .iter()
+                            .any(|address| addresses.contains(address))
+                }) || starts.iter().any(|start| {
+                    start
+                        .addresses
+                        .iter()
+                        .any(|address| addresses.contains(address))
+                });
+                let existing = gateways
+                    .iter_mut()
+                    .find(|handle| handle.name == gateway_config.name);
+
+                match existing {
+                    Some(handle)
+                        if handle.bind_addresses == addresses
+                            && handle.store.get_state().gateway_config().h2c
+                                == gateway_config.h2c =>
+                    {
+                        handle.retired = false;
+                        updates.push((handle.store.clone(), state));
+                        report.updated.push(gateway_config.name);
+                    }
+                    Some(handle) if handle.shutdown.is_some() && !address_taken => {
+                        // Runtime gateway with new addresses or h2c: restart its listeners
+                        if let Some(shutdown) = handle.shutdown.take() {
+                            let _ = shutdown.send(true);
+                        }
+                        starts.push(PendingStart {
+                            state,
+                            addresses,
+                            restart: true,
+                        });
+                    }
+                    Some(handle) => {
+                        handle.retired = false;
+                        updates.push((handle.store.clone(), state));
+                        warn!(
+                            "Gateway {} listeners changed, restart required to listen on {:?}",
+                            gateway_config.name, addresses
+                        );
+                        report.restart_required.push(gateway_config.name);
+                    }
+                    None if address_taken => {
+                        warn!(
+                            "Gateway {} addresses {:?} are still bound, restart required",
+                            gateway_config.name, addresses
+                        );
+                        report.restart_required.push(gateway_config.name);
+                    }
+                    None => {
+                        starts.push(PendingStart {
+                            state,
+                            addresses,
+                            restart: false,
+                        });
+                    }
+                }
+            }
+            // Stopped above, their handle comes back once restarted
+            gateways.retain(|handle| {
+                !starts
+                    .iter()
+                    .any(|start| start.restart && start.state.gateway_config().name == handle.name)
+            });
+        }
+
+        for start in starts {
+            let name = start.state.gateway_config().name.clone();
+            let store = Arc::new(GatewayStateStore::new(start.state));
+            match self.start(store.clone(), &start.addresses).await {
+                Ok(shutdown) => {
+                    info!("Gateway {} started on {:?}", name, start.addresses);
+                    self.gateways.write().unwrap().push(GatewayHandle {
+                        name: name.clone(),
+                        bind_addresses: start.addresses,
+                        store,
+                        shutdown: Some(shutdown),
+                        retired: false,
+                    });
+                    if start.restart {
+                        report.updated.push(name);
+                    } else {
+                        report.added.push(name);
+                    }
+                }
+                Err(e) => {
+                    error!("Gateway {} cannot listen: {}", name, e);
+                    report.failed.push(ConfigIssue {
+                        gateway: Some(name),
+                        field: "bind_addresses".to_string(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        for (store, state) in updates {
+            store.update_state(state).await;
+        }
+        Ok(report)
+    }
+}
@@ -1,61 +1,87 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use arc_swap::ArcSwap;
 use tracing::debug;
 
 use crate::{
-    config::{proxy::http::load_balancer::UpStreamLoadBalaner, source_config::GatewayConfig},
+    config::{
+        proxy::http::{load_balancer::UpStreamLoadBalaner, resilience::RetryBudget},
+        source_config::GatewayConfig,
+    },
+    error::GatewayResult,
     gateway::{
         interceptor::{Interceptor, Phase},
-        interceptor_builder::{utils::build_interceptors, InterceptorBuilderRegistry},
+        interceptor_builder::{
+            utils::{build_interceptors, NamedInterceptor},
+            InterceptorBuilderRegistry,
+        },
     },
 };
 
 #[derive(Clone)]
 pub struct GatewayState {
     gateway_config: GatewayConfig,
-    interceptors: Vec<Arc<dyn Interceptor>>,
+    interceptors: Vec<NamedInterceptor>,
     upstream_load_balancers: Arc<Vec<UpStreamLoadBalaner>>,
+    retry_budgets: Arc<HashMap<String, Arc<RetryBudget>>>,
+}
+
+/// Retry budget per router filter name, for routers with a retry policy.
+fn build_retry_budgets(gateway_config: &GatewayConfig) -> HashMap<String, Arc<RetryBudget>> {
+    gateway_config
+        .routers
+        .iter()
+        .filter_map(|router_config| {
+            let filter = router_config.filter.clone()?;
+            let retry = router_config.retry.as_ref()?;
+            let budget = RetryBudget::new(retry.budget_ratio, retry.budget_min);
+            Some((filter, Arc::new(budget)))
+        })
+        .collect()
 }
 
 impl GatewayState {
-    pub async fn build(gateway_config: GatewayConfig) -> Self {
+    pub async fn build(gateway_config: GatewayConfig) -> GatewayResult<Self> {
         let interceptor_builder_registry = InterceptorBuilderRegistry::build();
-        let interceptors =
-            build_interceptors(&gateway_config, &interceptor_builder_registry).unwrap_or_default();
+        let interceptors = build_interceptors(&gateway_config, &interceptor_builder_registry)?;
         debug!("Loaded {} interceptors", interceptors.len());
 
         let upstream_load_balancers =
             UpStreamLoadBalaner::from_upstream_config(gateway_config.upstreams.clone()).await;
 
-        Self {
+        let retry_budgets = build_retry_budgets(&gateway_config);
+
+        Ok(Self {
             gateway_config,
             interceptors,
             upstream_load_balancers: Arc::new(upstream_load_balancers),
-        }
+            retry_budgets: Arc::new(retry_budgets),
+        })
     }
 
-    pub fn build_sync(gateway_config: GatewayConfig) -> Self {
+    pub fn build_sync(gateway_config: GatewayConfig) -> GatewayResult<Self> {
         let interceptor_builder_registry = InterceptorBuilderRegistry::build();
-        let interceptors =
-            build_interceptors(&gateway_config, &interceptor_builder_registry).unwrap_or_default();
+        let interceptors = build_interceptors(&gateway_config, &interceptor_builder_registry)?;
         debug!("Loaded {} interceptors", interceptors.len());
 
         let upstream_load_balancers =
             UpStreamLoadBalaner::from_upstream_config_sync(gateway_config.upstreams.clone());
 
-        Self {
+        let retry_budgets = build_retry_budgets(&gateway_config);
+
+        Ok(Self {
             gateway_config,
             interceptors,
             upstream_load_balancers: Arc::new(upstream_load_balancers),
-        }
+            retry_budgets: Arc::new(retry_budgets),
+        })
     }
 
     pub fn gateway_config(&self) -> &GatewayConfig {
         &self.gateway_config
     }
 
-    pub fn interceptors(&self) -> &Vec<Arc<dyn Interceptor>> {
+    pub fn interceptors(&self) -> &Vec<NamedInterceptor> {
         &self.interceptors
     }
 
@@ -63,9 +89,14 @@ impl GatewayState {
         self.upstream_load_balancers.clone()
     }
 
+    pub fn retry_budget(&self, filter_name: &str) -> Option<Arc<RetryBudget>> {
+        self.retry_budgets.get(filter_name).cloned()
+    }
+
     pub fn get_interceptors(&self, phase: Phase, filter_name: String) -> Vec<Arc<dyn Interceptor>> {
         self.interceptors
             .iter()
+            .map(|named| &named.interceptor)
             .filter(|interceptor| {
                 let is_match_phase = interceptor.phase_mask() & phase.mask() != 0;
                 let default_filter = String::from("");
@@ -98,14 +129,16 @@ impl GatewayStateStore {
     }
 }
 
-pub fn build_gateway_state(gateway_config: GatewayConfig) -> GatewayState {
-    let gateway_state = GatewayState::build_sync(gateway_config);
+pub fn build_gateway_state(gateway_config: GatewayConfig) -> GatewayResult<GatewayState> {
+    let gateway_state = GatewayState::build_sync(gateway_config)?;
     debug!("Gateway state loaded with interceptors and load balancers");
-    gateway_state
+    Ok(gateway_state)
 }
 
-pub async fn build_gateway_state_async(gateway_config: GatewayConfig) -> GatewayState {
-    let gateway_state = GatewayState::build(gateway_config).await;
+pub async fn build_gateway_state_async(
+    gateway_config: GatewayConfig,
+) -> GatewayResult<GatewayState> {
+    let gateway_state = GatewayState::build(gateway_config).await?;
     debug!("Gateway state loaded with interceptors and load balancers");
-    gateway_state
+    Ok(gateway_state)
 }
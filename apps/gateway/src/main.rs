@@ -18,9 +18,14 @@ use tracing::{debug, info, warn};
 
 use shared_shared_observability::init_log_trace_metric;
 
-use config::{app_config::load_app_config, dn_config::DnConfig, proxy::http::Proxy};
+use config::{
+    app_config::load_app_config,
+    dn_config::DnConfig,
+    proxy::http::{resilience::register_circuit_breaker_gauge, Proxy},
+};
 use gateway::{
     build_http,
+    registry::GatewayRegistry,
     state::{build_gateway_state, GatewayStateStore},
 };
 
@@ -72,26 +77,30 @@ fn main() {
     server.bootstrap();
 
     let dn_config_clone = dn_config.clone();
-    let mut gateway_stores: Vec<Arc<GatewayStateStore>> = Vec::new();
+    let gateway_server_conf: ServerConf = dn_config_clone.clone().into();
+    let gateway_registry = Arc::new(GatewayRegistry::new(Arc::new(gateway_server_conf)));
 
     for gateway_config in &dn_config_clone.gateways {
         let clone_gateway_config = gateway_config.clone();
-        let gateway_state: gateway::state::GatewayState = build_gateway_state(clone_gateway_config);
+        let gateway_state: gateway::state::GatewayState = build_gateway_state(clone_gateway_config)
+            .unwrap_or_else(|e| panic!("Failed to build gateway {}: {}", gateway_config.name, e));
         let gateway_state_store = Arc::new(GatewayStateStore::new(gateway_state));
         let server_conf: ServerConf = dn_config_clone.clone().into();
         let service = build_http(gateway_state_store.clone(), Arc::new(server_conf));
         server.add_service(service);
-        gateway_stores.push(gateway_state_store);
+        gateway_registry.register(gateway_state_store);
     }
 
+    let _circuit_breaker_gauge = register_circuit_breaker_gauge(gateway_registry.clone());
+
     // Admin API as a background service (runs inside Pingora's Tokio runtime)
     let admin_api_key = app_config.admin_api_key.clone();
     if admin_api_key.is_none() {
-        warn!("GATEWAY_ADMIN_API_KEY not set — admin reload endpoint is open (no auth required)");
+        warn!("GATEWAY_ADMIN_API_KEY not set — admin endpoints are open (no auth required)");
     }
     let admin_state = Arc::new(AdminState {
         dp: app_config.dp.clone(),
-        gateway_stores,
+        gateway_registry,
         admin_api_key,
     });
     let admin_port = app_config.admin_port;
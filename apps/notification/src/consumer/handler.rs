@@ -24,6 +24,9 @@ pub async fn handle_consumer_message(
             platform,
             message,
         } => handle_payment_message(notification_state, user_id, platform, message).await,
+        NotificationMessage::SecurityAlert { user_id, message } => {
+            handle_security_alert_message(notification_state, user_id, message).await
+        }
     };
     result
 }
@@ -84,3 +87,29 @@ async fn handle_notification_message<'a>(
     }
     Ok(())
 }
+
+async fn handle_security_alert_message(
+    notification_state: Arc<RwLock<NotificationState>>,
+    user_id: uuid::Uuid,
+    message: String,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client_sender = {
+        let state_read_guard = notification_state.read().unwrap();
+        state_read_guard.get_client_sender_by_user_id(user_id)
+    };
+    let Some(client_sender) = client_sender else {
+        debug!("No client sender found for admin {:?}", user_id);
+        return Err(Box::new(ConsumerError::NotFoundClient { user_id }));
+    };
+    let websocket_message = ServerResponse::SecurityAlert { message };
+    if let Err(e) = client_sender.send(Message::Text(
+        serde_json::to_string(&websocket_message).unwrap().into(),
+    )) {
+        error!("Failed to send security alert to {:?}: {}", user_id, e);
+        return Err(Box::new(ConsumerError::FailedToSendMessage {
+            user_id,
+            message: e.to_string(),
+        }));
+    }
+    Ok(())
+}
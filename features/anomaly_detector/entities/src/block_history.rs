@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{entity::prelude::*, ActiveValue, ConnectionTrait};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// One block applied to a fingerprint, kept after the Redis block expires so repeat offenders
+/// can be escalated.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, Default)]
+#[sea_orm(table_name = "block_history")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub fingerprint: String,
+    pub reason: String,
+    pub duration_seconds: i64,
+    /// 1 for a first block, incremented for every block in the escalation window
+    pub offense: i32,
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub blocked_by: Option<String>,
+    pub blocked_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.id = ActiveValue::Set(Uuid::new_v4());
+            self.blocked_at = ActiveValue::Set(Utc::now().naive_utc());
+        }
+        Ok(self)
+    }
+}
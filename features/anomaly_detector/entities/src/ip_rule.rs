@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{entity::prelude::*, ActiveValue, ConnectionTrait};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An IP address or CIDR range on the allow or deny list.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, Default)]
+#[sea_orm(table_name = "ip_rules")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub cidr: String,
+    /// `allow` or `deny`
+    #[sea_orm(column_type = "String(StringLen::N(16))")]
+    pub kind: String,
+    pub reason: String,
+    #[sea_orm(column_type = "String(StringLen::N(255))", nullable)]
+    pub created_by: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.id = ActiveValue::Set(Uuid::new_v4());
+            self.created_at = ActiveValue::Set(Utc::now().naive_utc());
+        }
+        Ok(self)
+    }
+}
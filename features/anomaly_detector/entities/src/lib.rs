@@ -0,0 +1,2 @@
+pub mod block_history;
+pub mod ip_rule;
@@ -0,0 +1,14 @@
+pub use sea_orm_migration::prelude::{async_trait, MigrationTrait, MigratorTrait};
+
+mod m20261019_000001_create_anomaly_detector_tables;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![Box::new(
+            m20261019_000001_create_anomaly_detector_tables::Migration,
+        )]
+    }
+}
@@ -0,0 +1,150 @@
+use sea_orm_migration::prelude::*;
+
+use features_anomaly_detector_entities::{block_history, ip_rule};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000001_create_anomaly_detector_tables"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create block_history table
+        manager
+            .create_table(
+                Table::create()
+                    .table(block_history::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(block_history::Column::Id)
+                            .uuid()
+                            .extra("DEFAULT gen_random_uuid()")
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(block_history::Column::Fingerprint)
+                            .string()
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(block_history::Column::Reason)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(block_history::Column::DurationSeconds)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(block_history::Column::Offense)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(block_history::Column::BlockedBy)
+                            .string()
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(block_history::Column::BlockedAt)
+                            .date_time()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Index on (fingerprint, blocked_at) for the offense count and history queries
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_block_history_fingerprint_blocked_at")
+                    .table(block_history::Entity)
+                    .col(block_history::Column::Fingerprint)
+                    .col(block_history::Column::BlockedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create ip_rules table
+        manager
+            .create_table(
+                Table::create()
+                    .table(ip_rule::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ip_rule::Column::Id)
+                            .uuid()
+                            .extra("DEFAULT gen_random_uuid()")
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ip_rule::Column::Cidr)
+                            .string()
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ip_rule::Column::Kind)
+                            .string()
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ip_rule::Column::Reason)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(ip_rule::Column::CreatedBy)
+                            .string()
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ip_rule::Column::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A range is on one list only
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ip_rules_cidr")
+                    .table(ip_rule::Entity)
+                    .col(ip_rule::Column::Cidr)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ip_rule::Entity).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(block_history::Entity).to_owned())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+use features_anomaly_detector_migrations::Migrator;
+use sea_orm_migration::prelude::*;
+
+#[async_std::main]
+async fn main() {
+    cli::run_cli(Migrator).await;
+}
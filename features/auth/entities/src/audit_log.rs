@@ -0,0 +1,58 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use shared_shared_macro::Dto;
+
+/// Append-only record of a security-sensitive change, written from the audit topic.
+#[derive(Debug, Clone, DeriveEntityModel, Serialize, Default, Dto)]
+#[sea_orm(table_name = "audit_logs")]
+#[dto(
+    name(AuditLogForCreate),
+    columns(
+        id,
+        service,
+        action,
+        actor_id,
+        actor_client_id,
+        target_type,
+        target_id,
+        before,
+        after,
+        diff,
+        request_id,
+        occurred_at,
+        created_at
+    )
+)]
+pub struct Model {
+    /// Id of the published event, so a redelivered event is stored once
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub service: String,
+    #[sea_orm(column_type = "String(StringLen::N(128))")]
+    pub action: String,
+    #[sea_orm(nullable)]
+    pub actor_id: Option<Uuid>,
+    #[sea_orm(nullable)]
+    pub actor_client_id: Option<Uuid>,
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub target_type: String,
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub target_id: String,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub before: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub after: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub diff: Json,
+    #[sea_orm(column_type = "String(StringLen::N(128))", nullable)]
+    pub request_id: Option<String>,
+    pub occurred_at: DateTime,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{entity::prelude::*, ActiveValue, ConnectionTrait};
+use serde::Serialize;
+
+use shared_shared_macro::Dto;
+
+/// A user account at an external OpenID Connect provider linked to a local user.
+#[derive(Debug, Clone, DeriveEntityModel, Serialize, Default, Dto)]
+#[sea_orm(table_name = "external_identities")]
+#[dto(
+    name(ExternalIdentityForCreate),
+    columns(user_id, provider, subject, email, confirmed)
+)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Provider name as configured in `OIDC_PROVIDERS` (e.g. `google`)
+    #[sea_orm(column_type = "String(StringLen::N(64))")]
+    pub provider: String,
+    /// Stable user id at the provider (`sub` claim)
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub subject: String,
+    #[sea_orm(column_type = "String(StringLen::N(250))", nullable)]
+    pub email: Option<String>,
+    /// False while a link to an existing account waits for the code sent to its email
+    pub confirmed: bool,
+    /// Code mailed for the pending link, the only one `confirm_identity` accepts
+    pub link_code_id: Option<Uuid>,
+    /// Wrong codes tried against `link_code_id`
+    pub link_attempts: i32,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let current_time = Utc::now().naive_utc();
+        self.updated_at = ActiveValue::Set(current_time);
+        if insert {
+            self.created_at = ActiveValue::Set(current_time);
+        }
+        Ok(self)
+    }
+}
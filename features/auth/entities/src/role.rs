@@ -10,10 +10,13 @@ use crate::permission::Model as PermissionModel;
 
 #[derive(Debug, Clone, DeriveEntityModel, Serialize, Default, Dto)]
 #[sea_orm(table_name = "roles")]
-#[dto(name(RoleForCreate), columns(name, description, client_id, is_default))]
+#[dto(
+    name(RoleForCreate),
+    columns(name, description, client_id, is_default, parent_role_id)
+)]
 #[dto(
     name(RoleForUpdate),
-    columns(name, description, client_id, is_default),
+    columns(name, description, client_id, is_default, parent_role_id),
     option
 )]
 pub struct Model {
@@ -26,6 +29,9 @@ pub struct Model {
     #[sea_orm(column_type = "Uuid")]
     pub client_id: Uuid,
     pub is_default: bool,
+    /// Parent role whose permissions are inherited by this role
+    #[sea_orm(column_type = "Uuid", nullable)]
+    pub parent_role_id: Option<Uuid>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 
@@ -10,7 +10,16 @@ use shared_shared_macro::Dto;
 #[sea_orm(table_name = "tokens")]
 #[dto(
     name(TokenForCreate),
-    columns(access_token, refresh_token, user_id, client_id, scopes, code)
+    columns(
+        access_token,
+        refresh_token,
+        user_id,
+        client_id,
+        scopes,
+        code,
+        user_agent,
+        ip_address
+    )
 )]
 #[dto(name(TokenForUpdate), columns(access_token, refresh_token), option)]
 pub struct Model {
@@ -29,6 +38,13 @@ pub struct Model {
     pub access_token_expires_at: DateTime,
     pub refresh_token_expires_at: DateTime,
     pub revoked_at: Option<DateTime>,
+    /// `User-Agent` of the device the session was opened from
+    #[sea_orm(column_type = "String(StringLen::N(512))", nullable)]
+    pub user_agent: Option<String>,
+    #[sea_orm(column_type = "String(StringLen::N(64))", nullable)]
+    pub ip_address: Option<String>,
+    /// Last time the session was verified or refreshed
+    pub last_used_at: Option<DateTime>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
     #[serde(skip_serializing)]
@@ -57,6 +73,7 @@ impl ActiveModelBehavior for ActiveModel {
                 current_time + chrono::Duration::seconds(REFRESH_TOKEN_EXPIRATION),
             );
             self.revoked_at = ActiveValue::Set(None);
+            self.last_used_at = ActiveValue::Set(Some(current_time));
         }
         Ok(self)
     }
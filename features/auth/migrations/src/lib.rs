@@ -1,4 +1,8 @@
 pub use sea_orm_migration::prelude::{async_trait, MigrationTrait, MigratorTrait};
+use shared_shared_migrations::{
+    m20261019_000001_create_outbox_messages, m20261019_000002_create_dlq_messages,
+    m20261019_000003_add_relay_columns_to_outbox_messages,
+};
 
 mod m20220101_000001_create_table;
 mod m20220101_000002_create_id_version_index;
@@ -19,6 +23,11 @@ mod m20260413_seed_roles_and_permissions_for_admin_all;
 mod m20260613_add_is_sent_to_active_codes;
 mod m20260628_drop_unique_on_permission_resource;
 mod m20260717_create_field_permissions;
+mod m20261019_add_link_code_to_external_identities;
+mod m20261019_add_parent_role_id_to_roles;
+mod m20261019_add_session_fields_to_tokens;
+mod m20261019_create_audit_logs;
+mod m20261019_create_external_identities;
 
 pub struct Migrator;
 
@@ -44,6 +53,14 @@ impl MigratorTrait for Migrator {
             Box::new(m20260613_add_is_sent_to_active_codes::Migration),
             Box::new(m20260628_drop_unique_on_permission_resource::Migration),
             Box::new(m20260717_create_field_permissions::Migration),
+            Box::new(m20261019_add_parent_role_id_to_roles::Migration),
+            Box::new(m20261019_create_external_identities::Migration),
+            Box::new(m20261019_create_audit_logs::Migration),
+            Box::new(m20261019_add_session_fields_to_tokens::Migration),
+            Box::new(m20261019_000001_create_outbox_messages::Migration),
+            Box::new(m20261019_000002_create_dlq_messages::Migration),
+            Box::new(m20261019_000003_add_relay_columns_to_outbox_messages::Migration),
+            Box::new(m20261019_add_link_code_to_external_identities::Migration),
 
             // Alawys keep this seeding migration at the end of the list, as it depends on all previous migrations to be applied first.
             Box::new(m20260413_seed_roles_and_permissions_for_admin_all::Migration),
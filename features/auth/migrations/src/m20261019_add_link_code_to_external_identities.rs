@@ -0,0 +1,46 @@
+use features_auth_entities::external_identity;
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_add_link_code_to_external_identities"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(external_identity::Entity)
+                    .add_column(
+                        ColumnDef::new(external_identity::Column::LinkCodeId)
+                            .uuid()
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(external_identity::Column::LinkAttempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(external_identity::Entity)
+                    .drop_column(external_identity::Column::LinkCodeId)
+                    .drop_column(external_identity::Column::LinkAttempts)
+                    .to_owned(),
+            )
+            .await
+    }
+}
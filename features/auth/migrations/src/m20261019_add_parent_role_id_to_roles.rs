@@ -0,0 +1,35 @@
+use features_auth_entities::role;
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_add_parent_role_id_to_roles"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(role::Entity)
+                    .add_column(ColumnDef::new(role::Column::ParentRoleId).uuid().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(role::Entity)
+                    .drop_column(role::Column::ParentRoleId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
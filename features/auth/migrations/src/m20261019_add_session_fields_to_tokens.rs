@@ -0,0 +1,66 @@
+use features_auth_entities::token;
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_add_session_fields_to_tokens"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(token::Entity)
+                    .add_column(
+                        ColumnDef::new(token::Column::UserAgent)
+                            .string_len(512)
+                            .null(),
+                    )
+                    .add_column(
+                        ColumnDef::new(token::Column::IpAddress)
+                            .string_len(64)
+                            .null(),
+                    )
+                    .add_column(ColumnDef::new(token::Column::LastUsedAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tokens_user_id")
+                    .table(token::Entity)
+                    .col(token::Column::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tokens_user_id")
+                    .table(token::Entity)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(token::Entity)
+                    .drop_column(token::Column::UserAgent)
+                    .drop_column(token::Column::IpAddress)
+                    .drop_column(token::Column::LastUsedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
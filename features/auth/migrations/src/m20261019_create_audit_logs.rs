@@ -0,0 +1,135 @@
+use sea_orm_migration::prelude::*;
+
+use features_auth_entities::audit_log;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_create_audit_logs"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(audit_log::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(audit_log::Column::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::Service)
+                            .string()
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::Action)
+                            .string()
+                            .string_len(128)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(audit_log::Column::ActorId).uuid().null())
+                    .col(
+                        ColumnDef::new(audit_log::Column::ActorClientId)
+                            .uuid()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::TargetType)
+                            .string()
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::TargetId)
+                            .string()
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::Before)
+                            .json_binary()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::After)
+                            .json_binary()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::Diff)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::RequestId)
+                            .string()
+                            .string_len(128)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::OccurredAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(audit_log::Column::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // History of one record
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_logs_target")
+                    .table(audit_log::Entity)
+                    .col(audit_log::Column::TargetType)
+                    .col(audit_log::Column::TargetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Everything done by one actor
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_logs_actor_id")
+                    .table(audit_log::Entity)
+                    .col(audit_log::Column::ActorId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Default listing order
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_logs_occurred_at")
+                    .table(audit_log::Entity)
+                    .col(audit_log::Column::OccurredAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(audit_log::Entity).to_owned())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use sea_orm_migration::prelude::*;
+
+use features_auth_entities::external_identity;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_create_external_identities"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(external_identity::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(external_identity::Column::Id)
+                            .uuid()
+                            .extra("DEFAULT gen_random_uuid()")
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(external_identity::Column::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(external_identity::Column::Provider)
+                            .string()
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(external_identity::Column::Subject)
+                            .string()
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(external_identity::Column::Email)
+                            .string()
+                            .string_len(250)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(external_identity::Column::Confirmed)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(external_identity::Column::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(external_identity::Column::UpdatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(external_identity::Entity, external_identity::Column::UserId)
+                            .to(
+                                features_auth_entities::user::Entity,
+                                features_auth_entities::user::Column::Id,
+                            )
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One local user per provider account
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_external_identities_provider_subject")
+                    .table(external_identity::Entity)
+                    .col(external_identity::Column::Provider)
+                    .col(external_identity::Column::Subject)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Index on user_id for listing a user's linked accounts
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_external_identities_user_id")
+                    .table(external_identity::Entity)
+                    .col(external_identity::Column::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(external_identity::Entity).to_owned())
+            .await?;
+        Ok(())
+    }
+}
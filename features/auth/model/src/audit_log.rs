@@ -0,0 +1,83 @@
+use chrono::NaiveDateTime as DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+use shared_shared_macro::{ParamFilter, Response};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use features_auth_entities::audit_log::{AuditLogForCreateDto, ModelOptionDto};
+use shared_shared_audit::AuditEvent;
+
+use shared_shared_data_core::{
+    filter::{FilterEnum, FilterParam},
+    filter_deserialize::*,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Default, Response, ParamFilter)]
+pub struct AuditLogData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_client_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[skip_param]
+    pub before: Option<Json>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[skip_param]
+    pub after: Option<Json>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[skip_param]
+    pub diff: Option<Json>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub occurred_at: Option<DateTime>,
+}
+
+impl From<ModelOptionDto> for AuditLogData {
+    fn from(val: ModelOptionDto) -> Self {
+        AuditLogData {
+            id: val.id,
+            service: val.service,
+            action: val.action,
+            actor_id: val.actor_id.flatten(),
+            actor_client_id: val.actor_client_id.flatten(),
+            target_type: val.target_type,
+            target_id: val.target_id,
+            before: val.before.flatten(),
+            after: val.after.flatten(),
+            diff: val.diff,
+            request_id: val.request_id.flatten(),
+            occurred_at: val.occurred_at,
+        }
+    }
+}
+
+/// Row of an audit event, both types are foreign to this crate so it can't be a `From`.
+pub fn audit_log_for_create(val: AuditEvent) -> AuditLogForCreateDto {
+    AuditLogForCreateDto {
+        id: val.id,
+        service: val.service,
+        action: val.action,
+        actor_id: val.actor_id,
+        actor_client_id: val.actor_client_id,
+        target_type: val.target_type,
+        target_id: val.target_id,
+        before: val.before,
+        after: val.after,
+        diff: val.diff,
+        request_id: val.request_id,
+        occurred_at: val.occurred_at,
+        ..Default::default()
+    }
+}
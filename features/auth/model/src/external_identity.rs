@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime as DateTime;
+use serde::{Deserialize, Serialize};
+use shared_shared_macro::{ParamFilter, Response};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use features_auth_entities::external_identity::ModelOptionDto;
+
+use shared_shared_data_core::{
+    filter::{FilterEnum, FilterParam},
+    filter_deserialize::*,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Default, Response, ParamFilter)]
+pub struct ExternalIdentityData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[skip_param]
+    pub created_at: Option<DateTime>,
+}
+
+impl From<ModelOptionDto> for ExternalIdentityData {
+    fn from(val: ModelOptionDto) -> Self {
+        ExternalIdentityData {
+            id: val.id,
+            user_id: val.user_id,
+            provider: val.provider,
+            subject: val.subject,
+            email: val.email.flatten(),
+            confirmed: val.confirmed,
+            created_at: val.created_at,
+        }
+    }
+}
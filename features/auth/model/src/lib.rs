@@ -1,9 +1,12 @@
 pub mod access;
+pub mod audit_log;
 pub mod auth_code;
 pub mod authentication;
 pub mod client;
+pub mod external_identity;
 pub mod field_permission;
 pub mod login;
+pub mod oidc;
 pub mod password;
 pub mod permission;
 pub mod profile;
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shared_shared_macro::Response;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// One configured OpenID Connect provider.
+/// Endpoints are taken from `{issuer}/.well-known/openid-configuration` unless set explicitly,
+/// which allows OAuth2-only providers such as GitHub.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct OidcProviderConfig {
+    /// Provider key used in routes and stored on external identities (e.g. `google`)
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Callback registered at the provider, pointing to `/public/oidc/{name}/callback`
+    pub redirect_uri: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub authorization_endpoint: Option<String>,
+    #[serde(default)]
+    pub token_endpoint: Option<String>,
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+    /// Claim holding the provider user id, `sub` for OIDC providers
+    #[serde(default = "default_subject_claim")]
+    pub subject_claim: String,
+    /// Treat every returned email as verified, for providers that only return verified emails
+    #[serde(default)]
+    pub trust_email: bool,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "email".to_string(),
+        "profile".to_string(),
+    ]
+}
+
+fn default_subject_claim() -> String {
+    "sub".to_string()
+}
+
+impl OidcProviderConfig {
+    /// Parse the `OIDC_PROVIDERS` JSON array.
+    pub fn parse_list(raw: &str) -> Result<Vec<OidcProviderConfig>, serde_json::Error> {
+        serde_json::from_str(raw)
+    }
+
+    /// Merge explicitly configured endpoints over the discovered ones.
+    pub fn resolve_endpoints(
+        &self,
+        discovery: Option<&OidcDiscoveryDocument>,
+    ) -> Option<OidcEndpoints> {
+        let authorization_endpoint = self
+            .authorization_endpoint
+            .clone()
+            .or_else(|| discovery.map(|d| d.authorization_endpoint.clone()))?;
+        let token_endpoint = self
+            .token_endpoint
+            .clone()
+            .or_else(|| discovery.map(|d| d.token_endpoint.clone()))?;
+        let userinfo_endpoint = self
+            .userinfo_endpoint
+            .clone()
+            .or_else(|| discovery.and_then(|d| d.userinfo_endpoint.clone()))?;
+        Some(OidcEndpoints {
+            authorization_endpoint,
+            token_endpoint,
+            userinfo_endpoint,
+        })
+    }
+
+    /// Whether every endpoint is configured and discovery can be skipped.
+    pub fn has_static_endpoints(&self) -> bool {
+        self.authorization_endpoint.is_some()
+            && self.token_endpoint.is_some()
+            && self.userinfo_endpoint.is_some()
+    }
+}
+
+/// Subset of the OpenID Provider Metadata used for login.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct OidcDiscoveryDocument {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+    #[serde(default)]
+    pub jwks_uri: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OidcEndpoints {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+}
+
+/// Token endpoint response, only the access token is needed to call userinfo.
+#[derive(Deserialize, Debug)]
+pub struct OidcTokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub id_token: Option<String>,
+    #[serde(default)]
+    pub token_type: Option<String>,
+}
+
+/// Normalized user info returned by a provider.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OidcUserInfo {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+impl OidcUserInfo {
+    /// Build from raw userinfo claims. Returns None when the subject claim is missing.
+    pub fn from_claims(claims: &Value, config: &OidcProviderConfig) -> Option<OidcUserInfo> {
+        let subject = match claims.get(&config.subject_claim)? {
+            Value::String(s) if !s.is_empty() => s.clone(),
+            Value::Number(n) => n.to_string(),
+            _ => return None,
+        };
+        let email = claims
+            .get("email")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_lowercase());
+        let email_verified = email.is_some()
+            && (config.trust_email
+                || match claims.get("email_verified") {
+                    Some(Value::Bool(b)) => *b,
+                    Some(Value::String(s)) => s == "true",
+                    _ => false,
+                });
+        let name = claims
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        Some(OidcUserInfo {
+            subject,
+            email,
+            email_verified,
+            name,
+        })
+    }
+}
+
+/// How a provider account is attached to the local user that already owns its email.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OidcLinkMode {
+    /// The local account confirmed its email, the provider account is linked right away
+    Linked,
+    /// Anyone could have registered the unconfirmed local account with this email, the link
+    /// waits for the code sent to it
+    Pending,
+}
+
+impl OidcLinkMode {
+    pub fn for_existing_user(confirmed: Option<bool>) -> OidcLinkMode {
+        if confirmed == Some(true) {
+            OidcLinkMode::Linked
+        } else {
+            OidcLinkMode::Pending
+        }
+    }
+}
+
+/// Social login in progress, cached under the `state` sent to the provider until the callback
+/// consumes it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OidcLoginState {
+    pub request_id: Uuid,
+    pub provider: String,
+    /// PKCE verifier, only its S256 challenge is sent to the provider
+    pub code_verifier: String,
+    /// SHA-256 of the binding cookie set on the browser that started the login
+    pub binding_hash: String,
+}
+
+/// Query for `GET /public/oidc/{provider}/authorize`.
+/// `state` is the id of an authentication request created with `/public/requests/code`,
+/// the provider gets a random single-use state instead.
+#[derive(Deserialize, Debug)]
+pub struct OidcAuthorizeQuery {
+    pub state: String,
+}
+
+/// Query the provider sends back to `GET /public/oidc/{provider}/callback`.
+#[derive(Deserialize, Debug)]
+pub struct OidcCallbackQuery {
+    pub code: String,
+    pub state: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, ToSchema, Response, Debug)]
+pub struct OidcAuthorizeData {
+    pub authorization_url: String,
+}
+
+/// Body of `POST /public/oidc/identities/{identity_id}/confirm`.
+#[derive(Deserialize, Serialize, Validate, Debug, ToSchema)]
+pub struct OidcIdentityConfirmRequest {
+    #[validate(length(
+        min = 1,
+        max = 10,
+        code = "link_code",
+        message = "the length of link_code must be between 1 and 10"
+    ))]
+    pub link_code: String,
+}
@@ -18,10 +18,13 @@ fn validate_resource_format(resource: &str) -> Result<(), validator::ValidationE
         return Err(validator::ValidationError::new("resource_format")
             .with_message("resource must have non-empty service key and entity key".into()));
     }
-    let valid_chars = |s: &str| s.chars().all(|c| c.is_ascii_uppercase() || c == '_' || c.is_ascii_digit());
+    // A `*` segment is a wildcard matching any service or entity key
+    let valid_chars = |s: &str| {
+        s == "*" || s.chars().all(|c| c.is_ascii_uppercase() || c == '_' || c.is_ascii_digit())
+    };
     if !valid_chars(parts[0]) || !valid_chars(parts[1]) {
         return Err(validator::ValidationError::new("resource_format")
-            .with_message("resource must use UPPER_SNAKE_CASE (e.g. AUTH:ROLE)".into()));
+            .with_message("resource must use UPPER_SNAKE_CASE or * (e.g. AUTH:ROLE, AUTH:*)".into()));
     }
     Ok(())
 }
@@ -68,6 +71,14 @@ impl Into<PermissionForCreateRequestDto> for PermissionForUpdateRequest {
     }
 }
 
+/// Query for `GET /permissions/explain`: can `user_id` perform `action` (bitmask) on `resource`.
+#[derive(Deserialize, Debug)]
+pub struct PermissionExplainQuery {
+    pub user_id: Uuid,
+    pub resource: String,
+    pub action: u32,
+}
+
 use shared_shared_data_core::{
     filter::{FilterEnum, FilterParam},
     filter_deserialize::*,
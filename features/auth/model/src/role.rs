@@ -26,6 +26,8 @@ pub struct RoleForCreateRequest {
     #[validate(required(code = "client_id_required", message = "client_id is required"))]
     pub client_id: Option<Uuid>,
     pub is_default: Option<bool>,
+    /// Role to inherit permissions from
+    pub parent_role_id: Option<Uuid>,
 }
 
 impl Into<RoleForCreateDto> for RoleForCreateRequest {
@@ -35,6 +37,7 @@ impl Into<RoleForCreateDto> for RoleForCreateRequest {
             description: self.description,
             client_id: self.client_id.unwrap_or_default(),
             is_default: self.is_default.unwrap_or(false),
+            parent_role_id: self.parent_role_id,
         }
     }
 }
@@ -59,6 +62,8 @@ pub struct RoleForUpdateRequest {
     #[validate(required(code = "client_id_required", message = "client_id is required"))]
     pub client_id: Option<Uuid>,
     pub is_default: Option<bool>,
+    /// Role to inherit permissions from
+    pub parent_role_id: Option<Uuid>,
 }
 
 impl Into<RoleForUpdateDto> for RoleForUpdateRequest {
@@ -68,6 +73,7 @@ impl Into<RoleForUpdateDto> for RoleForUpdateRequest {
             description: Some(self.description),
             client_id: self.client_id,
             is_default: self.is_default,
+            parent_role_id: Some(self.parent_role_id),
         }
     }
 }
@@ -96,6 +102,8 @@ pub struct RoleData {
     client_id: Option<Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     is_default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_role_id: Option<Uuid>,
 
     #[skip_param]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,6 +119,18 @@ impl RoleData {
         self.id
     }
 
+    pub fn get_name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn get_client_id(&self) -> Option<Uuid> {
+        self.client_id
+    }
+
+    pub fn get_parent_role_id(&self) -> Option<Uuid> {
+        self.parent_role_id
+    }
+
     /// Apply field selection from query params.
     /// - `?fields=id,name` filters top-level entity fields
     /// - `?includes=client[id,name]` filters fields within related entities
@@ -136,6 +156,9 @@ impl RoleData {
             if !fields.contains(&"is_default".to_string()) {
                 self.is_default = None;
             }
+            if !fields.contains(&"parent_role_id".to_string()) {
+                self.parent_role_id = None;
+            }
             if !fields.contains(&"permissions".to_string()) && !includes.contains(&"permissions".to_string()) {
                 self.permissions = None;
             }
@@ -175,6 +198,7 @@ impl Into<RoleData> for ModelOptionDto {
             id: self.id,
             client_id: self.client_id,
             is_default: self.is_default,
+            parent_role_id: self.parent_role_id.flatten(),
             permissions: permissions_data,
             client: client_data,
             ..Default::default()
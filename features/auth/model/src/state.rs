@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::oidc::OidcLoginState;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AuthAppState {}
 
@@ -14,6 +16,7 @@ impl Default for AuthAppState {
 pub enum AuthCacheState {
     AccessToken(Uuid),
     RefreshToken(Uuid),
+    OidcLogin(OidcLoginState),
     Default,
 }
 
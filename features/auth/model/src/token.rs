@@ -4,7 +4,7 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
-use features_auth_entities::token::{ModelOptionDto, TokenForCreateDto};
+use features_auth_entities::token::{Model, ModelOptionDto, TokenForCreateDto};
 use shared_shared_macro::{ParamFilter, Response};
 
 #[derive(Clone, Deserialize, Serialize, Validate, Debug, ToSchema)]
@@ -57,6 +57,9 @@ pub struct TokenData {
     access_token_expires_at: Option<DateTime>,
     refresh_token_expires_at: Option<DateTime>,
     revoked_at: Option<DateTime>,
+    user_agent: Option<String>,
+    ip_address: Option<String>,
+    last_used_at: Option<DateTime>,
     created_at: Option<DateTime>,
     updated_at: Option<DateTime>,
 }
@@ -73,9 +76,44 @@ impl Into<TokenData> for ModelOptionDto {
             access_token_expires_at: self.access_token_expires_at,
             refresh_token_expires_at: self.refresh_token_expires_at,
             revoked_at: self.revoked_at.unwrap().or(None),
+            user_agent: self.user_agent.flatten(),
+            ip_address: self.ip_address.flatten(),
+            last_used_at: self.last_used_at.flatten(),
             created_at: self.created_at,
             updated_at: self.updated_at,
             ..Default::default()
         }
     }
 }
+
+/// Device a token is requested from, read from the request headers.
+#[derive(Clone, Debug, Default)]
+pub struct SessionDevice {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// An active sign-in of a user, one per issued access/refresh token pair.
+#[derive(Serialize, Debug, ToSchema, Default, Response)]
+pub struct SessionData {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime,
+    pub last_used_at: Option<DateTime>,
+}
+
+impl From<Model> for SessionData {
+    fn from(model: Model) -> Self {
+        SessionData {
+            id: model.id,
+            client_id: model.client_id,
+            user_agent: model.user_agent,
+            ip_address: model.ip_address,
+            created_at: model.created_at,
+            last_used_at: model.last_used_at,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use features_auth_model::oidc::{OidcDiscoveryDocument, OidcProviderConfig, OidcUserInfo};
+    use serde_json::json;
+
+    fn google() -> OidcProviderConfig {
+        let raw = r#"[{
+            "name": "google",
+            "issuer": "https://accounts.google.com",
+            "client_id": "client",
+            "client_secret": "secret",
+            "redirect_uri": "https://auth.example.com/public/oidc/google/callback"
+        }]"#;
+        OidcProviderConfig::parse_list(raw).unwrap().remove(0)
+    }
+
+    #[test]
+    fn provider_config_uses_defaults() {
+        let config = google();
+        assert_eq!(config.scopes, vec!["openid", "email", "profile"]);
+        assert_eq!(config.subject_claim, "sub");
+        assert!(!config.trust_email);
+        assert!(!config.has_static_endpoints());
+    }
+
+    #[test]
+    fn explicit_endpoints_override_discovery() {
+        let mut config = google();
+        config.userinfo_endpoint = Some("https://custom/userinfo".to_string());
+        let discovery = OidcDiscoveryDocument {
+            issuer: config.issuer.clone(),
+            authorization_endpoint: "https://accounts.google.com/auth".to_string(),
+            token_endpoint: "https://oauth2.googleapis.com/token".to_string(),
+            userinfo_endpoint: Some("https://openidconnect.googleapis.com/userinfo".to_string()),
+            jwks_uri: None,
+        };
+        let endpoints = config.resolve_endpoints(Some(&discovery)).unwrap();
+        assert_eq!(endpoints.token_endpoint, discovery.token_endpoint);
+        assert_eq!(endpoints.userinfo_endpoint, "https://custom/userinfo");
+        assert!(google().resolve_endpoints(None).is_none());
+    }
+
+    #[test]
+    fn user_info_requires_verified_email() {
+        let config = google();
+        let claims = json!({ "sub": "123", "email": "A@Example.com", "email_verified": true });
+        let info = OidcUserInfo::from_claims(&claims, &config).unwrap();
+        assert_eq!(info.subject, "123");
+        assert_eq!(info.email.as_deref(), Some("a@example.com"));
+        assert!(info.email_verified);
+
+        let claims = json!({ "sub": "123", "email": "a@example.com" });
+        assert!(
+            !OidcUserInfo::from_claims(&claims, &config)
+                .unwrap()
+                .email_verified
+        );
+        assert!(OidcUserInfo::from_claims(&json!({ "email": "a@example.com" }), &config).is_none());
+    }
+
+    #[test]
+    fn user_info_supports_numeric_subject_and_trusted_email() {
+        let mut config = google();
+        config.subject_claim = "id".to_string();
+        config.trust_email = true;
+        let claims = json!({ "id": 42, "email": "dev@example.com" });
+        let info = OidcUserInfo::from_claims(&claims, &config).unwrap();
+        assert_eq!(info.subject, "42");
+        assert!(info.email_verified);
+    }
+}
@@ -20,7 +20,8 @@ impl ActiveCodeRemoteService {
         });
 
         let data = Self::call_api(endpoint, reqwest::Method::POST, Some(body), HashMap::new())
-            .await?;
+            .await
+            .map_err(|e| e.to_string())?;
 
         let response: MarkAsSentResponse =
             serde_json::from_value(data).map_err(|e| e.to_string())?;
@@ -35,7 +35,7 @@ impl AuthenticationRequestService {
         .await;
         if res.is_err() {
             let err_msg = res.err().unwrap();
-            return Err(err_msg);
+            return Err(err_msg.to_string());
         }
         let data = res.unwrap();
 
@@ -81,7 +81,7 @@ impl AuthenticationRequestService {
         .await;
         if res.is_err() {
             let err_msg = res.err().unwrap();
-            return Err(err_msg);
+            return Err(err_msg.to_string());
         }
         let data = res.unwrap();
         let login_data: AuthLoginData = from_value(data).map_err(|e| e.to_string())?;
@@ -113,7 +113,7 @@ impl AuthenticationRequestService {
         .await;
         if res.is_err() {
             let err_msg = res.err().unwrap();
-            return Err(err_msg);
+            return Err(err_msg.to_string());
         }
         let data = res.unwrap();
         let register_data: AuthRegisterData = from_value(data).map_err(|e| e.to_string())?;
@@ -1,5 +1,6 @@
 use features_auth_model::permission::PermissionData;
 use shared_shared_app::state::FieldPermissionEntry;
+use shared_shared_auth::hierarchy::RESOURCE_WILDCARD;
 use shared_shared_data_core::filter::{FilterCondition, FilterEnum, FilterOperator, FilterParam};
 use shared_shared_macro::RemoteService;
 
@@ -8,9 +9,23 @@ use shared_shared_macro::RemoteService;
 pub struct PermissionService {}
 
 impl PermissionService {
+    /// Fetch the permissions of the service by role name: the ones on `SERVICE_KEY:` resources
+    /// and the wildcard ones on every service, `*` and `*:RESOURCE`.
     pub async fn get_roles_by_service_name<'a>(
         service_key: String,
     ) -> HashMap<String, Vec<PermissionData>> {
+        let mut role_permissions: HashMap<String, Vec<PermissionData>> = HashMap::new();
+        for resource_prefix in [format!("{}:", service_key), RESOURCE_WILDCARD.to_string()] {
+            Self::collect_role_permissions(resource_prefix, &mut role_permissions).await;
+        }
+        role_permissions
+    }
+
+    /// Calls GET /roles?includes=permissions&permissions[resource]=sw|PREFIX on every page.
+    async fn collect_role_permissions(
+        resource_prefix: String,
+        role_permissions: &mut HashMap<String, Vec<PermissionData>>,
+    ) {
         let permission_endpoint =
             std::env::var("AUTH_ROLES_ENDPOINT").expect("AUTH_ROLES_ENDPOINT must be set");
         let permission_baggage_header = std::env::var("AUTH_ROLES_BAGGAGE_HEADER")
@@ -25,14 +40,13 @@ impl PermissionService {
         let condition = FilterCondition::leaf(FilterEnum::String(FilterParam {
             name: "permissions[resource]".to_string(),
             operator: FilterOperator::StartWith,
-            value: Some(format!("{}:", service_key)),
-            raw_value: format!("{}:", service_key),
+            value: Some(resource_prefix.clone()),
+            raw_value: resource_prefix,
         }));
 
         let query_string = condition.to_query_string();
         let page_size = 20;
         let mut page = 1u64;
-        let mut role_permissions: HashMap<String, Vec<PermissionData>> = HashMap::new();
 
         loop {
             let url = format!(
@@ -42,7 +56,7 @@ impl PermissionService {
             let res = Self::call_api(url, reqwest::Method::GET, None, headers.clone()).await;
             if let Err(err_msg) = res {
                 debug!("Error calling permission service: {}", err_msg);
-                return role_permissions;
+                return;
             }
             let res = res.unwrap();
             debug!("Permission service response page {}: {:?}", page, res);
@@ -77,8 +91,6 @@ impl PermissionService {
             }
             page += 1;
         }
-
-        role_permissions
     }
 
     /// Fetch field-level permissions for all roles that have entries for this service.
@@ -19,7 +19,7 @@ impl TokenService {
         let res = Self::call_api(verify_endpoint, Method::POST, Some(body), HashMap::new()).await;
         if res.is_err() {
             let err_msg = res.err().unwrap();
-            return Err(err_msg);
+            return Err(err_msg.to_string());
         }
         let data = res.unwrap();
         debug!("Token validation response: {:?}", data);
@@ -1,4 +1,3 @@
-use sea_orm::ConnectionTrait;
 use shared_shared_macro::Mutation;
 
 use features_auth_entities::access::{
@@ -24,11 +23,7 @@ impl AccessMutation {
         data: AccessForCreateDto,
         txn: &impl ConnectionTrait,
     ) -> Result<Uuid, DbErr> {
-        let model: Model = data.into();
-        let mut active_model: ActiveModel = model.into();
-        active_model.not_set(Column::Id);
-        let result = active_model.insert(txn).await?;
-        Ok(result.id)
+        AccessMutationManager::create_with_conn(txn, data.into()).await
     }
 
     pub fn update<'a>(
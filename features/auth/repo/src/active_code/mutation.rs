@@ -1,4 +1,3 @@
-use sea_orm::ConnectionTrait;
 use shared_shared_macro::Mutation;
 
 use features_auth_entities::active_code::{
@@ -25,11 +24,7 @@ impl ActiveCodeMutation {
         data: ActiveCodeForCreateDto,
         txn: &impl ConnectionTrait,
     ) -> Result<Uuid, DbErr> {
-        let model: Model = data.into();
-        let mut active_model: ActiveModel = model.into();
-        active_model.not_set(Column::Id);
-        let result = active_model.insert(txn).await?;
-        Ok(result.id)
+        ActiveCodeMutationManager::create_with_conn(txn, data.into()).await
     }
 
     pub fn update<'a>(
@@ -39,6 +34,14 @@ impl ActiveCodeMutation {
         ActiveCodeMutationManager::update_by_id_uuid(id, data.into())
     }
 
+    pub async fn update_with_txn(
+        id: Uuid,
+        data: ActiveCodeForUpdateDto,
+        txn: &impl ConnectionTrait,
+    ) -> Result<bool, DbErr> {
+        ActiveCodeMutationManager::update_by_id_with_conn(txn, id, data.into()).await
+    }
+
     pub fn delete<'a>(id: Uuid) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
         ActiveCodeMutationManager::delete_by_id_uuid(id)
     }
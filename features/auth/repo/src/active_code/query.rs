@@ -17,6 +17,10 @@ struct ActiveCodeQueryManager;
 pub struct ActiveCodeQuery {}
 
 impl ActiveCodeQuery {
+    pub async fn get(id: Uuid) -> Result<ModelOptionDto, DbErr> {
+        ActiveCodeQueryManager::get_by_id_uuid(id).await
+    }
+
     pub async fn search(
         pagination: &Pagination,
         order: &Order,
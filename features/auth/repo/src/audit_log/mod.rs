@@ -0,0 +1,5 @@
+mod mutation;
+mod query;
+
+pub use mutation::AuditLogMutation;
+pub use query::AuditLogQuery;
@@ -0,0 +1,25 @@
+use chrono::Utc;
+use sea_orm::{sea_query::OnConflict, ActiveValue, DbErr, EntityTrait};
+use tracing::debug;
+
+use shared_shared_config::db::write_db;
+
+use features_auth_entities::audit_log::{ActiveModel, AuditLogForCreateDto, Column, Entity, Model};
+
+pub struct AuditLogMutation {}
+
+impl AuditLogMutation {
+    /// Store an audit event. Events are keyed by their id, a redelivered event is ignored.
+    pub async fn create(data: AuditLogForCreateDto) -> Result<(), DbErr> {
+        debug!("Create audit_log {:?}", data.id);
+        let db = write_db().expect("DB_WRITE is not initialized");
+        let model: Model = data.into();
+        let mut active_model: ActiveModel = model.into();
+        active_model.created_at = ActiveValue::Set(Utc::now().naive_utc());
+        Entity::insert(active_model)
+            .on_conflict(OnConflict::column(Column::Id).do_nothing().to_owned())
+            .exec_without_returning(db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use tracing::debug;
+use uuid::Uuid;
+
+use shared_shared_data_core::{
+    filter::FilterEnum,
+    order::Order,
+    paging::{Pagination, QueryResult},
+};
+use shared_shared_macro::Query;
+
+use features_auth_entities::audit_log::{ActiveModel, Column, Entity, ModelOptionDto};
+use features_auth_model::audit_log::AuditLogData;
+
+#[derive(Query)]
+#[query(key_type(Uuid))]
+#[query_filter(column_name(Column))]
+struct AuditLogQueryManager;
+
+pub struct AuditLogQuery {}
+
+impl AuditLogQuery {
+    pub async fn get(id: Uuid) -> Result<AuditLogData, DbErr> {
+        let model = AuditLogQueryManager::get_by_id_uuid(id).await?;
+        Ok(model.into())
+    }
+
+    pub async fn search(
+        pagination: &Pagination,
+        order: &Order,
+        filters: &FilterCondition,
+    ) -> Result<QueryResult<AuditLogData>, DbErr> {
+        debug!("AuditLogQuery::search filters: {:?}", filters);
+        let result = AuditLogQueryManager::filter(pagination, order, filters).await?;
+        let mapped_result = QueryResult {
+            total_page: result.total_page,
+            result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
+        };
+        Ok(mapped_result)
+    }
+}
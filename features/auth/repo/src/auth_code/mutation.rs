@@ -1,4 +1,3 @@
-use sea_orm::ConnectionTrait;
 use shared_shared_macro::Mutation;
 
 use features_auth_entities::auth_code::{
@@ -69,6 +69,8 @@ impl AuthCodeQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
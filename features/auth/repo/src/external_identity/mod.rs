@@ -0,0 +1,6 @@
+mod mutation;
+mod query;
+mod util;
+
+pub use mutation::ExternalIdentityMutation;
+pub use query::ExternalIdentityQuery;
@@ -0,0 +1,86 @@
+use sea_orm::{
+    sea_query::{Expr, ExprTrait},
+    ColumnTrait, QueryFilter,
+};
+use tracing::debug;
+
+use shared_shared_macro::Mutation;
+
+use features_auth_entities::external_identity::{
+    ActiveModel, Column, Entity, ExternalIdentityForCreateDto, Model, ModelOptionDto,
+};
+
+use crate::external_identity::util::assign;
+
+#[derive(Mutation)]
+#[mutation(key_type(Uuid))]
+struct ExternalIdentityMutationManager {}
+
+pub struct ExternalIdentityMutation {}
+
+impl ExternalIdentityMutation {
+    pub fn create<'a>(
+        data: ExternalIdentityForCreateDto,
+    ) -> impl std::future::Future<Output = Result<Uuid, DbErr>> + 'a {
+        debug!("Create external_identity {:?}", data);
+        ExternalIdentityMutationManager::create_uuid(data.into())
+    }
+
+    pub async fn create_with_txn(
+        data: ExternalIdentityForCreateDto,
+        txn: &impl ConnectionTrait,
+    ) -> Result<Uuid, DbErr> {
+        ExternalIdentityMutationManager::create_with_conn(txn, data.into()).await
+    }
+
+    /// Bind the code mailed for a pending link, replacing the previous one and its attempts.
+    pub async fn set_link_code_with_txn(
+        id: Uuid,
+        code_id: Uuid,
+        txn: &impl ConnectionTrait,
+    ) -> Result<bool, DbErr> {
+        debug!("Set link code {:?} of external_identity {:?}", code_id, id);
+        ExternalIdentityMutationManager::update_by_id_with_conn(
+            txn,
+            id,
+            ModelOptionDto {
+                link_code_id: Some(Some(code_id)),
+                link_attempts: Some(0),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Count a wrong link code, in the database so concurrent attempts are all counted.
+    pub async fn record_link_attempt(id: Uuid) -> Result<bool, DbErr> {
+        debug!("Record link attempt of external_identity {:?}", id);
+        let db = ExternalIdentityMutationManager::get_db();
+        let result = Entity::update_many()
+            .col_expr(Column::LinkAttempts, Expr::col(Column::LinkAttempts).add(1))
+            .filter(Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Mark a pending link as confirmed by the owner of the local account.
+    pub async fn confirm_with_txn(id: Uuid, txn: &impl ConnectionTrait) -> Result<bool, DbErr> {
+        debug!("Confirm external_identity {:?}", id);
+        ExternalIdentityMutationManager::update_by_id_with_conn(
+            txn,
+            id,
+            ModelOptionDto {
+                confirmed: Some(true),
+                link_code_id: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    pub fn delete<'a>(id: Uuid) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
+        debug!("Delete external_identity {:?}", id);
+        ExternalIdentityMutationManager::delete_by_id_uuid(id)
+    }
+}
@@ -0,0 +1,75 @@
+use tracing::debug;
+use uuid::Uuid;
+
+use shared_shared_data_core::{
+    filter::{FilterEnum, FilterParam},
+    order::Order,
+    paging::{Pagination, QueryResult},
+};
+use shared_shared_macro::Query;
+
+use features_auth_entities::external_identity::{ActiveModel, Column, Entity, ModelOptionDto};
+use features_auth_model::external_identity::ExternalIdentityData;
+
+#[derive(Query)]
+#[query(key_type(Uuid))]
+#[query_filter(column_name(Column))]
+struct ExternalIdentityQueryManager;
+
+pub struct ExternalIdentityQuery {}
+
+impl ExternalIdentityQuery {
+    pub async fn get(id: Uuid) -> Result<ExternalIdentityData, DbErr> {
+        let model = ExternalIdentityQueryManager::get_by_id_uuid(id).await?;
+        Ok(model.into())
+    }
+
+    /// The identity with its pending link code, which `ExternalIdentityData` does not expose.
+    pub async fn get_raw(id: Uuid) -> Result<ModelOptionDto, DbErr> {
+        ExternalIdentityQueryManager::get_by_id_uuid(id).await
+    }
+
+    /// Find the identity linked to a provider account, None when the account is not linked yet.
+    pub async fn find_by_provider_subject(
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<ExternalIdentityData>, DbErr> {
+        let filters = vec![
+            FilterEnum::String(FilterParam {
+                name: Column::Provider.to_string(),
+                operator: FilterOperator::Equal,
+                value: Some(provider.to_string()),
+                raw_value: provider.to_string(),
+            }),
+            FilterEnum::String(FilterParam {
+                name: Column::Subject.to_string(),
+                operator: FilterOperator::Equal,
+                value: Some(subject.to_string()),
+                raw_value: subject.to_string(),
+            }),
+        ];
+        let result = ExternalIdentityQueryManager::filter(
+            &Pagination::new(1, 1),
+            &Order::default(),
+            &FilterCondition::from(filters),
+        )
+        .await?;
+        Ok(result.result.into_iter().next().map(|m| m.into()))
+    }
+
+    pub async fn search(
+        pagination: &Pagination,
+        order: &Order,
+        filters: &FilterCondition,
+    ) -> Result<QueryResult<ExternalIdentityData>, DbErr> {
+        debug!("ExternalIdentityQuery::search filters: {:?}", filters);
+        let result = ExternalIdentityQueryManager::filter(pagination, order, filters).await?;
+        let mapped_result = QueryResult {
+            total_page: result.total_page,
+            result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
+        };
+        Ok(mapped_result)
+    }
+}
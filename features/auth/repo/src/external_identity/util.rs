@@ -0,0 +1,18 @@
+use sea_orm::Set;
+
+use shared_shared_macro_rule::set_if_some;
+
+use features_auth_entities::external_identity::{ActiveModel, ModelOptionDto};
+
+pub fn assign(mut active_model: ActiveModel, model_option: ModelOptionDto) -> ActiveModel {
+    set_if_some!(active_model.id, model_option.id);
+    set_if_some!(active_model.user_id, model_option.user_id);
+    set_if_some!(active_model.provider, model_option.provider);
+    set_if_some!(active_model.subject, model_option.subject);
+    set_if_some!(active_model.email, model_option.email);
+    set_if_some!(active_model.confirmed, model_option.confirmed);
+    set_if_some!(active_model.link_code_id, model_option.link_code_id);
+    set_if_some!(active_model.link_attempts, model_option.link_attempts);
+
+    active_model
+}
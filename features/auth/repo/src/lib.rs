@@ -1,8 +1,10 @@
 pub mod access;
 pub mod active_code;
+pub mod audit_log;
 pub mod auth_code;
 pub mod authentication;
 pub mod client;
+pub mod external_identity;
 pub mod field_permission;
 pub mod permission;
 pub mod role;
@@ -29,6 +29,7 @@ use features_auth_model::role::RoleData;
     field(client),
     name("client")
 )]
+#[query_sort(columns(Name, UpdatedAt))]
 struct RoleQueryManager;
 
 pub struct RoleQuery {}
@@ -89,6 +90,8 @@ impl RoleQuery {
                     role_data
                 })
                 .collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         debug!("RoleQuery::search mapped_result: {:?}", mapped_result);
         Ok(mapped_result)
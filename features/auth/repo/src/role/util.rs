@@ -10,6 +10,7 @@ pub fn assign(mut active_model: ActiveModel, model_option: ModelOptionDto) -> Ac
     set_if_some!(active_model.description, model_option.description);
     set_if_some!(active_model.client_id, model_option.client_id);
     set_if_some!(active_model.is_default, model_option.is_default);
+    set_if_some!(active_model.parent_role_id, model_option.parent_role_id);
 
     active_model
 }
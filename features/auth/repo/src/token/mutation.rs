@@ -1,3 +1,5 @@
+use chrono::{Duration, Utc};
+use sea_orm::{sea_query::Expr, ColumnTrait, Condition, QueryFilter};
 use tracing::debug;
 
 use shared_shared_macro::Mutation;
@@ -36,4 +38,39 @@ impl TokenMutation {
         debug!("Delete token {:?}", id);
         TokenMutationManager::delete_by_id_uuid(id)
     }
+
+    /// Mark a session as revoked, false when it was already revoked.
+    pub async fn revoke(id: Uuid) -> Result<bool, DbErr> {
+        debug!("Revoke token {:?}", id);
+        let db = TokenMutationManager::get_db();
+        let now = Utc::now().naive_utc();
+        let result = Entity::update_many()
+            .col_expr(Column::RevokedAt, Expr::value(Some(now)))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .filter(Column::Id.eq(id))
+            .filter(Column::RevokedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Record a use of the session holding `access_token`, at most once per `interval`.
+    pub async fn touch_by_access_token(
+        access_token: &str,
+        interval: Duration,
+    ) -> Result<bool, DbErr> {
+        let db = TokenMutationManager::get_db();
+        let now = Utc::now().naive_utc();
+        let result = Entity::update_many()
+            .col_expr(Column::LastUsedAt, Expr::value(Some(now)))
+            .filter(Column::AccessToken.eq(access_token))
+            .filter(
+                Condition::any()
+                    .add(Column::LastUsedAt.is_null())
+                    .add(Column::LastUsedAt.lt(now - interval)),
+            )
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
 }
@@ -1,3 +1,4 @@
+use chrono::Utc;
 use uuid::Uuid;
 
 use shared_shared_data_core::{
@@ -7,7 +8,7 @@ use shared_shared_data_core::{
 };
 use shared_shared_macro::Query;
 
-use features_auth_entities::token::{ActiveModel, Column, Entity, ModelOptionDto};
+use features_auth_entities::token::{ActiveModel, Column, Entity, Model, ModelOptionDto};
 use features_auth_model::token::TokenData;
 
 #[derive(Query)]
@@ -33,7 +34,43 @@ impl TokenQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
+
+    pub async fn find_by_id(id: Uuid) -> Result<Option<Model>, DbErr> {
+        let db = TokenQueryManager::get_db();
+        Entity::find_by_id(id).one(db).await
+    }
+
+    /// Sessions of a user that are not revoked and can still be refreshed, most recently used first.
+    pub async fn find_active_by_user_id(user_id: Uuid) -> Result<Vec<Model>, DbErr> {
+        let db = TokenQueryManager::get_db();
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::RevokedAt.is_null())
+            .filter(Column::RefreshTokenExpiresAt.gt(Utc::now().naive_utc()))
+            .order_by_desc(Column::LastUsedAt)
+            .order_by_desc(Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    /// Whether the user has signed in before, from the given `User-Agent` when set.
+    pub async fn has_session(user_id: Uuid, user_agent: Option<&str>) -> Result<bool, DbErr> {
+        let db = TokenQueryManager::get_db();
+        let mut select = Entity::find().filter(Column::UserId.eq(user_id));
+        if let Some(user_agent) = user_agent {
+            select = select.filter(Column::UserAgent.eq(user_agent));
+        }
+        let found = select
+            .select_only()
+            .column(Column::Id)
+            .into_tuple::<Uuid>()
+            .one(db)
+            .await?;
+        Ok(found.is_some())
+    }
 }
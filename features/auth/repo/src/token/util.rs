@@ -20,6 +20,9 @@ pub fn assign(mut active_model: ActiveModel, model_option: ModelOptionDto) -> Ac
         model_option.refresh_token_expires_at
     );
     set_if_some!(active_model.revoked_at, model_option.revoked_at);
+    set_if_some!(active_model.user_agent, model_option.user_agent);
+    set_if_some!(active_model.ip_address, model_option.ip_address);
+    set_if_some!(active_model.last_used_at, model_option.last_used_at);
     set_if_some!(active_model.created_at, model_option.created_at);
     set_if_some!(active_model.updated_at, model_option.updated_at);
 
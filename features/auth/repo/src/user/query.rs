@@ -21,8 +21,8 @@ use features_auth_model::user::UserData;
 
 #[derive(Query)]
 #[query(key_type(Uuid))]
-#[query_filter(column_name(AccessColumn))]
-#[query_filter(column_name(RoleColumn))]
+#[query_filter(column_name(AccessColumn), prefix("accesses"), entity(AccessEntity))]
+#[query_filter(column_name(RoleColumn), prefix("role"), entity(RoleEntity))]
 #[query_filter(column_name(Column))]
 #[query_related(
     entity(AccessEntity),
@@ -31,38 +31,6 @@ use features_auth_model::user::UserData;
 )]
 struct UserQueryManager;
 
-impl UserQueryManager {
-    fn build_filter_condition(filter_condition: &FilterCondition) -> Condition {
-        match filter_condition {
-            FilterCondition::And(conditions) => {
-                let mut condition = Condition::all();
-                for c in conditions {
-                    condition = condition.add(Self::build_filter_condition(c));
-                }
-                condition
-            }
-            FilterCondition::Or(conditions) => {
-                let mut condition = Condition::any();
-                for c in conditions {
-                    condition = condition.add(Self::build_filter_condition(c));
-                }
-                condition
-            }
-            FilterCondition::Leaf(filter_enum) => {
-                let name = filter_enum.get_name();
-                if name.starts_with("role.`") {
-                    if let Ok(column) = RoleColumn::from_str(name.as_str()) {
-                        return Self::filter_condition_rolecolumn(column, filter_enum);
-                    }
-                } else if let Ok(column) = Column::from_str(name.as_str()) {
-                    return Self::filter_condition_column(column, filter_enum);
-                }
-                Condition::all()
-            }
-        }
-    }
-}
-
 pub struct UserQuery;
 
 impl UserQuery {
@@ -215,6 +183,8 @@ impl UserQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
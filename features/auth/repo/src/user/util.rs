@@ -9,6 +9,7 @@ pub fn assign(mut active_model: ActiveModel, model_option: ModelOptionDto) -> Ac
     set_if_some!(active_model.id, model_option.id);
     set_if_some!(active_model.email, model_option.email);
     set_if_some!(active_model.is_active, model_option.is_active);
+    set_if_some!(active_model.confirmed, model_option.confirmed);
     if let Some(password) = model_option.password {
         warn!("Password is changing");
         active_model.password = Set(password);
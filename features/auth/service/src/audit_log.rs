@@ -0,0 +1,41 @@
+use tracing::debug;
+use uuid::Uuid;
+
+use shared_shared_audit::AuditEvent;
+use shared_shared_data_app::result::Result;
+use shared_shared_data_core::{
+    filter::FilterCondition,
+    order::Order,
+    paging::{Pagination, QueryResult},
+};
+
+use features_auth_model::audit_log::{audit_log_for_create, AuditLogData};
+use features_auth_repo::audit_log::{AuditLogMutation, AuditLogQuery};
+
+pub struct AuditLogService {}
+
+impl AuditLogService {
+    /// Persist an event consumed from the audit topic.
+    pub async fn store(event: AuditEvent) -> Result<()> {
+        debug!(
+            "Store audit event {} {} on {}:{}",
+            event.id, event.action, event.target_type, event.target_id
+        );
+        AuditLogMutation::create(audit_log_for_create(event)).await?;
+        Ok(())
+    }
+
+    pub async fn get_audit_log(id: Uuid) -> Result<AuditLogData> {
+        let audit_log = AuditLogQuery::get(id).await?;
+        Ok(audit_log)
+    }
+
+    pub async fn search(
+        pagination: &Pagination,
+        order: &Order,
+        filters: &FilterCondition,
+    ) -> Result<QueryResult<AuditLogData>> {
+        let result = AuditLogQuery::search(pagination, order, filters).await?;
+        Ok(result)
+    }
+}
@@ -1,9 +1,11 @@
-use sea_orm::TransactionTrait;
 use tracing::debug;
 use uuid::Uuid;
 
-use shared_shared_app::event_task::producer::{Producer, ProducerMessage};
-use shared_shared_config::db::DB_WRITE;
+use shared_shared_app::event_task::{
+    outbox::Outbox,
+    producer::{Producer, ProducerMessage},
+};
+use shared_shared_config::db::UnitOfWork;
 use shared_shared_data_app::result::Result;
 use shared_shared_data_core::{
     filter::{FilterCondition, FilterEnum, FilterParam},
@@ -38,6 +40,50 @@ use rand::{thread_rng, Rng};
 pub struct AuthenticationRequestService {}
 
 impl AuthenticationRequestService {
+    /// Id of the role assigned to users registering through `client_id`.
+    pub(crate) async fn default_role_id(client_id: Uuid) -> Result<Uuid> {
+        let filters = vec![
+            FilterEnum::Bool(FilterParam {
+                name: "is_default".to_string(),
+                operator: shared_shared_data_core::filter::FilterOperator::Equal,
+                value: Some(true),
+                raw_value: "true".to_string(),
+            }),
+            FilterEnum::Uuid(FilterParam {
+                name: "client_id".to_string(),
+                operator: shared_shared_data_core::filter::FilterOperator::Equal,
+                value: Some(client_id),
+                raw_value: client_id.to_string(),
+            }),
+        ];
+
+        let default_roles = RoleQuery::search(
+            &Pagination::default(),
+            &Order::default(),
+            &FilterCondition::from(filters),
+            &QueryParams::default(),
+            &FilterCondition::and(vec![]),
+        )
+        .await;
+        if default_roles.is_err() {
+            let error = default_roles.err().unwrap();
+            debug!("Error fetching default roles: {:?}", error);
+            return Err(AppError::Unknown);
+        }
+        let default_roles = default_roles.unwrap();
+        debug!(
+            "Default roles for client_id {}: {:?}",
+            client_id, default_roles
+        );
+        if default_roles.result.is_empty() {
+            debug!("No default roles found for client_id {}", client_id);
+            return Err(AppError::Auth(AuthError::UnknowRole));
+        }
+        let default_role = &default_roles.result[0];
+        debug!("Assigning default role: {:?}", default_role);
+        Ok(default_role.get_id().unwrap())
+    }
+
     pub async fn request<'a>(request: AuthenticationRequestForCreateDto) -> Result<Uuid> {
         let request_id = AuthenticationRequestMutation::create(request).await;
         Ok(request_id.unwrap())
@@ -153,57 +199,19 @@ impl AuthenticationRequestService {
         debug!("Client data: {:?}", client_data);
 
         let client_key = client_data.client_key.clone();
-        let filters = vec![
-            FilterEnum::Bool(FilterParam {
-                name: "is_default".to_string(),
-                operator: shared_shared_data_core::filter::FilterOperator::Equal,
-                value: Some(true),
-                raw_value: "true".to_string(),
-            }),
-            FilterEnum::Uuid(FilterParam {
-                name: "client_id".to_string(),
-                operator: shared_shared_data_core::filter::FilterOperator::Equal,
-                value: Some(client_id),
-                raw_value: client_id.to_string(),
-            }),
-        ];
-
-        let default_roles = RoleQuery::search(
-            &Pagination::default(),
-            &Order::default(),
-            &FilterCondition::from(filters),
-            &QueryParams::default(),
-            &FilterCondition::and(vec![]),
-        )
-        .await;
-        if default_roles.is_err() {
-            let error = default_roles.err().unwrap();
-            debug!("Error fetching default roles: {:?}", error);
-            return Err(AppError::Unknown);
-        }
-        let default_roles = default_roles.unwrap();
-        debug!(
-            "Default roles for client_id {}: {:?}",
-            client_id, default_roles
-        );
-        if default_roles.result.is_empty() {
-            debug!("No default roles found for client_id {}", client_id);
-            return Err(AppError::Auth(AuthError::UnknowRole));
-        }
-        let default_role = &default_roles.result[0];
-        debug!("Assigning default role: {:?}", default_role);
+        let default_role_id = Self::default_role_id(client_id).await?;
 
         // Begin transaction for all DB writes
-        let db = DB_WRITE.get().expect("DB_WRITE is not initialized");
-        let txn = db.begin().await.map_err(|_| AppError::Unknown)?;
+        let unit_of_work = UnitOfWork::begin().await.map_err(|_| AppError::Unknown)?;
 
         // 1. Create user
-        let user_id = UserMutation::create_user_with_txn(create_user_request.into(), &txn)
-            .await
-            .map_err(|e| {
-                debug!("Error creating user: {:?}", e);
-                AppError::Auth(AuthError::ExistingUser)
-            })?;
+        let user_id =
+            UserMutation::create_user_with_txn(create_user_request.into(), unit_of_work.txn())
+                .await
+                .map_err(|e| {
+                    debug!("Error creating user: {:?}", e);
+                    AppError::Auth(AuthError::ExistingUser)
+                })?;
 
         // 2. Generate and save active code
         let active_code: String = thread_rng()
@@ -217,7 +225,7 @@ impl AuthenticationRequestService {
             user_id,
             code: active_code.clone(),
         };
-        ActiveCodeMutation::create_with_txn(active_code_dto, &txn)
+        ActiveCodeMutation::create_with_txn(active_code_dto, unit_of_work.txn())
             .await
             .map_err(|e| {
                 debug!("Error creating active code: {:?}", e);
@@ -227,10 +235,10 @@ impl AuthenticationRequestService {
         // 3. Assign default role to user
         let access_dto = AccessForCreateDto {
             user_id,
-            role_id: default_role.get_id().unwrap(),
+            role_id: default_role_id,
             key: "".to_string(),
         };
-        AccessMutation::create_with_txn(access_dto, &txn)
+        AccessMutation::create_with_txn(access_dto, unit_of_work.txn())
             .await
             .map_err(|e| {
                 debug!("Error assigning role to user: {:?}", e);
@@ -246,20 +254,14 @@ impl AuthenticationRequestService {
             user_id: Some(user_id),
         };
         let (_code_id, auth_code) =
-            AuthCodeMutation::create_with_txn(auth_code_request.into(), &txn)
+            AuthCodeMutation::create_with_txn(auth_code_request.into(), unit_of_work.txn())
                 .await
                 .map_err(|e| {
                     debug!("Error creating auth code: {:?}", e);
                     AppError::Unknown
                 })?;
 
-        // 5. Commit transaction - all DB writes succeed or none do
-        txn.commit().await.map_err(|e| {
-            debug!("Error committing transaction: {:?}", e);
-            AppError::Unknown
-        })?;
-
-        // 6. Send Kafka AFTER commit - DB is consistent regardless of Kafka outcome
+        // 5. Store the SignUp event in the outbox, it is published once the user exists
         let auth_message = AuthMessage::SignUp {
             message: SignUpMessage::Success {
                 user_id,
@@ -274,10 +276,18 @@ impl AuthenticationRequestService {
             payload: auth_message,
             key: None,
         };
-        if let Err(e) = producer.send(&message).await {
-            // Log but don't fail - user is registered, they can request a new code
-            debug!("Error sending signup message to Kafka: {:?}", e.reason);
-        }
+        Outbox::enqueue(unit_of_work.txn(), producer.topic(), &message)
+            .await
+            .map_err(|e| {
+                debug!("Error storing signup message in outbox: {:?}", e);
+                AppError::Unknown
+            })?;
+
+        // 6. Commit transaction - all DB writes and the event succeed or none do
+        unit_of_work.commit().await.map_err(|e| {
+            debug!("Error committing transaction: {:?}", e);
+            AppError::Unknown
+        })?;
 
         let result = AuthRegisterData {
             user_id,
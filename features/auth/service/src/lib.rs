@@ -1,21 +1,27 @@
 mod active_code;
+mod audit_log;
 mod authentication;
 mod field_permission;
 mod login;
+mod oidc;
 mod password;
 mod permission;
 mod register;
 mod role;
+mod session;
 mod token;
 mod user;
 
 pub use active_code::ActiveCodeService;
+pub use audit_log::AuditLogService;
 pub use authentication::AuthenticationRequestService;
 pub use field_permission::FieldPermissionService;
 pub use login::LoginService;
+pub use oidc::OidcService;
 pub use password::PasswordService;
 pub use permission::PermissionService;
 pub use register::RegisterService;
 pub use role::RoleService;
+pub use session::SessionService;
 pub use token::TokenService;
 pub use user::UserService;
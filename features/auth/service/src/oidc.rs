@@ -0,0 +1,666 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use reqwest::Client;
+use sea_orm::ConnectionTrait;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+    time::Duration,
+};
+use tracing::{debug, error};
+use url::Url;
+use uuid::Uuid;
+
+use shared_shared_app::event_task::{outbox::Outbox, producer::ProducerMessage};
+use shared_shared_config::db::{write_db, UnitOfWork};
+use shared_shared_data_app::result::Result;
+use shared_shared_data_cache::cache::Cache;
+use shared_shared_data_error::{app::AppError, auth::AuthError};
+
+use features_auth_entities::{
+    access::AccessForCreateDto,
+    active_code::{ActiveCodeForCreateDto, ActiveCodeForUpdateDto},
+    external_identity::ExternalIdentityForCreateDto,
+    user::{ModelOptionDto as UserModelOptionDto, UserForUpdateDto},
+};
+use features_auth_model::{
+    auth_code::AuthCodeForCreateRequest,
+    authentication::AuthRegisterData,
+    oidc::{
+        OidcDiscoveryDocument, OidcEndpoints, OidcLinkMode, OidcLoginState, OidcProviderConfig,
+        OidcTokenResponse, OidcUserInfo,
+    },
+    state::AuthCacheState,
+    user::UserForCreateRequest,
+};
+use features_auth_repo::{
+    access::AccessMutation,
+    active_code::{mutation::ActiveCodeMutation, query::ActiveCodeQuery},
+    auth_code::AuthCodeMutation,
+    authentication::AuthenticationRequestQuery,
+    client::ClientQuery,
+    external_identity::{ExternalIdentityMutation, ExternalIdentityQuery},
+    user::{UserMutation, UserQuery},
+};
+use features_auth_stream::{signin::SignInMessage, AuthMessage};
+
+use crate::AuthenticationRequestService;
+
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+const HTTP_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_LANGUAGE: &str = "en-US";
+/// Wrong codes accepted for a pending link before a new code has to be requested
+const MAX_LINK_ATTEMPTS: i32 = 5;
+
+/// A login has to come back from the provider within this time.
+const LOGIN_STATE_TTL_SECS: u64 = 600;
+const LOGIN_STATE_KEY_PREFIX: &str = "oidc_login";
+const STATE_LENGTH: usize = 32;
+/// RFC 7636 allows 43 to 128 characters.
+const CODE_VERIFIER_LENGTH: usize = 64;
+const BINDING_LENGTH: usize = 32;
+
+/// Providers configured through the `OIDC_PROVIDERS` JSON array.
+static PROVIDERS: LazyLock<Vec<OidcProviderConfig>> = LazyLock::new(|| {
+    let raw = std::env::var("OIDC_PROVIDERS").unwrap_or_else(|_| "[]".to_string());
+    OidcProviderConfig::parse_list(&raw).unwrap_or_else(|e| {
+        error!("Invalid OIDC_PROVIDERS configuration: {}", e);
+        vec![]
+    })
+});
+
+/// Discovery documents by issuer, fetched once per process.
+static DISCOVERY_CACHE: LazyLock<RwLock<HashMap<String, OidcDiscoveryDocument>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub struct OidcService {}
+
+impl OidcService {
+    fn http_client() -> Client {
+        Client::builder()
+            .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default()
+    }
+
+    pub fn provider(name: &str) -> Result<OidcProviderConfig> {
+        PROVIDERS
+            .iter()
+            .find(|p| p.name == name)
+            .cloned()
+            .ok_or(AppError::Auth(AuthError::UnknownProvider))
+    }
+
+    /// Fetch (or reuse) the provider metadata from `{issuer}/.well-known/openid-configuration`.
+    pub async fn discover(config: &OidcProviderConfig) -> Result<OidcDiscoveryDocument> {
+        if let Some(document) = DISCOVERY_CACHE
+            .read()
+            .ok()
+            .and_then(|cache| cache.get(&config.issuer).cloned())
+        {
+            return Ok(document);
+        }
+        let url = format!("{}{}", config.issuer.trim_end_matches('/'), DISCOVERY_PATH);
+        let document: OidcDiscoveryDocument = Self::http_client()
+            .get(&url)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| Self::provider_error("discovery", e))?
+            .json()
+            .await
+            .map_err(|e| Self::provider_error("discovery", e))?;
+        if document.issuer.trim_end_matches('/') != config.issuer.trim_end_matches('/') {
+            error!(
+                "Discovery issuer mismatch: expected {}, got {}",
+                config.issuer, document.issuer
+            );
+            return Err(AppError::Auth(AuthError::ProviderFailed));
+        }
+        if let Ok(mut cache) = DISCOVERY_CACHE.write() {
+            cache.insert(config.issuer.clone(), document.clone());
+        }
+        Ok(document)
+    }
+
+    pub async fn endpoints(config: &OidcProviderConfig) -> Result<OidcEndpoints> {
+        let discovery = if config.has_static_endpoints() {
+            None
+        } else {
+            Some(Self::discover(config).await?)
+        };
+        config
+            .resolve_endpoints(discovery.as_ref())
+            .ok_or(AppError::Auth(AuthError::ProviderFailed))
+    }
+
+    /// URL of the provider consent page. `state` is passed through to the callback,
+    /// `code_challenge` is the S256 challenge of the PKCE verifier sent with the code.
+    pub fn authorization_url(
+        config: &OidcProviderConfig,
+        endpoints: &OidcEndpoints,
+        state: &str,
+        code_challenge: &str,
+    ) -> Result<String> {
+        let mut url = Url::parse(&endpoints.authorization_endpoint)
+            .map_err(|_| AppError::Auth(AuthError::ProviderFailed))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", &config.redirect_uri)
+            .append_pair("scope", &config.scopes.join(" "))
+            .append_pair("state", state)
+            .append_pair("code_challenge", code_challenge)
+            .append_pair("code_challenge_method", "S256");
+        Ok(url.to_string())
+    }
+
+    pub async fn exchange_code(
+        config: &OidcProviderConfig,
+        endpoints: &OidcEndpoints,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<OidcTokenResponse> {
+        Self::http_client()
+            .post(&endpoints.token_endpoint)
+            .header("Accept", "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", config.redirect_uri.as_str()),
+                ("client_id", config.client_id.as_str()),
+                ("client_secret", config.client_secret.as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| Self::provider_error("token", e))?
+            .json()
+            .await
+            .map_err(|e| Self::provider_error("token", e))
+    }
+
+    pub async fn fetch_user_info(
+        config: &OidcProviderConfig,
+        endpoints: &OidcEndpoints,
+        access_token: &str,
+    ) -> Result<OidcUserInfo> {
+        let claims: Value = Self::http_client()
+            .get(&endpoints.userinfo_endpoint)
+            .bearer_auth(access_token)
+            .header("Accept", "application/json")
+            .header("User-Agent", "auth-service")
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| Self::provider_error("userinfo", e))?
+            .json()
+            .await
+            .map_err(|e| Self::provider_error("userinfo", e))?;
+        OidcUserInfo::from_claims(&claims, config).ok_or_else(|| {
+            error!(
+                "Userinfo of {} has no {} claim",
+                config.name, config.subject_claim
+            );
+            AppError::Auth(AuthError::ProviderFailed)
+        })
+    }
+
+    pub fn code_challenge(code_verifier: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+    }
+
+    /// What is cached of the binding cookie of a login.
+    pub fn binding_hash(binding: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(binding.as_bytes()))
+    }
+
+    fn login_state_key(state: &str) -> String {
+        format!("{}:{}", LOGIN_STATE_KEY_PREFIX, state)
+    }
+
+    fn random_string(length: usize) -> String {
+        thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(length)
+            .map(char::from)
+            .collect()
+    }
+
+    /// Start a social login for an existing authentication request.
+    /// Returns the provider URL and the binding the browser has to send back to the callback.
+    pub async fn authorize(
+        cache: &Cache<String, AuthCacheState>,
+        provider: &str,
+        request_id: &str,
+    ) -> Result<(String, String)> {
+        let config = Self::provider(provider)?;
+        let request_id = Uuid::parse_str(request_id).map_err(|_| AppError::Unknown)?;
+        AuthenticationRequestQuery::get(request_id)
+            .await
+            .map_err(|_| AppError::EntityNotFound {
+                entity: "request".to_string(),
+            })?;
+        let endpoints = Self::endpoints(&config).await?;
+
+        let state = Self::random_string(STATE_LENGTH);
+        let code_verifier = Self::random_string(CODE_VERIFIER_LENGTH);
+        let binding = Self::random_string(BINDING_LENGTH);
+        let authorization_url = Self::authorization_url(
+            &config,
+            &endpoints,
+            &state,
+            &Self::code_challenge(&code_verifier),
+        )?;
+        let login = OidcLoginState {
+            request_id,
+            provider: config.name.clone(),
+            code_verifier,
+            binding_hash: Self::binding_hash(&binding),
+        };
+        cache
+            .insert(
+                Self::login_state_key(&state),
+                AuthCacheState::OidcLogin(login),
+                Some(Duration::from_secs(LOGIN_STATE_TTL_SECS)),
+            )
+            .map_err(|e| {
+                error!("Error caching OIDC login state: {}", e);
+                AppError::Unknown
+            })?;
+        Ok((authorization_url, binding))
+    }
+
+    /// Take the login started by `authorize` out of the cache. A state is accepted once, for its
+    /// provider and from the browser holding its binding.
+    pub fn take_login_state(
+        cache: &Cache<String, AuthCacheState>,
+        provider: &str,
+        state: &str,
+        binding: Option<&str>,
+    ) -> Result<OidcLoginState> {
+        let key = Self::login_state_key(state);
+        let login = match cache.get(&key) {
+            Ok(Some(AuthCacheState::OidcLogin(login))) => login,
+            Ok(_) => return Err(AppError::Auth(AuthError::InvalidOidcState)),
+            Err(e) => {
+                error!("Error reading OIDC login state: {}", e);
+                return Err(AppError::Unknown);
+            }
+        };
+        // Of two callbacks racing on the same state only the one removing it goes on
+        let removed = cache.remove(&key).map_err(|e| {
+            error!("Error removing OIDC login state: {}", e);
+            AppError::Unknown
+        })?;
+        let bound =
+            binding.is_some_and(|binding| Self::binding_hash(binding) == login.binding_hash);
+        if !removed || !bound || login.provider != provider {
+            return Err(AppError::Auth(AuthError::InvalidOidcState));
+        }
+        Ok(login)
+    }
+
+    /// Finish a social login: resolve the provider account to a local user and issue an auth code.
+    /// `topic` is the one of the auth producer, a pending link sends its code through it.
+    pub async fn callback(
+        cache: &Cache<String, AuthCacheState>,
+        topic: &str,
+        provider: &str,
+        code: &str,
+        state: &str,
+        binding: Option<&str>,
+    ) -> Result<AuthRegisterData> {
+        let config = Self::provider(provider)?;
+        let login = Self::take_login_state(cache, &config.name, state, binding)?;
+        let request_code_data = AuthenticationRequestQuery::get(login.request_id)
+            .await
+            .map_err(|_| AppError::EntityNotFound {
+                entity: "request".to_string(),
+            })?;
+        let client_id = request_code_data
+            .client_id
+            .ok_or(AppError::Auth(AuthError::InvalidOidcState))?;
+
+        let endpoints = Self::endpoints(&config).await?;
+        let token = Self::exchange_code(&config, &endpoints, code, &login.code_verifier).await?;
+        let user_info = Self::fetch_user_info(&config, &endpoints, &token.access_token).await?;
+        debug!("OIDC user info from {}: {:?}", provider, user_info);
+
+        let user_id = Self::resolve_user(topic, &config.name, &user_info, client_id).await?;
+
+        let redirect_uri = request_code_data.redirect_uri.clone().unwrap_or_default();
+        let auth_code_request = AuthCodeForCreateRequest {
+            client_id: Some(client_id),
+            redirect_uri: request_code_data.redirect_uri,
+            scopes: request_code_data.scopes,
+            user_id: Some(user_id),
+        };
+        let db = write_db().expect("DB_WRITE is not initialized");
+        let (_code_id, auth_code) = AuthCodeMutation::create_with_txn(auth_code_request.into(), db)
+            .await
+            .map_err(|e| {
+                debug!("Error creating auth code: {:?}", e);
+                AppError::Unknown
+            })?;
+
+        Ok(AuthRegisterData {
+            user_id,
+            id_token: auth_code,
+            redirect_uri,
+        })
+    }
+
+    /// Find the local user for a provider account.
+    /// 1. An already linked identity wins, a link waiting for confirmation gets a new code.
+    /// 2. Otherwise the user with the same *verified* email is linked right away when it
+    ///    confirmed its email, otherwise a pending link is created and a code is sent to it.
+    /// 3. Otherwise a new, already active user is created with the client's default role.
+    pub async fn resolve_user(
+        topic: &str,
+        provider: &str,
+        user_info: &OidcUserInfo,
+        client_id: Uuid,
+    ) -> Result<Uuid> {
+        if let Some(identity) =
+            ExternalIdentityQuery::find_by_provider_subject(provider, &user_info.subject)
+                .await
+                .map_err(AppError::DbErr)?
+        {
+            let user_id = identity.user_id.ok_or(AppError::Unknown)?;
+            if identity.confirmed == Some(true) {
+                return Ok(user_id);
+            }
+            let identity_id = identity.id.ok_or(AppError::Unknown)?;
+            let unit_of_work = UnitOfWork::begin().await.map_err(|_| AppError::Unknown)?;
+            Self::send_link_code(
+                unit_of_work.txn(),
+                topic,
+                user_id,
+                client_id,
+                provider,
+                identity_id,
+            )
+            .await?;
+            unit_of_work.commit().await.map_err(|e| {
+                debug!("Error committing transaction: {:?}", e);
+                AppError::Unknown
+            })?;
+            return Err(AppError::Auth(AuthError::LinkPending));
+        }
+
+        let email = match (&user_info.email, user_info.email_verified) {
+            (Some(email), true) => email.clone(),
+            _ => return Err(AppError::Auth(AuthError::UnverifiedEmail)),
+        };
+        let identity = ExternalIdentityForCreateDto {
+            user_id: Uuid::nil(),
+            provider: provider.to_string(),
+            subject: user_info.subject.clone(),
+            email: Some(email.clone()),
+            confirmed: false,
+        };
+
+        match UserQuery::get_user_by_email(email.clone()).await {
+            Ok(existing) => {
+                return Self::link_existing_user(topic, existing, identity, client_id).await
+            }
+            Err(AppError::Auth(AuthError::NotFoundUser)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let default_role_id = AuthenticationRequestService::default_role_id(client_id).await?;
+        // Social users never log in with a password, store an unusable random one
+        let create_user_request = UserForCreateRequest {
+            email,
+            password: Self::random_string(48),
+            language: DEFAULT_LANGUAGE.to_string(),
+        };
+
+        let unit_of_work = UnitOfWork::begin().await.map_err(|_| AppError::Unknown)?;
+        let user_id =
+            UserMutation::create_user_with_txn(create_user_request.into(), unit_of_work.txn())
+                .await
+                .map_err(|e| {
+                    debug!("Error creating user: {:?}", e);
+                    AppError::Auth(AuthError::ExistingUser)
+                })?;
+        // The provider verified the email, no activation code is needed
+        UserMutation::update_with_txn(
+            user_id,
+            UserForUpdateDto {
+                email: None,
+                language: None,
+                password: None,
+                confirmed: Some(true),
+                two_factor_enabled: None,
+                is_active: Some(true),
+            },
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(|e| {
+            debug!("Error activating user: {:?}", e);
+            AppError::Unknown
+        })?;
+        AccessMutation::create_with_txn(
+            AccessForCreateDto {
+                user_id,
+                role_id: default_role_id,
+                key: "".to_string(),
+            },
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(|e| {
+            debug!("Error assigning role to user: {:?}", e);
+            AppError::Auth(AuthError::UnknowRole)
+        })?;
+        ExternalIdentityMutation::create_with_txn(
+            ExternalIdentityForCreateDto {
+                user_id,
+                confirmed: true,
+                ..identity
+            },
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(AppError::DbErr)?;
+        unit_of_work.commit().await.map_err(|e| {
+            debug!("Error committing transaction: {:?}", e);
+            AppError::Unknown
+        })?;
+
+        Ok(user_id)
+    }
+
+    /// Link a provider account to the user owning its email, see [`OidcLinkMode`].
+    async fn link_existing_user(
+        topic: &str,
+        existing: UserModelOptionDto,
+        identity: ExternalIdentityForCreateDto,
+        client_id: Uuid,
+    ) -> Result<Uuid> {
+        let user_id = existing.id.ok_or(AppError::Unknown)?;
+        if OidcLinkMode::for_existing_user(existing.confirmed) == OidcLinkMode::Linked {
+            debug!(
+                "Linking {} account to existing user {}",
+                identity.provider, user_id
+            );
+            ExternalIdentityMutation::create(ExternalIdentityForCreateDto {
+                user_id,
+                confirmed: true,
+                ..identity
+            })
+            .await
+            .map_err(AppError::DbErr)?;
+            return Ok(user_id);
+        }
+
+        debug!(
+            "User {} did not confirm its email, {} link waits for confirmation",
+            user_id, identity.provider
+        );
+        let provider = identity.provider.clone();
+        let unit_of_work = UnitOfWork::begin().await.map_err(|_| AppError::Unknown)?;
+        let identity_id = ExternalIdentityMutation::create_with_txn(
+            ExternalIdentityForCreateDto {
+                user_id,
+                ..identity
+            },
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(AppError::DbErr)?;
+        Self::send_link_code(
+            unit_of_work.txn(),
+            topic,
+            user_id,
+            client_id,
+            &provider,
+            identity_id,
+        )
+        .await?;
+        unit_of_work.commit().await.map_err(|e| {
+            debug!("Error committing transaction: {:?}", e);
+            AppError::Unknown
+        })?;
+        Err(AppError::Auth(AuthError::LinkPending))
+    }
+
+    /// Store a link code for `user_id` and the message mailing it to the user in `txn`,
+    /// with the email template and sender of the client the login started from.
+    async fn send_link_code(
+        txn: &impl ConnectionTrait,
+        topic: &str,
+        user_id: Uuid,
+        client_id: Uuid,
+        provider: &str,
+        identity_id: Uuid,
+    ) -> Result<()> {
+        let user = UserQuery::get_user_by_id_raw(user_id).await?;
+        let client = ClientQuery::get(client_id).await?;
+        let link_code: String = thread_rng()
+            .sample_iter(&rand::distributions::Uniform::from(0..10))
+            .take(6)
+            .map(|n| n.to_string())
+            .collect();
+        let code_id = ActiveCodeMutation::create_with_txn(
+            ActiveCodeForCreateDto {
+                user_id,
+                code: link_code.clone(),
+            },
+            txn,
+        )
+        .await
+        .map_err(|e| {
+            debug!("Error creating link code: {:?}", e);
+            AppError::Unknown
+        })?;
+        ExternalIdentityMutation::set_link_code_with_txn(identity_id, code_id, txn)
+            .await
+            .map_err(AppError::DbErr)?;
+
+        let client_email = client.get_email().unwrap_or_default();
+        let message = ProducerMessage {
+            payload: AuthMessage::SignIn {
+                message: SignInMessage::LinkRequest {
+                    user_id: user_id.to_string(),
+                    email: user.email.unwrap_or_default(),
+                    provider: provider.to_string(),
+                    identity_id: identity_id.to_string(),
+                    link_code,
+                    app_key: client.client_key.unwrap_or_default(),
+                    language_code: user.language.unwrap_or_default(),
+                    client_email,
+                },
+            },
+            key: None,
+        };
+        Outbox::enqueue(txn, topic, &message).await.map_err(|e| {
+            debug!("Error storing link request message in outbox: {:?}", e);
+            AppError::Unknown
+        })?;
+        Ok(())
+    }
+
+    /// Confirm a pending link with the code mailed to the owner of the local account.
+    /// Only the last code sent for this identity is accepted, and only for
+    /// `MAX_LINK_ATTEMPTS` wrong tries. The account keeps its credentials.
+    pub async fn confirm_identity(identity_id: Uuid, link_code: String) -> Result<Uuid> {
+        let identity = ExternalIdentityQuery::get_raw(identity_id)
+            .await
+            .map_err(|_| AppError::EntityNotFound {
+                entity: "external_identity".to_string(),
+            })?;
+        let user_id = identity.user_id.ok_or(AppError::Unknown)?;
+        let code_id = match identity.link_code_id.flatten() {
+            Some(code_id) if identity.confirmed != Some(true) => code_id,
+            _ => return Err(AppError::Auth(AuthError::LinkCodeNotFound)),
+        };
+        if identity.link_attempts.unwrap_or_default() >= MAX_LINK_ATTEMPTS {
+            return Err(AppError::Auth(AuthError::TooManyLinkAttempts));
+        }
+
+        let active_code = ActiveCodeQuery::get(code_id)
+            .await
+            .map_err(|_| AppError::Auth(AuthError::LinkCodeNotFound))?;
+        if active_code.is_used == Some(true)
+            || active_code.user_id != Some(user_id)
+            || active_code.code.as_deref() != Some(link_code.as_str())
+        {
+            ExternalIdentityMutation::record_link_attempt(identity_id)
+                .await
+                .map_err(AppError::DbErr)?;
+            return Err(AppError::Auth(AuthError::LinkCodeNotFound));
+        }
+        let expiration_time = active_code.expiration_time.ok_or(AppError::Unknown)?;
+        if Utc::now().naive_utc() > expiration_time {
+            return Err(AppError::Auth(AuthError::LinkCodeExpired));
+        }
+
+        let unit_of_work = UnitOfWork::begin().await.map_err(|_| AppError::Unknown)?;
+        ActiveCodeMutation::update_with_txn(
+            code_id,
+            ActiveCodeForUpdateDto {
+                is_used: Some(true),
+                is_sent: None,
+            },
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(|_| AppError::Unknown)?;
+        ExternalIdentityMutation::confirm_with_txn(identity_id, unit_of_work.txn())
+            .await
+            .map_err(AppError::DbErr)?;
+        UserMutation::update_with_txn(
+            user_id,
+            UserForUpdateDto {
+                email: None,
+                language: None,
+                password: None,
+                confirmed: Some(true),
+                two_factor_enabled: None,
+                is_active: None,
+            },
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(|_| AppError::Unknown)?;
+        unit_of_work.commit().await.map_err(|e| {
+            debug!("Error committing transaction: {:?}", e);
+            AppError::Unknown
+        })?;
+
+        Ok(user_id)
+    }
+
+    fn provider_error(step: &str, e: reqwest::Error) -> AppError {
+        error!("OIDC {} request failed: {}", step, e);
+        AppError::Auth(AuthError::ProviderFailed)
+    }
+}
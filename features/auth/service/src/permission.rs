@@ -1,19 +1,26 @@
+use std::collections::HashMap;
+
 use shared_shared_data_core::{
     filter::{FilterCondition, FilterEnum, FilterOperator, FilterParam},
     order::Order,
     paging::{Pagination, QueryResult},
+    query_params::QueryParams,
 };
 use tracing::debug;
 use uuid::Uuid;
 
+use shared_shared_auth::hierarchy::{PermissionExplanation, RoleHierarchy};
 use shared_shared_data_app::result::Result;
 
-use features_auth_model::permission::{
-    PermissionData, PermissionForCreateRequest, PermissionForUpdateRequest,
+use features_auth_model::{
+    permission::{PermissionData, PermissionForCreateRequest, PermissionForUpdateRequest},
+    role::RoleData,
 };
 use features_auth_repo::{
     permission::{PermissionMutation, PermissionQuery},
+    role::RoleQuery,
     role_permission::RolePermissionQuery,
+    user::UserQuery,
 };
 
 pub struct PermissionService {}
@@ -62,6 +69,8 @@ impl PermissionService {
             return Ok(QueryResult {
                 total_page: 0,
                 result: vec![],
+                next_cursor: None,
+                prev_cursor: None,
             });
         }
         let permission_ids_str = permission_ids
@@ -92,4 +101,80 @@ impl PermissionService {
         let result = PermissionMutation::delete(permission_id).await?;
         Ok(result)
     }
+
+    /// Explain whether a user can perform `action` on `resource`.
+    /// Walks the parent chain and wildcard resources of every role assigned to the user.
+    pub async fn explain(
+        user_id: Uuid,
+        resource: String,
+        action: u32,
+    ) -> Result<PermissionExplanation> {
+        // An unknown user has no access, answer the documented 404 instead of "not allowed"
+        UserQuery::get_user_by_id_raw(user_id).await?;
+        let role_names: Vec<String> = UserQuery::get_access_data_by_user_id(user_id)
+            .await?
+            .into_iter()
+            .map(|access| access.role_name)
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        let roles = Self::all_roles_with_permissions().await?;
+        let role_names_by_id: HashMap<Uuid, String> = roles
+            .iter()
+            .filter_map(|role| Some((role.get_id()?, role.get_name()?)))
+            .collect();
+
+        let mut parents: HashMap<String, Vec<String>> = HashMap::new();
+        let mut permissions: HashMap<String, Vec<(String, u32)>> = HashMap::new();
+        for role in roles {
+            let Some(name) = role.get_name() else {
+                continue;
+            };
+            if let Some(parent_name) = role
+                .get_parent_role_id()
+                .and_then(|parent_id| role_names_by_id.get(&parent_id))
+            {
+                parents.insert(name.clone(), vec![parent_name.clone()]);
+            }
+            let masks = role
+                .permissions
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|p| Some((p.resource?, p.mask.unwrap_or(0) as u32)))
+                .collect();
+            permissions.insert(name, masks);
+        }
+
+        let hierarchy = RoleHierarchy::new(parents);
+        let explanation = hierarchy.explain(&role_names, &resource, action, |role| {
+            permissions.get(role).cloned().unwrap_or_default()
+        });
+        debug!("Permission explanation for user {}: {:?}", user_id, explanation);
+        Ok(explanation)
+    }
+
+    async fn all_roles_with_permissions() -> Result<Vec<RoleData>> {
+        let order = Order::default();
+        let mut query_params = QueryParams::default();
+        query_params.add_includes(vec!["permissions".to_string()]);
+        let mut page = 1u64;
+        let mut roles: Vec<RoleData> = vec![];
+        loop {
+            let pagination = Pagination::new(page, 100);
+            let result = RoleQuery::search(
+                &pagination,
+                &order,
+                &FilterCondition::and(vec![]),
+                &query_params,
+                &FilterCondition::and(vec![]),
+            )
+            .await?;
+            roles.extend(result.result);
+            if page >= result.total_page {
+                break;
+            }
+            page += 1;
+        }
+        Ok(roles)
+    }
 }
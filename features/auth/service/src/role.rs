@@ -1,3 +1,6 @@
+use sea_orm::DbErr;
+use std::collections::HashSet;
+
 use shared_shared_data_core::{
     filter::{FilterCondition, FilterEnum, FilterOperator, FilterParam},
     order::Order,
@@ -8,6 +11,7 @@ use tracing::debug;
 use uuid::Uuid;
 
 use shared_shared_data_app::result::Result;
+use shared_shared_data_error::{app::AppError, auth::AuthError};
 
 use features_auth_model::role::{RoleData, RoleForCreateRequest};
 use features_auth_repo::{
@@ -24,10 +28,42 @@ impl RoleService {
     }
 
     pub async fn get<'a>(role_id: Uuid, query_params: &QueryParams) -> Result<RoleData> {
-        let role = RoleQuery::get(role_id, query_params, &FilterCondition::and(vec![])).await?;
+        let role = RoleQuery::get(role_id, query_params, &FilterCondition::and(vec![]))
+            .await
+            .map_err(|e| match e {
+                DbErr::RecordNotFound(_) => AppError::Auth(AuthError::UnknowRole),
+                e => AppError::DbErr(e),
+            })?;
         Ok(role.into())
     }
 
+    /// A role can only inherit from an existing role of the same client that is neither itself
+    /// nor one of its descendants, `role_id` being None for a new role. Role names are only
+    /// unique per client, a parent of another client would grant what its namesakes have.
+    pub async fn check_parent(
+        role_id: Option<Uuid>,
+        client_id: Option<Uuid>,
+        parent_role_id: Option<Uuid>,
+    ) -> Result<()> {
+        let mut visited: HashSet<Uuid> = HashSet::new();
+        let mut ancestor_id = parent_role_id;
+        while let Some(id) = ancestor_id {
+            if Some(id) == role_id {
+                return Err(AppError::Auth(AuthError::RoleCycle));
+            }
+            // A cycle stored before parents were checked
+            if !visited.insert(id) {
+                break;
+            }
+            let ancestor = Self::get(id, &QueryParams::default()).await?;
+            if Some(id) == parent_role_id && ancestor.get_client_id() != client_id {
+                return Err(AppError::Auth(AuthError::ParentRoleOfOtherClient));
+            }
+            ancestor_id = ancestor.get_parent_role_id();
+        }
+        Ok(())
+    }
+
     pub async fn delete<'a>(role_id: Uuid) -> Result<bool> {
         let result = RoleMutation::delete(role_id).await?;
         Ok(result)
@@ -69,4 +105,28 @@ impl RoleService {
         }
         Ok(true)
     }
+
+    /// Permission ids currently assigned to a role, sorted for stable audit snapshots.
+    pub async fn permission_ids(role_id: Uuid) -> Result<Vec<Uuid>> {
+        let filters = vec![FilterEnum::Uuid(FilterParam {
+            name: "role_id".to_string(),
+            operator: FilterOperator::Equal,
+            value: Some(role_id),
+            raw_value: role_id.to_string(),
+        })];
+        let search = RolePermissionQuery::search(
+            &Pagination::new(1, 200),
+            &Order::default(),
+            &FilterCondition::from(filters),
+        )
+        .await?;
+        let mut permission_ids: Vec<Uuid> = search
+            .result
+            .into_iter()
+            .filter_map(|rp| rp.permission_id)
+            .collect();
+        permission_ids.sort();
+        permission_ids.dedup();
+        Ok(permission_ids)
+    }
 }
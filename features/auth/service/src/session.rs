@@ -0,0 +1,175 @@
+use chrono::Duration;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::Instant,
+};
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use shared_shared_app::event_task::producer::{Producer, ProducerMessage};
+use shared_shared_auth::token::{
+    get_access_token_cache_key, get_refresh_token_cache_key, insecured_decode_jti,
+};
+use shared_shared_data_app::result::Result;
+use shared_shared_data_cache::cache::Cache;
+use shared_shared_data_error::{app::AppError, auth::TokenError};
+
+use features_auth_model::{
+    state::AuthCacheState,
+    token::{SessionData, SessionDevice},
+};
+use features_auth_repo::{
+    client::ClientQuery,
+    token::{TokenMutation, TokenQuery},
+    user::UserQuery,
+};
+use features_auth_stream::{signin::SignInMessage, AuthMessage};
+
+/// `last_used_at` is written at most once per interval to avoid a write on every verification.
+const SESSION_TOUCH_INTERVAL: i64 = 60; // seconds
+/// Tokens whose last touch is remembered before the expired ones are dropped.
+const MAX_TRACKED_TOUCHES: usize = 10_000;
+
+/// Last touch of each access token by this instance, the database is only queried once per
+/// interval and token. Other instances still go through the condition of the update.
+static LAST_TOUCHES: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub struct SessionService {}
+
+impl SessionService {
+    pub async fn list_sessions(user_id: Uuid) -> Result<Vec<SessionData>> {
+        let sessions = TokenQuery::find_active_by_user_id(user_id).await?;
+        Ok(sessions.into_iter().map(SessionData::from).collect())
+    }
+
+    /// Revoke a session of `user_id`. Its tokens stop verifying and refreshing immediately.
+    pub async fn revoke_session(
+        cache: &Cache<String, AuthCacheState>,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<SessionData> {
+        let session = TokenQuery::find_by_id(session_id)
+            .await?
+            .filter(|token| token.user_id == user_id && token.revoked_at.is_none())
+            .ok_or(AppError::NotFound(format!("session {}", session_id)))?;
+        TokenMutation::revoke(session_id).await?;
+
+        // Only the last issued tokens of a user are cached, drop them if they belong to this session
+        let cached_tokens = [
+            (
+                get_access_token_cache_key(user_id),
+                session.access_token.as_str(),
+            ),
+            (
+                get_refresh_token_cache_key(user_id),
+                session.refresh_token.as_str(),
+            ),
+        ];
+        for (cache_key, token) in cached_tokens {
+            let Ok(jti) = insecured_decode_jti(token) else {
+                continue;
+            };
+            let cached_jti = match cache.get(&cache_key) {
+                Ok(Some(AuthCacheState::AccessToken(jti))) => Some(jti),
+                Ok(Some(AuthCacheState::RefreshToken(jti))) => Some(jti),
+                Ok(_) => None,
+                Err(e) => {
+                    error!("Error reading token cache for user_id: {}: {}", user_id, e);
+                    return Err(AppError::Unknown);
+                }
+            };
+            if cached_jti == Some(jti) {
+                cache.remove(&cache_key).map_err(|_| AppError::Unknown)?;
+                debug!(
+                    "Token cache {} removed for session {}",
+                    cache_key, session_id
+                );
+            }
+        }
+
+        Ok(session.into())
+    }
+
+    /// Fail when the session a refresh token belongs to was revoked.
+    pub async fn ensure_active(session_id: Uuid) -> Result<()> {
+        match TokenQuery::find_by_id(session_id).await? {
+            Some(token) if token.revoked_at.is_none() => Ok(()),
+            _ => Err(AppError::Token(TokenError::InvalidToken)),
+        }
+    }
+
+    pub async fn touch(access_token: &str) {
+        if !Self::touch_due(access_token) {
+            return;
+        }
+        let touched = TokenMutation::touch_by_access_token(
+            access_token,
+            Duration::seconds(SESSION_TOUCH_INTERVAL),
+        )
+        .await;
+        if let Err(e) = touched {
+            error!("Error updating session last use: {}", e);
+        }
+    }
+
+    /// Whether this instance did not touch `access_token` within the interval, and record it.
+    fn touch_due(access_token: &str) -> bool {
+        let interval = SESSION_TOUCH_INTERVAL as u64;
+        let mut last_touches = LAST_TOUCHES.lock().unwrap_or_else(|e| e.into_inner());
+        if last_touches
+            .get(access_token)
+            .is_some_and(|last| last.elapsed().as_secs() < interval)
+        {
+            return false;
+        }
+        if last_touches.len() >= MAX_TRACKED_TOUCHES {
+            last_touches.retain(|_, last| last.elapsed().as_secs() < interval);
+        }
+        last_touches.insert(access_token.to_string(), Instant::now());
+        true
+    }
+
+    /// Whether `device` was never used by the user before. A first sign-in is not a new device.
+    pub async fn is_new_device(user_id: Uuid, device: &SessionDevice) -> Result<bool> {
+        let Some(user_agent) = device.user_agent.as_deref() else {
+            return Ok(false);
+        };
+        if !TokenQuery::has_session(user_id, None).await? {
+            return Ok(false);
+        }
+        Ok(!TokenQuery::has_session(user_id, Some(user_agent)).await?)
+    }
+
+    /// Ask the notification service to warn the user about a sign-in from a new device.
+    pub async fn notify_new_device(
+        producer: &Producer,
+        user_id: Uuid,
+        client_id: Uuid,
+        device: &SessionDevice,
+    ) -> Result<()> {
+        let user = UserQuery::get_user_by_id_raw(user_id).await?;
+        let client = ClientQuery::get(client_id).await?;
+        let auth_message = AuthMessage::SignIn {
+            message: SignInMessage::NewDevice {
+                user_id: user_id.to_string(),
+                email: user.email.unwrap_or_default(),
+                client_id: client_id.to_string(),
+                user_agent: device.user_agent.clone().unwrap_or_default(),
+                ip_address: device.ip_address.clone().unwrap_or_default(),
+                app_key: client.client_key.clone().unwrap_or_default(),
+                language_code: user.language.unwrap_or_default(),
+                client_email: client.get_email().unwrap_or_default(),
+            },
+        };
+        let message = ProducerMessage {
+            payload: auth_message,
+            key: None,
+        };
+        if let Err(e) = producer.send(&message).await {
+            debug!("Error sending new device message to Kafka: {:?}", e.reason);
+        }
+        Ok(())
+    }
+}
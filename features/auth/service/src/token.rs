@@ -1,5 +1,5 @@
 use std::time::Duration;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use shared_shared_auth::{
@@ -12,12 +12,13 @@ use shared_shared_auth::{
     },
 };
 
+use shared_shared_app::event_task::producer::Producer;
 use shared_shared_data_error::auth::TokenError;
 
 use features_auth_entities::token::{TokenForCreateDto, TokenForUpdateDto};
 use features_auth_model::{
     state::AuthCacheState,
-    token::{GrantType, TokenForCreateRequest, TokenForVerifyRequest},
+    token::{GrantType, SessionDevice, TokenForCreateRequest, TokenForVerifyRequest},
 };
 use shared_shared_data_app::result::Result;
 use shared_shared_data_cache::cache::Cache;
@@ -30,6 +31,8 @@ use features_auth_repo::{
     user::UserQuery,
 };
 
+use crate::session::SessionService;
+
 pub struct TokenService;
 
 impl TokenService {
@@ -37,7 +40,9 @@ impl TokenService {
     // For example, create_token, delete_token, get_token, etc.
     pub async fn create_authorization_data<'a>(
         cache: &'a Cache<String, AuthCacheState>,
+        producer: Option<&'a Producer>,
         token_request: &'a TokenForCreateRequest,
+        device: SessionDevice,
     ) -> Result<AuthorizationCodeData> {
         // Convert TokenForCreateRequest to TokenForCreateDto
         let grant_type = token_request.grant_type.clone().unwrap();
@@ -78,6 +83,7 @@ impl TokenService {
                 let user_id = auth_code.user_id.unwrap();
                 let accesses = UserQuery::get_access_data_by_user_id(user_id).await?;
                 debug!("AuthCode found: {:?}", auth_code);
+                let is_new_device = SessionService::is_new_device(user_id, &device).await?;
                 let authorization_code_data = create_new_token_authorization_data(
                     cache,
                     user_id,
@@ -85,6 +91,7 @@ impl TokenService {
                     &client_secret,
                     accesses,
                     scopes,
+                    &device,
                 )
                 .await;
                 if authorization_code_data.is_err() {
@@ -92,6 +99,18 @@ impl TokenService {
                     return Err(authorization_code_data.err().unwrap());
                 }
                 let authorization_code_data = authorization_code_data.unwrap();
+                // The tokens are issued, a failed notification must not fail the sign-in
+                if let (true, Some(producer)) = (is_new_device, producer) {
+                    if let Err(e) =
+                        SessionService::notify_new_device(producer, user_id, client_id, &device)
+                            .await
+                    {
+                        warn!(
+                            "Error notifying new device for user_id: {}: {:?}",
+                            user_id, e
+                        );
+                    }
+                }
 
                 authorization_data = authorization_code_data;
                 debug!("Access token is created successfully");
@@ -145,6 +164,7 @@ impl TokenService {
                 return Err(AppError::Token(TokenError::InvalidToken));
             }
         }
+        SessionService::touch(&token).await;
 
         Ok(access_data)
     }
@@ -157,6 +177,7 @@ pub async fn create_new_token_authorization_data<'a>(
     client_secret: &str,
     accesses: Vec<UserAccessData>,
     scopes: Vec<String>,
+    device: &SessionDevice,
 ) -> Result<AuthorizationCodeData> {
     let access_token = create_access_token(user_id, client_id, client_secret, accesses)
         .map_err(|error| AppError::Token(error));
@@ -173,6 +194,8 @@ pub async fn create_new_token_authorization_data<'a>(
     dto.client_id = client_id;
     dto.access_token = access_token.clone();
     dto.scopes = scopes.clone();
+    dto.user_agent = device.user_agent.clone();
+    dto.ip_address = device.ip_address.clone();
     let token_id = TokenMutation::create(dto).await?;
     debug!("Token created with id: {}", token_id);
 
@@ -249,6 +272,7 @@ pub async fn create_refresh_token_authorization_data<'a>(
             return Err(AppError::Token(TokenError::InvalidToken));
         }
     }
+    SessionService::ensure_active(token_id).await?;
 
     let accesses = UserQuery::get_access_data_by_user_id(user_id).await?;
     let (access_token, jti) = create_access_token(user_id, client_id, client_secret, accesses)
@@ -268,6 +292,7 @@ pub async fn create_refresh_token_authorization_data<'a>(
         refresh_token: Some(refresh_token.clone()),
     };
     TokenMutation::update(token_id, token_for_update).await?;
+    SessionService::touch(&access_token).await;
     cache
         .insert(
             get_refresh_token_cache_key(user_id),
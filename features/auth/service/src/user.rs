@@ -68,4 +68,28 @@ impl UserService {
         }
         Ok(true)
     }
+
+    /// Role ids currently assigned to a user, sorted for stable audit snapshots.
+    pub async fn role_ids(user_id: Uuid) -> Result<Vec<Uuid>> {
+        let filters = vec![FilterEnum::Uuid(FilterParam {
+            name: "user_id".to_string(),
+            operator: FilterOperator::Equal,
+            value: Some(user_id),
+            raw_value: user_id.to_string(),
+        })];
+        let search = AccessQuery::search(
+            &Pagination::new(1, 200),
+            &Order::default(),
+            &FilterCondition::from(filters),
+        )
+        .await?;
+        let mut role_ids: Vec<Uuid> = search
+            .result
+            .into_iter()
+            .filter_map(|access| access.role_id)
+            .collect();
+        role_ids.sort();
+        role_ids.dedup();
+        Ok(role_ids)
+    }
 }
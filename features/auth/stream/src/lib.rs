@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use shared_shared_data_core::event::EventContract;
+
 use crate::{password::PasswordMessage, signin::SignInMessage, signup::SignUpMessage};
 
 pub mod password;
@@ -15,3 +17,8 @@ pub enum AuthMessage {
     SignUp { message: SignUpMessage },
     Password { message: PasswordMessage },
 }
+
+impl EventContract for AuthMessage {
+    const EVENT_TYPE: &'static str = "auth.message";
+    const VERSION: u32 = 1;
+}
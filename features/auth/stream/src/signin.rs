@@ -21,4 +21,35 @@ pub enum SignInMessage {
         email: String,
         login_code: String,
     },
+    NewDevice {
+        user_id: String,
+        email: String,
+        client_id: String,
+        user_agent: String,
+        ip_address: String,
+        /// Key of the client, prefix of the email template
+        #[serde(default)]
+        app_key: String,
+        #[serde(default)]
+        language_code: String,
+        /// Sender of the email
+        #[serde(default)]
+        client_email: String,
+    },
+    /// A provider account waits to be linked to the local account owning its email.
+    LinkRequest {
+        user_id: String,
+        email: String,
+        provider: String,
+        identity_id: String,
+        link_code: String,
+        /// Key of the client, prefix of the email template
+        #[serde(default)]
+        app_key: String,
+        #[serde(default)]
+        language_code: String,
+        /// Sender of the email
+        #[serde(default)]
+        client_email: String,
+    },
 }
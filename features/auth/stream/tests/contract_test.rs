@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_auth_stream::AuthMessage;
+
+#[test]
+fn auth_message_fixtures_still_decode() {
+    assert_fixtures::<AuthMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/auth.message"
+    ));
+}
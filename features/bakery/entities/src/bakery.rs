@@ -16,6 +16,9 @@ pub struct Model {
     pub profit_margin: f64,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub created_by: Option<Uuid>,
+    pub updated_by: Option<Uuid>,
+    pub deleted_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,12 +1,16 @@
 pub use sea_orm_migration::prelude::{async_trait, MigrationTrait, MigratorTrait};
 
 mod m20250428_000001_create_table;
+mod m20261019_add_soft_delete_and_audit_to_bakery;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20250428_000001_create_table::Migration)]
+        vec![
+            Box::new(m20250428_000001_create_table::Migration),
+            Box::new(m20261019_add_soft_delete_and_audit_to_bakery::Migration),
+        ]
     }
 }
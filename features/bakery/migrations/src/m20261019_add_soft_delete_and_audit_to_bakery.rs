@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+use features_bakery_entities::bakery;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_add_soft_delete_and_audit_to_bakery"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(bakery::Entity)
+                    .add_column(ColumnDef::new(bakery::Column::CreatedBy).uuid().null())
+                    .add_column(ColumnDef::new(bakery::Column::UpdatedBy).uuid().null())
+                    .add_column(ColumnDef::new(bakery::Column::DeletedAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(bakery::Entity)
+                    .drop_column(bakery::Column::CreatedBy)
+                    .drop_column(bakery::Column::UpdatedBy)
+                    .drop_column(bakery::Column::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -9,46 +9,15 @@ use shared_shared_data_core::{
 use shared_shared_macro::Query;
 
 use features_bakery_entities::baker::{ActiveModel, Column, Entity, ModelOptionDto};
-use features_bakery_entities::bakery::Column as BakeryColumn;
+use features_bakery_entities::bakery::{Column as BakeryColumn, Entity as BakeryEntity};
 
 #[derive(Query)]
 #[query(key_type(i32))]
 #[query_filter(column_name(Column))]
-#[query_filter(column_name(BakeryColumn))]
+#[query_filter(column_name(BakeryColumn), prefix("bakery"), entity(BakeryEntity))]
 struct BakerQueryManager;
 
 impl BakerQueryManager {
-    fn build_filter_condition(filter_condition: &FilterCondition) -> Condition {
-        match filter_condition {
-            FilterCondition::And(conditions) => {
-                let mut condition = Condition::all();
-                for c in conditions {
-                    condition = condition.add(Self::build_filter_condition(c));
-                }
-                condition
-            }
-            FilterCondition::Or(conditions) => {
-                let mut condition = Condition::any();
-                for c in conditions {
-                    condition = condition.add(Self::build_filter_condition(c));
-                }
-                condition
-            }
-            FilterCondition::Leaf(filter_enum) => {
-                let name = filter_enum.get_name();
-                let column_name = name.as_str();
-                if column_name.starts_with("bakery.") {
-                    let bakery_column_name = &column_name[7..];
-                    if let Ok(column) = BakeryColumn::from_str(bakery_column_name) {
-                        return Self::filter_condition_bakerycolumn(column, filter_enum);
-                    }
-                } else if let Ok(column) = Column::from_str(column_name) {
-                    return Self::filter_condition_column(column, filter_enum);
-                }
-                Condition::all()
-            }
-        }
-    }
     /*
     async fn advance_search(
         db: &DbConn,
@@ -76,6 +45,8 @@ impl BakerQueryManager {
         let page_result = QueryResult {
             total_page: Self::compute_pages_number(num_items, page_size),
             result: result,
+            next_cursor: None,
+            prev_cursor: None,
         };
 
         // let s1 = Entity::find().find_also_related(BakeryEntity);
@@ -137,6 +108,8 @@ impl BakerQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
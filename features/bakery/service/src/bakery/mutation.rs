@@ -7,7 +7,7 @@ use features_bakery_entities::bakery::{
 use super::util::assign;
 
 #[derive(Mutation)]
-#[mutation(key_type(i32))]
+#[mutation(key_type(i32), soft_delete, audit)]
 struct BakeryMutationManager {}
 
 pub struct BakeryMutation {}
@@ -22,4 +22,8 @@ impl BakeryMutation {
     pub fn delete<'a>(id: i32) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
         BakeryMutationManager::delete_by_id_i32(id)
     }
+
+    pub fn restore<'a>(id: i32) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
+        BakeryMutationManager::restore_by_id(id)
+    }
 }
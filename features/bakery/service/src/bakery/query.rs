@@ -11,7 +11,7 @@ use features_bakery_entities::bakery::{ActiveModel, Column, Entity, ModelOptionD
 use features_bakery_model::bakery::BakeryData;
 
 #[derive(Query)]
-#[query(key_type(i32))]
+#[query(key_type(i32), soft_delete)]
 #[query_filter(column_name(Column))]
 struct BakeryQueryManager;
 
@@ -33,6 +33,8 @@ impl BakeryQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
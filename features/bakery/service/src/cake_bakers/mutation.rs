@@ -2,20 +2,20 @@ use sea_orm::{entity::ActiveModelTrait, EntityTrait};
 use sea_orm::{DbConn, DbErr};
 
 use features_bakery_entities::cakes_bakers::{ActiveModel, CakeBakerForCreateDto, Entity, Model};
-use shared_shared_config::db::DB_WRITE;
+use shared_shared_config::db::write_db;
 
 struct CakeMutationManager {}
 
 impl CakeMutationManager {
     async fn insert(model: Model) -> Result<bool, DbErr> {
-        let db: &DbConn = DB_WRITE.get().expect("DB_WRITE is not initialized");
+        let db: &DbConn = write_db().expect("DB_WRITE is not initialized");
         let active_model: ActiveModel = model.into();
         let _result = active_model.insert(db).await;
         Ok(true)
     }
 
     async fn delete_by_id(cake_id: i32, baker_id: i32) -> Result<bool, DbErr> {
-        let db: &DbConn = DB_WRITE.get().expect("DB_WRITE is not initialized");
+        let db: &DbConn = write_db().expect("DB_WRITE is not initialized");
         let model: ActiveModel = Entity::find_by_id((cake_id, baker_id))
             .one(db)
             .await?
@@ -1,6 +1,7 @@
 use uuid::Uuid;
 
 use shared_shared_data_core::{
+    aggregate::{AggregateRow, Aggregation},
     filter::FilterEnum,
     order::Order,
     paging::{Pagination, QueryResult},
@@ -13,6 +14,7 @@ use features_bakery_model::order::OrderData;
 #[derive(Query)]
 #[query(key_type(i32))]
 #[query_filter(column_name(Column))]
+#[query_sort(columns(BakeryId, CustomerId, PlacedAt, Total, UpdatedAt))]
 struct OrderQueryManager;
 
 pub struct OrderQuery {}
@@ -32,7 +34,16 @@ impl OrderQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
+
+    pub async fn aggregate<'a>(
+        aggregation: &Aggregation,
+        filters: &FilterCondition,
+    ) -> Result<Vec<AggregateRow>, DbErr> {
+        OrderQueryManager::aggregate(aggregation, filters).await
+    }
 }
@@ -30,7 +30,7 @@ impl EmailTemplateService {
 
         let data = Self::call_api(url, reqwest::Method::GET, None, HashMap::new())
             .await
-            .map_err(|e| e)?;
+            .map_err(|e| e.to_string())?;
         let email_template = QueryResult::<EmailTemplateData>::from_value(data)?;
         if email_template.result.is_empty() {
             return Err("Email template not found".to_string());
@@ -28,7 +28,7 @@ impl TemplatePlaceholderService {
 
         let data = Self::call_api(url, reqwest::Method::GET, None, HashMap::new())
             .await
-            .map_err(|e| e)?;
+            .map_err(|e| e.to_string())?;
         let result = QueryResult::<TemplatePlaceholderData>::from_value(data)?;
         if result.result.is_empty() {
             return Err("Email template not found".to_string());
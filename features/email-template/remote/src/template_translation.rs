@@ -36,7 +36,7 @@ impl TemplateTranslationService {
         let url = format!("{}?{}", endpoint, condition.to_query_string());
         let data = Self::call_api(url, reqwest::Method::GET, None, HashMap::new())
             .await
-            .map_err(|e| e)?;
+            .map_err(|e| e.to_string())?;
         let result = QueryResult::<TemplateTranslationData>::from_value(data)?;
         if result.result.is_empty() {
             return Err("Template translation not found".to_string());
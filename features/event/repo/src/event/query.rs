@@ -14,6 +14,7 @@ use features_event_model::EventData;
 #[derive(Query)]
 #[query(key_type(Uuid))]
 #[query_filter(column_name(Column))]
+#[query_search(columns(EventName, VenueName))]
 struct EventQueryManager;
 
 pub struct EventQuery;
@@ -33,6 +34,8 @@ impl EventQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "event_type", rename_all = "snake_case")]
 pub enum EventMessage {
@@ -8,6 +10,11 @@ pub enum EventMessage {
     Update { message: ChangeEventMessage },
 }
 
+impl EventContract for EventMessage {
+    const EVENT_TYPE: &'static str = "event.message";
+    const VERSION: u32 = 1;
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NewEventMessage {
     pub id: Uuid,
@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_event_stream::EventMessage;
+
+#[test]
+fn event_message_fixtures_still_decode() {
+    assert_fixtures::<EventMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/event.message"
+    ));
+}
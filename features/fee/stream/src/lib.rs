@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "event_type", rename_all = "snake_case")]
 pub enum FeeEventMessage {
@@ -8,6 +10,11 @@ pub enum FeeEventMessage {
     Update { message: ChangeFeeEventMessage },
 }
 
+impl EventContract for FeeEventMessage {
+    const EVENT_TYPE: &'static str = "fee.event";
+    const VERSION: u32 = 1;
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NewFeeEventMessage {
     pub id: Uuid,
@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_fee_stream::FeeEventMessage;
+
+#[test]
+fn fee_event_fixtures_still_decode() {
+    assert_fixtures::<FeeEventMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fee.event"
+    ));
+}
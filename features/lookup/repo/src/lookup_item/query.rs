@@ -18,6 +18,7 @@ use features_lookup_model::lookup_item::LookupItemData;
 #[query(key_type(Uuid))]
 #[query_filter(column_name(Column))]
 #[query_related(entity(LookupTypeEntity), field(lookup_type), name("lookup_type"))]
+#[query_sort(columns(Code, Name, SortOrder, UpdatedAt))]
 struct LookupItemQueryManager;
 
 pub struct LookupItemQuery;
@@ -50,6 +51,8 @@ impl LookupItemQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
@@ -74,6 +77,8 @@ impl LookupItemQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
 
         Ok(mapped_result)
@@ -18,6 +18,7 @@ use features_lookup_model::lookup_type::LookupTypeData;
 #[query(key_type(Uuid))]
 #[query_filter(column_name(Column))]
 #[query_related(entity(ItemEntity), field(items), name("items"))]
+#[query_sort(columns(Code, Name, UpdatedAt))]
 struct LookupTypeQueryManager;
 
 pub struct LookupTypeQuery;
@@ -106,6 +107,8 @@ impl LookupTypeQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
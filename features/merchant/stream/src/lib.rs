@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "event_type", rename_all = "snake_case")]
 pub enum MerchantEventMessage {
@@ -8,6 +10,11 @@ pub enum MerchantEventMessage {
     Update { message: ChangeMerchantEventMessage },
 }
 
+impl EventContract for MerchantEventMessage {
+    const EVENT_TYPE: &'static str = "merchant.event";
+    const VERSION: u32 = 1;
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NewMerchantEventMessage {
     pub id: Uuid,
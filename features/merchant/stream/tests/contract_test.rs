@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_merchant_stream::MerchantEventMessage;
+
+#[test]
+fn merchant_event_fixtures_still_decode() {
+    assert_fixtures::<MerchantEventMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/merchant.event"
+    ));
+}
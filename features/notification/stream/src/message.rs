@@ -1,7 +1,9 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize, Clone)]
+use shared_shared_data_core::event::EventContract;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "message_type", rename_all = "snake_case")]
 pub enum NotificationMessage {
     Notification {
@@ -14,4 +16,15 @@ pub enum NotificationMessage {
         platform: String,
         message: String,
     },
+
+    /// Sent to an admin, e.g. when the anomaly detector blocks a client
+    SecurityAlert {
+        user_id: Uuid,
+        message: String,
+    },
+}
+
+impl EventContract for NotificationMessage {
+    const EVENT_TYPE: &'static str = "notification.message";
+    const VERSION: u32 = 1;
 }
@@ -7,6 +7,7 @@ pub enum ServerResponse {
     Auth { status: Auth },
     Payment { platform: String, message: String },
     Notification { message: String },
+    SecurityAlert { message: String },
     Pong,
 }
 
@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_notification_stream::message::NotificationMessage;
+
+#[test]
+fn notification_message_fixtures_still_decode() {
+    assert_fixtures::<NotificationMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/notification.message"
+    ));
+}
@@ -1,4 +1,8 @@
 pub use sea_orm_migration::prelude::{async_trait, MigrationTrait, MigratorTrait};
+use shared_shared_migrations::{
+    m20261019_000001_create_outbox_messages, m20261019_000002_create_dlq_messages,
+    m20261019_000003_add_relay_columns_to_outbox_messages,
+};
 
 pub mod m20260307_000001_create_payment_tables;
 pub mod m20260310_000001_change_transaction_id_type_in_payment_table;
@@ -13,6 +17,9 @@ impl MigratorTrait for Migrator {
             Box::new(m20260307_000001_create_payment_tables::Migration),
             Box::new(m20260310_000001_change_transaction_id_type_in_payment_table::Migration),
             Box::new(m20260429_000001_add_metadata_to_payments::Migration),
+            Box::new(m20261019_000001_create_outbox_messages::Migration),
+            Box::new(m20261019_000002_create_dlq_messages::Migration),
+            Box::new(m20261019_000003_add_relay_columns_to_outbox_messages::Migration),
         ]
     }
 }
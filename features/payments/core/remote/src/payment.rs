@@ -31,7 +31,8 @@ impl PaymentRemoteService {
             Some(body),
             Self::headers_with_baggage(baggage),
         )
-        .await?;
+        .await
+        .map_err(|e| e.to_string())?;
 
         let id = data
             .get("id")
@@ -46,8 +47,9 @@ impl PaymentRemoteService {
             .expect("PAYMENT_CORE_ENDPOINT_GET_PAYMENT must be set");
 
         let url = format!("{}/{}", endpoint, payment_id);
-        let data =
-            Self::call_api(url, Method::GET, None, Self::headers_with_baggage(baggage)).await?;
+        let data = Self::call_api(url, Method::GET, None, Self::headers_with_baggage(baggage))
+            .await
+            .map_err(|e| e.to_string())?;
         serde_json::from_value::<PaymentData>(data).map_err(|e| e.to_string())
     }
 
@@ -67,7 +69,8 @@ impl PaymentRemoteService {
             Some(body),
             Self::headers_with_baggage(baggage),
         )
-        .await?;
+        .await
+        .map_err(|e| e.to_string())?;
         Ok(true)
     }
 }
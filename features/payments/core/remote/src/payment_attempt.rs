@@ -20,7 +20,9 @@ impl PaymentAttemptRemoteService {
         headers.insert("baggage".to_string(), baggage.to_string());
 
         let body = serde_json::to_value(&req).map_err(|e| e.to_string())?;
-        let data = Self::call_api(endpoint, Method::POST, Some(body), headers).await?;
+        let data = Self::call_api(endpoint, Method::POST, Some(body), headers)
+            .await
+            .map_err(|e| e.to_string())?;
 
         let id = data
             .get("id")
@@ -32,6 +32,20 @@ impl PaymentMutation {
         PaymentMutationManager::update_by_id_uuid(payment_id, data.into())
     }
 
+    /// Updates the payment on `txn` and returns it as updated.
+    pub async fn update_payment_with_txn(
+        payment_id: Uuid,
+        data: PaymentForUpdateDto,
+        txn: &impl ConnectionTrait,
+    ) -> Result<Model, DbErr> {
+        let exists = Entity::find_by_id(payment_id)
+            .one(txn)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
+        let active_model = assign(exists.into(), data.into());
+        active_model.update(txn).await
+    }
+
     pub fn bulk_update_payments<'a>(
         data: Vec<(Uuid, PaymentForUpdateDto)>,
     ) -> impl std::future::Future<Output = Result<Vec<Uuid>, DbErr>> + 'a {
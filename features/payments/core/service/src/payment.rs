@@ -2,6 +2,8 @@ use sea_orm::Iden;
 use tracing::debug;
 use uuid::Uuid;
 
+use shared_shared_app::event_task::{outbox::Outbox, producer::ProducerMessage};
+use shared_shared_config::db::UnitOfWork;
 use shared_shared_data_core::{
     filter::{FilterCondition, FilterEnum, FilterOperator, FilterParam},
     order::Order,
@@ -14,6 +16,7 @@ use features_payments_core_model::payment::{
     PaymentData, PaymentForCreateRequest, PaymentForUpdateRequest,
 };
 use features_payments_core_repo::payment::{PaymentMutation, PaymentQuery};
+use features_payments_core_stream::{PaymentCoreEventMessage, PaymentSucceededMessage};
 
 pub struct PaymentService {}
 
@@ -75,6 +78,63 @@ impl PaymentService {
         }
     }
 
+    /// Updates the payment and, when it becomes succeeded, stores the `Succeeded` event for
+    /// `topic` in the outbox in the same transaction.
+    pub async fn update_payment_with_event(
+        payment_id: Uuid,
+        payment_request: PaymentForUpdateRequest,
+        topic: &str,
+    ) -> Result<bool, AppError> {
+        let is_succeeded = payment_request.status.as_deref() == Some("succeeded");
+        let unit_of_work = UnitOfWork::begin().await.map_err(|e| {
+            debug!("Error starting transaction: {:?}", e);
+            AppError::Internal("Failed to update payment".to_string())
+        })?;
+
+        let payment = PaymentMutation::update_payment_with_txn(
+            payment_id,
+            payment_request.into(),
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(|e| {
+            debug!("Error updating payment: {:?}", e);
+            AppError::Internal("Failed to update payment".to_string())
+        })?;
+
+        if is_succeeded {
+            let wallet_id = payment
+                .metadata
+                .get("wallet_id")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse::<Uuid>().ok());
+            let message = ProducerMessage {
+                key: None,
+                payload: PaymentCoreEventMessage::Succeeded {
+                    message: PaymentSucceededMessage {
+                        payment_id,
+                        user_id: payment.user_id,
+                        wallet_id,
+                        amount: payment.amount,
+                        currency: payment.currency,
+                    },
+                },
+            };
+            Outbox::enqueue(unit_of_work.txn(), topic, &message)
+                .await
+                .map_err(|e| {
+                    debug!("Error storing payment success event in outbox: {:?}", e);
+                    AppError::Internal("Failed to update payment".to_string())
+                })?;
+        }
+
+        unit_of_work.commit().await.map_err(|e| {
+            debug!("Error committing transaction: {:?}", e);
+            AppError::Internal("Failed to update payment".to_string())
+        })?;
+        Ok(true)
+    }
+
     pub async fn delete_payment(payment_id: Uuid) -> Result<bool, AppError> {
         let result = PaymentMutation::delete_payment(payment_id).await;
         match result {
@@ -1,12 +1,19 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "event_type", rename_all = "snake_case")]
 pub enum PaymentCoreEventMessage {
     Succeeded { message: PaymentSucceededMessage },
 }
 
+impl EventContract for PaymentCoreEventMessage {
+    const EVENT_TYPE: &'static str = "payment_core.event";
+    const VERSION: u32 = 1;
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PaymentSucceededMessage {
     pub payment_id: Uuid,
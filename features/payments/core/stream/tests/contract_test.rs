@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_payments_core_stream::PaymentCoreEventMessage;
+
+#[test]
+fn payment_core_event_fixtures_still_decode() {
+    assert_fixtures::<PaymentCoreEventMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/payment_core.event"
+    ));
+}
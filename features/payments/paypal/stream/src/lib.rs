@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "event_type", rename_all = "snake_case")]
 pub enum PaypalEventMessage {
@@ -10,6 +12,11 @@ pub enum PaypalEventMessage {
     ApiLog { message: ApiLogEventMessage },
 }
 
+impl EventContract for PaypalEventMessage {
+    const EVENT_TYPE: &'static str = "paypal.event";
+    const VERSION: u32 = 1;
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OrderEventMessage {
     pub id: Uuid,
@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_payments_paypal_stream::PaypalEventMessage;
+
+#[test]
+fn paypal_event_fixtures_still_decode() {
+    assert_fixtures::<PaypalEventMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/paypal.event"
+    ));
+}
@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_payments_stripe_stream::StripeEventMessage;
+
+#[test]
+fn stripe_event_fixtures_still_decode() {
+    assert_fixtures::<StripeEventMessage>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/stripe.event"
+    ));
+}
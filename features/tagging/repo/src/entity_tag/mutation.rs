@@ -33,11 +33,9 @@ impl EntityTagMutation {
         entity_id: Uuid,
     ) -> Result<bool, AppError> {
         use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter};
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
-        let db = DB_WRITE
-            .get()
-            .ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
+        let db = write_db().ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
 
         let result = Entity::delete_many()
             .filter(
@@ -46,7 +44,7 @@ impl EntityTagMutation {
                     .add(Column::EntityType.eq(entity_type))
                     .add(Column::EntityId.eq(entity_id)),
             )
-            .exec(db.as_ref())
+            .exec(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to delete entity tag: {}", e)))?;
 
@@ -56,11 +54,9 @@ impl EntityTagMutation {
     /// Bulk create entity tags (skip duplicates)
     pub async fn bulk_create(items: Vec<EntityTagForCreateDto>) -> Result<(i32, i32), AppError> {
         use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, Condition, EntityTrait, QueryFilter};
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
-        let db = DB_WRITE
-            .get()
-            .ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
+        let db = write_db().ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
 
         let mut created = 0;
         let mut skipped = 0;
@@ -74,7 +70,7 @@ impl EntityTagMutation {
                         .add(Column::EntityType.eq(&item.entity_type))
                         .add(Column::EntityId.eq(item.entity_id)),
                 )
-                .one(db.as_ref())
+                .one(db)
                 .await
                 .map_err(|e| AppError::Internal(format!("Failed to check existing: {}", e)))?;
 
@@ -94,7 +90,7 @@ impl EntityTagMutation {
             };
 
             active
-                .insert(db.as_ref())
+                .insert(db)
                 .await
                 .map_err(|e| AppError::Internal(format!("Failed to create entity tag: {}", e)))?;
             created += 1;
@@ -110,11 +106,9 @@ impl EntityTagMutation {
         entity_ids: &[Uuid],
     ) -> Result<u64, AppError> {
         use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter};
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
-        let db = DB_WRITE
-            .get()
-            .ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
+        let db = write_db().ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
 
         let result = Entity::delete_many()
             .filter(
@@ -123,7 +117,7 @@ impl EntityTagMutation {
                     .add(Column::EntityType.eq(entity_type))
                     .add(Column::EntityId.is_in(entity_ids.to_vec())),
             )
-            .exec(db.as_ref())
+            .exec(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to bulk delete: {}", e)))?;
 
@@ -133,16 +127,14 @@ impl EntityTagMutation {
     /// Reassign all entity_tags from one tag to another (used during merge)
     pub async fn reassign_tag(source_tag_id: Uuid, target_tag_id: Uuid) -> Result<u64, AppError> {
         use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, Condition, EntityTrait, QueryFilter};
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
-        let db = DB_WRITE
-            .get()
-            .ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
+        let db = write_db().ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
 
         // Find all entity_tags for the source tag
         let entity_tags = Entity::find()
             .filter(Column::TagId.eq(source_tag_id))
-            .all(db.as_ref())
+            .all(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to find entity tags: {}", e)))?;
 
@@ -157,7 +149,7 @@ impl EntityTagMutation {
                         .add(Column::EntityType.eq(&entity_tag.entity_type))
                         .add(Column::EntityId.eq(entity_tag.entity_id)),
                 )
-                .one(db.as_ref())
+                .one(db)
                 .await
                 .map_err(|e| AppError::Internal(format!("Failed to check existing: {}", e)))?;
 
@@ -165,7 +157,7 @@ impl EntityTagMutation {
                 // Delete the source association (would be a duplicate)
                 let active: ActiveModel = entity_tag.into();
                 active
-                    .delete(db.as_ref())
+                    .delete(db)
                     .await
                     .map_err(|e| AppError::Internal(format!("Failed to delete: {}", e)))?;
             } else {
@@ -173,7 +165,7 @@ impl EntityTagMutation {
                 let mut active: ActiveModel = entity_tag.into();
                 active.tag_id = ActiveValue::Set(target_tag_id);
                 active
-                    .update(db.as_ref())
+                    .update(db)
                     .await
                     .map_err(|e| AppError::Internal(format!("Failed to reassign: {}", e)))?;
                 reassigned += 1;
@@ -32,11 +32,9 @@ impl EntityTagQuery {
         _query_params: &QueryParams,
     ) -> Result<QueryResult<EntityTagData>, AppError> {
         use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
-        use shared_shared_config::db::DB_READ;
+        use shared_shared_config::db::read_db;
 
-        let db = DB_READ
-            .get()
-            .ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
+        let db = read_db().ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
 
         let condition = Condition::all()
             .add(Column::TenantId.eq(tenant_id))
@@ -45,7 +43,7 @@ impl EntityTagQuery {
 
         let total = Entity::find()
             .filter(condition.clone())
-            .count(db.as_ref())
+            .count(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to count entity tags: {}", e)))?;
 
@@ -56,7 +54,7 @@ impl EntityTagQuery {
         let models = Entity::find()
             .filter(condition)
             .order_by_desc(Column::CreatedAt)
-            .paginate(db.as_ref(), page_size)
+            .paginate(db, page_size)
             .fetch_page(page.saturating_sub(1))
             .await
             .map_err(|e| AppError::Internal(format!("Failed to fetch entity tags: {}", e)))?;
@@ -64,6 +62,8 @@ impl EntityTagQuery {
         Ok(QueryResult {
             total_page: total_page,
             result: models.into_iter().map(|m| m.into()).collect(),
+            next_cursor: None,
+            prev_cursor: None,
         })
     }
 
@@ -74,11 +74,9 @@ impl EntityTagQuery {
         _order: &Order,
     ) -> Result<QueryResult<EntityTagData>, AppError> {
         use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
-        use shared_shared_config::db::DB_READ;
+        use shared_shared_config::db::read_db;
 
-        let db = DB_READ
-            .get()
-            .ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
+        let db = read_db().ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
 
         let condition = Condition::all()
             .add(Column::TenantId.eq(tenant_id))
@@ -86,7 +84,7 @@ impl EntityTagQuery {
 
         let total = Entity::find()
             .filter(condition.clone())
-            .count(db.as_ref())
+            .count(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to count entity tags: {}", e)))?;
 
@@ -97,7 +95,7 @@ impl EntityTagQuery {
         let models = Entity::find()
             .filter(condition)
             .order_by_desc(Column::CreatedAt)
-            .paginate(db.as_ref(), page_size)
+            .paginate(db, page_size)
             .fetch_page(page.saturating_sub(1))
             .await
             .map_err(|e| AppError::Internal(format!("Failed to fetch entity tags: {}", e)))?;
@@ -105,21 +103,21 @@ impl EntityTagQuery {
         Ok(QueryResult {
             total_page: total_page,
             result: models.into_iter().map(|m| m.into()).collect(),
+            next_cursor: None,
+            prev_cursor: None,
         })
     }
 
     /// Count how many entities a tag is associated with
     pub async fn get_usage_count(tag_id: Uuid) -> Result<i32, AppError> {
         use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
-        use shared_shared_config::db::DB_READ;
+        use shared_shared_config::db::read_db;
 
-        let db = DB_READ
-            .get()
-            .ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
+        let db = read_db().ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
 
         let count = Entity::find()
             .filter(Column::TagId.eq(tag_id))
-            .count(db.as_ref())
+            .count(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to count usage: {}", e)))?;
 
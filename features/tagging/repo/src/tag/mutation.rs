@@ -36,14 +36,12 @@ impl TagMutation {
     /// Increment usage_count by 1
     pub async fn increment_usage_count(tag_id: Uuid) -> Result<(), AppError> {
         use sea_orm::{ActiveModelTrait, ActiveValue, EntityTrait};
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
-        let db = DB_WRITE
-            .get()
-            .ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
+        let db = write_db().ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
 
         let tag = Entity::find_by_id(tag_id)
-            .one(db.as_ref())
+            .one(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to find tag: {}", e)))?
             .ok_or(AppError::EntityNotFound { entity: "Tag".to_string() })?;
@@ -55,7 +53,7 @@ impl TagMutation {
         };
         active.usage_count = ActiveValue::Set(current_count + 1);
         active
-            .update(db.as_ref())
+            .update(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to increment usage count: {}", e)))?;
         Ok(())
@@ -64,14 +62,12 @@ impl TagMutation {
     /// Decrement usage_count by 1 (min 0)
     pub async fn decrement_usage_count(tag_id: Uuid) -> Result<(), AppError> {
         use sea_orm::{ActiveModelTrait, ActiveValue, EntityTrait};
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
-        let db = DB_WRITE
-            .get()
-            .ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
+        let db = write_db().ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
 
         let tag = Entity::find_by_id(tag_id)
-            .one(db.as_ref())
+            .one(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to find tag: {}", e)))?
             .ok_or(AppError::EntityNotFound { entity: "Tag".to_string() })?;
@@ -83,7 +79,7 @@ impl TagMutation {
         };
         active.usage_count = ActiveValue::Set((current_count - 1).max(0));
         active
-            .update(db.as_ref())
+            .update(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to decrement usage count: {}", e)))?;
         Ok(())
@@ -92,14 +88,12 @@ impl TagMutation {
     /// Set alias_of on a source tag (used during merge)
     pub async fn set_alias(source_tag_id: Uuid, target_tag_id: Uuid) -> Result<(), AppError> {
         use sea_orm::{ActiveModelTrait, ActiveValue, EntityTrait};
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
-        let db = DB_WRITE
-            .get()
-            .ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
+        let db = write_db().ok_or(AppError::Internal("DB_WRITE not initialized".to_string()))?;
 
         let tag = Entity::find_by_id(source_tag_id)
-            .one(db.as_ref())
+            .one(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to find tag: {}", e)))?
             .ok_or(AppError::EntityNotFound { entity: "Source tag".to_string() })?;
@@ -108,7 +102,7 @@ impl TagMutation {
         active.alias_of = ActiveValue::Set(Some(target_tag_id));
         active.is_active = ActiveValue::Set(false);
         active
-            .update(db.as_ref())
+            .update(db)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to set alias: {}", e)))?;
         Ok(())
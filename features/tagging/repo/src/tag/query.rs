@@ -17,6 +17,7 @@ use features_tagging_model::tag::TagData;
 #[query(key_type(Uuid))]
 #[query_filter(column_name(Column))]
 #[query_related(entity(TagGroupEntity), field(tag_group), name("tag_group"))]
+#[query_sort(columns(Name, SortOrder, UsageCount, UpdatedAt))]
 struct TagQueryManager;
 
 pub struct TagQuery;
@@ -49,6 +50,8 @@ impl TagQuery {
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
@@ -60,11 +63,9 @@ impl TagQuery {
         limit: u64,
     ) -> Result<Vec<TagData>, AppError> {
         use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
-        use shared_shared_config::db::DB_READ;
+        use shared_shared_config::db::read_db;
 
-        let db = DB_READ
-            .get()
-            .ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
+        let db = read_db().ok_or(AppError::Internal("DB_READ not initialized".to_string()))?;
 
         let models = Entity::find()
             .filter(
@@ -75,7 +76,7 @@ impl TagQuery {
                     .add(Column::Name.contains(search_term.to_string())),
             )
             .order_by_asc(Column::Name)
-            .paginate(db.as_ref(), limit)
+            .paginate(db, limit)
             .fetch_page(0)
             .await
             .map_err(|e| AppError::Internal(format!("Failed to search tags: {}", e)))?;
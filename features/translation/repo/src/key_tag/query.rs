@@ -37,6 +37,8 @@ impl KeyTagQueryManager {
         let mapped_result = QueryResult {
             total_page: query_result.total_page,
             result: query_result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: query_result.next_cursor,
+            prev_cursor: query_result.prev_cursor,
         };
         Ok(mapped_result.result)
     }
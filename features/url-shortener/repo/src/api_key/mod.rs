@@ -4,3 +4,8 @@ mod util;
 
 pub use mutation::ApiKeyMutation;
 pub use query::ApiKeyQuery;
+
+use shared_shared_auth::policy::{AccessPolicy, PolicyCondition};
+
+/// Users only manage the API keys they own.
+pub const API_KEY_POLICY: AccessPolicy = AccessPolicy::new(&[PolicyCondition::Owner("user_id")]);
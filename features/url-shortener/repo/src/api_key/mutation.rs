@@ -1,3 +1,4 @@
+use shared_shared_auth::policy::PolicySubject;
 use shared_shared_macro::Mutation;
 
 use features_url_shortener_entities::api_key::{
@@ -8,6 +9,7 @@ use crate::api_key::util::assign;
 
 #[derive(Mutation)]
 #[mutation(key_type(Uuid))]
+#[policy(crate::api_key::API_KEY_POLICY)]
 struct ApiKeyMutationManager {}
 
 pub struct ApiKeyMutation;
@@ -24,4 +26,11 @@ impl ApiKeyMutation {
     ) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
         ApiKeyMutationManager::delete_by_id_uuid(id)
     }
+
+    pub fn delete_api_key_for_subject<'a>(
+        id: Uuid,
+        subject: &'a PolicySubject,
+    ) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
+        ApiKeyMutationManager::delete_by_id_for_subject(id, subject)
+    }
 }
@@ -4,3 +4,9 @@ mod util;
 
 pub use mutation::ShortenedUrlMutation;
 pub use query::ShortenedUrlQuery;
+
+use shared_shared_auth::policy::{AccessPolicy, PolicyCondition};
+
+/// Users only see and change the URLs they created.
+pub const SHORTENED_URL_POLICY: AccessPolicy =
+    AccessPolicy::new(&[PolicyCondition::Owner("user_id")]);
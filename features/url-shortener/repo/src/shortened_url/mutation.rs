@@ -1,3 +1,4 @@
+use shared_shared_auth::policy::PolicySubject;
 use shared_shared_macro::Mutation;
 
 use features_url_shortener_entities::shortened_url::{
@@ -9,6 +10,7 @@ use crate::shortened_url::util::assign;
 
 #[derive(Mutation)]
 #[mutation(key_type(Uuid))]
+#[policy(crate::shortened_url::SHORTENED_URL_POLICY)]
 struct ShortenedUrlMutationManager {}
 
 pub struct ShortenedUrlMutation;
@@ -32,4 +34,19 @@ impl ShortenedUrlMutation {
     ) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
         ShortenedUrlMutationManager::delete_by_id_uuid(id)
     }
+
+    pub fn update_shortened_url_for_subject<'a>(
+        id: Uuid,
+        data: ShortenedUrlForUpdateDto,
+        subject: &'a PolicySubject,
+    ) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
+        ShortenedUrlMutationManager::update_by_id_for_subject(id, data.into(), subject)
+    }
+
+    pub fn delete_shortened_url_for_subject<'a>(
+        id: Uuid,
+        subject: &'a PolicySubject,
+    ) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
+        ShortenedUrlMutationManager::delete_by_id_for_subject(id, subject)
+    }
 }
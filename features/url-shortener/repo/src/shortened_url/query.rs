@@ -1,5 +1,6 @@
 use uuid::Uuid;
 
+use shared_shared_auth::policy::PolicySubject;
 use shared_shared_data_core::{
     filter::{FilterEnum, FilterParam},
     order::Order,
@@ -15,6 +16,7 @@ use features_url_shortener_model::shortened_url::ShortenedUrlData;
 #[derive(Query)]
 #[query(key_type(Uuid))]
 #[query_filter(column_name(Column))]
+#[policy(crate::shortened_url::SHORTENED_URL_POLICY)]
 struct ShortenedUrlQueryManager;
 
 pub struct ShortenedUrlQuery;
@@ -28,6 +30,20 @@ impl ShortenedUrlQuery {
         Ok(model.into())
     }
 
+    /// Get a URL visible to the subject under `SHORTENED_URL_POLICY`.
+    pub async fn get_by_id_for_subject(
+        id: Uuid,
+        subject: &PolicySubject,
+    ) -> Result<ShortenedUrlData, AppError> {
+        let model = ShortenedUrlQueryManager::get_by_id_for_subject(id, subject)
+            .await
+            .map_err(|e| match e {
+                DbErr::RecordNotFound(_) => AppError::NotFound(format!("shortened_url {}", id)),
+                e => AppError::DbErr(e),
+            })?;
+        Ok(model.into())
+    }
+
     pub async fn get_by_short_code(code: &str) -> Result<ShortenedUrlData, AppError> {
         let code_param: FilterParam<String> = FilterParam {
             name: Column::ShortCode.to_string(),
@@ -50,24 +66,21 @@ impl ShortenedUrlQuery {
         Ok(item.into())
     }
 
-    pub async fn get_user_urls(
-        user_id: &Uuid,
+    /// List the URLs visible to the subject under `SHORTENED_URL_POLICY`.
+    pub async fn search_for_subject(
+        subject: &PolicySubject,
         pagination: &Pagination,
         order: &Order,
         filters: &FilterCondition,
     ) -> Result<QueryResult<ShortenedUrlData>, AppError> {
-        let mut filters = filters.clone();
-        filters.push_leaf(FilterEnum::Uuid(FilterParam {
-            name: Column::UserId.to_string(),
-            operator: FilterOperator::Equal,
-            value: Some(*user_id),
-            raw_value: user_id.to_string(),
-        }));
-
-        let result = ShortenedUrlQueryManager::filter(pagination, order, &filters).await?;
+        let result =
+            ShortenedUrlQueryManager::filter_for_subject(pagination, order, filters, subject)
+                .await?;
         let mapped_result = QueryResult {
             total_page: result.total_page,
             result: result.result.into_iter().map(|m| m.into()).collect(),
+            next_cursor: result.next_cursor,
+            prev_cursor: result.prev_cursor,
         };
         Ok(mapped_result)
     }
@@ -1,8 +1,10 @@
 use rand::Rng;
+use sea_orm::DbErr;
 use sha2::{Digest, Sha256};
 use tracing::error;
 use uuid::Uuid;
 
+use shared_shared_auth::policy::PolicySubject;
 use shared_shared_data_core::{
     order::Order,
     paging::{Pagination, QueryResult},
@@ -70,22 +72,17 @@ impl ApiKeyService {
         Ok(api_key)
     }
 
-    /// Revoke an API key. Verifies ownership.
-    pub async fn revoke_api_key(id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-        // Get key to verify ownership
-        let keys = ApiKeyQuery::list_by_user_id(&user_id, &Pagination::default(), &Order::default())
-            .await?;
-        let key_exists = keys.result.iter().any(|k| k.id == Some(id));
-        if !key_exists {
-            return Err(AppError::Internal(
-                "Not authorized to revoke this API key".to_string(),
-            ));
-        }
-
-        ApiKeyMutation::delete_api_key(id).await.map_err(|e| {
-            error!("Error revoking API key: {:?}", e);
-            AppError::Internal("Failed to revoke API key".to_string())
-        })
+    /// Revoke an API key the subject owns.
+    pub async fn revoke_api_key(id: Uuid, subject: &PolicySubject) -> Result<bool, AppError> {
+        ApiKeyMutation::delete_api_key_for_subject(id, subject)
+            .await
+            .map_err(|e| match e {
+                DbErr::RecordNotFound(_) => AppError::NotFound(format!("api_key {}", id)),
+                e => {
+                    error!("Error revoking API key: {:?}", e);
+                    AppError::Internal("Failed to revoke API key".to_string())
+                }
+            })
     }
 
     /// List API keys for a user (without exposing hashes).
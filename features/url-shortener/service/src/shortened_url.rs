@@ -1,8 +1,10 @@
 use chrono::Utc;
 use rand::Rng;
+use sea_orm::DbErr;
 use tracing::{debug, error};
 use uuid::Uuid;
 
+use shared_shared_auth::policy::PolicySubject;
 use shared_shared_data_core::{
     filter::FilterCondition,
     order::Order,
@@ -170,25 +172,27 @@ impl ShortenedUrlService {
         Ok(cached.original_url)
     }
 
-    /// Update a shortened URL. Verifies ownership.
+    /// Update a shortened URL the subject is allowed to modify.
     pub async fn update_short_url(
         id: Uuid,
-        user_id: Uuid,
+        subject: &PolicySubject,
         req: UpdateShortenedUrlRequest,
     ) -> Result<bool, AppError> {
-        // Verify ownership
-        let existing = ShortenedUrlQuery::get_by_id(id, &Default::default()).await?;
-        if existing.user_id != Some(user_id) {
-            return Err(AppError::Internal("Not authorized to update this URL".to_string()));
-        }
+        let existing = ShortenedUrlQuery::get_by_id_for_subject(id, subject).await?;
 
         let short_code = existing.short_code.unwrap_or_default();
         let dto: features_url_shortener_entities::shortened_url::ShortenedUrlForUpdateDto =
             req.into();
-        let result = ShortenedUrlMutation::update_shortened_url(id, dto).await.map_err(|e| {
-            error!("Error updating shortened URL: {:?}", e);
-            AppError::Internal("Failed to update shortened URL".to_string())
-        })?;
+        let result = ShortenedUrlMutation::update_shortened_url_for_subject(id, dto, subject)
+            .await
+            .map_err(|e| match e {
+                // Deleted or moved out of the policy since it was read
+                DbErr::RecordNotFound(_) => AppError::NotFound(format!("shortened_url {}", id)),
+                e => {
+                    error!("Error updating shortened URL: {:?}", e);
+                    AppError::Internal("Failed to update shortened URL".to_string())
+                }
+            })?;
 
         // Invalidate cache
         UrlShortenerCache::invalidate(&short_code);
@@ -196,19 +200,20 @@ impl ShortenedUrlService {
         Ok(result)
     }
 
-    /// Delete a shortened URL. Verifies ownership.
-    pub async fn delete_short_url(id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-        // Verify ownership
-        let existing = ShortenedUrlQuery::get_by_id(id, &Default::default()).await?;
-        if existing.user_id != Some(user_id) {
-            return Err(AppError::Internal("Not authorized to delete this URL".to_string()));
-        }
+    /// Delete a shortened URL the subject is allowed to modify.
+    pub async fn delete_short_url(id: Uuid, subject: &PolicySubject) -> Result<bool, AppError> {
+        let existing = ShortenedUrlQuery::get_by_id_for_subject(id, subject).await?;
 
         let short_code = existing.short_code.unwrap_or_default();
-        let result = ShortenedUrlMutation::delete_shortened_url(id).await.map_err(|e| {
-            error!("Error deleting shortened URL: {:?}", e);
-            AppError::Internal("Failed to delete shortened URL".to_string())
-        })?;
+        let result = ShortenedUrlMutation::delete_shortened_url_for_subject(id, subject)
+            .await
+            .map_err(|e| match e {
+                DbErr::RecordNotFound(_) => AppError::NotFound(format!("shortened_url {}", id)),
+                e => {
+                    error!("Error deleting shortened URL: {:?}", e);
+                    AppError::Internal("Failed to delete shortened URL".to_string())
+                }
+            })?;
 
         // Invalidate cache
         UrlShortenerCache::invalidate(&short_code);
@@ -216,13 +221,18 @@ impl ShortenedUrlService {
         Ok(result)
     }
 
-    /// List URLs for a specific user with pagination and filters.
+    /// Get a single URL visible to the subject.
+    pub async fn get_url(id: Uuid, subject: &PolicySubject) -> Result<ShortenedUrlData, AppError> {
+        ShortenedUrlQuery::get_by_id_for_subject(id, subject).await
+    }
+
+    /// List URLs visible to the subject with pagination and filters.
     pub async fn list_user_urls(
-        user_id: &Uuid,
+        subject: &PolicySubject,
         pagination: &Pagination,
         order: &Order,
         filters: &FilterCondition,
     ) -> Result<QueryResult<ShortenedUrlData>, AppError> {
-        ShortenedUrlQuery::get_user_urls(user_id, pagination, order, filters).await
+        ShortenedUrlQuery::search_for_subject(subject, pagination, order, filters).await
     }
 }
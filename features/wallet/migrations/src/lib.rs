@@ -1,4 +1,8 @@
 pub use sea_orm_migration::prelude::{async_trait, MigrationTrait, MigratorTrait};
+use shared_shared_migrations::{
+    m20261019_000001_create_outbox_messages, m20261019_000002_create_dlq_messages,
+    m20261019_000003_add_relay_columns_to_outbox_messages,
+};
 
 pub mod m20260101_000001_create_wallet_tables;
 pub mod m20260102_000001_create_top_up_transaction_table;
@@ -16,6 +20,9 @@ impl MigratorTrait for Migrator {
             Box::new(m20260102_000001_create_top_up_transaction_table::Migration),
             Box::new(m20260103_000001_create_p2p_and_withdrawal_table::Migration),
             Box::new(m20260104_000001_create_idempotency_table::Migration),
+            Box::new(m20261019_000001_create_outbox_messages::Migration),
+            Box::new(m20261019_000002_create_dlq_messages::Migration),
+            Box::new(m20261019_000003_add_relay_columns_to_outbox_messages::Migration),
             Box::new(m20260104_000001_add_version_to_wallet::Migration),
         ]
     }
@@ -36,6 +36,7 @@ pub struct WalletForUpdateRequest {
     pub balance: Option<f32>,
     pub currency: Option<String>,
     pub is_active: Option<bool>,
+    /// Version the update was made from, the update fails with 409 once the wallet changed
     pub version: Option<i32>,
 }
 
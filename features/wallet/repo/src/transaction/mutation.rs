@@ -20,6 +20,16 @@ impl TransactionMutation {
         TransactionMutationManager::create_uuid(data.into())
     }
 
+    pub async fn create_transaction_with_txn(
+        data: TransactionForCreateDto,
+        txn: &impl ConnectionTrait,
+    ) -> Result<Model, DbErr> {
+        let model: Model = data.into();
+        let mut active_model: ActiveModel = model.into();
+        active_model.not_set(Column::Id);
+        active_model.insert(txn).await
+    }
+
     pub fn update_transaction<'a>(
         transaction_id: Uuid,
         data: TransactionForUpdateDto,
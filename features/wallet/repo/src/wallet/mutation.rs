@@ -1,3 +1,4 @@
+use sea_orm::{QuerySelect, Set};
 use shared_shared_macro::Mutation;
 
 use features_wallet_entities::wallet::{
@@ -7,7 +8,7 @@ use features_wallet_entities::wallet::{
 use crate::wallet::util::assign;
 
 #[derive(Mutation)]
-#[mutation(key_type(Uuid))]
+#[mutation(key_type(Uuid), version_column = version)]
 struct WalletMutationManager {}
 
 pub struct WalletMutation;
@@ -26,6 +27,26 @@ impl WalletMutation {
         WalletMutationManager::update_by_id_uuid(wallet_id, data.into())
     }
 
+    /// Adds `amount` to the balance on `txn`. The wallet row stays locked until the
+    /// transaction ends, so concurrent credits are applied one after the other.
+    pub async fn credit_with_txn(
+        wallet_id: Uuid,
+        amount: f32,
+        txn: &impl ConnectionTrait,
+    ) -> Result<Model, DbErr> {
+        let wallet = Entity::find_by_id(wallet_id)
+            .lock_exclusive()
+            .one(txn)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
+        let balance = wallet.balance + amount;
+        let version = wallet.version + 1;
+        let mut active_model: ActiveModel = wallet.into();
+        active_model.balance = Set(balance);
+        active_model.version = Set(version);
+        active_model.update(txn).await
+    }
+
     pub fn delete_wallet<'a>(
         wallet_id: Uuid,
     ) -> impl std::future::Future<Output = Result<bool, DbErr>> + 'a {
@@ -1,6 +1,9 @@
+use sea_orm::DbErr;
 use tracing::debug;
 use uuid::Uuid;
 
+use shared_shared_app::event_task::{outbox::Outbox, producer::ProducerMessage};
+use shared_shared_config::db::UnitOfWork;
 use shared_shared_data_core::{
     filter::FilterCondition,
     order::Order,
@@ -8,8 +11,15 @@ use shared_shared_data_core::{
 };
 use shared_shared_data_error::app::AppError;
 
-use features_wallet_model::wallet::{WalletData, WalletForCreateRequest, WalletForUpdateRequest};
-use features_wallet_repo::wallet::{WalletMutation, WalletQuery};
+use features_wallet_model::{
+    transaction::TransactionForCreateRequest,
+    wallet::{WalletData, WalletForCreateRequest, WalletForUpdateRequest},
+};
+use features_wallet_repo::{
+    transaction::TransactionMutation,
+    wallet::{WalletMutation, WalletQuery},
+};
+use features_wallet_stream::{TransactionCreatedEvent, TransactionEvent};
 
 pub struct WalletService {}
 
@@ -55,6 +65,7 @@ impl WalletService {
         let result = WalletMutation::update_wallet(wallet_id, wallet_request.into()).await;
         match result {
             Ok(success) => Ok(success),
+            Err(e @ DbErr::RecordNotUpdated) => Err(e.into()),
             Err(e) => {
                 debug!("Error updating wallet: {:?}", e);
                 Err(AppError::Internal("Failed to update wallet".to_string()))
@@ -84,7 +95,7 @@ impl WalletService {
                 balance: Some(new_balance),
                 currency: None,
                 is_active: None,
-                version: Some(version + 1),
+                version: Some(version),
             };
             match Self::update_wallet(wallet_id, update_req).await {
                 Ok(_) => return Self::get_wallet_by_id(wallet_id).await,
@@ -115,7 +126,7 @@ impl WalletService {
                 balance: Some(new_balance),
                 currency: None,
                 is_active: None,
-                version: Some(version + 1),
+                version: Some(version),
             };
             match Self::update_wallet(wallet_id, update_req).await {
                 Ok(_) => return Self::get_wallet_by_id(wallet_id).await,
@@ -130,4 +141,59 @@ impl WalletService {
         }
         unreachable!()
     }
+
+    /// Credits the wallet, records the transaction and stores its `transaction.created` event
+    /// for `topic` in the outbox, all in one database transaction.
+    pub async fn deposit(
+        transaction_request: TransactionForCreateRequest,
+        topic: &str,
+    ) -> Result<Uuid, AppError> {
+        let unit_of_work = UnitOfWork::begin().await.map_err(|e| {
+            debug!("Error starting transaction: {:?}", e);
+            AppError::Internal("Failed to credit wallet".to_string())
+        })?;
+
+        let wallet_id = transaction_request.wallet_id;
+        WalletMutation::credit_with_txn(wallet_id, transaction_request.amount, unit_of_work.txn())
+            .await
+            .map_err(|e| {
+                debug!("Error crediting wallet {}: {:?}", wallet_id, e);
+                AppError::Internal("Failed to credit wallet".to_string())
+            })?;
+
+        let transaction = TransactionMutation::create_transaction_with_txn(
+            transaction_request.into(),
+            unit_of_work.txn(),
+        )
+        .await
+        .map_err(|e| {
+            debug!("Error creating transaction: {:?}", e);
+            AppError::Internal("Failed to create transaction".to_string())
+        })?;
+
+        let message = ProducerMessage {
+            key: Some(wallet_id.to_string()),
+            payload: TransactionEvent::Created(TransactionCreatedEvent {
+                transaction_id: transaction.id,
+                wallet_id,
+                transaction_type: transaction.transaction_type,
+                amount: transaction.amount.to_string(),
+                currency: transaction.currency,
+                status: transaction.status,
+                created_at: transaction.created_at.and_utc().to_rfc3339(),
+            }),
+        };
+        Outbox::enqueue(unit_of_work.txn(), topic, &message)
+            .await
+            .map_err(|e| {
+                debug!("Error storing transaction event in outbox: {:?}", e);
+                AppError::Internal("Failed to create transaction".to_string())
+            })?;
+
+        unit_of_work.commit().await.map_err(|e| {
+            debug!("Error committing transaction: {:?}", e);
+            AppError::Internal("Failed to credit wallet".to_string())
+        })?;
+        Ok(transaction.id)
+    }
 }
@@ -11,3 +11,5 @@ pub use transaction_event::{
     TransactionUpdatedEvent,
 };
 pub use wallet_event::{WalletCreatedEvent, WalletDeletedEvent, WalletEvent, WalletUpdatedEvent};
+
+pub const PRODUCER_KEY: &str = "wallet";
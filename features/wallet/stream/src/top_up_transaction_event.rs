@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopUpTransactionInitiatedEvent {
     pub top_up_transaction_id: Uuid,
@@ -50,3 +52,8 @@ pub enum TopUpTransactionEvent {
     #[serde(rename = "top_up_transaction.updated")]
     Updated(TopUpTransactionUpdatedEvent),
 }
+
+impl EventContract for TopUpTransactionEvent {
+    const EVENT_TYPE: &'static str = "wallet.top_up_transaction";
+    const VERSION: u32 = 1;
+}
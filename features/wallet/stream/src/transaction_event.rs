@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionCreatedEvent {
     pub transaction_id: Uuid,
@@ -50,3 +52,8 @@ pub enum TransactionEvent {
     #[serde(rename = "transaction.failed")]
     Failed(TransactionFailedEvent),
 }
+
+impl EventContract for TransactionEvent {
+    const EVENT_TYPE: &'static str = "wallet.transaction";
+    const VERSION: u32 = 1;
+}
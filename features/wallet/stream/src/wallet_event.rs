@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use shared_shared_data_core::event::EventContract;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletCreatedEvent {
     pub wallet_id: Uuid,
@@ -36,3 +38,8 @@ pub enum WalletEvent {
     #[serde(rename = "wallet.deleted")]
     Deleted(WalletDeletedEvent),
 }
+
+impl EventContract for WalletEvent {
+    const EVENT_TYPE: &'static str = "wallet.wallet";
+    const VERSION: u32 = 1;
+}
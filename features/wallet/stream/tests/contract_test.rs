@@ -0,0 +1,27 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use features_wallet_stream::{TopUpTransactionEvent, TransactionEvent, WalletEvent};
+
+#[test]
+fn transaction_event_fixtures_still_decode() {
+    assert_fixtures::<TransactionEvent>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/wallet.transaction"
+    ));
+}
+
+#[test]
+fn top_up_transaction_event_fixtures_still_decode() {
+    assert_fixtures::<TopUpTransactionEvent>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/wallet.top_up_transaction"
+    ));
+}
+
+#[test]
+fn wallet_event_fixtures_still_decode() {
+    assert_fixtures::<WalletEvent>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/wallet.wallet"
+    ));
+}
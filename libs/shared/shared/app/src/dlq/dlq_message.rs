@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+/// A message the service sent to the DLQ topic, collected by the DLQ admin.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "dlq_messages")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub source_topic: String,
+    /// Consumer group that gave up on the message
+    pub consumer_group: Option<String>,
+    pub source_key: Option<String>,
+    pub headers: Json,
+    /// Original payload, or the payload edited by an operator
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+    #[sea_orm(column_type = "Text")]
+    pub error: String,
+    pub status: String,
+    /// DLQ message whose replay failed into this one
+    pub replay_of: Option<Uuid>,
+    pub replay_count: i32,
+    pub last_replayed_at: Option<DateTime>,
+    pub last_replayed_by: Option<Uuid>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_replay_error: Option<String>,
+    pub failed_at: DateTime,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use shared_shared_data_core::event::EventContract;
+
+pub mod dlq_message;
+mod model;
+pub mod routes;
+mod service;
+
+pub use model::{DlqMessageData, DlqMessageFilter, DlqMessageForUpdateRequest};
+pub use service::{DlqAdmin, DlqService, DLQ_REPLAY_PRODUCER_KEY};
+
+/// Set on a replayed message with the id of its DLQ message. When the replay fails again,
+/// the new DLQ message is linked to the replayed one.
+pub const REPLAY_OF_HEADER: &str = "x-dlq-replay-of";
+
+/// Message sent to the DLQ topic by the consumer. Only `origin_payload` and `error_msg`
+/// are set on messages sent before the DLQ admin.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DlqPayload {
+    #[serde(default)]
+    pub id: Option<Uuid>,
+    /// Key of the service that consumed the message
+    #[serde(default)]
+    pub service: Option<String>,
+    #[serde(default)]
+    pub source_topic: Option<String>,
+    /// Consumer group that gave up on the message, replayed to its replay topic
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub source_key: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Epoch milliseconds
+    #[serde(default)]
+    pub failed_at: Option<i64>,
+    pub origin_payload: String,
+    pub error_msg: String,
+}
+
+impl EventContract for DlqPayload {
+    const EVENT_TYPE: &'static str = "dlq.message";
+    const VERSION: u32 = 1;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DlqStatus {
+    Pending,
+    /// Sent to the replay topic of its consumer group
+    Replayed,
+    /// Replayed and back in the DLQ
+    ReplayFailed,
+    Discarded,
+}
+
+impl DlqStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Replayed => "replayed",
+            Self::ReplayFailed => "replay_failed",
+            Self::Discarded => "discarded",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "replayed" => Some(Self::Replayed),
+            "replay_failed" => Some(Self::ReplayFailed),
+            "discarded" => Some(Self::Discarded),
+            _ => None,
+        }
+    }
+
+    /// Messages waiting for an operator, the only ones that can be edited, replayed or discarded
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Pending | Self::ReplayFailed)
+    }
+}
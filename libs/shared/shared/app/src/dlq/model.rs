@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime as DateTime;
+use serde::{Deserialize, Serialize};
+use shared_shared_macro::Response;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::dlq::dlq_message;
+
+#[derive(Serialize, Debug, Clone, ToSchema, Response)]
+pub struct DlqMessageData {
+    pub id: Uuid,
+    pub source_topic: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumer_group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_key: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub payload: String,
+    pub error: String,
+    /// `pending`, `replayed`, `replay_failed` or `discarded`
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<Uuid>,
+    pub replay_count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_replayed_at: Option<DateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_replayed_by: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_replay_error: Option<String>,
+    pub failed_at: DateTime,
+}
+
+impl From<dlq_message::Model> for DlqMessageData {
+    fn from(model: dlq_message::Model) -> Self {
+        DlqMessageData {
+            id: model.id,
+            source_topic: model.source_topic,
+            consumer_group: model.consumer_group,
+            source_key: model.source_key,
+            headers: serde_json::from_value(model.headers).unwrap_or_default(),
+            payload: model.payload,
+            error: model.error,
+            status: model.status,
+            replay_of: model.replay_of,
+            replay_count: model.replay_count,
+            last_replayed_at: model.last_replayed_at,
+            last_replayed_by: model.last_replayed_by,
+            last_replay_error: model.last_replay_error,
+            failed_at: model.failed_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DlqMessageFilter {
+    /// `pending`, `replayed`, `replay_failed` or `discarded`
+    pub status: Option<String>,
+    pub source_topic: Option<String>,
+}
+
+/// Payload replayed instead of the original one, e.g. with a field fixed.
+#[derive(Deserialize, Debug, Validate, ToSchema)]
+pub struct DlqMessageForUpdateRequest {
+    #[validate(length(min = 1))]
+    pub payload: String,
+}
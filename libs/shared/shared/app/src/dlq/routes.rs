@@ -0,0 +1,176 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Router,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{instrument, Level};
+use uuid::Uuid;
+
+use shared_shared_auth::{permission::Auth, ResourcePermission};
+use shared_shared_data_app::{
+    json::{ResponseJson, ValidJson},
+    result::Result,
+};
+use shared_shared_data_core::paging::{Pagination, QueryResult};
+use shared_shared_data_error::app::AppError;
+
+use crate::dlq::{
+    DlqMessageData, DlqMessageFilter, DlqMessageForUpdateRequest, DlqService,
+    DLQ_REPLAY_PRODUCER_KEY,
+};
+use crate::state::AppState;
+
+const TAG: &str = "dlq";
+
+#[utoipa::path(
+    get,
+    path = "/dlq-messages",
+    tag = TAG,
+    params(DlqMessageFilter, Pagination),
+    responses(
+        (status = 200, description = "Filtered DLQ messages", body = QueryResult<DlqMessageData>),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+pub async fn filter_dlq_messages<T, C, R>(
+    _auth: Auth<R>,
+    State(state): State<AppState<T, C>>,
+    Query(filter): Query<DlqMessageFilter>,
+    Query(pagination): Query<Pagination>,
+) -> Result<ResponseJson<QueryResult<DlqMessageData>>>
+where
+    C: Clone + Serialize + DeserializeOwned + Default + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    R: ResourcePermission + Send + Sync + 'static,
+{
+    let result = DlqService::get_dlq_messages(&state.write_db, &filter, &pagination).await?;
+    Ok(ResponseJson(result))
+}
+
+#[utoipa::path(
+    get,
+    path = "/dlq-messages/{dlq_message_id}",
+    tag = TAG,
+    responses(
+        (status = 200, description = "DLQ message retrieved", body = DlqMessageData),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+pub async fn get_dlq_message<T, C, R>(
+    _auth: Auth<R>,
+    State(state): State<AppState<T, C>>,
+    Path(dlq_message_id): Path<Uuid>,
+) -> Result<ResponseJson<DlqMessageData>>
+where
+    C: Clone + Serialize + DeserializeOwned + Default + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    R: ResourcePermission + Send + Sync + 'static,
+{
+    let message = DlqService::get_dlq_message(&state.write_db, dlq_message_id).await?;
+    Ok(ResponseJson(message))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/dlq-messages/{dlq_message_id}",
+    tag = TAG,
+    request_body = DlqMessageForUpdateRequest,
+    responses(
+        (status = 200, description = "DLQ message payload updated", body = DlqMessageData),
+        (status = 409, description = "DLQ message already replayed or discarded"),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+pub async fn update_dlq_message<T, C, W>(
+    _auth: Auth<W>,
+    State(state): State<AppState<T, C>>,
+    Path(dlq_message_id): Path<Uuid>,
+    ValidJson(req): ValidJson<DlqMessageForUpdateRequest>,
+) -> Result<ResponseJson<DlqMessageData>>
+where
+    C: Clone + Serialize + DeserializeOwned + Default + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    W: ResourcePermission + Send + Sync + 'static,
+{
+    let message = DlqService::update_payload(&state.write_db, dlq_message_id, req.payload).await?;
+    Ok(ResponseJson(message))
+}
+
+#[utoipa::path(
+    post,
+    path = "/dlq-messages/{dlq_message_id}/replay",
+    tag = TAG,
+    responses(
+        (status = 200, description = "DLQ message replayed to the replay topic of its group", body = DlqMessageData),
+        (status = 409, description = "DLQ message already replayed or discarded, or without source topic or group"),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+pub async fn replay_dlq_message<T, C, W>(
+    auth: Auth<W>,
+    State(state): State<AppState<T, C>>,
+    Path(dlq_message_id): Path<Uuid>,
+) -> Result<ResponseJson<DlqMessageData>>
+where
+    C: Clone + Serialize + DeserializeOwned + Default + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    W: ResourcePermission + Send + Sync + 'static,
+{
+    let producer = state
+        .get_producer(DLQ_REPLAY_PRODUCER_KEY.to_string())
+        .ok_or(AppError::Internal("DLQ admin not started".to_string()))?;
+    let message =
+        DlqService::replay(&state.write_db, &producer, dlq_message_id, auth.user_id()).await?;
+    Ok(ResponseJson(message))
+}
+
+#[utoipa::path(
+    post,
+    path = "/dlq-messages/{dlq_message_id}/discard",
+    tag = TAG,
+    responses(
+        (status = 200, description = "DLQ message discarded", body = DlqMessageData),
+        (status = 409, description = "DLQ message already replayed or discarded"),
+    )
+)]
+#[instrument(level = Level::INFO, skip_all)]
+pub async fn discard_dlq_message<T, C, W>(
+    _auth: Auth<W>,
+    State(state): State<AppState<T, C>>,
+    Path(dlq_message_id): Path<Uuid>,
+) -> Result<ResponseJson<DlqMessageData>>
+where
+    C: Clone + Serialize + DeserializeOwned + Default + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    W: ResourcePermission + Send + Sync + 'static,
+{
+    let message = DlqService::discard(&state.write_db, dlq_message_id).await?;
+    Ok(ResponseJson(message))
+}
+
+/// DLQ admin routes, reading with the `R` permission and editing, replaying and discarding
+/// with the `W` one.
+pub fn routes<T, C, R, W>(app_state: &AppState<T, C>) -> Router
+where
+    C: Clone + Serialize + DeserializeOwned + Default + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    R: ResourcePermission + Send + Sync + 'static,
+    W: ResourcePermission + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/dlq-messages", get(filter_dlq_messages::<T, C, R>))
+        .route(
+            "/dlq-messages/{dlq_message_id}",
+            get(get_dlq_message::<T, C, R>).patch(update_dlq_message::<T, C, W>),
+        )
+        .route(
+            "/dlq-messages/{dlq_message_id}/replay",
+            post(replay_dlq_message::<T, C, W>),
+        )
+        .route(
+            "/dlq-messages/{dlq_message_id}/discard",
+            post(discard_dlq_message::<T, C, W>),
+        )
+        .with_state(app_state.clone())
+}
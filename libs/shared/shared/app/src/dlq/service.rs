@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use shared_shared_data_core::paging::{Pagination, QueryResult};
+use shared_shared_data_error::app::AppError;
+
+use crate::dlq::{
+    dlq_message, DlqMessageData, DlqMessageFilter, DlqPayload, DlqStatus, REPLAY_OF_HEADER,
+};
+use crate::event_task::consumer::{consumer_task, replay_topic, ConsumerConfig};
+use crate::event_task::producer::{Producer, ProducerConfig};
+use crate::state::AppState;
+
+/// Key of the producer replaying DLQ messages in the app state.
+pub const DLQ_REPLAY_PRODUCER_KEY: &str = "dlq_replay";
+
+const MAX_STORE_BACKOFF: Duration = Duration::from_secs(60);
+
+/// DLQ admin of a service. The DLQ topic is shared by all services, each one collects the
+/// messages it sent, recognized by its app key, into its `dlq_messages` table.
+///
+/// An API enables it with `DlqAdmin::start` in `StartApp::custom_handler`, the DLQ routes in
+/// `StartApp::routes` and a migration creating `dlq_messages`.
+pub struct DlqAdmin;
+
+impl DlqAdmin {
+    /// Starts collecting the DLQ messages of `app_key` and registers the producer replaying
+    /// them, on the cluster of `DLQ_KAFKA_BOOTSTRAP_SERVERS`.
+    pub async fn start<T, C>(app_key: &str, app_state: &mut AppState<T, C>)
+    where
+        C: Clone + Serialize + DeserializeOwned + Default + Sync,
+        T: Clone,
+    {
+        let producer = Producer::from_config(ProducerConfig::from_env(
+            "DLQ_KAFKA_BOOTSTRAP_SERVERS".to_string(),
+            "DLQ_KAFKA_TOPIC".to_string(),
+        ))
+        .await;
+        app_state.set_producer(DLQ_REPLAY_PRODUCER_KEY.to_string(), producer.clone());
+
+        // One group per service so every DLQ message is stored once, from the start of the
+        // topic the first time
+        let consumer_config = ConsumerConfig::from_env(
+            "DLQ_KAFKA_BOOTSTRAP_SERVERS".to_string(),
+            "DLQ_KAFKA_TOPIC".to_string(),
+            format!("{}_dlq_admin", app_key.to_lowercase()),
+        )
+        .with_offset_reset("earliest")
+        .with_retry_delays(Vec::new());
+        let collector = DlqCollector {
+            db: app_state.write_db.clone(),
+            service: app_key.to_string(),
+        };
+        let dlq_key = app_key.to_string();
+        tokio::spawn(async move {
+            if let Err(e) =
+                consumer_task(consumer_config, collector, producer, dlq_key, collect).await
+            {
+                error!("Error in DLQ collector task: {:?}", e);
+            }
+        });
+    }
+}
+
+#[derive(Clone)]
+struct DlqCollector {
+    db: DatabaseConnection,
+    service: String,
+}
+
+/// Stores a DLQ message of the service. A message that is the failed replay of another one
+/// marks that one `replay_failed`.
+///
+/// A failure here would send the message back to the DLQ topic it was read from, the store is
+/// retried until the database is back instead.
+async fn collect(
+    message: DlqPayload,
+    collector: DlqCollector,
+    _headers: Option<HashMap<String, String>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if message.service.as_deref() != Some(collector.service.as_str()) {
+        return Ok(());
+    }
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        match store(&collector.db, &message).await {
+            Ok(id) => {
+                debug!("Collected DLQ message {}", id);
+                return Ok(());
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to store DLQ message, retrying in {:?}: {}",
+                    backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_STORE_BACKOFF);
+            }
+        }
+    }
+}
+
+async fn store(db: &DatabaseConnection, message: &DlqPayload) -> Result<Uuid, DbErr> {
+    let now = Utc::now().naive_utc();
+    let id = message.id.unwrap_or_else(Uuid::new_v4);
+    let replay_of = message
+        .headers
+        .get(REPLAY_OF_HEADER)
+        .and_then(|replay_of| Uuid::parse_str(replay_of).ok());
+    let failed_at = message
+        .failed_at
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|failed_at| failed_at.naive_utc())
+        .unwrap_or(now);
+
+    let row = dlq_message::ActiveModel {
+        id: Set(id),
+        source_topic: Set(message.source_topic.clone().unwrap_or_default()),
+        consumer_group: Set(message.group.clone()),
+        source_key: Set(message.source_key.clone()),
+        headers: Set(serde_json::to_value(&message.headers).unwrap_or_default()),
+        payload: Set(message.origin_payload.clone()),
+        error: Set(message.error_msg.clone()),
+        status: Set(DlqStatus::Pending.as_str().to_string()),
+        replay_of: Set(replay_of),
+        replay_count: Set(0),
+        last_replayed_at: Set(None),
+        last_replayed_by: Set(None),
+        last_replay_error: Set(None),
+        failed_at: Set(failed_at),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    let txn = db.begin().await?;
+    // Delivered again after a crash or a rebalance
+    let inserted = dlq_message::Entity::insert(row)
+        .on_conflict(
+            OnConflict::column(dlq_message::Column::Id)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+    if inserted > 0 {
+        if let Some(replay_of) = replay_of {
+            dlq_message::Entity::update_many()
+                .col_expr(
+                    dlq_message::Column::Status,
+                    Expr::value(DlqStatus::ReplayFailed.as_str()),
+                )
+                .col_expr(dlq_message::Column::UpdatedAt, Expr::value(now))
+                .filter(dlq_message::Column::Id.eq(replay_of))
+                .exec(&txn)
+                .await?;
+        }
+    }
+    txn.commit().await?;
+    Ok(id)
+}
+
+pub struct DlqService;
+
+impl DlqService {
+    pub async fn get_dlq_messages(
+        db: &DatabaseConnection,
+        filter: &DlqMessageFilter,
+        pagination: &Pagination,
+    ) -> Result<QueryResult<DlqMessageData>, AppError> {
+        let mut query = dlq_message::Entity::find();
+        if let Some(status) = &filter.status {
+            query = query.filter(dlq_message::Column::Status.eq(status.as_str()));
+        }
+        if let Some(source_topic) = &filter.source_topic {
+            query = query.filter(dlq_message::Column::SourceTopic.eq(source_topic.as_str()));
+        }
+        let paginator = query
+            .order_by_desc(dlq_message::Column::FailedAt)
+            .paginate(db, pagination.page_size.unwrap_or(10));
+        let total_page = paginator.num_pages().await?;
+        let result = paginator
+            .fetch_page(pagination.page.unwrap_or(1).saturating_sub(1))
+            .await?
+            .into_iter()
+            .map(DlqMessageData::from)
+            .collect();
+        Ok(QueryResult {
+            total_page,
+            result,
+            next_cursor: None,
+            prev_cursor: None,
+        })
+    }
+
+    pub async fn get_dlq_message(
+        db: &DatabaseConnection,
+        id: Uuid,
+    ) -> Result<DlqMessageData, AppError> {
+        Ok(Self::find(db, id).await?.into())
+    }
+
+    pub async fn update_payload(
+        db: &DatabaseConnection,
+        id: Uuid,
+        payload: String,
+    ) -> Result<DlqMessageData, AppError> {
+        let message = Self::find_open(db, id).await?;
+        let mut message: dlq_message::ActiveModel = message.into();
+        message.payload = Set(payload);
+        message.updated_at = Set(Utc::now().naive_utc());
+        Ok(message.update(db).await?.into())
+    }
+
+    /// Sends the message to the replay topic of the consumer group that gave up on it, with
+    /// its key and headers, so the other groups of the source topic don't handle it again.
+    /// The outcome is recorded on the message, a send error is returned after being recorded.
+    pub async fn replay(
+        db: &DatabaseConnection,
+        producer: &Producer,
+        id: Uuid,
+        replayed_by: Option<Uuid>,
+    ) -> Result<DlqMessageData, AppError> {
+        let message = Self::find_open(db, id).await?;
+        // Sent before the DLQ admin or before replay topics, it can only be discarded
+        let topic = match (&message.source_topic, &message.consumer_group) {
+            (source_topic, Some(group)) if !source_topic.is_empty() => {
+                replay_topic(source_topic, group)
+            }
+            _ => {
+                return Err(AppError::Conflict(
+                    "DLQ message has no source topic or consumer group".to_string(),
+                ))
+            }
+        };
+        let mut headers: HashMap<String, String> =
+            serde_json::from_value(message.headers.clone()).unwrap_or_default();
+        headers.insert(REPLAY_OF_HEADER.to_string(), id.to_string());
+
+        let result = producer
+            .send_raw(
+                &topic,
+                message.source_key.as_deref(),
+                &message.payload,
+                &headers,
+            )
+            .await;
+
+        let now = Utc::now().naive_utc();
+        let replay_count = message.replay_count + 1;
+        let mut message: dlq_message::ActiveModel = message.into();
+        message.replay_count = Set(replay_count);
+        message.last_replayed_at = Set(Some(now));
+        message.last_replayed_by = Set(replayed_by);
+        message.updated_at = Set(now);
+        match &result {
+            Ok(_) => {
+                message.status = Set(DlqStatus::Replayed.as_str().to_string());
+                message.last_replay_error = Set(None);
+            }
+            Err(e) => message.last_replay_error = Set(Some(e.reason.clone())),
+        }
+        let message = message.update(db).await?;
+
+        match result {
+            Ok(_) => {
+                info!("Replayed DLQ message {} to {}", id, topic);
+                Ok(message.into())
+            }
+            Err(e) => {
+                error!("Failed to replay DLQ message {}: {}", id, e.reason);
+                Err(AppError::Internal(
+                    "Failed to replay DLQ message".to_string(),
+                ))
+            }
+        }
+    }
+
+    pub async fn discard(db: &DatabaseConnection, id: Uuid) -> Result<DlqMessageData, AppError> {
+        let message = Self::find_open(db, id).await?;
+        let mut message: dlq_message::ActiveModel = message.into();
+        message.status = Set(DlqStatus::Discarded.as_str().to_string());
+        message.updated_at = Set(Utc::now().naive_utc());
+        Ok(message.update(db).await?.into())
+    }
+
+    async fn find(db: &DatabaseConnection, id: Uuid) -> Result<dlq_message::Model, AppError> {
+        dlq_message::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(AppError::EntityNotFound {
+                entity: "dlq_message".to_string(),
+            })
+    }
+
+    /// Replayed and discarded messages are kept as they are, changing them is a conflict.
+    async fn find_open(db: &DatabaseConnection, id: Uuid) -> Result<dlq_message::Model, AppError> {
+        let message = Self::find(db, id).await?;
+        match DlqStatus::parse(&message.status) {
+            Some(status) if status.is_open() => Ok(message),
+            _ => Err(AppError::Conflict(format!(
+                "DLQ message is {}",
+                message.status
+            ))),
+        }
+    }
+}
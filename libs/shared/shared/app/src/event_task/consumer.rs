@@ -1,38 +1,130 @@
+use futures_util::future::join_all;
 use futures_util::StreamExt;
 use opentelemetry::global;
-use opentelemetry::propagation::Extractor;
 use rdkafka::config::ClientConfig;
-use rdkafka::consumer::{Consumer, StreamConsumer};
-use rdkafka::message::{Headers, Message, OwnedHeaders};
-use serde::{Deserialize, Serialize};
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::message::{BorrowedMessage, Headers, Message};
+use rdkafka::{Offset, TopicPartitionList};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::future::Future;
-use tracing::{debug, error};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, error, warn, Instrument};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
 
-use crate::event_task::producer::{Producer, ProducerMessage};
+use shared_shared_data_core::event::{EventContract, EventEnvelope};
+
+use crate::dlq::DlqPayload;
+use crate::event_task::offset::OffsetTracker;
+use crate::event_task::producer::{envelope, Producer, ProducerConfig};
+
+/// Retry topic number a message comes from, absent on the main topic.
+pub const RETRY_ATTEMPT_HEADER: &str = "x-retry-attempt";
+/// Epoch milliseconds before which a retried message is not handled.
+pub const RETRY_NOT_BEFORE_HEADER: &str = "x-retry-not-before";
+/// Error of the last failed attempt.
+pub const RETRY_ERROR_HEADER: &str = "x-retry-error";
+/// Id of the event, given to the handler. Absent on payloads sent before the envelope.
+pub const EVENT_ID_HEADER: &str = "x-event-id";
+/// Version the event was produced with, before upcasting, given to the handler.
+pub const EVENT_VERSION_HEADER: &str = "x-event-version";
+/// Tenant of the producer, given to the handler when set.
+pub const EVENT_TENANT_HEADER: &str = "x-event-tenant";
+
+const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+const DEFAULT_RETRY_DELAYS_MS: &str = "5000,60000,600000";
+const MAX_FORWARD_BACKOFF: Duration = Duration::from_secs(30);
+const SEEK_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct ConsumerConfig {
     server: String,
     topic: String,
     group: String,
+    max_in_flight: usize,
+    retry_delays: Vec<Duration>,
+    offset_reset: String,
 }
 
 impl ConsumerConfig {
+    /// `KAFKA_CONSUMER_MAX_IN_FLIGHT` and `KAFKA_CONSUMER_RETRY_DELAYS_MS` (comma separated)
+    /// override the defaults for every consumer of the service.
     pub fn from_env(server_env: String, topic_env: String, group: String) -> Self {
         let bootstrap_servers = std::env::var(&server_env)
             .expect(format!("consumer kafka server variable ${} not set", server_env).as_str());
         let consumer_topic = std::env::var(&topic_env)
             .expect(format!("consumer kafka topic variable ${} not set", topic_env).as_str());
+        let max_in_flight = std::env::var("KAFKA_CONSUMER_MAX_IN_FLIGHT")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MAX_IN_FLIGHT);
+        let retry_delays = std::env::var("KAFKA_CONSUMER_RETRY_DELAYS_MS")
+            .unwrap_or_else(|_| DEFAULT_RETRY_DELAYS_MS.to_string());
 
         Self {
             server: bootstrap_servers,
             topic: consumer_topic,
             group,
+            max_in_flight: max_in_flight.max(1),
+            retry_delays: parse_delays(&retry_delays),
+            offset_reset: "latest".to_string(),
         }
     }
+
+    /// Messages handled at the same time per partition. Messages with the same key are
+    /// always handled one after the other, in offset order.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Delay before each retry. A failed message goes through one retry topic per delay,
+    /// `<topic>.<group>.retry.<n>`, before the DLQ. No delays sends failures straight to the DLQ.
+    pub fn with_retry_delays(mut self, retry_delays: Vec<Duration>) -> Self {
+        self.retry_delays = retry_delays;
+        self
+    }
+
+    /// Where a group without committed offset starts on the main topic, `latest` by default or
+    /// `earliest`. Retry topics are always read from the start.
+    pub fn with_offset_reset(mut self, offset_reset: &str) -> Self {
+        self.offset_reset = offset_reset.to_string();
+        self
+    }
+
+    /// Retry topics are per group, so a failure is only retried by the group it failed in.
+    fn retry_topic(&self, attempt: usize) -> String {
+        format!("{}.{}.retry.{}", self.topic, self.group, attempt)
+    }
 }
 
+/// Topic the DLQ admin replays the messages `group` gave up on, consumed by that group only
+/// so the other groups of `topic` don't handle them again.
+pub fn replay_topic(topic: &str, group: &str) -> String {
+    format!("{}.{}.replay", topic, group)
+}
+
+fn parse_delays(value: &str) -> Vec<Duration> {
+    value
+        .split(',')
+        .filter_map(|delay| delay.trim().parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .collect()
+}
+
+/// Consumes the topic, its retry topics and its replay topic with at-least-once delivery:
+/// - the offset of a message is committed once the handler succeeded, or once the message
+///   was forwarded to a retry topic or the DLQ, and never past a message still in flight,
+/// - at most `max_in_flight` messages are handled per partition, messages with the same key
+///   in order,
+/// - a failed message is retried through the retry topics, then sent to the DLQ. A payload
+///   that can't be deserialized goes to the DLQ directly.
+///
+/// A message is handled again after a crash or a rebalance, handlers must be idempotent.
 pub async fn consumer_task<M, S, F, Fut>(
     config: ConsumerConfig,
     state: S,
@@ -41,21 +133,78 @@ pub async fn consumer_task<M, S, F, Fut>(
     handler: F,
 ) -> Result<(), Box<dyn std::error::Error + Send>>
 where
-    M: for<'de> Deserialize<'de> + std::fmt::Debug + Send + 'static,
+    M: EventContract + std::fmt::Debug + Send + 'static,
+    S: Clone + Send + 'static,
+    F: Fn(M, S, Option<HashMap<String, String>>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send + 'static,
+{
+    let retry_topics: Vec<String> = (1..=config.retry_delays.len())
+        .map(|attempt| config.retry_topic(attempt))
+        .collect();
+
+    // Retried messages go back to the cluster they were consumed from
+    let retry_producer = match retry_topics.first() {
+        Some(retry_topic) => Some(
+            Producer::from_config(ProducerConfig {
+                kafka_server_env: config.server.clone(),
+                kafka_topic_env: retry_topic.clone(),
+            })
+            .await,
+        ),
+        None => None,
+    };
+
+    let worker = Arc::new(Worker {
+        handler,
+        topic: config.topic.clone(),
+        dlq_producer,
+        dlq_key,
+        group: config.group.clone(),
+        retry_producer,
+        retry_topics: retry_topics.clone(),
+        retry_delays: config.retry_delays.clone(),
+        _message: PhantomData::<fn() -> M>,
+    });
+
+    // One consumer per topic, so messages waiting for their retry delay don't hold back
+    // the main topic. Replayed messages are handled like new ones, retries included.
+    let mut topics = vec![config.topic.clone()];
+    topics.extend(retry_topics);
+    topics.push(replay_topic(&config.topic, &config.group));
+    let consumers = topics
+        .into_iter()
+        .map(|topic| consume_topic(&config, topic, worker.clone(), state.clone()));
+    join_all(consumers).await;
+    Ok(())
+}
+
+async fn consume_topic<M, S, F, Fut>(
+    config: &ConsumerConfig,
+    topic: String,
+    worker: Arc<Worker<M, F>>,
+    state: S,
+) where
+    M: EventContract + std::fmt::Debug + Send + 'static,
     S: Clone + Send + 'static,
     F: Fn(M, S, Option<HashMap<String, String>>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send + 'static,
 {
-    let bootstrap_server = config.server;
-    let topic = config.topic;
-    let group = config.group;
+    let bootstrap_server = &config.server;
+    let group = &config.group;
+    // A retry or replay topic only exists once a message failed, the group has no offset on
+    // it yet and must not skip the messages forwarded before it subscribed
+    let offset_reset = if topic == config.topic {
+        config.offset_reset.as_str()
+    } else {
+        "earliest"
+    };
 
     let consumer: StreamConsumer = ClientConfig::new()
         .set("group.id", group.as_str())
-        .set("bootstrap.servers", &bootstrap_server)
-        .set("auto.offset.reset", "latest")
+        .set("bootstrap.servers", bootstrap_server)
+        .set("auto.offset.reset", offset_reset)
         .set("session.timeout.ms", "6000") // Example: longer session timeout
-        .set("enable.auto.commit", "true")
+        .set("enable.auto.commit", "false") // Committed once the message is handled
         .set("allow.auto.create.topics", "true") // Allow Kafka to create topic if it doesn't exist
         .create()
         .expect("Consumer creation failed");
@@ -69,138 +218,418 @@ where
         topic, group
     );
 
-    // Wrap handler in Arc so it can be cloned into spawned tasks safely
-    let handler = std::sync::Arc::new(handler);
+    let consumer = Arc::new(consumer);
+    let max_in_flight = config.max_in_flight;
+    let mut partitions: HashMap<i32, Partition> = HashMap::new();
 
     let mut message_stream = consumer.stream();
     while let Some(message) = message_stream.next().await {
         debug!("Received message from Kafka");
-        match message {
-            Ok(borrow_message) => {
-                // 1. Extract tracing context from message headers
-                let headers = borrow_message.headers();
-                let owned_headers: Option<OwnedHeaders> = headers.map(|h| h.detach());
-                let trace_span = match owned_headers {
-                    Some(ref owned_headers) => {
-                        debug!("Message headers: {:?}", owned_headers);
-                        // Extract context
-                        let parent_cx = global::get_text_map_propagator(|propagator| {
-                            propagator.extract(&KafkaHeaderExtractor(owned_headers))
-                        });
-                        // Create a new span that links to the extracted parent context
-                        let span = tracing::info_span!("process_kafka_message");
-                        let _result = span.set_parent(parent_cx);
-                        span
-                    }
-                    None => {
-                        debug!("No headers found in message");
-                        tracing::info_span!("process_kafka_message_no_headers")
-                    }
-                };
-
-                // TODO set up headers hashmap to pass to handler
-                let headers: HashMap<String, String> = HashMap::new();
-
-                let origin_message = match borrow_message.payload_view::<str>() {
-                    Some(Ok(s)) => s,
-                    Some(Err(e)) => {
-                        error!("Error while deserializing message payload: {:?}", e);
-                        continue;
-                    }
-                    None => {
-                        error!("No payload in message");
-                        continue;
-                    }
-                };
-                let message: M = match serde_json::from_str(&origin_message) {
-                    Ok(event) => event,
-                    Err(e) => {
-                        error!("Failed to deserialize message: {}", e);
-                        continue;
-                    }
-                };
-                debug!("Deserialized message: {:?}", message);
-                let handler = handler.clone();
-                // TODO Remove clone of state and producer by using channels or some other method of sharing state with the task without cloning
-                let handler_state = state.clone();
-                let handler_origin_message = origin_message.to_string();
-                let handler_producer = dlq_producer.clone();
-                let dlq_key_clone = dlq_key.clone();
-                tokio::spawn(async move {
-                    let _enter = trace_span.enter();
-
-                    let result = (handler)(message, handler_state, Some(headers)).await;
-                    match result {
-                        Ok(_) => {
-                            debug!("Event handled successfully");
-                        }
-                        Err(e) => {
-                            let error_message = e.to_string();
-                            error!("Failed to handle event: {}", error_message);
-                            debug!(
-                                "Failed to handle event: {} and send message to DLQ topic",
-                                error_message
-                            );
-
-                            let dlq_message = ProducerMessage {
-                                payload: create_dlq_payload(
-                                    handler_origin_message.to_string(),
-                                    error_message,
-                                )
-                                .unwrap(),
-                                key: Some(dlq_key_clone),
-                            };
-                            let result = handler_producer.send(&dlq_message).await;
-                            if result.is_ok() {
-                                debug!("Sent message to DLQ topic successfully");
-                            } else {
-                                error!(
-                                    "Failed to send message to DLQ topic: {}",
-                                    result.err().unwrap().reason
-                                );
-                            }
-                        }
-                    }
-                });
-            }
+        let message = match message {
+            Ok(borrow_message) => ConsumedMessage::from(&borrow_message),
             Err(e) => {
-                error!("Kafka error: {}", e)
+                error!("Kafka error: {}", e);
+                continue;
             }
+        };
+
+        // A retried message waits for its delay with its partition paused, not in a lane
+        // holding a permit, so the consumer keeps polling and stays in the group
+        if let Some(delay) = message.delay() {
+            if delay_partition(&consumer, &message, delay) {
+                continue;
+            }
+        }
+
+        let partition = partitions
+            .entry(message.partition)
+            .or_insert_with(|| Partition::new(max_in_flight));
+        // Waits while the partition has `max_in_flight` messages being handled
+        let permit = partition
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("Partition semaphore closed");
+        partition.tracker.lock().unwrap().start(message.offset);
+
+        let lane = message.lane(max_in_flight);
+        let tracker = partition.tracker.clone();
+        let sender = partition.lanes[lane].get_or_insert_with(|| {
+            spawn_lane(worker.clone(), consumer.clone(), state.clone(), tracker)
+        });
+        if sender.send((message, permit)).is_err() {
+            error!("Kafka consumer lane stopped");
         }
     }
-    Ok(())
 }
 
-#[derive(Serialize)]
-struct DlqMessage {
-    origin_payload: String,
-    error_msg: String,
+/// A consumed message, owned so it can be handed to a lane task.
+struct ConsumedMessage {
+    topic: String,
+    partition: i32,
+    offset: i64,
+    key: Option<String>,
+    payload: Option<Vec<u8>>,
+    headers: HashMap<String, String>,
 }
 
-fn create_dlq_payload(
-    origin_payload: String,
-    error_msg: String,
-) -> Result<DlqMessage, serde_json::Error> {
-    let report = DlqMessage {
-        origin_payload,
-        error_msg,
-    };
+impl From<&BorrowedMessage<'_>> for ConsumedMessage {
+    fn from(message: &BorrowedMessage<'_>) -> Self {
+        let headers = message
+            .headers()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|header| {
+                        let value = String::from_utf8_lossy(header.value?).to_string();
+                        Some((header.key.to_string(), value))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        debug!("Message headers: {:?}", headers);
+
+        Self {
+            topic: message.topic().to_string(),
+            partition: message.partition(),
+            offset: message.offset(),
+            key: message
+                .key()
+                .map(|key| String::from_utf8_lossy(key).to_string()),
+            payload: message.payload().map(|payload| payload.to_vec()),
+            headers,
+        }
+    }
+}
+
+impl ConsumedMessage {
+    /// Messages with the same key go to the same lane, so they are handled in order.
+    /// Messages without key are spread over the lanes.
+    fn lane(&self, lanes: usize) -> usize {
+        let mut hasher = DefaultHasher::new();
+        match &self.key {
+            Some(key) => key.hash(&mut hasher),
+            None => self.offset.hash(&mut hasher),
+        }
+        (hasher.finish() % lanes as u64) as usize
+    }
+
+    fn attempt(&self) -> usize {
+        self.headers
+            .get(RETRY_ATTEMPT_HEADER)
+            .and_then(|attempt| attempt.parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn not_before(&self) -> Option<u64> {
+        self.headers
+            .get(RETRY_NOT_BEFORE_HEADER)
+            .and_then(|not_before| not_before.parse().ok())
+    }
+
+    /// Time left before a retried message can be handled, None when it is due.
+    fn delay(&self) -> Option<Duration> {
+        let not_before = self.not_before()?;
+        let now = epoch_millis();
+        (not_before > now).then(|| Duration::from_millis(not_before - now))
+    }
+}
+
+/// Pauses the partition of `message` and seeks back to it, the message is fetched again once
+/// `delay` passed. False when the seek failed and the message has to be handled now.
+fn delay_partition(
+    consumer: &Arc<StreamConsumer>,
+    message: &ConsumedMessage,
+    delay: Duration,
+) -> bool {
+    let mut partitions = TopicPartitionList::new();
+    partitions.add_partition(&message.topic, message.partition);
+    if let Err(e) = consumer.pause(&partitions) {
+        warn!(
+            "Failed to pause {}/{}: {}",
+            message.topic, message.partition, e
+        );
+        return false;
+    }
+    // The messages fetched after it are dropped and fetched again with it
+    if let Err(e) = consumer.seek(
+        &message.topic,
+        message.partition,
+        Offset::Offset(message.offset),
+        SEEK_TIMEOUT,
+    ) {
+        warn!(
+            "Failed to seek {}/{} back to {}: {}",
+            message.topic, message.partition, message.offset, e
+        );
+        if let Err(e) = consumer.resume(&partitions) {
+            warn!(
+                "Failed to resume {}/{}: {}",
+                message.topic, message.partition, e
+            );
+        }
+        return false;
+    }
+    debug!(
+        "Retry {}/{} at {} delayed by {:?}",
+        message.topic, message.partition, message.offset, delay
+    );
+
+    let consumer = consumer.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        // Fails for a partition revoked in the meantime, the new owner is not paused
+        if let Err(e) = consumer.resume(&partitions) {
+            warn!("Failed to resume delayed partition: {}", e);
+        }
+    });
+    true
+}
+
+type Lane = mpsc::UnboundedSender<(ConsumedMessage, OwnedSemaphorePermit)>;
+
+struct Partition {
+    permits: Arc<Semaphore>,
+    tracker: Arc<Mutex<OffsetTracker>>,
+    lanes: Vec<Option<Lane>>,
+}
 
-    Ok(report)
+impl Partition {
+    fn new(max_in_flight: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_in_flight)),
+            tracker: Arc::new(Mutex::new(OffsetTracker::default())),
+            lanes: (0..max_in_flight).map(|_| None).collect(),
+        }
+    }
+}
+
+/// Handles the messages of a lane one after the other and commits the partition offsets.
+fn spawn_lane<M, S, F, Fut>(
+    worker: Arc<Worker<M, F>>,
+    consumer: Arc<StreamConsumer>,
+    state: S,
+    tracker: Arc<Mutex<OffsetTracker>>,
+) -> Lane
+where
+    M: EventContract + std::fmt::Debug + Send + 'static,
+    S: Clone + Send + 'static,
+    F: Fn(M, S, Option<HashMap<String, String>>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send + 'static,
+{
+    let (sender, mut receiver): (Lane, _) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some((message, permit)) = receiver.recv().await {
+            worker.process(state.clone(), &message).await;
+            let committable = tracker.lock().unwrap().finish(message.offset);
+            if let Some(offset) = committable {
+                commit(&consumer, &message.topic, message.partition, offset);
+            }
+            drop(permit);
+        }
+    });
+    sender
+}
+
+struct Worker<M, F> {
+    handler: F,
+    topic: String,
+    dlq_producer: Producer,
+    dlq_key: String,
+    group: String,
+    retry_producer: Option<Producer>,
+    retry_topics: Vec<String>,
+    retry_delays: Vec<Duration>,
+    _message: PhantomData<fn() -> M>,
 }
 
-struct KafkaHeaderExtractor<'a>(&'a OwnedHeaders);
+impl<M, F> Worker<M, F>
+where
+    M: EventContract + std::fmt::Debug + Send + 'static,
+{
+    async fn process<S, Fut>(&self, state: S, message: &ConsumedMessage)
+    where
+        F: Fn(M, S, Option<HashMap<String, String>>) -> Fut,
+        Fut: Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        let decoded = match message.payload.as_deref().map(std::str::from_utf8) {
+            Some(Ok(payload)) => EventEnvelope::<M>::decode(payload)
+                .map(|envelope| (payload.to_string(), envelope))
+                .map_err(|e| {
+                    (
+                        payload.to_string(),
+                        format!("Failed to deserialize message: {}", e),
+                    )
+                }),
+            Some(Err(e)) => Err((
+                String::from_utf8_lossy(message.payload.as_deref().unwrap()).to_string(),
+                e.to_string(),
+            )),
+            None => Err((String::new(), "No payload in message".to_string())),
+        };
+
+        // Create a span that links to the context extracted from the message headers, or from
+        // the envelope when the headers were lost on the way
+        let mut trace_headers = message.headers.clone();
+        if let Ok((_, envelope)) = &decoded {
+            for (name, value) in &envelope.trace {
+                trace_headers
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+        let parent_cx =
+            global::get_text_map_propagator(|propagator| propagator.extract(&trace_headers));
+        let span = tracing::info_span!("process_kafka_message");
+        let _result = span.set_parent(parent_cx);
 
-impl<'a> Extractor for KafkaHeaderExtractor<'a> {
-    fn get(&self, key: &str) -> Option<&str> {
-        for header in self.0.iter() {
-            if header.key == key {
-                return header.value.and_then(|v| std::str::from_utf8(v).ok());
+        async {
+            let (payload, envelope) = match decoded {
+                Ok(decoded) => decoded,
+                Err((payload, error_message)) => {
+                    error!("Failed to decode message: {}", error_message);
+                    self.send_to_dlq(message, &payload, error_message).await;
+                    return;
+                }
+            };
+            debug!(
+                "Decoded {} v{} message: {:?}",
+                envelope.event_type, envelope.version, envelope.data
+            );
+
+            let mut headers = message.headers.clone();
+            if !envelope.id.is_nil() {
+                headers.insert(EVENT_ID_HEADER.to_string(), envelope.id.to_string());
+            }
+            headers.insert(
+                EVENT_VERSION_HEADER.to_string(),
+                envelope.version.to_string(),
+            );
+            if let Some(tenant) = envelope.tenant {
+                headers.insert(EVENT_TENANT_HEADER.to_string(), tenant);
+            }
+
+            let result = (self.handler)(envelope.data, state, Some(headers)).await;
+            match result {
+                Ok(_) => {
+                    debug!("Event handled successfully");
+                }
+                Err(e) => {
+                    let error_message = e.to_string();
+                    error!("Failed to handle event: {}", error_message);
+                    self.retry_or_dlq(message, &payload, error_message).await;
+                }
             }
         }
-        None
+        .instrument(span)
+        .await
     }
-    fn keys(&self) -> Vec<&str> {
-        self.0.iter().map(|h| h.key).collect()
+
+    /// Forwards a failed message to the next retry topic, or to the DLQ after the last one.
+    async fn retry_or_dlq(&self, message: &ConsumedMessage, payload: &str, error_message: String) {
+        let attempt = message.attempt();
+        let (Some(producer), Some(delay)) = (&self.retry_producer, self.retry_delays.get(attempt))
+        else {
+            return self.send_to_dlq(message, payload, error_message).await;
+        };
+        let not_before = epoch_millis() + delay.as_millis() as u64;
+
+        let mut headers = message.headers.clone();
+        headers.insert(RETRY_ATTEMPT_HEADER.to_string(), (attempt + 1).to_string());
+        headers.insert(RETRY_NOT_BEFORE_HEADER.to_string(), not_before.to_string());
+        headers.insert(RETRY_ERROR_HEADER.to_string(), error_message);
+        self.forward(
+            producer,
+            &self.retry_topics[attempt],
+            message.key.as_deref(),
+            payload,
+            &headers,
+        )
+        .await;
+    }
+
+    /// The DLQ message keeps what is needed to replay it to the replay topic of the group.
+    async fn send_to_dlq(
+        &self,
+        message: &ConsumedMessage,
+        origin_payload: &str,
+        error_message: String,
+    ) {
+        let mut headers = message.headers.clone();
+        headers.remove(RETRY_ATTEMPT_HEADER);
+        headers.remove(RETRY_NOT_BEFORE_HEADER);
+        headers.remove(RETRY_ERROR_HEADER);
+        let dlq_message = DlqPayload {
+            id: Some(Uuid::new_v4()),
+            service: Some(self.dlq_key.clone()),
+            source_topic: Some(self.topic.clone()),
+            group: Some(self.group.clone()),
+            source_key: message.key.clone(),
+            headers,
+            failed_at: Some(epoch_millis() as i64),
+            origin_payload: origin_payload.to_string(),
+            error_msg: error_message,
+        };
+        let payload = envelope(&dlq_message).encode().unwrap();
+        self.forward(
+            &self.dlq_producer,
+            self.dlq_producer.topic(),
+            Some(&self.dlq_key),
+            &payload,
+            &HashMap::new(),
+        )
+        .await;
+    }
+
+    /// The offset is only committed once the message is forwarded, so sending is retried
+    /// until it succeeds.
+    async fn forward(
+        &self,
+        producer: &Producer,
+        topic: &str,
+        key: Option<&str>,
+        payload: &str,
+        headers: &HashMap<String, String>,
+    ) {
+        let mut backoff = Duration::from_millis(500);
+        loop {
+            match producer.send_raw(topic, key, payload, headers).await {
+                Ok(_) => {
+                    debug!("Sent message to {} topic successfully", topic);
+                    return;
+                }
+                Err(e) => {
+                    error!("Failed to send message to {} topic: {}", topic, e.reason);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_FORWARD_BACKOFF);
+                }
+            }
+        }
     }
 }
+
+fn commit(consumer: &StreamConsumer, topic: &str, partition: i32, offset: i64) {
+    let mut offsets = TopicPartitionList::new();
+    if let Err(e) = offsets.add_partition_offset(topic, partition, Offset::Offset(offset)) {
+        warn!(
+            "Invalid offset {} for {}/{}: {}",
+            offset, topic, partition, e
+        );
+        return;
+    }
+    // Fails for a partition revoked by a rebalance, the new owner handles the messages again
+    if let Err(e) = consumer.commit(&offsets, CommitMode::Async) {
+        warn!(
+            "Failed to commit offset {} for {}/{}: {}",
+            offset, topic, partition, e
+        );
+    }
+}
+
+fn epoch_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
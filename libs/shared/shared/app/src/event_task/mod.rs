@@ -1,2 +1,5 @@
 pub mod consumer;
+pub mod offset;
+pub mod outbox;
+pub mod outbox_message;
 pub mod producer;
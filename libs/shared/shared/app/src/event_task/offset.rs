@@ -0,0 +1,33 @@
+use std::collections::BTreeSet;
+
+/// Offsets of one partition being handled. Messages finish out of order, only the offsets
+/// below the oldest message still in flight are safe to commit.
+#[derive(Debug, Default)]
+pub struct OffsetTracker {
+    in_flight: BTreeSet<i64>,
+    next: Option<i64>,
+    committed: Option<i64>,
+}
+
+impl OffsetTracker {
+    pub fn start(&mut self, offset: i64) {
+        self.in_flight.insert(offset);
+        self.next = Some(self.next.map_or(offset + 1, |next| next.max(offset + 1)));
+    }
+
+    /// Marks the message done and returns the offset to commit, the next one to consume,
+    /// when it moved forward.
+    pub fn finish(&mut self, offset: i64) -> Option<i64> {
+        self.in_flight.remove(&offset);
+        let commit = self.in_flight.first().copied().or(self.next)?;
+        if self.committed.is_some_and(|committed| committed >= commit) {
+            return None;
+        }
+        self.committed = Some(commit);
+        Some(commit)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, SubsecRound, Utc};
+use opentelemetry::global;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend,
+    DbErr, EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    Statement, TransactionTrait,
+};
+use tracing::{debug, error, warn, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
+
+use crate::event_task::outbox_message;
+use shared_shared_data_core::event::EventContract;
+
+use crate::event_task::producer::{envelope, Producer, ProducerMessage};
+
+/// Id of the outbox row, set on every relayed message. A message can be published twice
+/// when the relay stops between the Kafka ack and its commit: consumers must be idempotent,
+/// this id is the key to dedupe on for events without a natural one.
+pub const OUTBOX_ID_HEADER: &str = "x-outbox-id";
+
+/// Only one relay per schema claims messages at a time, the others wait for their next poll.
+const RELAY_LOCK_QUERY: &str =
+    "SELECT pg_try_advisory_xact_lock(hashtext(current_schema() || '.outbox_messages')) AS locked";
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub struct Outbox;
+
+impl Outbox {
+    /// Stores `message` for `topic` on `db`, the open transaction of the caller, so the event
+    /// exists if and only if the data it describes is committed.
+    pub async fn enqueue<C, T>(
+        db: &C,
+        topic: &str,
+        message: &ProducerMessage<T>,
+    ) -> Result<Uuid, DbErr>
+    where
+        C: ConnectionTrait,
+        T: EventContract,
+    {
+        let envelope = envelope(&message.payload);
+        let payload = envelope
+            .encode()
+            .map_err(|e| DbErr::Custom(format!("Serialization error: {}", e)))?;
+
+        // The relay publishes the message under the trace of the current request
+        let headers = envelope.trace.clone();
+
+        let id = Uuid::new_v4();
+        let row = outbox_message::ActiveModel {
+            id: Set(id),
+            topic: Set(topic.to_string()),
+            key: Set(message.key.clone()),
+            payload: Set(payload),
+            headers: Set(serde_json::to_value(headers).unwrap_or_default()),
+            attempts: Set(0),
+            last_error: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+            published_at: Set(None),
+            claimed_until: Set(None),
+            parked_at: Set(None),
+        };
+        outbox_message::Entity::insert(row).exec(db).await?;
+        debug!("Outbox message {} stored for topic {}", id, topic);
+        Ok(id)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OutboxConfig {
+    pub batch_size: u64,
+    pub poll_interval: Duration,
+    pub max_backoff: Duration,
+    pub retention: Duration,
+    /// How long a claimed batch belongs to the relay that claimed it
+    pub lease: Duration,
+    /// Failed attempts after which a message is parked instead of retried
+    pub max_attempts: i32,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            poll_interval: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(60),
+            retention: Duration::from_secs(72 * 3600),
+            lease: Duration::from_secs(60),
+            max_attempts: 10,
+        }
+    }
+}
+
+impl OutboxConfig {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            batch_size: env_or("OUTBOX_BATCH_SIZE", default.batch_size),
+            poll_interval: Duration::from_millis(env_or(
+                "OUTBOX_POLL_INTERVAL_MS",
+                default.poll_interval.as_millis() as u64,
+            )),
+            max_backoff: Duration::from_millis(env_or(
+                "OUTBOX_MAX_BACKOFF_MS",
+                default.max_backoff.as_millis() as u64,
+            )),
+            retention: Duration::from_secs(
+                env_or("OUTBOX_RETENTION_HOURS", default.retention.as_secs() / 3600) * 3600,
+            ),
+            lease: Duration::from_secs(env_or("OUTBOX_LEASE_SECS", default.lease.as_secs())),
+            max_attempts: env_or("OUTBOX_MAX_ATTEMPTS", default.max_attempts as u64)
+                .clamp(1, i32::MAX as u64) as i32,
+        }
+    }
+
+    /// Delay before the next poll after `failures` failed batches in a row.
+    pub fn backoff(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.poll_interval
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct RelayLock {
+    locked: bool,
+}
+
+/// Publishes the stored outbox messages with `producer`, each to the topic it was stored for,
+/// in the order they were stored. Runs until the process stops.
+///
+/// A message that fails to publish stops the batch so later messages don't overtake it,
+/// the relay retries with a backoff up to `max_backoff`. After `max_attempts` failures the
+/// message is parked and the next ones are published.
+pub async fn relay_task(db: DatabaseConnection, producer: Producer, config: OutboxConfig) {
+    let mut failures: u32 = 0;
+    let mut last_cleanup = Instant::now();
+
+    loop {
+        if last_cleanup.elapsed() >= CLEANUP_INTERVAL {
+            last_cleanup = Instant::now();
+            if let Err(e) = cleanup(&db, config.retention).await {
+                warn!("Failed to delete published outbox messages: {}", e);
+            }
+        }
+
+        let delay = match relay_batch(&db, &producer, &config).await {
+            Ok(Batch::Full) => {
+                failures = 0;
+                continue;
+            }
+            Ok(Batch::Drained) => {
+                failures = 0;
+                config.poll_interval
+            }
+            Ok(Batch::Failed) => {
+                failures = failures.saturating_add(1);
+                config.backoff(failures)
+            }
+            Err(e) => {
+                error!("Outbox relay error: {}", e);
+                failures = failures.saturating_add(1);
+                config.backoff(failures)
+            }
+        };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+enum Batch {
+    /// More messages may be waiting
+    Full,
+    Drained,
+    Failed,
+}
+
+/// Claims the next pending messages for `lease`, in a transaction of its own: nothing is held
+/// open while they are published. `None` when another relay holds the lock or a live claim.
+async fn claim_batch(
+    db: &DatabaseConnection,
+    batch_size: u64,
+    claimed_until: NaiveDateTime,
+) -> Result<Option<Vec<outbox_message::Model>>, DbErr> {
+    let txn = db.begin().await?;
+    let lock = RelayLock::find_by_statement(Statement::from_string(
+        DbBackend::Postgres,
+        RELAY_LOCK_QUERY,
+    ))
+    .one(&txn)
+    .await?;
+    if !lock.is_some_and(|lock| lock.locked) {
+        txn.rollback().await?;
+        return Ok(None);
+    }
+
+    let now = Utc::now().naive_utc();
+    let claimed = outbox_message::Entity::find()
+        .filter(outbox_message::Column::PublishedAt.is_null())
+        .filter(outbox_message::Column::ParkedAt.is_null())
+        .filter(outbox_message::Column::ClaimedUntil.gt(now))
+        .count(&txn)
+        .await?;
+    if claimed > 0 {
+        txn.rollback().await?;
+        return Ok(None);
+    }
+
+    let rows = outbox_message::Entity::find()
+        .filter(outbox_message::Column::PublishedAt.is_null())
+        .filter(outbox_message::Column::ParkedAt.is_null())
+        .order_by_asc(outbox_message::Column::CreatedAt)
+        .order_by_asc(outbox_message::Column::Id)
+        .limit(batch_size)
+        .all(&txn)
+        .await?;
+    if !rows.is_empty() {
+        outbox_message::Entity::update_many()
+            .col_expr(
+                outbox_message::Column::ClaimedUntil,
+                Expr::value(Some(claimed_until)),
+            )
+            .filter(outbox_message::Column::Id.is_in(rows.iter().map(|row| row.id)))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+    Ok(Some(rows))
+}
+
+async fn relay_batch(
+    db: &DatabaseConnection,
+    producer: &Producer,
+    config: &OutboxConfig,
+) -> Result<Batch, DbErr> {
+    let deadline = Instant::now() + config.lease;
+    // Microseconds as stored by Postgres, the claim is released by comparing it
+    let claimed_until = (Utc::now().naive_utc()
+        + chrono::Duration::from_std(config.lease).unwrap_or(chrono::Duration::zero()))
+    .trunc_subsecs(6);
+    let Some(rows) = claim_batch(db, config.batch_size, claimed_until).await? else {
+        return Ok(Batch::Drained);
+    };
+    let full = rows.len() as u64 == config.batch_size;
+
+    let mut result = if full { Batch::Full } else { Batch::Drained };
+    let mut rows = rows.into_iter().peekable();
+    // Each outcome is committed on its own, a message published before a failure stays published
+    while let Some(row) = rows.next_if(|_| Instant::now() < deadline) {
+        match publish(producer, &row).await {
+            Ok(()) => {
+                let mut row: outbox_message::ActiveModel = row.into();
+                row.published_at = Set(Some(Utc::now().naive_utc()));
+                row.claimed_until = Set(None);
+                row.update(db).await?;
+            }
+            Err(reason) => {
+                let attempts = row.attempts + 1;
+                let parked = attempts >= config.max_attempts;
+                if parked {
+                    error!(
+                        "Outbox message {} parked after {} attempts: {}",
+                        row.id, attempts, reason
+                    );
+                } else {
+                    warn!(
+                        "Failed to publish outbox message {} (attempt {}): {}",
+                        row.id, attempts, reason
+                    );
+                }
+                let mut row: outbox_message::ActiveModel = row.into();
+                row.attempts = Set(attempts);
+                row.last_error = Set(Some(reason));
+                row.claimed_until = Set(None);
+                if parked {
+                    row.parked_at = Set(Some(Utc::now().naive_utc()));
+                }
+                row.update(db).await?;
+                if !parked {
+                    result = Batch::Failed;
+                    break;
+                }
+            }
+        }
+    }
+
+    // Messages left by a failure or an expired lease go back to the next claim
+    let left: Vec<Uuid> = rows.map(|row| row.id).collect();
+    if !left.is_empty() {
+        outbox_message::Entity::update_many()
+            .col_expr(
+                outbox_message::Column::ClaimedUntil,
+                Expr::value(None::<NaiveDateTime>),
+            )
+            .filter(outbox_message::Column::Id.is_in(left))
+            .filter(outbox_message::Column::ClaimedUntil.eq(claimed_until))
+            .exec(db)
+            .await?;
+    }
+    Ok(result)
+}
+
+async fn publish(producer: &Producer, row: &outbox_message::Model) -> Result<(), String> {
+    let mut headers: HashMap<String, String> =
+        serde_json::from_value(row.headers.clone()).unwrap_or_default();
+
+    // Published under the trace of the request that stored the message
+    let parent_cx = global::get_text_map_propagator(|propagator| propagator.extract(&headers));
+    let span = tracing::info_span!("relay outbox message", topic = row.topic.as_str());
+    let _result = span.set_parent(parent_cx);
+
+    headers.insert(OUTBOX_ID_HEADER.to_string(), row.id.to_string());
+    producer
+        .send_raw(&row.topic, row.key.as_deref(), &row.payload, &headers)
+        .instrument(span)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.reason)
+}
+
+async fn cleanup(db: &DatabaseConnection, retention: Duration) -> Result<(), DbErr> {
+    let before = Utc::now().naive_utc()
+        - chrono::Duration::from_std(retention).unwrap_or(chrono::Duration::zero());
+    let result = outbox_message::Entity::delete_many()
+        .filter(outbox_message::Column::PublishedAt.lt(before))
+        .exec(db)
+        .await?;
+    debug!("Deleted {} published outbox messages", result.rows_affected);
+    Ok(())
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+
+/// An event stored in the same transaction as the data it describes, published to Kafka
+/// afterwards by the outbox relay. Every service using the outbox has this table in its schema.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "outbox_messages")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub topic: String,
+    pub key: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+    /// Trace context of the request that stored the event
+    pub headers: Json,
+    pub attempts: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_error: Option<String>,
+    pub created_at: DateTime,
+    pub published_at: Option<DateTime>,
+    /// Set while a relay publishes the message, another relay may take it over afterwards
+    pub claimed_until: Option<DateTime>,
+    /// Set once the message failed `max_attempts` times, it is no longer published
+    pub parked_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
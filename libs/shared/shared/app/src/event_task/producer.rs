@@ -1,7 +1,11 @@
+use std::collections::HashMap;
+
 use rdkafka::{producer::FutureProducer, util::Timeout, ClientConfig};
 use serde::Serialize;
 use tracing::{debug, instrument};
 
+use shared_shared_data_core::event::{EventContract, EventEnvelope};
+
 #[derive(Clone, Debug)]
 pub struct ProducerConfig {
     pub kafka_server_env: String,
@@ -81,36 +85,62 @@ impl Producer {
         &self.topic
     }
 
+    /// Sends the payload in an `EventEnvelope` with the tenant and trace context of the
+    /// current span.
     #[instrument(name = "send message", skip_all)]
     pub async fn send<T>(
         &self,
         message: &ProducerMessage<T>,
     ) -> Result<ProducerResult, ProducerError>
     where
-        T: Serialize,
+        T: EventContract,
     {
-        let payload_str = serde_json::to_string(&message.payload).map_err(|e| ProducerError {
-            reason: format!("Serialization error: {}", e),
-        })?;
-        let payload_as_bytes = payload_str.as_bytes();
+        let payload_str = envelope(&message.payload)
+            .encode()
+            .map_err(|e| ProducerError {
+                reason: format!("Serialization error: {}", e),
+            })?;
         let current_span = tracing::Span::current();
-        let topic = self.topic.clone();
         current_span.record("message", &payload_str.as_str());
-        current_span.record("topic", topic.as_str());
-        let context = current_span.context();
+        current_span.record("topic", self.topic.as_str());
+
+        self.send_raw(
+            &self.topic,
+            message.key.as_deref(),
+            &payload_str,
+            &HashMap::new(),
+        )
+        .await
+    }
 
-        let mut headers = OwnedHeaders::new();
-        // Inject current span context into Kafka headers
+    /// Sends an already serialized payload to `topic` with extra headers, e.g. a consumed
+    /// message forwarded to a retry topic. The current span context is injected in the headers.
+    pub async fn send_raw(
+        &self,
+        topic: &str,
+        key: Option<&str>,
+        payload: &str,
+        headers: &HashMap<String, String>,
+    ) -> Result<ProducerResult, ProducerError> {
+        let mut headers = headers.clone();
+        let context = tracing::Span::current().context();
         global::get_text_map_propagator(|propagator| {
-            propagator.inject_context(&context, &mut KafkaHeaderPropagator(&mut headers));
+            propagator.inject_context(&context, &mut headers);
         });
+        let mut kafka_headers = OwnedHeaders::new();
+        for (name, value) in &headers {
+            kafka_headers = kafka_headers.insert(Header {
+                key: name,
+                value: Some(value),
+            });
+        }
 
-        let key = message.key.clone().unwrap_or_default();
-        debug!("Kafka message {} headers: {:?}", payload_str, headers);
-        let record = rdkafka::producer::FutureRecord::to(&self.topic)
-            .payload(payload_as_bytes)
-            .headers(headers)
-            .key(&key);
+        let key = key.unwrap_or_default();
+        debug!("Kafka message {} headers: {:?}", payload, kafka_headers);
+        let record = rdkafka::producer::FutureRecord::to(topic)
+            .payload(payload.as_bytes())
+            .headers(kafka_headers)
+            .key(key);
 
         match self.producer.send(record, Timeout::Never).await {
             Ok((partition, offset)) => Ok(ProducerResult { partition, offset }),
@@ -154,18 +184,19 @@ impl Producer {
     }
 }
 
-use opentelemetry::{global, propagation::Injector};
+/// Envelope of `payload` with the trace context of the current span and the tenant of the
+/// service, `TENANT` as registered in Consul.
+pub fn envelope<T: EventContract>(payload: &T) -> EventEnvelope<&T> {
+    let context = tracing::Span::current().context();
+    let mut trace = HashMap::new();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut trace);
+    });
+    EventEnvelope::wrap(payload)
+        .with_tenant(std::env::var("TENANT").ok())
+        .with_trace(trace)
+}
+
+use opentelemetry::global;
 use rdkafka::message::{Header, OwnedHeaders};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
-
-struct KafkaHeaderPropagator<'a>(&'a mut OwnedHeaders);
-
-impl<'a> Injector for KafkaHeaderPropagator<'a> {
-    fn set(&mut self, key: &str, value: String) {
-        let headers = std::mem::take(self.0);
-        *self.0 = headers.insert(Header {
-            key,
-            value: Some(&value),
-        });
-    }
-}
@@ -1,8 +1,10 @@
 pub mod config;
 pub mod discovery;
+pub mod dlq;
 pub mod doc;
 pub mod event_task;
 pub mod health;
 pub mod mapper;
+pub mod role_hierarchy;
 pub mod start_app;
 pub mod state;
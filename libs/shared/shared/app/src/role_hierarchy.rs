@@ -0,0 +1,105 @@
+use std::{sync::Arc, time::Duration};
+
+use shared_shared_auth::hierarchy::RoleHierarchy;
+use shared_shared_macro::RemoteService;
+use tokio::time::interval;
+
+use crate::discovery::get_consul_client;
+
+#[derive(Debug, RemoteService)]
+#[remote(name(auth_service))]
+pub struct RoleHierarchyService {}
+
+impl RoleHierarchyService {
+    /// Fetch the role inheritance tree as role name → parent role names.
+    /// Calls GET /roles?fields=id,name,parent_role_id and resolves parent ids to names.
+    /// None when the auth service can't be reached, the tree known so far is kept then.
+    pub async fn get_role_hierarchy() -> Option<HashMap<String, Vec<String>>> {
+        let permission_endpoint =
+            std::env::var("AUTH_ROLES_ENDPOINT").expect("AUTH_ROLES_ENDPOINT must be set");
+        let permission_baggage_header = std::env::var("AUTH_ROLES_BAGGAGE_HEADER")
+            .expect("AUTH_ROLES_BAGGAGE_HEADER must be set");
+        let mut headers = HashMap::new();
+        headers.insert("baggage".to_string(), permission_baggage_header);
+
+        let page_size = 50;
+        let mut page = 1u64;
+        let mut role_names: HashMap<String, String> = HashMap::new();
+        let mut role_parent_ids: Vec<(String, String)> = vec![];
+
+        loop {
+            let url = format!(
+                "{}?fields=id,name,parent_role_id&page={}&page_size={}",
+                permission_endpoint, page, page_size
+            );
+            let res = Self::call_api(url, reqwest::Method::GET, None, headers.clone()).await;
+            if let Err(err_msg) = res {
+                debug!("Error calling role service: {}", err_msg);
+                return None;
+            }
+            let res = res.unwrap();
+
+            let total_page = res.get("total_page").and_then(|v| v.as_u64()).unwrap_or(0);
+            let roles = match res.get("result").and_then(|r| r.as_array()) {
+                Some(r) => r.clone(),
+                None => break,
+            };
+
+            for role in roles {
+                let id = role.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+                let name = role
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                if id.is_empty() || name.is_empty() {
+                    continue;
+                }
+                role_names.insert(id.to_string(), name.to_string());
+                if let Some(parent_id) = role.get("parent_role_id").and_then(|v| v.as_str()) {
+                    role_parent_ids.push((name.to_string(), parent_id.to_string()));
+                }
+            }
+
+            if page >= total_page {
+                break;
+            }
+            page += 1;
+        }
+
+        let mut role_parents: HashMap<String, Vec<String>> = HashMap::new();
+        for (role_name, parent_id) in role_parent_ids {
+            if let Some(parent_name) = role_names.get(&parent_id) {
+                role_parents
+                    .entry(role_name)
+                    .or_default()
+                    .push(parent_name.clone());
+            }
+        }
+        Some(role_parents)
+    }
+}
+
+/// Replaces the role inheritance tree (`AppState.role_hierarchy`) every 30 seconds, so a
+/// removed parent stops granting its permissions. Run by `start_app` for the services set up
+/// with `AUTH_ROLES_ENDPOINT`.
+pub async fn sync_role_hierarchy(role_hierarchy: Arc<Mutex<RoleHierarchy>>) {
+    let mut interval = interval(Duration::from_secs(30));
+    loop {
+        interval.tick().await;
+        let consul_client = match get_consul_client() {
+            Ok(consul_client) => consul_client,
+            Err(e) => {
+                error!("Failed to create consul client: {}", e);
+                continue;
+            }
+        };
+        RoleHierarchyService::update_remote(&consul_client).await;
+        let Some(role_parents) = RoleHierarchyService::get_role_hierarchy().await else {
+            continue;
+        };
+        match role_hierarchy.lock() {
+            Ok(mut hierarchy) => *hierarchy = RoleHierarchy::new(role_parents),
+            Err(poisoned) => error!("Failed to acquire lock on role hierarchy: {}", poisoned),
+        }
+    }
+}
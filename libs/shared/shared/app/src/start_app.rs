@@ -18,6 +18,7 @@ use crate::config::AppConfig;
 use crate::discovery::{deregister_service, get_consul_client, register_service};
 use crate::health::health_checker_handler;
 use crate::mapper::main_response_mapper;
+use crate::role_hierarchy::sync_role_hierarchy;
 use crate::state::AppState;
 
 pub trait StartApp<T, C = ()>
@@ -118,51 +119,36 @@ where
             let cache = Cache::<String, C>::new(cache_url.as_str(), cache_prefix.as_str())
                 .expect("Failed to connect to redis cache");
 
-            let consul_client = get_consul_client()?;
             let service_name = format!("{}_service", app_key.clone().to_lowercase());
             let service_port = port;
             let current_ip = local_ip_address::local_ip()
                 .map(|ip| ip.to_string())
                 .unwrap_or_else(|_| "127.0.0.1".to_string());
             let instance_id = format!("{}-{}-{}", service_name, current_ip, port);
-            register_service(
-                &consul_client,
-                service_name.as_str(),
-                instance_id.as_str(),
-                current_ip.as_str(),
-                service_port,
-            )
-            .await?;
+            let consul_client = if app_config.has_discovery_service {
+                let consul_client = get_consul_client()?;
+                register_service(
+                    &consul_client,
+                    service_name.as_str(),
+                    instance_id.as_str(),
+                    current_ip.as_str(),
+                    service_port,
+                )
+                .await?;
+                Some(consul_client)
+            } else {
+                info!("Discovery service disabled, {} is not registered", service_name);
+                None
+            };
             let db_write_connection = db_write.get_connection();
             let mut app_state = AppState::new(&db_write_connection, cache, state);
 
-            let axum_layer = OtelAxumLayer::default().filter(|str| {
-                let prefixs = vec!["/public/healthchecker", "/swagger-ui", "/api-docs"];
-                for p in prefixs {
-                    if str.starts_with(p) || str.contains(p) {
-                        return false;
-                    }
-                }
-                true
-            });
-            let metrics = HttpMetricsLayerBuilder::new()
-                // .with_provider(metric_provider)
-                .build();
-
-            let public_paths: &'static [&'static str] = self.public_paths();
-            let routes_all = Router::new()
-                .route("/public/healthchecker", get(health_checker_handler))
-                .merge(self.routes(&app_state))
-                .layer(middleware::from_fn(move |req, next| {
-                    shared_shared_auth::permission::require_baggage_header(req, next, public_paths)
-                }))
-                .layer(OtelInResponseLayer::default()) // OtelInResponseLayer: INJECTS the active trace context into the response headers.
-                .layer(middleware::map_response(main_response_mapper))
-                .layer(metrics) // Add HTTP metrics layer
-                // .layer(middleware::from_fn(mw_ctx_resolver)) // Add custom context resolver middleware
-                .layer(axum_layer); // OtelAxumLayer: Starts the trace and extracts parent context from request headers
+            let routes_all = app_router(self.routes(&app_state), self.public_paths());
 
             self.custom_handler(&mut app_state).await?;
+            if env::var("AUTH_ROLES_ENDPOINT").is_ok() {
+                tokio::spawn(sync_role_hierarchy(app_state.role_hierarchy.clone()));
+            }
             let addr = format!("0.0.0.0:{port}");
             println!("Binding to address: {}", addr);
             let listener = tokio::net::TcpListener::bind(addr.clone())
@@ -194,7 +180,9 @@ where
             // TODO disconnect Cache
             info!("Cache connection closed");
 
-            deregister_service(&consul_client, &service_name, instance_id.as_str()).await?;
+            if let Some(consul_client) = consul_client {
+                deregister_service(&consul_client, &service_name, instance_id.as_str()).await?;
+            }
 
             debug!("Stopped {} app", app_config.app_key);
             Ok(())
@@ -202,6 +190,36 @@ where
     }
 }
 
+/// `routes` with the health check and the layers every service runs behind: actor, baggage
+/// check, response mapping, metrics and tracing. Used by `start_app` and by in-process tests.
+pub fn app_router(routes: Router, public_paths: &'static [&'static str]) -> Router {
+    let axum_layer = OtelAxumLayer::default().filter(|str| {
+        let prefixs = vec!["/public/healthchecker", "/swagger-ui", "/api-docs"];
+        for p in prefixs {
+            if str.starts_with(p) || str.contains(p) {
+                return false;
+            }
+        }
+        true
+    });
+    let metrics = HttpMetricsLayerBuilder::new()
+        // .with_provider(metric_provider)
+        .build();
+
+    Router::new()
+        .route("/public/healthchecker", get(health_checker_handler))
+        .merge(routes)
+        .layer(middleware::from_fn(shared_shared_middleware::actor_middleware)) // Stamps the `audit` columns with the user of the request
+        .layer(middleware::from_fn(move |req, next| {
+            shared_shared_auth::permission::require_baggage_header(req, next, public_paths)
+        }))
+        .layer(OtelInResponseLayer::default()) // OtelInResponseLayer: INJECTS the active trace context into the response headers.
+        .layer(middleware::map_response(main_response_mapper))
+        .layer(metrics) // Add HTTP metrics layer
+        // .layer(middleware::from_fn(mw_ctx_resolver)) // Add custom context resolver middleware
+        .layer(axum_layer) // OtelAxumLayer: Starts the trace and extracts parent context from request headers
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         info!("Press Ctrl+C to shut down");
@@ -7,7 +7,7 @@ use std::{
 };
 use tracing::error;
 
-use shared_shared_auth::permission::StatePermission;
+use shared_shared_auth::{hierarchy::RoleHierarchy, permission::StatePermission};
 use shared_shared_data_cache::cache::Cache;
 
 use crate::event_task::producer::Producer;
@@ -33,6 +33,8 @@ where
     pub state: Option<T>,
     pub producer: Arc<Mutex<HashMap<String, Producer>>>,
     pub permissions_map: Arc<Mutex<HashMap<String, Vec<(String, u32)>>>>,
+    /// Role inheritance: a role resolves the masks of all its ancestors
+    pub role_hierarchy: Arc<Mutex<RoleHierarchy>>,
     /// Field-level permissions: role_name → Vec<FieldPermissionEntry>
     pub field_permissions_map: Arc<Mutex<HashMap<String, Vec<FieldPermissionEntry>>>>,
 }
@@ -49,6 +51,7 @@ where
             state: self.state.clone(),
             producer: self.producer.clone(),
             permissions_map: self.permissions_map.clone(),
+            role_hierarchy: self.role_hierarchy.clone(),
             field_permissions_map: self.field_permissions_map.clone(),
         }
     }
@@ -60,18 +63,17 @@ where
     T: Clone,
 {
     fn get_permission_map(&self, role_name: String, resource_name: String) -> u32 {
-        let permission_map = self.permissions_map.clone();
-        let permission = match permission_map.lock() {
-            Ok(guard) => guard.get(&role_name).cloned(),
-            Err(_err) => None,
+        let permission_map = match self.permissions_map.lock() {
+            Ok(guard) => guard,
+            Err(_err) => return 0,
         };
-        match permission {
-            Some(perms) => perms
-                .into_iter()
-                .filter(|(res, _)| res == &resource_name)
-                .fold(0, |acc, (_, p)| acc | p),
-            None => 0,
-        }
+        let hierarchy = match self.role_hierarchy.lock() {
+            Ok(guard) => guard,
+            Err(_err) => return 0,
+        };
+        hierarchy.effective_mask(&role_name, &resource_name, |role| {
+            permission_map.get(role).cloned().unwrap_or_default()
+        })
     }
 
     fn get_field_permissions(&self, role_name: &str, resource: &str, action: u32) -> Vec<String> {
@@ -115,6 +117,7 @@ where
             state,
             producer: Arc::new(Mutex::new(HashMap::new())),
             permissions_map: Arc::new(Mutex::new(HashMap::new())),
+            role_hierarchy: Arc::new(Mutex::new(RoleHierarchy::default())),
             field_permissions_map: Arc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -134,6 +137,18 @@ where
         permission_map.insert(role_name, permissions);
     }
 
+    /// Replaces the whole role tree, so roles whose parent was removed stop inheriting.
+    pub fn set_role_hierarchy(&mut self, role_parents: HashMap<String, Vec<String>>) {
+        let mut hierarchy = match self.role_hierarchy.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                error!("Failed to acquire lock on role hierarchy: {}", poisoned);
+                return;
+            }
+        };
+        *hierarchy = RoleHierarchy::new(role_parents);
+    }
+
     pub fn set_field_permission_map(
         &mut self,
         role_name: String,
@@ -0,0 +1,44 @@
+use shared_shared_app::dlq::{DlqPayload, DlqStatus};
+use shared_shared_app::event_task::consumer::replay_topic;
+
+#[test]
+fn payload_sent_before_the_dlq_admin_is_still_read() {
+    let payload: DlqPayload =
+        serde_json::from_str(r#"{"origin_payload":"{}","error_msg":"boom"}"#).unwrap();
+    assert_eq!(payload.id, None);
+    assert_eq!(payload.service, None);
+    assert_eq!(payload.source_topic, None);
+    assert_eq!(payload.group, None);
+    assert!(payload.headers.is_empty());
+    assert_eq!(payload.error_msg, "boom");
+}
+
+#[test]
+fn messages_are_replayed_to_the_group_that_gave_up_on_them() {
+    let payload: DlqPayload = serde_json::from_str(
+        r#"{"source_topic":"payment_core_topic","group":"wallet_group","origin_payload":"{}","error_msg":"boom"}"#,
+    )
+    .unwrap();
+    let topic = replay_topic(
+        payload.source_topic.as_deref().unwrap(),
+        payload.group.as_deref().unwrap(),
+    );
+    assert_eq!(topic, "payment_core_topic.wallet_group.replay");
+}
+
+#[test]
+fn only_pending_and_replay_failed_messages_are_open() {
+    for status in [
+        DlqStatus::Pending,
+        DlqStatus::Replayed,
+        DlqStatus::ReplayFailed,
+        DlqStatus::Discarded,
+    ] {
+        assert_eq!(DlqStatus::parse(status.as_str()), Some(status));
+    }
+    assert!(DlqStatus::Pending.is_open());
+    assert!(DlqStatus::ReplayFailed.is_open());
+    assert!(!DlqStatus::Replayed.is_open());
+    assert!(!DlqStatus::Discarded.is_open());
+    assert_eq!(DlqStatus::parse("unknown"), None);
+}
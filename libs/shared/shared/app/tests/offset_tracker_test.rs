@@ -0,0 +1,47 @@
+use shared_shared_app::event_task::offset::OffsetTracker;
+
+#[test]
+fn commits_next_offset_when_all_finished() {
+    let mut tracker = OffsetTracker::default();
+    tracker.start(10);
+    assert_eq!(tracker.finish(10), Some(11));
+    assert_eq!(tracker.in_flight(), 0);
+}
+
+#[test]
+fn does_not_commit_past_a_message_in_flight() {
+    let mut tracker = OffsetTracker::default();
+    tracker.start(10);
+    tracker.start(11);
+    tracker.start(12);
+
+    // 11 and 12 finish first, 10 is still being handled
+    assert_eq!(tracker.finish(12), Some(10));
+    assert_eq!(tracker.finish(11), None);
+    assert_eq!(tracker.finish(10), Some(13));
+}
+
+#[test]
+fn commit_only_moves_forward() {
+    let mut tracker = OffsetTracker::default();
+    tracker.start(5);
+    tracker.start(6);
+    assert_eq!(tracker.finish(5), Some(6));
+
+    tracker.start(7);
+    assert_eq!(tracker.finish(7), None);
+    assert_eq!(tracker.finish(6), Some(8));
+}
+
+#[test]
+fn redelivered_offsets_do_not_move_commit_back() {
+    let mut tracker = OffsetTracker::default();
+    tracker.start(20);
+    assert_eq!(tracker.finish(20), Some(21));
+
+    // Redelivered after a rebalance
+    tracker.start(18);
+    assert_eq!(tracker.finish(18), None);
+    tracker.start(21);
+    assert_eq!(tracker.finish(21), Some(22));
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use shared_shared_app::event_task::outbox::OutboxConfig;
+
+fn config() -> OutboxConfig {
+    OutboxConfig {
+        batch_size: 100,
+        poll_interval: Duration::from_millis(500),
+        max_backoff: Duration::from_secs(10),
+        retention: Duration::from_secs(3600),
+        lease: Duration::from_secs(60),
+        max_attempts: 10,
+    }
+}
+
+#[test]
+fn backoff_doubles_with_every_failure() {
+    let config = config();
+    assert_eq!(config.backoff(1), Duration::from_millis(500));
+    assert_eq!(config.backoff(2), Duration::from_millis(1000));
+    assert_eq!(config.backoff(4), Duration::from_millis(4000));
+}
+
+#[test]
+fn backoff_is_capped() {
+    let config = config();
+    assert_eq!(config.backoff(6), Duration::from_secs(10));
+    assert_eq!(config.backoff(u32::MAX), Duration::from_secs(10));
+}
@@ -0,0 +1,93 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap},
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{event::AuditEvent, publisher::AuditPublisher};
+
+/// Header set by the gateway `request_id` interceptor, which replaces the one of the client.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Who performed a request and how to correlate it, taken from the gateway headers.
+/// Never rejects: public endpoints simply have no actor, and requests that didn't go through
+/// the `request_id` interceptor get a random id.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuditContext {
+    pub actor_id: Option<Uuid>,
+    pub actor_client_id: Option<Uuid>,
+    pub request_id: Option<String>,
+}
+
+impl AuditContext {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let baggage = headers
+            .get("baggage")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let baggage_uuid = |name: &str| {
+            baggage.split(',').find_map(|kv| {
+                let mut pieces = kv.splitn(2, '=');
+                let key = pieces.next()?.trim();
+                let value = pieces.next()?.trim();
+                if key == name {
+                    Uuid::parse_str(value).ok()
+                } else {
+                    None
+                }
+            })
+        };
+        let request_id = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        Self {
+            actor_id: baggage_uuid("user_id"),
+            actor_client_id: baggage_uuid("client_id"),
+            request_id: Some(request_id),
+        }
+    }
+
+    /// Attribute an event to the actor of this request.
+    pub fn event(&self, action: &str, target_type: &str, target_id: impl ToString) -> AuditEvent {
+        AuditEvent {
+            actor_id: self.actor_id,
+            actor_client_id: self.actor_client_id,
+            request_id: self.request_id.clone(),
+            ..AuditEvent::new(action, target_type, target_id)
+        }
+    }
+
+    /// Publish a change performed in this request. Failures are logged and never fail the request.
+    pub async fn record<B, A>(
+        &self,
+        action: &str,
+        target_type: &str,
+        target_id: impl ToString,
+        before: Option<&B>,
+        after: Option<&A>,
+    ) where
+        B: Serialize,
+        A: Serialize,
+    {
+        let event = self
+            .event(action, target_type, target_id)
+            .with_change(before, after);
+        AuditPublisher::publish(event).await;
+    }
+}
+
+impl<S> FromRequestParts<S> for AuditContext
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
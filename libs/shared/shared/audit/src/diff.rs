@@ -0,0 +1,92 @@
+use serde_json::{Map, Value};
+
+pub const REDACTED: &str = "***";
+
+/// Field names whose values never leave the service, matched exactly or as a `_suffix`.
+const SENSITIVE_FIELDS: &[&str] = &[
+    "password",
+    "secret",
+    "token",
+    "hash",
+    "key",
+    "reset_code",
+    "change_code",
+];
+
+pub fn is_sensitive(field: &str) -> bool {
+    let field = field.to_lowercase();
+    SENSITIVE_FIELDS
+        .iter()
+        .any(|s| field == *s || field.ends_with(&format!("_{}", s)))
+}
+
+/// Replace the values of sensitive fields, recursively.
+pub fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| {
+                    if is_sensitive(&k) && !v.is_null() {
+                        (k, Value::String(REDACTED.to_string()))
+                    } else {
+                        (k, redact(v))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+/// Top-level fields that differ between two snapshots, as `{field: {before, after}}`.
+/// A changed sensitive field is still reported, with both values redacted.
+/// Non-object snapshots are compared as a whole under the `value` key.
+pub fn diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+    let empty = Map::new();
+    let (before_map, after_map) = match (before, after) {
+        (Some(Value::Object(b)), Some(Value::Object(a))) => (b, a),
+        (Some(Value::Object(b)), None) => (b, &empty),
+        (None, Some(Value::Object(a))) => (&empty, a),
+        (None, None) => return Value::Object(Map::new()),
+        (b, a) => {
+            let mut changes = Map::new();
+            if b != a {
+                changes.insert("value".to_string(), change(b.cloned(), a.cloned(), false));
+            }
+            return Value::Object(changes);
+        }
+    };
+
+    let mut changes = Map::new();
+    for (field, old) in before_map {
+        let new = after_map.get(field);
+        if new != Some(old) {
+            changes.insert(
+                field.clone(),
+                change(Some(old.clone()), new.cloned(), is_sensitive(field)),
+            );
+        }
+    }
+    for (field, new) in after_map {
+        if !before_map.contains_key(field) {
+            changes.insert(
+                field.clone(),
+                change(None, Some(new.clone()), is_sensitive(field)),
+            );
+        }
+    }
+    Value::Object(changes)
+}
+
+fn change(before: Option<Value>, after: Option<Value>, sensitive: bool) -> Value {
+    let mask = |v: Option<Value>| match v {
+        Some(Value::Null) | None => Value::Null,
+        Some(_) if sensitive => Value::String(REDACTED.to_string()),
+        Some(v) => redact(v),
+    };
+    let mut map = Map::new();
+    map.insert("before".to_string(), mask(before));
+    map.insert("after".to_string(), mask(after));
+    Value::Object(map)
+}
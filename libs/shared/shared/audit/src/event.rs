@@ -0,0 +1,69 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use shared_shared_data_core::event::EventContract;
+
+use crate::diff::{diff, redact};
+
+/// Message published on the audit topic for every security-sensitive change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    /// Service that performed the change (e.g. `AUTH`)
+    pub service: String,
+    /// Dotted action name (e.g. `user.assign_roles`)
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    pub actor_client_id: Option<Uuid>,
+    /// Kind of the changed record (e.g. `user`, `client`)
+    pub target_type: String,
+    pub target_id: String,
+    /// Snapshots with sensitive fields redacted
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    /// Changed fields as `{field: {before, after}}`
+    pub diff: Value,
+    /// `X-Request-Id` set by the gateway
+    pub request_id: Option<String>,
+    pub occurred_at: NaiveDateTime,
+}
+
+impl EventContract for AuditEvent {
+    const EVENT_TYPE: &'static str = "audit.event";
+    const VERSION: u32 = 1;
+}
+
+impl AuditEvent {
+    pub fn new(action: &str, target_type: &str, target_id: impl ToString) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            service: String::new(),
+            action: action.to_string(),
+            actor_id: None,
+            actor_client_id: None,
+            target_type: target_type.to_string(),
+            target_id: target_id.to_string(),
+            before: None,
+            after: None,
+            diff: Value::Object(Default::default()),
+            request_id: None,
+            occurred_at: Utc::now().naive_utc(),
+        }
+    }
+
+    /// Set both snapshots and compute the diff before redacting them.
+    pub fn with_change<B, A>(mut self, before: Option<&B>, after: Option<&A>) -> Self
+    where
+        B: Serialize,
+        A: Serialize,
+    {
+        let before = before.and_then(|b| serde_json::to_value(b).ok());
+        let after = after.and_then(|a| serde_json::to_value(a).ok());
+        self.diff = diff(before.as_ref(), after.as_ref());
+        self.before = before.map(redact);
+        self.after = after.map(redact);
+        self
+    }
+}
@@ -0,0 +1,8 @@
+pub mod context;
+pub mod diff;
+pub mod event;
+pub mod publisher;
+
+pub use context::AuditContext;
+pub use event::AuditEvent;
+pub use publisher::AuditPublisher;
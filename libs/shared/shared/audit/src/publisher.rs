@@ -0,0 +1,63 @@
+use std::sync::OnceLock;
+use tracing::{debug, error, warn};
+
+use shared_shared_app::event_task::producer::{Producer, ProducerConfig, ProducerMessage};
+
+use crate::event::AuditEvent;
+
+pub const AUDIT_KAFKA_BOOTSTRAP_SERVERS: &str = "AUDIT_KAFKA_BOOTSTRAP_SERVERS";
+pub const AUDIT_KAFKA_TOPIC: &str = "AUDIT_KAFKA_TOPIC";
+
+static PUBLISHER: OnceLock<AuditPublisher> = OnceLock::new();
+
+/// Process-wide producer for the audit topic, set once at startup like `DB_WRITE`.
+pub struct AuditPublisher {
+    service: String,
+    producer: Producer,
+}
+
+impl AuditPublisher {
+    pub fn init(service: &str, producer: Producer) {
+        let publisher = AuditPublisher {
+            service: service.to_string(),
+            producer,
+        };
+        if PUBLISHER.set(publisher).is_err() {
+            warn!("Audit publisher is already initialized");
+        }
+    }
+
+    /// Connect to the topic configured by `AUDIT_KAFKA_BOOTSTRAP_SERVERS` / `AUDIT_KAFKA_TOPIC`.
+    pub async fn init_from_env(service: &str) {
+        let producer = Producer::from_config(ProducerConfig::from_env(
+            AUDIT_KAFKA_BOOTSTRAP_SERVERS.to_string(),
+            AUDIT_KAFKA_TOPIC.to_string(),
+        ))
+        .await;
+        Self::init(service, producer);
+    }
+
+    pub fn is_initialized() -> bool {
+        PUBLISHER.get().is_some()
+    }
+
+    /// Send an event keyed by its target so changes of one record stay ordered.
+    pub async fn publish(mut event: AuditEvent) {
+        let Some(publisher) = PUBLISHER.get() else {
+            debug!("Audit publisher not initialized, dropping {:?}", event);
+            return;
+        };
+        event.service = publisher.service.clone();
+        let message = ProducerMessage {
+            key: Some(format!("{}:{}", event.target_type, event.target_id)),
+            payload: event,
+        };
+        match publisher.producer.send(&message).await {
+            Ok(_) => debug!("Audit event {} sent", message.payload.id),
+            Err(e) => error!(
+                "Failed to send audit event {}: {}",
+                message.payload.action, e.reason
+            ),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use http::{HeaderMap, HeaderValue};
+use serde_json::json;
+use uuid::Uuid;
+
+use shared_shared_audit::{
+    diff::{diff, redact, REDACTED},
+    AuditContext, AuditEvent,
+};
+
+#[test]
+fn test_diff_reports_changed_added_and_removed_fields() {
+    let before = json!({ "name": "old", "is_active": true, "removed": 1 });
+    let after = json!({ "name": "new", "is_active": true, "added": "x" });
+    let changes = diff(Some(&before), Some(&after));
+    assert_eq!(
+        changes,
+        json!({
+            "name": { "before": "old", "after": "new" },
+            "removed": { "before": 1, "after": null },
+            "added": { "before": null, "after": "x" },
+        })
+    );
+}
+
+#[test]
+fn test_diff_of_create_and_delete() {
+    let record = json!({ "name": "client" });
+    assert_eq!(
+        diff(None, Some(&record)),
+        json!({ "name": { "before": null, "after": "client" } })
+    );
+    assert_eq!(
+        diff(Some(&record), None),
+        json!({ "name": { "before": "client", "after": null } })
+    );
+    assert_eq!(diff(None, None), json!({}));
+}
+
+#[test]
+fn test_sensitive_fields_are_redacted_but_still_reported() {
+    let before = json!({ "client_secret": "s1", "password": "p1", "email": "a@b.c" });
+    let after = json!({ "client_secret": "s2", "password": "p1", "email": "a@b.c" });
+    let changes = diff(Some(&before), Some(&after));
+    assert_eq!(
+        changes,
+        json!({ "client_secret": { "before": REDACTED, "after": REDACTED } })
+    );
+
+    let redacted = redact(json!({ "api_key": "k", "nested": { "token": "t" }, "api_key_id": 3 }));
+    assert_eq!(
+        redacted,
+        json!({ "api_key": REDACTED, "nested": { "token": REDACTED }, "api_key_id": 3 })
+    );
+}
+
+#[test]
+fn test_context_reads_actor_and_request_id_from_gateway_headers() {
+    let user_id = Uuid::new_v4();
+    let client_id = Uuid::new_v4();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "baggage",
+        HeaderValue::from_str(&format!(
+            "accesses=ADMIN*,user_id={},client_id={}",
+            user_id, client_id
+        ))
+        .unwrap(),
+    );
+    headers.insert("x-request-id", HeaderValue::from_static("req-123"));
+
+    let context = AuditContext::from_headers(&headers);
+    assert_eq!(context.actor_id, Some(user_id));
+    assert_eq!(context.actor_client_id, Some(client_id));
+    assert_eq!(context.request_id.as_deref(), Some("req-123"));
+
+    let context = AuditContext::from_headers(&HeaderMap::new());
+    assert_eq!(context.actor_id, None);
+    assert_eq!(context.actor_client_id, None);
+    let request_id = context.request_id.unwrap();
+    assert!(Uuid::parse_str(&request_id).is_ok());
+    assert_ne!(
+        AuditContext::from_headers(&HeaderMap::new()).request_id,
+        Some(request_id)
+    );
+}
+
+#[test]
+fn test_event_snapshots_are_redacted() {
+    let context = AuditContext {
+        actor_id: Some(Uuid::new_v4()),
+        actor_client_id: None,
+        request_id: Some("req-1".to_string()),
+    };
+    let event: AuditEvent = context.event("client.update", "client", "c1").with_change(
+        Some(&json!({ "name": "a", "client_secret": "old" })),
+        Some(&json!({ "name": "b", "client_secret": "new" })),
+    );
+    assert_eq!(event.actor_id, context.actor_id);
+    assert_eq!(event.request_id.as_deref(), Some("req-1"));
+    assert_eq!(event.target_id, "c1");
+    assert_eq!(
+        event.before,
+        Some(json!({ "name": "a", "client_secret": REDACTED }))
+    );
+    assert_eq!(event.diff["name"], json!({ "before": "a", "after": "b" }));
+    assert_eq!(
+        event.diff["client_secret"],
+        json!({ "before": REDACTED, "after": REDACTED })
+    );
+}
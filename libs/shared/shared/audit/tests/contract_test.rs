@@ -0,0 +1,11 @@
+use shared_shared_data_core::event::assert_fixtures;
+
+use shared_shared_audit::AuditEvent;
+
+#[test]
+fn audit_event_fixtures_still_decode() {
+    assert_fixtures::<AuditEvent>(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/audit.event"
+    ));
+}
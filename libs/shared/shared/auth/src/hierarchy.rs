@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use utoipa::ToSchema;
+
+use shared_shared_macro::Response;
+
+use crate::permission::{ADMIN, SUPER_ADMIN_ROLE};
+
+/// Segment wildcard for permission resources.
+/// `AUTH:*` matches every `AUTH:` resource, `*:USER` matches `USER` in every service.
+pub const RESOURCE_WILDCARD: &str = "*";
+
+/// Check whether a permission resource (which may contain wildcards) covers a concrete resource.
+/// Resources are compared segment by segment on `:`; a trailing `*` also covers deeper segments.
+pub fn resource_matches(pattern: &str, resource: &str) -> bool {
+    if pattern == RESOURCE_WILDCARD || pattern == resource {
+        return true;
+    }
+    let pattern_parts: Vec<&str> = pattern.split(':').collect();
+    let resource_parts: Vec<&str> = resource.split(':').collect();
+    for (i, part) in pattern_parts.iter().enumerate() {
+        let is_last = i == pattern_parts.len() - 1;
+        match resource_parts.get(i) {
+            Some(_) if *part == RESOURCE_WILDCARD && is_last => return true,
+            Some(_) if *part == RESOURCE_WILDCARD => continue,
+            Some(value) if value == part => continue,
+            _ => return false,
+        }
+    }
+    pattern_parts.len() == resource_parts.len()
+}
+
+/// Parent relationships between roles: role_name → parent role names.
+/// A role inherits every permission mask of its ancestors.
+#[derive(Clone, Debug, Default)]
+pub struct RoleHierarchy {
+    parents: HashMap<String, Vec<String>>,
+}
+
+impl RoleHierarchy {
+    pub fn new(parents: HashMap<String, Vec<String>>) -> Self {
+        Self { parents }
+    }
+
+    pub fn set_parents(&mut self, role_name: String, parents: Vec<String>) {
+        if parents.is_empty() {
+            self.parents.remove(&role_name);
+        } else {
+            self.parents.insert(role_name, parents);
+        }
+    }
+
+    pub fn parents_of(&self, role_name: &str) -> &[String] {
+        self.parents
+            .get(role_name)
+            .map(|p| p.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the role itself followed by all of its ancestors (breadth-first).
+    /// Cycles are ignored, every role appears at most once.
+    pub fn lineage(&self, role_name: &str) -> Vec<String> {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<String> = VecDeque::new();
+        let mut result = vec![];
+        queue.push_back(role_name.to_string());
+        while let Some(role) = queue.pop_front() {
+            if !visited.insert(role.clone()) {
+                continue;
+            }
+            for parent in self.parents_of(&role) {
+                queue.push_back(parent.clone());
+            }
+            result.push(role);
+        }
+        result
+    }
+
+    /// Resolve the effective mask of a role on a resource.
+    /// `permissions_of` returns the directly assigned (resource, mask) pairs of a role.
+    /// Inheriting from the super-admin role grants every bit.
+    pub fn effective_mask<F>(&self, role_name: &str, resource: &str, permissions_of: F) -> u32
+    where
+        F: Fn(&str) -> Vec<(String, u32)>,
+    {
+        let mut mask = 0u32;
+        for role in self.lineage(role_name) {
+            if role == SUPER_ADMIN_ROLE {
+                return u32::MAX;
+            }
+            mask = permissions_of(&role)
+                .iter()
+                .filter(|(pattern, _)| resource_matches(pattern, resource))
+                .fold(mask, |acc, (_, p)| acc | p);
+        }
+        mask
+    }
+
+    /// Explain which roles (direct or inherited) grant `action` on `resource`.
+    pub fn explain<F>(
+        &self,
+        role_names: &[String],
+        resource: &str,
+        action: u32,
+        permissions_of: F,
+    ) -> PermissionExplanation
+    where
+        F: Fn(&str) -> Vec<(String, u32)>,
+    {
+        let mut grants = vec![];
+        let mut effective_mask = 0u32;
+        for role_name in role_names {
+            for role in self.lineage(role_name) {
+                let inherited_from = if &role == role_name {
+                    None
+                } else {
+                    Some(role.clone())
+                };
+                if role == SUPER_ADMIN_ROLE {
+                    effective_mask = u32::MAX;
+                    grants.push(PermissionGrant {
+                        role_name: role_name.clone(),
+                        inherited_from,
+                        resource: RESOURCE_WILDCARD.to_string(),
+                        mask: u32::MAX,
+                    });
+                    continue;
+                }
+                for (pattern, mask) in permissions_of(&role) {
+                    if !resource_matches(&pattern, resource) {
+                        continue;
+                    }
+                    effective_mask |= mask;
+                    grants.push(PermissionGrant {
+                        role_name: role_name.clone(),
+                        inherited_from: inherited_from.clone(),
+                        resource: pattern,
+                        mask,
+                    });
+                }
+            }
+        }
+        let allowed = (effective_mask & ADMIN) == ADMIN || (effective_mask & action) == action;
+        PermissionExplanation {
+            resource: resource.to_string(),
+            action,
+            allowed,
+            effective_mask,
+            grants,
+        }
+    }
+}
+
+/// A single permission entry that contributed to an effective mask.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PermissionGrant {
+    /// Role assigned to the user
+    pub role_name: String,
+    /// Ancestor role the permission was inherited from, None when assigned directly
+    pub inherited_from: Option<String>,
+    /// Permission resource as stored, possibly containing wildcards
+    pub resource: String,
+    pub mask: u32,
+}
+
+/// Answer to "why can (or can't) a user perform `action` on `resource`".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema, Response)]
+pub struct PermissionExplanation {
+    pub resource: String,
+    pub action: u32,
+    pub allowed: bool,
+    pub effective_mask: u32,
+    pub grants: Vec<PermissionGrant>,
+}
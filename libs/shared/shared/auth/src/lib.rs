@@ -1,6 +1,8 @@
 pub mod claim;
 pub mod data;
+pub mod hierarchy;
 pub mod permission;
+pub mod policy;
 pub mod token;
 
 pub trait ResourcePermission {
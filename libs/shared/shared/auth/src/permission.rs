@@ -13,7 +13,7 @@ pub const UPDATE: u32 = 1 << 2; // 4
 pub const DELETE: u32 = 1 << 3; // 8
 pub const ADMIN: u32 = 1 << 4; // 16 (The "Super User" bit)
 
-const SUPER_ADMIN_ROLE: &str = "ADMIN_ALL";
+pub const SUPER_ADMIN_ROLE: &str = "ADMIN_ALL";
 
 /// Field-level access control data injected into request extensions by `Auth<R>`.
 /// Used by `field_access_middleware` to filter response fields and validate update payloads.
@@ -48,6 +48,7 @@ where
 }
 
 pub trait StatePermission {
+    /// Effective mask of a role on a resource, including inherited and wildcard permissions.
     fn get_permission_map(&self, role_name: String, resource_name: String) -> u32;
     fn get_field_permissions(&self, role_name: &str, resource: &str, action: u32) -> Vec<String>;
     fn has_field_permissions(&self, resource: &str) -> bool;
@@ -199,6 +200,32 @@ where
     }
 }
 
+/// Extractor for self-service endpoints: any signed-in user, acting on their own data only.
+/// No resource permission is checked, handlers must scope every query to `user_id`.
+pub struct SelfAccess {
+    pub user_id: Uuid,
+}
+
+impl<S> FromRequestParts<S> for SelfAccess
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let access_token = parts
+            .headers
+            .get("baggage")
+            .and_then(|v| v.to_str().ok())
+            .and_then(AccessTokenStruct::from_string)
+            .ok_or(AppError::Auth(AuthError::InsufficientPermission))?;
+        parts.extensions.insert(AccessChecked);
+        Ok(SelfAccess {
+            user_id: access_token.user_id,
+        })
+    }
+}
+
 /// Middleware that requires the `baggage` header on all routes except those listed in skip paths.
 /// Public routes must be explicitly listed. Any unlisted route without `baggage` is rejected.
 ///
@@ -0,0 +1,275 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+use sea_orm::{ColumnTrait, Condition, EntityTrait};
+use serde::Serialize;
+use serde_json::Value as Json;
+use std::{collections::HashMap, str::FromStr};
+use uuid::Uuid;
+
+use shared_shared_data_core::filter::{FilterCondition, FilterEnum, FilterOperator, FilterParam};
+use shared_shared_data_error::{app::AppError, auth::AuthError};
+
+use crate::{claim::AccessTokenStruct, permission::SUPER_ADMIN_ROLE};
+
+/// Constant compared against a resource attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyValue {
+    Str(&'static str),
+    Bool(bool),
+    I32(i32),
+}
+
+/// A single row-level condition. Every condition of a policy must hold.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyCondition {
+    /// Resource column must equal the subject's `user_id`
+    Owner(&'static str),
+    /// Resource column must equal the subject's `tenant_id`
+    Tenant(&'static str),
+    /// Resource column must equal the subject attribute with the given key
+    SubjectAttribute {
+        column: &'static str,
+        attribute: &'static str,
+    },
+    /// Resource column must equal a constant value
+    Equals(&'static str, PolicyValue),
+}
+
+/// Declarative attribute-based access policy for one resource.
+/// Applied by the `Query`/`Mutation` derive macros through `#[policy(...)]`.
+///
+/// # Example
+/// ```ignore
+/// pub const SHORTENED_URL_POLICY: AccessPolicy =
+///     AccessPolicy::new(&[PolicyCondition::Owner("user_id")]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AccessPolicy {
+    pub conditions: &'static [PolicyCondition],
+    /// Roles that are not restricted by the policy
+    pub bypass_roles: &'static [&'static str],
+}
+
+impl AccessPolicy {
+    pub const fn new(conditions: &'static [PolicyCondition]) -> Self {
+        Self {
+            conditions,
+            bypass_roles: &[SUPER_ADMIN_ROLE],
+        }
+    }
+
+    pub const fn with_bypass_roles(self, bypass_roles: &'static [&'static str]) -> Self {
+        Self {
+            conditions: self.conditions,
+            bypass_roles,
+        }
+    }
+
+    pub fn bypasses(&self, subject: &PolicySubject) -> bool {
+        subject
+            .roles
+            .iter()
+            .any(|role| self.bypass_roles.contains(&role.as_str()))
+    }
+
+    /// Translate the policy into extra filter conditions for the subject.
+    /// Returns None when the subject lacks an attribute the policy requires (deny all).
+    pub fn to_filter_condition(&self, subject: &PolicySubject) -> Option<FilterCondition> {
+        if self.bypasses(subject) {
+            return Some(FilterCondition::and(vec![]));
+        }
+        let mut leaves = vec![];
+        for condition in self.conditions {
+            let leaf = match condition {
+                PolicyCondition::Owner(column) => FilterEnum::Uuid(FilterParam {
+                    name: column.to_string(),
+                    operator: FilterOperator::Equal,
+                    value: Some(subject.user_id),
+                    raw_value: subject.user_id.to_string(),
+                }),
+                PolicyCondition::Tenant(column) => {
+                    string_filter(column, subject.tenant_id.clone()?)
+                }
+                PolicyCondition::SubjectAttribute { column, attribute } => {
+                    string_filter(column, subject.attributes.get(*attribute)?.clone())
+                }
+                PolicyCondition::Equals(column, PolicyValue::Str(value)) => {
+                    string_filter(column, value.to_string())
+                }
+                PolicyCondition::Equals(column, PolicyValue::Bool(value)) => {
+                    FilterEnum::Bool(FilterParam {
+                        name: column.to_string(),
+                        operator: FilterOperator::Equal,
+                        value: Some(*value),
+                        raw_value: value.to_string(),
+                    })
+                }
+                PolicyCondition::Equals(column, PolicyValue::I32(value)) => {
+                    FilterEnum::I32(FilterParam {
+                        name: column.to_string(),
+                        operator: FilterOperator::Equal,
+                        value: Some(*value),
+                        raw_value: value.to_string(),
+                    })
+                }
+            };
+            leaves.push(leaf);
+        }
+        Some(FilterCondition::from(leaves))
+    }
+
+    /// Translate the policy into a `WHERE` condition on the columns of `E`.
+    /// Returns None when the subject lacks an attribute the policy requires, or when a
+    /// condition names a column `E` doesn't have (deny all).
+    pub fn to_condition<E: EntityTrait>(&self, subject: &PolicySubject) -> Option<Condition> {
+        let mut condition = Condition::all();
+        if self.bypasses(subject) {
+            return Some(condition);
+        }
+        for policy_condition in self.conditions {
+            let expr = match policy_condition {
+                PolicyCondition::Owner(column) => {
+                    E::Column::from_str(column).ok()?.eq(subject.user_id)
+                }
+                PolicyCondition::Tenant(column) => E::Column::from_str(column)
+                    .ok()?
+                    .eq(subject.tenant_id.clone()?),
+                PolicyCondition::SubjectAttribute { column, attribute } => {
+                    E::Column::from_str(column)
+                        .ok()?
+                        .eq(subject.attributes.get(*attribute)?.clone())
+                }
+                PolicyCondition::Equals(column, PolicyValue::Str(value)) => {
+                    E::Column::from_str(column).ok()?.eq(*value)
+                }
+                PolicyCondition::Equals(column, PolicyValue::Bool(value)) => {
+                    E::Column::from_str(column).ok()?.eq(*value)
+                }
+                PolicyCondition::Equals(column, PolicyValue::I32(value)) => {
+                    E::Column::from_str(column).ok()?.eq(*value)
+                }
+            };
+            condition = condition.add(expr);
+        }
+        Some(condition)
+    }
+
+    /// Check an already loaded record against the policy.
+    /// The record is inspected through its serde representation, keyed by column name.
+    pub fn is_satisfied_by<T: Serialize>(&self, subject: &PolicySubject, record: &T) -> bool {
+        if self.bypasses(subject) {
+            return true;
+        }
+        let record = match serde_json::to_value(record) {
+            Ok(Json::Object(map)) => map,
+            _ => return false,
+        };
+        self.conditions.iter().all(|condition| {
+            let (column, expected) = match condition {
+                PolicyCondition::Owner(column) => {
+                    (column, Some(Json::from(subject.user_id.to_string())))
+                }
+                PolicyCondition::Tenant(column) => {
+                    (column, subject.tenant_id.clone().map(Json::from))
+                }
+                PolicyCondition::SubjectAttribute { column, attribute } => (
+                    column,
+                    subject.attributes.get(*attribute).cloned().map(Json::from),
+                ),
+                PolicyCondition::Equals(column, PolicyValue::Str(value)) => {
+                    (column, Some(Json::from(*value)))
+                }
+                PolicyCondition::Equals(column, PolicyValue::Bool(value)) => {
+                    (column, Some(Json::from(*value)))
+                }
+                PolicyCondition::Equals(column, PolicyValue::I32(value)) => {
+                    (column, Some(Json::from(*value)))
+                }
+            };
+            match (record.get(*column), expected) {
+                (Some(actual), Some(expected)) => *actual == expected,
+                _ => false,
+            }
+        })
+    }
+}
+
+fn string_filter(column: &str, value: String) -> FilterEnum {
+    FilterEnum::String(FilterParam {
+        name: column.to_string(),
+        operator: FilterOperator::Equal,
+        raw_value: value.clone(),
+        value: Some(value),
+    })
+}
+
+/// The caller a policy is evaluated for, built from the `baggage` header.
+#[derive(Debug, Clone, Default)]
+pub struct PolicySubject {
+    pub user_id: Uuid,
+    pub tenant_id: Option<String>,
+    pub roles: Vec<String>,
+    /// Extra attributes for `PolicyCondition::SubjectAttribute`
+    pub attributes: HashMap<String, String>,
+}
+
+impl PolicySubject {
+    pub fn new(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    pub fn with_roles(mut self, roles: Vec<String>) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl<S> FromRequestParts<S> for PolicySubject
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let baggage = parts
+            .headers
+            .get("baggage")
+            .and_then(|v| v.to_str().ok())
+            .ok_or(AppError::Auth(AuthError::InsufficientPermission))?;
+        let access_token = AccessTokenStruct::from_string(baggage)
+            .ok_or(AppError::Auth(AuthError::InsufficientPermission))?;
+        let tenant_id = baggage.split(',').find_map(|pair| {
+            let mut kv = pair.splitn(2, '=');
+            match (kv.next(), kv.next()) {
+                (Some(key), Some(value))
+                    if key.trim() == "tenant_id" && !value.trim().is_empty() =>
+                {
+                    Some(value.trim().to_string())
+                }
+                _ => None,
+            }
+        });
+        Ok(PolicySubject {
+            user_id: access_token.user_id,
+            tenant_id,
+            roles: access_token
+                .accesses
+                .into_iter()
+                .map(|access| access.role_name)
+                .collect(),
+            attributes: HashMap::new(),
+        })
+    }
+}
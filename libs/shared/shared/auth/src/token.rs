@@ -111,6 +111,24 @@ pub fn insecured_decode_access_token(token: &str) -> Result<AccessTokenStruct, T
     result
 }
 
+/// Read the `jti` of an access or refresh token without verifying it.
+pub fn insecured_decode_jti(token: &str) -> Result<Uuid, TokenError> {
+    let claims = insecure_decode::<Claims>(token).map_err(|err| {
+        error!("Error decoding token: {:?}", err);
+        TokenError::InvalidToken
+    })?;
+    Uuid::from_str(claims.claims.jti.as_str()).map_err(|_| TokenError::InvalidToken)
+}
+
+/// Read the `exp` (seconds since epoch) of a token without verifying it.
+pub fn insecured_decode_exp(token: &str) -> Result<u64, TokenError> {
+    let claims = insecure_decode::<Claims>(token).map_err(|err| {
+        error!("Error decoding token: {:?}", err);
+        TokenError::InvalidToken
+    })?;
+    Ok(claims.claims.exp)
+}
+
 pub fn decode_access_token(
     token: &str,
     client_secret: &str,
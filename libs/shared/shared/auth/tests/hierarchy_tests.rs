@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use shared_shared_auth::{
+    hierarchy::{resource_matches, RoleHierarchy},
+    permission::{ADMIN, CREATE, DELETE, READ, UPDATE},
+};
+
+fn permissions() -> HashMap<String, Vec<(String, u32)>> {
+    let mut map = HashMap::new();
+    map.insert("VIEWER".to_string(), vec![("AUTH:USER".to_string(), READ)]);
+    map.insert(
+        "EDITOR".to_string(),
+        vec![("AUTH:USER".to_string(), UPDATE | CREATE)],
+    );
+    map.insert(
+        "AUTH_ADMIN".to_string(),
+        vec![("AUTH:*".to_string(), ADMIN)],
+    );
+    map
+}
+
+fn hierarchy() -> RoleHierarchy {
+    let mut parents = HashMap::new();
+    parents.insert("EDITOR".to_string(), vec!["VIEWER".to_string()]);
+    parents.insert("MANAGER".to_string(), vec!["EDITOR".to_string()]);
+    RoleHierarchy::new(parents)
+}
+
+#[test]
+fn test_resource_matches_exact_and_wildcards() {
+    assert!(resource_matches("AUTH:USER", "AUTH:USER"));
+    assert!(!resource_matches("AUTH:USER", "AUTH:ROLE"));
+    assert!(resource_matches("AUTH:*", "AUTH:ROLE"));
+    assert!(!resource_matches("AUTH:*", "BAKERY:CAKE"));
+    assert!(resource_matches("*:USER", "PROFILE:USER"));
+    assert!(!resource_matches("*:USER", "PROFILE:ROLE"));
+    assert!(resource_matches("*", "LOOKUP:TYPE"));
+    assert!(resource_matches("LOOKUP:*", "LOOKUP:TYPE:ITEM"));
+    assert!(!resource_matches("LOOKUP:TYPE", "LOOKUP:TYPE:ITEM"));
+}
+
+#[test]
+fn test_lineage_includes_ancestors_in_order() {
+    let lineage = hierarchy().lineage("MANAGER");
+    assert_eq!(lineage, vec!["MANAGER", "EDITOR", "VIEWER"]);
+}
+
+#[test]
+fn test_lineage_ignores_cycles() {
+    let mut h = RoleHierarchy::default();
+    h.set_parents("A".to_string(), vec!["B".to_string()]);
+    h.set_parents("B".to_string(), vec!["A".to_string()]);
+    assert_eq!(h.lineage("A"), vec!["A", "B"]);
+}
+
+#[test]
+fn test_effective_mask_inherits_parent_masks() {
+    let perms = permissions();
+    let lookup = |role: &str| perms.get(role).cloned().unwrap_or_default();
+    let mask = hierarchy().effective_mask("MANAGER", "AUTH:USER", lookup);
+    assert_eq!(mask, READ | CREATE | UPDATE);
+    assert_eq!(mask & DELETE, 0);
+}
+
+#[test]
+fn test_effective_mask_uses_wildcard_resources() {
+    let perms = permissions();
+    let lookup = |role: &str| perms.get(role).cloned().unwrap_or_default();
+    let mask = hierarchy().effective_mask("AUTH_ADMIN", "AUTH:CLIENT", lookup);
+    assert_eq!(mask, ADMIN);
+}
+
+#[test]
+fn test_effective_mask_super_admin_ancestor() {
+    let mut h = hierarchy();
+    h.set_parents("ROOT".to_string(), vec!["ADMIN_ALL".to_string()]);
+    let mask = h.effective_mask("ROOT", "ANY:THING", |_| vec![]);
+    assert_eq!(mask, u32::MAX);
+}
+
+#[test]
+fn test_explain_reports_inherited_grants() {
+    let perms = permissions();
+    let lookup = |role: &str| perms.get(role).cloned().unwrap_or_default();
+    let explanation = hierarchy().explain(&["EDITOR".to_string()], "AUTH:USER", READ, lookup);
+    assert!(explanation.allowed);
+    assert_eq!(explanation.grants.len(), 2);
+    let inherited = explanation
+        .grants
+        .iter()
+        .find(|g| g.inherited_from.is_some())
+        .unwrap();
+    assert_eq!(inherited.inherited_from.as_deref(), Some("VIEWER"));
+    assert_eq!(inherited.mask, READ);
+}
+
+#[test]
+fn test_explain_denied_without_matching_grant() {
+    let perms = permissions();
+    let lookup = |role: &str| perms.get(role).cloned().unwrap_or_default();
+    let explanation = hierarchy().explain(&["VIEWER".to_string()], "AUTH:USER", DELETE, lookup);
+    assert!(!explanation.allowed);
+    assert_eq!(explanation.effective_mask, READ);
+}
@@ -0,0 +1,142 @@
+use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+use serde_json::json;
+use uuid::Uuid;
+
+use shared_shared_auth::policy::{AccessPolicy, PolicyCondition, PolicySubject, PolicyValue};
+use shared_shared_data_core::filter::FilterEnum;
+
+const OWNER_POLICY: AccessPolicy = AccessPolicy::new(&[PolicyCondition::Owner("user_id")]);
+
+const TENANT_POLICY: AccessPolicy = AccessPolicy::new(&[
+    PolicyCondition::Tenant("merchant_id"),
+    PolicyCondition::Equals("is_active", PolicyValue::Bool(true)),
+]);
+
+#[test]
+fn test_owner_policy_builds_user_filter() {
+    let user_id = Uuid::new_v4();
+    let subject = PolicySubject::new(user_id);
+    let condition = OWNER_POLICY.to_filter_condition(&subject).unwrap();
+    let leaves = condition.collect_leaves();
+    assert_eq!(leaves.len(), 1);
+    match &leaves[0] {
+        FilterEnum::Uuid(param) => {
+            assert_eq!(param.name, "user_id");
+            assert_eq!(param.value, Some(user_id));
+        }
+        other => panic!("unexpected filter {:?}", other),
+    }
+}
+
+#[test]
+fn test_bypass_role_adds_no_filter() {
+    let subject = PolicySubject::new(Uuid::new_v4()).with_roles(vec!["ADMIN_ALL".to_string()]);
+    let condition = OWNER_POLICY.to_filter_condition(&subject).unwrap();
+    assert!(condition.collect_leaves().is_empty());
+    assert!(OWNER_POLICY.is_satisfied_by(&subject, &json!({ "user_id": Uuid::new_v4() })));
+}
+
+#[test]
+fn test_custom_bypass_roles() {
+    let policy = OWNER_POLICY.with_bypass_roles(&["SUPPORT"]);
+    let support = PolicySubject::new(Uuid::new_v4()).with_roles(vec!["SUPPORT".to_string()]);
+    let admin = PolicySubject::new(Uuid::new_v4()).with_roles(vec!["ADMIN_ALL".to_string()]);
+    assert!(policy.bypasses(&support));
+    assert!(!policy.bypasses(&admin));
+}
+
+#[test]
+fn test_missing_tenant_denies() {
+    let subject = PolicySubject::new(Uuid::new_v4());
+    assert!(TENANT_POLICY.to_filter_condition(&subject).is_none());
+    assert!(!TENANT_POLICY
+        .is_satisfied_by(&subject, &json!({ "merchant_id": "m1", "is_active": true })));
+}
+
+#[test]
+fn test_tenant_policy_builds_all_conditions() {
+    let subject = PolicySubject::new(Uuid::new_v4()).with_tenant("m1");
+    let leaves = TENANT_POLICY
+        .to_filter_condition(&subject)
+        .unwrap()
+        .collect_leaves();
+    assert_eq!(leaves.len(), 2);
+    assert!(
+        matches!(&leaves[0], FilterEnum::String(p) if p.name == "merchant_id" && p.raw_value == "m1")
+    );
+    assert!(
+        matches!(&leaves[1], FilterEnum::Bool(p) if p.name == "is_active" && p.value == Some(true))
+    );
+}
+
+mod merchant_row {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "merchant_row")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub merchant_id: String,
+        pub is_active: bool,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+#[test]
+fn test_policy_as_where_condition() {
+    let subject = PolicySubject::new(Uuid::new_v4()).with_tenant("m1");
+    let condition = TENANT_POLICY
+        .to_condition::<merchant_row::Entity>(&subject)
+        .unwrap();
+    let sql = merchant_row::Entity::find()
+        .filter(condition)
+        .build(DbBackend::Postgres)
+        .to_string();
+    assert!(sql.ends_with(
+        r#"WHERE "merchant_row"."merchant_id" = 'm1' AND "merchant_row"."is_active" = TRUE"#
+    ));
+
+    assert!(TENANT_POLICY
+        .to_condition::<merchant_row::Entity>(&PolicySubject::new(Uuid::new_v4()))
+        .is_none());
+    // `user_id` is not a column of the entity
+    assert!(OWNER_POLICY
+        .to_condition::<merchant_row::Entity>(&subject)
+        .is_none());
+}
+
+#[test]
+fn test_is_satisfied_by_record() {
+    let user_id = Uuid::new_v4();
+    let subject = PolicySubject::new(user_id).with_tenant("m1");
+    assert!(OWNER_POLICY.is_satisfied_by(&subject, &json!({ "user_id": user_id, "name": "a" })));
+    assert!(!OWNER_POLICY.is_satisfied_by(&subject, &json!({ "user_id": Uuid::new_v4() })));
+    assert!(!OWNER_POLICY.is_satisfied_by(&subject, &json!({ "name": "a" })));
+    assert!(
+        TENANT_POLICY.is_satisfied_by(&subject, &json!({ "merchant_id": "m1", "is_active": true }))
+    );
+    assert!(!TENANT_POLICY.is_satisfied_by(
+        &subject,
+        &json!({ "merchant_id": "m1", "is_active": false })
+    ));
+}
+
+#[test]
+fn test_subject_attribute_condition() {
+    const REGION_POLICY: AccessPolicy = AccessPolicy::new(&[PolicyCondition::SubjectAttribute {
+        column: "region",
+        attribute: "region",
+    }]);
+    let subject = PolicySubject::new(Uuid::new_v4()).with_attribute("region", "eu");
+    assert!(REGION_POLICY.to_filter_condition(&subject).is_some());
+    assert!(REGION_POLICY.is_satisfied_by(&subject, &json!({ "region": "eu" })));
+    assert!(!REGION_POLICY.is_satisfied_by(&subject, &json!({ "region": "us" })));
+    assert!(REGION_POLICY
+        .to_filter_condition(&PolicySubject::new(Uuid::new_v4()))
+        .is_none());
+}
@@ -3,7 +3,8 @@ use shared_shared_auth::{
     token::{
         create_access_token, create_refresh_token, decode_access_token, decode_refresh_token,
         get_access_token_cache_key, get_refresh_token_cache_key, insecured_decode_access_token,
-        REFRESH_TOKEN_EXPIRATION, TOKEN_EXPIRATION, TOKEN_TYPE,
+        insecured_decode_exp, insecured_decode_jti, REFRESH_TOKEN_EXPIRATION, TOKEN_EXPIRATION,
+        TOKEN_TYPE,
     },
 };
 use uuid::Uuid;
@@ -197,3 +198,28 @@ fn test_decode_refresh_token_with_access_token_fails() {
     let result = decode_refresh_token(&access_token, SECRET);
     assert!(result.is_err(), "Access token should not decode as refresh token");
 }
+
+#[test]
+fn test_insecured_decode_jti_of_access_and_refresh_tokens() {
+    let (access_token, access_jti) =
+        create_access_token(test_user_id(), test_client_id(), SECRET, test_accesses()).unwrap();
+    let (refresh_token, refresh_jti) =
+        create_refresh_token(test_user_id(), test_client_id(), SECRET, Uuid::new_v4()).unwrap();
+
+    assert_eq!(insecured_decode_jti(&access_token).unwrap(), access_jti);
+    assert_eq!(insecured_decode_jti(&refresh_token).unwrap(), refresh_jti);
+    assert!(insecured_decode_jti("not-a-token").is_err());
+}
+
+#[test]
+fn test_insecured_decode_exp_of_access_token() {
+    let before = chrono::Utc::now().timestamp() as u64;
+    let (access_token, _) =
+        create_access_token(test_user_id(), test_client_id(), SECRET, test_accesses()).unwrap();
+    let after = chrono::Utc::now().timestamp() as u64;
+
+    let exp = insecured_decode_exp(&access_token).unwrap();
+    assert!(exp >= before + TOKEN_EXPIRATION as u64);
+    assert!(exp <= after + TOKEN_EXPIRATION as u64);
+    assert!(insecured_decode_exp("not-a-token").is_err());
+}
@@ -1,6 +1,6 @@
-use std::{sync::Arc, time::Duration};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
 
-use sea_orm::{ConnectOptions, DatabaseConnection};
+use sea_orm::{ConnectOptions, DatabaseConnection, DatabaseTransaction, DbErr, TransactionTrait};
 
 #[derive(Clone, Debug)]
 pub struct Database {
@@ -79,3 +79,101 @@ use std::sync::OnceLock;
 
 pub static DB_READ: OnceLock<Arc<DatabaseConnection>> = OnceLock::new();
 pub static DB_WRITE: OnceLock<Arc<DatabaseConnection>> = OnceLock::new();
+
+tokio::task_local! {
+    static SCOPED_DB: &'static DatabaseConnection;
+}
+
+/// Runs `future` with `db` standing in for both `DB_READ` and `DB_WRITE`, so tests of one
+/// process can each work on their own database. Tasks spawned by `future` do not inherit it.
+pub async fn with_db<F: Future>(db: &'static DatabaseConnection, future: F) -> F::Output {
+    SCOPED_DB.scope(db, future).await
+}
+
+/// Connection for reads: the one of [`with_db`] when in its scope, `DB_READ` otherwise.
+pub fn read_db() -> Option<&'static DatabaseConnection> {
+    SCOPED_DB
+        .try_with(|db| *db)
+        .ok()
+        .or_else(|| DB_READ.get().map(Arc::as_ref))
+}
+
+/// Connection for writes: the one of [`with_db`] when in its scope, `DB_WRITE` otherwise.
+pub fn write_db() -> Option<&'static DatabaseConnection> {
+    SCOPED_DB
+        .try_with(|db| *db)
+        .ok()
+        .or_else(|| DB_WRITE.get().map(Arc::as_ref))
+}
+
+/// Writes of several entities committed together. The `_with_conn` functions generated by the
+/// `Mutation` and `Query` derives take part in it when given `txn()`.
+///
+/// Dropping it without `commit` rolls the writes back, an early `?` return leaves nothing
+/// behind.
+pub struct UnitOfWork {
+    txn: DatabaseTransaction,
+}
+
+impl UnitOfWork {
+    /// Starts a unit of work on [`write_db`].
+    pub async fn begin() -> Result<Self, DbErr> {
+        let db = write_db().expect("DB_WRITE is not initialized");
+        Self::begin_on(db).await
+    }
+
+    pub async fn begin_on(db: &DatabaseConnection) -> Result<Self, DbErr> {
+        Ok(UnitOfWork {
+            txn: db.begin().await?,
+        })
+    }
+
+    pub fn txn(&self) -> &DatabaseTransaction {
+        &self.txn
+    }
+
+    pub async fn commit(self) -> Result<(), DbErr> {
+        self.txn.commit().await
+    }
+
+    pub async fn rollback(self) -> Result<(), DbErr> {
+        self.txn.rollback().await
+    }
+
+    /// Runs `work` in a unit of work on [`write_db`], committed when it succeeds and rolled back
+    /// when it fails.
+    pub async fn run<T, E, F>(work: F) -> Result<T, E>
+    where
+        F: for<'c> FnOnce(
+            &'c DatabaseTransaction,
+        ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>,
+        E: From<DbErr>,
+    {
+        let db = write_db().expect("DB_WRITE is not initialized");
+        Self::run_on(db, work).await
+    }
+
+    pub async fn run_on<T, E, F>(db: &DatabaseConnection, work: F) -> Result<T, E>
+    where
+        F: for<'c> FnOnce(
+            &'c DatabaseTransaction,
+        ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>,
+        E: From<DbErr>,
+    {
+        let unit_of_work = Self::begin_on(db).await?;
+        let result = work(unit_of_work.txn()).await;
+        match result {
+            Ok(value) => {
+                unit_of_work.commit().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                // The error of the work is the one worth returning
+                if let Err(rollback_error) = unit_of_work.rollback().await {
+                    log::warn!("Failed to roll back unit of work: {}", rollback_error);
+                }
+                Err(e)
+            }
+        }
+    }
+}
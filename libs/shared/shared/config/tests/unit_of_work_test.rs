@@ -0,0 +1,73 @@
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr, MockDatabase, MockExecResult,
+};
+
+use shared_shared_config::db::UnitOfWork;
+
+fn mock_db() -> DatabaseConnection {
+    MockDatabase::new(DatabaseBackend::Postgres)
+        .append_exec_results([MockExecResult {
+            last_insert_id: 0,
+            rows_affected: 1,
+        }])
+        .into_connection()
+}
+
+fn log_of(db: DatabaseConnection) -> String {
+    format!("{:?}", db.into_transaction_log())
+}
+
+#[tokio::test]
+async fn successful_work_is_committed() {
+    let db = mock_db();
+    let rows = UnitOfWork::run_on(&db, |txn| {
+        Box::pin(async move {
+            let result = txn
+                .execute_unprepared("UPDATE wallets SET balance = 1")
+                .await?;
+            Ok::<_, DbErr>(result.rows_affected())
+        })
+    })
+    .await
+    .unwrap();
+    assert_eq!(rows, 1);
+
+    let log = log_of(db);
+    assert!(log.contains("UPDATE wallets SET balance = 1"));
+    assert!(log.contains("COMMIT"));
+    assert!(!log.contains("ROLLBACK"));
+}
+
+#[tokio::test]
+async fn failed_work_is_rolled_back_with_its_error() {
+    let db = mock_db();
+    let result: Result<(), DbErr> = UnitOfWork::run_on(&db, |txn| {
+        Box::pin(async move {
+            txn.execute_unprepared("UPDATE wallets SET balance = 1")
+                .await?;
+            Err(DbErr::Custom("insufficient balance".to_string()))
+        })
+    })
+    .await;
+    assert!(matches!(result, Err(DbErr::Custom(message)) if message == "insufficient balance"));
+
+    let log = log_of(db);
+    assert!(log.contains("ROLLBACK"));
+    assert!(!log.contains("COMMIT"));
+}
+
+#[tokio::test]
+async fn dropped_unit_of_work_is_rolled_back() {
+    let db = mock_db();
+    {
+        let unit_of_work = UnitOfWork::begin_on(&db).await.unwrap();
+        unit_of_work
+            .txn()
+            .execute_unprepared("UPDATE wallets SET balance = 1")
+            .await
+            .unwrap();
+    }
+    let log = log_of(db);
+    assert!(log.contains("ROLLBACK"));
+    assert!(!log.contains("COMMIT"));
+}
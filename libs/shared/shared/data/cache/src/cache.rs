@@ -1,8 +1,10 @@
 use redis::{Client, Commands, RedisResult};
 use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::marker::PhantomData;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 // Re-import the Cache struct from the previous immersive
 // In a real project, this would be in your `lib.rs` or a separate module.
 // For this example, we'll include the necessary parts again for self-containment.
@@ -11,6 +13,9 @@ use std::time::Duration;
 ///
 /// This cache leverages Redis for storage, expiration (TTL), and concurrent access.
 /// Keys and values are serialized to JSON before being stored in Redis.
+///
+/// [`Cache::in_memory`] keeps the entries in the process instead, for tests that have no
+/// Redis server.
 
 #[derive(Clone)]
 pub struct Cache<K, V>
@@ -18,8 +23,8 @@ where
     K: Eq + std::hash::Hash + Clone + Serialize + DeserializeOwned + Display,
     V: Clone + Serialize + DeserializeOwned,
 {
-    /// The Redis client used to connect to the Redis server.
-    client: Client,
+    /// Where the entries are stored.
+    backend: Backend,
     /// An optional default time-to-live for cache entries.
     default_ttl: Option<Duration>,
     /// A prefix for all keys stored in Redis to avoid conflicts.
@@ -33,6 +38,17 @@ where
     _phantom_v: PhantomData<V>,
 }
 
+/// Serialized entries of an in-memory cache with the instant they expire at, by full key.
+type MemoryStore = Arc<Mutex<HashMap<String, (String, Option<Instant>)>>>;
+
+#[derive(Clone)]
+enum Backend {
+    /// The Redis client used to connect to the Redis server.
+    Redis(Client),
+    /// Entries kept in the process, see [`Cache::in_memory`].
+    Memory(MemoryStore),
+}
+
 impl<K, V> Cache<K, V>
 where
     K: Eq + std::hash::Hash + Clone + Serialize + DeserializeOwned + Display,
@@ -50,7 +66,7 @@ where
     pub fn new(redis_url: &str, key_prefix: &str) -> RedisResult<Self> {
         let client = Client::open(redis_url)?;
         Ok(Cache {
-            client,
+            backend: Backend::Redis(client),
             default_ttl: None,
             key_prefix: key_prefix.to_string(),
             _phantom_k: PhantomData, // Initialize PhantomData
@@ -76,7 +92,7 @@ where
         let client = Client::open(redis_url)?;
         client.get_connection()?; // Test connection
         Ok(Cache {
-            client,
+            backend: Backend::Redis(client),
             default_ttl: Some(default_ttl),
             key_prefix: key_prefix.to_string(),
             _phantom_k: PhantomData, // Initialize PhantomData
@@ -84,9 +100,31 @@ where
         })
     }
 
-    /// Helper to get a connection from the client.
-    fn get_connection(&self) -> RedisResult<redis::Connection> {
-        self.client.get_connection()
+    /// Creates a new `Cache` instance keeping its entries in the process memory, shared by its
+    /// clones only.
+    ///
+    /// Meant for tests: TTLs are honoured, entries are not shared with other processes.
+    pub fn in_memory(key_prefix: &str) -> Self {
+        Cache {
+            backend: Backend::Memory(Arc::new(Mutex::new(HashMap::new()))),
+            default_ttl: None,
+            key_prefix: key_prefix.to_string(),
+            _phantom_k: PhantomData,
+            _phantom_v: PhantomData,
+        }
+    }
+
+    /// Helper to get a connection to Redis, or the entries of an in-memory cache.
+    fn get_connection(&self) -> RedisResult<Connection<'_>> {
+        match &self.backend {
+            Backend::Redis(client) => Ok(Connection::Redis(client.get_connection()?)),
+            Backend::Memory(store) => {
+                let mut entries = store.lock().unwrap_or_else(PoisonError::into_inner);
+                let now = Instant::now();
+                entries.retain(|_, (_, expires_at)| expires_at.is_none_or(|at| at > now));
+                Ok(Connection::Memory(entries))
+            }
+        }
     }
 
     /// Constructs the full Redis key with the configured prefix.
@@ -119,14 +157,18 @@ where
 
         let actual_ttl = ttl.or(self.default_ttl);
 
-        match actual_ttl {
-            Some(duration) => {
+        match (&mut conn, actual_ttl) {
+            (Connection::Redis(conn), Some(duration)) => {
                 let duration_secs = duration.as_secs();
                 let _: () = conn.set_ex(&full_key, serialized_value, duration_secs)?;
             }
-            None => {
+            (Connection::Redis(conn), None) => {
                 let _: () = conn.set(&full_key, serialized_value)?;
             }
+            (Connection::Memory(entries), _) => {
+                let expires_at = actual_ttl.map(|duration| Instant::now() + duration);
+                entries.insert(full_key, (serialized_value, expires_at));
+            }
         }
         Ok(())
     }
@@ -145,7 +187,10 @@ where
     pub fn get(&self, key: &K) -> RedisResult<Option<V>> {
         let mut conn = self.get_connection()?;
         let full_key = self.get_full_key(key);
-        let result: Option<String> = conn.get(&full_key)?;
+        let result: Option<String> = match &mut conn {
+            Connection::Redis(conn) => conn.get(&full_key)?,
+            Connection::Memory(entries) => entries.get(&full_key).map(|(value, _)| value.clone()),
+        };
 
         match result {
             Some(serialized_value) => {
@@ -173,8 +218,42 @@ where
     pub fn remove(&self, key: &K) -> RedisResult<bool> {
         let mut conn = self.get_connection()?;
         let full_key = self.get_full_key(key);
-        let count: i64 = conn.del(&full_key)?;
-        Ok(count > 0)
+        match &mut conn {
+            Connection::Redis(conn) => {
+                let count: i64 = conn.del(&full_key)?;
+                Ok(count > 0)
+            }
+            Connection::Memory(entries) => Ok(entries.remove(&full_key).is_some()),
+        }
+    }
+
+    /// Removes every entry whose key (without the cache prefix) matches a Redis glob `pattern`.
+    ///
+    /// Keys are listed with `SCAN`, so large caches are not blocked like with `KEYS`.
+    /// Use [`escape_pattern`] for literal parts of the pattern.
+    ///
+    /// # Returns
+    /// A `RedisResult<usize>` with the number of removed entries.
+    pub fn remove_matching(&self, pattern: &str) -> RedisResult<usize> {
+        let mut conn = self.get_connection()?;
+        let full_pattern = format!("{}:{}", self.key_prefix, pattern);
+        match &mut conn {
+            Connection::Redis(conn) => {
+                let keys: Vec<String> = conn.scan_match::<_, String>(&full_pattern)?.collect();
+                if keys.is_empty() {
+                    return Ok(0);
+                }
+                let count: usize = conn.del(keys)?;
+                Ok(count)
+            }
+            Connection::Memory(entries) => {
+                let full_pattern: Vec<char> = full_pattern.chars().collect();
+                let count = entries.len();
+                entries
+                    .retain(|key, _| !glob_match(&full_pattern, &key.chars().collect::<Vec<_>>()));
+                Ok(count - entries.len())
+            }
+        }
     }
 
     /// Clears all entries from the cache that match the configured key prefix.
@@ -187,10 +266,17 @@ where
     /// A `RedisResult<()>` indicating success or failure.
     pub fn clear(&self) -> RedisResult<()> {
         let mut conn = self.get_connection()?;
-        let pattern = format!("{}*", self.key_prefix);
-        let keys: Vec<String> = conn.keys(&pattern)?;
-        if !keys.is_empty() {
-            let _: () = conn.del(keys)?;
+        match &mut conn {
+            Connection::Redis(conn) => {
+                let pattern = format!("{}*", self.key_prefix);
+                let keys: Vec<String> = conn.keys(&pattern)?;
+                if !keys.is_empty() {
+                    let _: () = conn.del(keys)?;
+                }
+            }
+            Connection::Memory(entries) => {
+                entries.retain(|key, _| !key.starts_with(&self.key_prefix))
+            }
         }
         Ok(())
     }
@@ -204,9 +290,17 @@ where
     /// A `RedisResult<usize>` which is `Ok(count)` on success or `Err(redis::Error)` on failure.
     pub fn len(&self) -> RedisResult<usize> {
         let mut conn = self.get_connection()?;
-        let pattern = format!("{}*", self.key_prefix);
-        let keys: Vec<String> = conn.keys(&pattern)?;
-        Ok(keys.len())
+        match &mut conn {
+            Connection::Redis(conn) => {
+                let pattern = format!("{}*", self.key_prefix);
+                let keys: Vec<String> = conn.keys(&pattern)?;
+                Ok(keys.len())
+            }
+            Connection::Memory(entries) => Ok(entries
+                .keys()
+                .filter(|key| key.starts_with(&self.key_prefix))
+                .count()),
+        }
     }
 
     /// Returns `true` if the cache contains no entries matching the prefix.
@@ -218,3 +312,64 @@ where
         Ok(self.len()? == 0)
     }
 }
+
+/// A Redis connection, or the locked entries of an in-memory cache with the expired ones
+/// dropped.
+enum Connection<'a> {
+    Redis(redis::Connection),
+    Memory(std::sync::MutexGuard<'a, HashMap<String, (String, Option<Instant>)>>),
+}
+
+/// Redis glob matching for the in-memory cache: `*`, `?`, `[...]` classes and `\` escapes.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|skip| glob_match(&pattern[1..], &text[skip..])),
+        Some('?') => !text.is_empty() && glob_match(&pattern[1..], &text[1..]),
+        Some('[') => {
+            let Some(end) = pattern
+                .iter()
+                .skip(1)
+                .position(|c| *c == ']')
+                .map(|i| i + 1)
+            else {
+                return text.first() == Some(&'[') && glob_match(&pattern[1..], &text[1..]);
+            };
+            let Some(c) = text.first() else {
+                return false;
+            };
+            let (negated, class) = match &pattern[1..end] {
+                ['^', class @ ..] => (true, class),
+                class => (false, class),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= (class[i]..=class[i + 2]).contains(c);
+                    i += 3;
+                } else {
+                    matched |= class[i] == *c;
+                    i += 1;
+                }
+            }
+            matched != negated && glob_match(&pattern[end + 1..], &text[1..])
+        }
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Escapes the glob characters of `literal` for [`Cache::remove_matching`].
+pub fn escape_pattern(literal: &str) -> String {
+    let mut escaped = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
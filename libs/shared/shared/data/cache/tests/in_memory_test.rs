@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use shared_shared_data_cache::cache::{escape_pattern, Cache};
+
+#[test]
+fn test_insert_get_remove() {
+    let cache = Cache::<String, u32>::in_memory("test");
+    cache.insert("a".to_string(), 1, None).unwrap();
+
+    assert_eq!(cache.get(&"a".to_string()).unwrap(), Some(1));
+    assert_eq!(cache.get(&"b".to_string()).unwrap(), None);
+    assert!(cache.remove(&"a".to_string()).unwrap());
+    assert!(!cache.remove(&"a".to_string()).unwrap());
+    assert!(cache.is_empty().unwrap());
+}
+
+#[test]
+fn test_clones_share_entries() {
+    let cache = Cache::<String, String>::in_memory("test");
+    let clone = cache.clone();
+    cache
+        .insert("a".to_string(), "value".to_string(), None)
+        .unwrap();
+
+    assert_eq!(
+        clone.get(&"a".to_string()).unwrap(),
+        Some("value".to_string())
+    );
+}
+
+#[test]
+fn test_expired_entry_is_gone() {
+    let cache = Cache::<String, u32>::in_memory("test");
+    cache
+        .insert("a".to_string(), 1, Some(Duration::from_millis(10)))
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+
+    assert_eq!(cache.get(&"a".to_string()).unwrap(), None);
+    assert_eq!(cache.len().unwrap(), 0);
+}
+
+#[test]
+fn test_remove_matching() {
+    let cache = Cache::<String, u32>::in_memory("test");
+    cache.insert("user:1:profile".to_string(), 1, None).unwrap();
+    cache.insert("user:2:profile".to_string(), 2, None).unwrap();
+    cache
+        .insert("user:*:settings".to_string(), 3, None)
+        .unwrap();
+    cache.insert("order:1".to_string(), 4, None).unwrap();
+
+    assert_eq!(cache.remove_matching("user:[12]:*").unwrap(), 2);
+    assert_eq!(
+        cache
+            .remove_matching(&escape_pattern("user:*:settings"))
+            .unwrap(),
+        1
+    );
+    assert_eq!(cache.len().unwrap(), 1);
+    assert_eq!(cache.get(&"order:1".to_string()).unwrap(), Some(4));
+}
+
+#[test]
+fn test_clear() {
+    let users = Cache::<String, u32>::in_memory("users");
+    users.insert("a".to_string(), 1, None).unwrap();
+    users.clear().unwrap();
+
+    assert!(users.is_empty().unwrap());
+}
@@ -0,0 +1,24 @@
+use std::future::Future;
+
+use chrono::{NaiveDateTime, Utc};
+use uuid::Uuid;
+
+tokio::task_local! {
+    static ACTOR: Option<Uuid>;
+}
+
+/// Runs `future` on behalf of `actor`, the user the `audit` columns of its writes are stamped
+/// with. Set per request by the actor middleware.
+pub async fn with_actor<F: Future>(actor: Option<Uuid>, future: F) -> F::Output {
+    ACTOR.scope(actor, future).await
+}
+
+/// User of the current request, `None` outside of one (consumers, jobs) or on public endpoints.
+pub fn current_actor() -> Option<Uuid> {
+    ACTOR.try_with(|actor| *actor).ok().flatten()
+}
+
+/// Time stamped in the `audit` and `soft_delete` columns.
+pub fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+/// Most groups an aggregation returns, the groups after it in key order are left out.
+pub const MAX_GROUPS: u64 = 1000;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AggregateFunction {
+    #[default]
+    Count,
+    Sum,
+}
+
+/// Group-by aggregation of the filtered rows, e.g. `group_by=bakery_id&function=sum&field=total`.
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Aggregation {
+    /// Column the rows are grouped by
+    pub group_by: String,
+    /// `count` (default) or `sum`
+    #[serde(default)]
+    #[param(inline, value_type = Option<String>)]
+    pub function: AggregateFunction,
+    /// Column summed by `sum`
+    pub field: Option<String>,
+}
+
+/// Aggregated value of one group, groups are ordered by key.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct AggregateRow {
+    pub key: Value,
+    pub value: Value,
+}
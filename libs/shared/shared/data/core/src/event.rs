@@ -0,0 +1,235 @@
+use std::{collections::HashMap, fmt, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Version of the payloads published before the envelope, the plain serialized event.
+pub const LEGACY_VERSION: u32 = 1;
+
+/// An event published to Kafka. Producers write `VERSION`, consumers read every version up
+/// to it, older data being upcast first.
+///
+/// A change consumers can't read as is (a field renamed, removed or made required) bumps
+/// `VERSION` and upcasts the previous versions in `upcast`.
+pub trait EventContract: Serialize + DeserializeOwned {
+    /// Stable name of the event, e.g. `auth.message`
+    const EVENT_TYPE: &'static str;
+    const VERSION: u32;
+
+    /// Turns the data of an older `version` into the current one. Versions that only added
+    /// optional fields need nothing.
+    fn upcast(version: u32, data: Value) -> Result<Value, EventError> {
+        let _ = version;
+        Ok(data)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventError {
+    Malformed(String),
+    WrongType { expected: String, found: String },
+    UnsupportedVersion { event_type: String, version: u32 },
+    Upcast(String),
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "Malformed event: {}", reason),
+            Self::WrongType { expected, found } => {
+                write!(f, "Expected event {}, found {}", expected, found)
+            }
+            Self::UnsupportedVersion {
+                event_type,
+                version,
+            } => write!(f, "Unsupported version {} of event {}", version, event_type),
+            Self::Upcast(reason) => write!(f, "Failed to upcast event: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for EventError {}
+
+/// Payload of every Kafka message: the event and what consumers need to know about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<T> {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub version: u32,
+    /// Nil on legacy payloads
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    /// Trace context of the producer, for hops that don't keep the Kafka headers
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub trace: HashMap<String, String>,
+    pub data: T,
+}
+
+impl<T: EventContract> EventEnvelope<T> {
+    pub fn new(data: T) -> Self {
+        Self::with_data::<T>(data)
+    }
+
+    /// Reads an envelope of any version up to `T::VERSION`, or a legacy payload as version
+    /// `LEGACY_VERSION` with a nil id.
+    pub fn decode(payload: &str) -> Result<Self, EventError> {
+        let value: Value =
+            serde_json::from_str(payload).map_err(|e| EventError::Malformed(e.to_string()))?;
+        let is_envelope = value.as_object().is_some_and(|object| {
+            ["type", "version", "data"]
+                .iter()
+                .all(|key| object.contains_key(*key))
+        });
+
+        let envelope = if is_envelope {
+            serde_json::from_value::<EventEnvelope<Value>>(value)
+                .map_err(|e| EventError::Malformed(e.to_string()))?
+        } else {
+            EventEnvelope {
+                event_type: T::EVENT_TYPE.to_string(),
+                version: LEGACY_VERSION,
+                id: Uuid::nil(),
+                occurred_at: Utc::now(),
+                tenant: None,
+                trace: HashMap::new(),
+                data: value,
+            }
+        };
+
+        if envelope.event_type != T::EVENT_TYPE {
+            return Err(EventError::WrongType {
+                expected: T::EVENT_TYPE.to_string(),
+                found: envelope.event_type,
+            });
+        }
+        if envelope.version == 0 || envelope.version > T::VERSION {
+            return Err(EventError::UnsupportedVersion {
+                event_type: envelope.event_type,
+                version: envelope.version,
+            });
+        }
+
+        let data = if envelope.version < T::VERSION {
+            T::upcast(envelope.version, envelope.data)?
+        } else {
+            envelope.data
+        };
+        let data =
+            serde_json::from_value(data).map_err(|e| EventError::Malformed(e.to_string()))?;
+        Ok(EventEnvelope {
+            event_type: envelope.event_type,
+            version: envelope.version,
+            id: envelope.id,
+            occurred_at: envelope.occurred_at,
+            tenant: envelope.tenant,
+            trace: envelope.trace,
+            data,
+        })
+    }
+}
+
+impl<'a, T: EventContract> EventEnvelope<&'a T> {
+    /// Envelope of an event the caller keeps, e.g. to log it once sent.
+    pub fn wrap(data: &'a T) -> Self {
+        Self::with_data::<T>(data)
+    }
+}
+
+impl<D> EventEnvelope<D> {
+    fn with_data<T: EventContract>(data: D) -> Self {
+        Self {
+            event_type: T::EVENT_TYPE.to_string(),
+            version: T::VERSION,
+            id: Uuid::new_v4(),
+            occurred_at: Utc::now(),
+            tenant: None,
+            trace: HashMap::new(),
+            data,
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant: Option<String>) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    pub fn with_trace(mut self, trace: HashMap<String, String>) -> Self {
+        self.trace = trace;
+        self
+    }
+}
+
+impl<D: Serialize> EventEnvelope<D> {
+    pub fn encode(&self) -> Result<String, EventError> {
+        serde_json::to_string(self).map_err(|e| EventError::Malformed(e.to_string()))
+    }
+}
+
+/// Compatibility check of a stream crate, run from its tests: every JSON payload recorded in
+/// `dir` must still decode as `T`, and re-encoding it must give the same event. One fixture
+/// at least must be of the current version, so bumping `VERSION` means recording a new one.
+///
+/// Panics with every failing fixture.
+pub fn assert_fixtures<T: EventContract>(dir: impl AsRef<Path>) {
+    let dir = dir.as_ref();
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read fixtures {}: {}", dir.display(), e))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+
+    let mut failures = Vec::new();
+    let mut current_version = false;
+    for path in &paths {
+        let payload = match std::fs::read_to_string(path) {
+            Ok(payload) => payload,
+            Err(e) => {
+                failures.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+        match check_fixture::<T>(&payload) {
+            Ok(version) => current_version |= version == T::VERSION,
+            Err(reason) => failures.push(format!("{}: {}", path.display(), reason)),
+        }
+    }
+
+    if paths.is_empty() {
+        failures.push(format!("no fixture in {}", dir.display()));
+    } else if !current_version {
+        failures.push(format!(
+            "no fixture of version {} of {}",
+            T::VERSION,
+            T::EVENT_TYPE
+        ));
+    }
+    assert!(
+        failures.is_empty(),
+        "Incompatible {} fixtures:\n{}",
+        T::EVENT_TYPE,
+        failures.join("\n")
+    );
+}
+
+fn check_fixture<T: EventContract>(payload: &str) -> Result<u32, String> {
+    let decoded = EventEnvelope::<T>::decode(payload).map_err(|e| e.to_string())?;
+    let encoded = EventEnvelope::wrap(&decoded.data)
+        .encode()
+        .map_err(|e| e.to_string())?;
+    let redecoded = EventEnvelope::<T>::decode(&encoded).map_err(|e| e.to_string())?;
+
+    let before = serde_json::to_value(&decoded.data).map_err(|e| e.to_string())?;
+    let after = serde_json::to_value(&redecoded.data).map_err(|e| e.to_string())?;
+    if before != after {
+        return Err(format!("re-encoded as {}", after));
+    }
+    Ok(decoded.version)
+}
@@ -77,6 +77,8 @@ pub fn apply_query_fields_to_query_result<T: Serialize>(
     QueryResult {
         total_page: result.total_page,
         result: apply_query_fields_vec(result.result, query_params),
+        next_cursor: result.next_cursor,
+        prev_cursor: result.prev_cursor,
     }
 }
 
@@ -16,6 +16,19 @@ pub enum FilterOperator {
     In,
     NotIn,
     StartWith,
+    /// Case-insensitive contains
+    ILike,
+    /// Inclusive range, the raw value is `from,to`
+    Between,
+    IsNull,
+    IsNotNull,
+    /// JSON containment, the raw value is a JSON document
+    JsonContains,
+    /// JSON path, the raw value is `a.b=value` to compare the text at the path or `a.b` to
+    /// check it exists
+    JsonPath,
+    /// Postgres full-text search, on the columns of `#[query_search]`
+    Search,
 }
 impl<'de> Deserialize<'de> for FilterOperator {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -36,6 +49,13 @@ impl<'de> Deserialize<'de> for FilterOperator {
             "in" => Ok(FilterOperator::In),
             "nin" => Ok(FilterOperator::NotIn),
             "sw" => Ok(FilterOperator::StartWith),
+            "ili" => Ok(FilterOperator::ILike),
+            "bt" => Ok(FilterOperator::Between),
+            "nu" => Ok(FilterOperator::IsNull),
+            "nnu" => Ok(FilterOperator::IsNotNull),
+            "jc" => Ok(FilterOperator::JsonContains),
+            "jp" => Ok(FilterOperator::JsonPath),
+            "ts" => Ok(FilterOperator::Search),
             _ => Err(serde::de::Error::custom("Invalid filter operator value")),
         }
     }
@@ -79,6 +99,35 @@ impl FilterEnum {
         }
     }
 
+    pub fn get_operator(self: &Self) -> &FilterOperator {
+        match self {
+            FilterEnum::String(param) => &param.operator,
+            FilterEnum::Json(param) => &param.operator,
+            FilterEnum::Bool(param) => &param.operator,
+            FilterEnum::I8(param) => &param.operator,
+            FilterEnum::I32(param) => &param.operator,
+            FilterEnum::I64(param) => &param.operator,
+            FilterEnum::U32(param) => &param.operator,
+            FilterEnum::U64(param) => &param.operator,
+            FilterEnum::Uuid(param) => &param.operator,
+            FilterEnum::F32(param) => &param.operator,
+            FilterEnum::F64(param) => &param.operator,
+            FilterEnum::DateTime(param) => &param.operator,
+            FilterEnum::VecString(param) => &param.operator,
+            FilterEnum::VecUuid(param) => &param.operator,
+        }
+    }
+
+    /// Full-text search over the `#[query_search]` columns, sent as `_search`.
+    pub fn search(text: &str) -> Self {
+        FilterEnum::String(FilterParam {
+            name: SEARCH_FILTER_NAME.to_string(),
+            value: Some(text.to_string()),
+            raw_value: text.to_string(),
+            operator: FilterOperator::Search,
+        })
+    }
+
     pub fn add_name_prefix(self: &mut Self, prefix: &str) {
         let prefix = format!("{}.{}", prefix, self.get_name());
 
@@ -137,6 +186,9 @@ pub struct FilterParam<T> {
     pub operator: FilterOperator,
 }
 
+/// Name of the leaf searching the `#[query_search]` columns, from the `_search` param.
+pub const SEARCH_FILTER_NAME: &str = "_search";
+
 pub fn convert_filter_param_to_query_string<T>(filter: &FilterParam<T>) -> String {
     let operator_str = match filter.operator {
         FilterOperator::Equal => "eq",
@@ -149,6 +201,13 @@ pub fn convert_filter_param_to_query_string<T>(filter: &FilterParam<T>) -> Strin
         FilterOperator::In => "in",
         FilterOperator::NotIn => "nin",
         FilterOperator::StartWith => "sw",
+        FilterOperator::ILike => "ili",
+        FilterOperator::Between => "bt",
+        FilterOperator::IsNull => "nu",
+        FilterOperator::IsNotNull => "nnu",
+        FilterOperator::JsonContains => "jc",
+        FilterOperator::JsonPath => "jp",
+        FilterOperator::Search => "ts",
     };
     format!("{}={}|{}", filter.name, operator_str, filter.raw_value)
 }
@@ -206,6 +265,9 @@ impl FilterCondition {
         let params: Vec<String> = leaves
             .iter()
             .map(|f| match f {
+                FilterEnum::String(p) if p.name == SEARCH_FILTER_NAME => {
+                    format!("{}={}", p.name, p.raw_value)
+                }
                 FilterEnum::String(p) => convert_filter_param_to_query_string(p),
                 FilterEnum::Bool(p) => convert_filter_param_to_query_string(p),
                 FilterEnum::I32(p) => convert_filter_param_to_query_string(p),
@@ -176,12 +176,7 @@ where
 {
     let value = String::deserialize(deserializer)?;
     let (first_str, last_str) = value.split_once('|').unwrap_or(("", ""));
-
-    // Normalize: if datetime value has no seconds part, append :00
-    let normalized = normalize_datetime_str(last_str);
-
-    let parsed_value = chrono::NaiveDateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M:%S")
-        .unwrap_or(chrono::NaiveDateTime::MIN);
+    let parsed_value = parse_datetime(last_str).unwrap_or(chrono::NaiveDateTime::MIN);
 
     let data = format!(
         r#"{{
@@ -204,6 +199,13 @@ where
     }
 }
 
+/// Parses a datetime filter value like `2024-01-01T10:30:00`, the seconds may be omitted.
+pub fn parse_datetime(s: &str) -> Option<DateTime> {
+    // Normalize: if datetime value has no seconds part, append :00
+    let normalized = normalize_datetime_str(s.trim());
+    chrono::NaiveDateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M:%S").ok()
+}
+
 /// Normalize a datetime string by appending `:00` for seconds if missing.
 /// Handles formats like `2024-01-01T10:30` -> `2024-01-01T10:30:00`
 fn normalize_datetime_str(s: &str) -> String {
@@ -306,11 +308,12 @@ where
             "name": "",
             "operator": "{}",
             "value": {},
-            "raw_value": "{}"
+            "raw_value": {}
         }}"#,
         first_str,
         transform(parsed_value.as_str()),
-        last_str
+        // Escaped, a JSON containment filter has quotes in its raw value
+        Value::String(last_str.to_string())
     );
 
     debug!("Data {}", data);
@@ -1,4 +1,8 @@
+pub mod actor;
+pub mod aggregate;
+pub mod cidr;
 pub mod deserialize;
+pub mod event;
 pub mod field_filter;
 pub mod filter;
 pub mod filter_deserialize;
@@ -1,7 +1,7 @@
 use serde::Deserialize;
 use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, ToSchema)]
+#[derive(Debug, Clone, PartialEq, ToSchema)]
 #[schema(examples(1, -1) )]
 pub enum OrderDirection {
     Asc = 1,
@@ -31,6 +31,18 @@ pub struct Order {
     #[serde(default = "default_order_direction")]
     #[param(inline, value_type = Option<String>)]
     pub order_direction: Option<OrderDirection>,
+    /// Comma separated sort keys, descending when prefixed with `-`, e.g. `name,-created_at`.
+    /// Takes precedence over `order_name` and `order_direction`.
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub sort: Option<String>,
+}
+
+/// Sort key of an `Order`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderKey {
+    pub name: String,
+    pub direction: OrderDirection,
 }
 
 impl Order {
@@ -38,6 +50,36 @@ impl Order {
         Self {
             order_name: None,
             order_direction: None,
+            sort: None,
+        }
+    }
+
+    /// Sort keys in priority order, from `sort` or else from `order_name` and
+    /// `order_direction`. Empty when no sort is requested.
+    pub fn keys(&self) -> Vec<OrderKey> {
+        match self.sort.as_deref().map(str::trim) {
+            Some(sort) if !sort.is_empty() => sort
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(|key| match key.strip_prefix('-') {
+                    Some(name) => OrderKey {
+                        name: name.to_string(),
+                        direction: OrderDirection::Desc,
+                    },
+                    None => OrderKey {
+                        name: key.to_string(),
+                        direction: OrderDirection::Asc,
+                    },
+                })
+                .collect(),
+            _ => match (&self.order_name, &self.order_direction) {
+                (Some(name), Some(direction)) => vec![OrderKey {
+                    name: name.clone(),
+                    direction: direction.clone(),
+                }],
+                _ => vec![],
+            },
         }
     }
 }
@@ -1,22 +1,212 @@
+use std::sync::OnceLock;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use sea_orm::{
+    sea_query::{value::sea_value_to_json_value, Alias, Expr, ExprTrait},
+    ColumnTrait, ColumnType, Condition,
+};
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
 use utoipa::{IntoParams, ToSchema};
 
 use shared_shared_macro::ResponseGeneric;
 
 #[derive(ResponseGeneric, Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct QueryResult<T> {
+    /// 0 when the count was skipped with `with_total=false`
     pub total_page: u64,
     pub result: Vec<T>,
+    /// Cursor of the page after this one, none on the last page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Cursor of the page before this one, none on the first page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
+}
+
+impl<T: serde::de::DeserializeOwned> QueryResult<T> {
+    pub fn from_value(value: serde_json::Value) -> Result<Self, String> {
+        serde_json::from_value(value).map_err(|e| {
+            tracing::error!("Failed to deserialize QueryResult: {}", e);
+            e.to_string()
+        })
+    }
+}
+
+impl<T> QueryResult<T> {
+    /// Maps the rows, keeping the count and the cursors.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> QueryResult<U> {
+        QueryResult {
+            total_page: self.total_page,
+            result: self.result.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            prev_cursor: self.prev_cursor,
+        }
+    }
+}
+
+/// Position in a keyset paginated query: the sort of the query and the sort value and id of
+/// the row the page starts after, or ends before when `backward`.
+///
+/// Sent to clients as an opaque string, `QueryResult::next_cursor` or `prev_cursor`, signed so
+/// that clients can't forge the values it compares rows with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Cursor {
+    pub column: String,
+    pub desc: bool,
+    pub value: Value,
+    pub id: Value,
+    #[serde(default)]
+    pub backward: bool,
+}
+
+impl Cursor {
+    /// Cursor of a row sorted on `column`, from its `value` in that column and its `id`.
+    pub fn new(
+        column: &str,
+        desc: bool,
+        value: &sea_orm::Value,
+        id: &sea_orm::Value,
+        backward: bool,
+    ) -> Self {
+        Self {
+            column: column.to_string(),
+            desc,
+            value: json_value(value),
+            id: json_value(id),
+            backward,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default());
+        let signature = URL_SAFE_NO_PAD.encode(cursor_mac(&payload).finalize().into_bytes());
+        format!("{payload}.{signature}")
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, String> {
+        let invalid = || "Invalid cursor".to_string();
+        let (payload, signature) = cursor.split_once('.').ok_or_else(invalid)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+        cursor_mac(payload)
+            .verify_slice(&signature)
+            .map_err(|_| invalid())?;
+        let bytes = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+        serde_json::from_slice(&bytes).map_err(|_| invalid())
+    }
+}
+
+/// HMAC of a cursor payload, keyed with `CURSOR_SECRET`. Without it the key is drawn per
+/// process, and cursors are only valid on the instance that gave them.
+fn cursor_mac(payload: &str) -> Hmac<Sha256> {
+    static KEY: OnceLock<Vec<u8>> = OnceLock::new();
+    let key = KEY.get_or_init(|| match std::env::var("CURSOR_SECRET") {
+        Ok(secret) if !secret.is_empty() => secret.into_bytes(),
+        _ => {
+            tracing::warn!("CURSOR_SECRET is not set, cursors are signed with a per process key");
+            let mut key = uuid::Uuid::new_v4().as_bytes().to_vec();
+            key.extend_from_slice(uuid::Uuid::new_v4().as_bytes());
+            key
+        }
+    });
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any size");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Rows after `(value, id)` in the order of `column` then `id_column`, both descending when
+/// `desc`, with Postgres' default placement of nulls: last ascending, first descending.
+pub fn keyset_condition<C: ColumnTrait>(
+    column: C,
+    id_column: C,
+    desc: bool,
+    value: &Value,
+    id: &Value,
+) -> Condition {
+    let sort = || Expr::col(column.as_column_ref());
+    let after = |expr: Expr, value: Expr| if desc { expr.lt(value) } else { expr.gt(value) };
+    let id_after = after(
+        Expr::col(id_column.as_column_ref()),
+        cast_value(id_column, id),
+    );
+
+    if value.is_null() {
+        let nulls_after = Condition::all().add(sort().is_null()).add(id_after);
+        return if desc {
+            Condition::any().add(nulls_after).add(sort().is_not_null())
+        } else {
+            nulls_after
+        };
+    }
+    let value = cast_value(column, value);
+    let condition = Condition::any()
+        .add(after(sort(), value.clone()))
+        .add(Condition::all().add(sort().eq(value)).add(id_after));
+    if desc {
+        condition
+    } else {
+        condition.add(sort().is_null())
+    }
+}
+
+/// `value` of a column as JSON. Dates and decimals are kept as text, which `cast_value` reads
+/// back without loss.
+fn json_value(value: &sea_orm::Value) -> Value {
+    use sea_orm::Value as SeaValue;
+    match value {
+        SeaValue::ChronoDate(Some(date)) => Value::String(date.to_string()),
+        SeaValue::ChronoTime(Some(time)) => Value::String(time.to_string()),
+        SeaValue::ChronoDateTime(Some(datetime)) => {
+            Value::String(datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+        }
+        SeaValue::ChronoDateTimeUtc(Some(datetime)) => Value::String(datetime.to_rfc3339()),
+        SeaValue::ChronoDateTimeLocal(Some(datetime)) => Value::String(datetime.to_rfc3339()),
+        SeaValue::ChronoDateTimeWithTimeZone(Some(datetime)) => {
+            Value::String(datetime.to_rfc3339())
+        }
+        SeaValue::Decimal(Some(decimal)) => Value::String(decimal.to_string()),
+        value => sea_value_to_json_value(value),
+    }
+}
+
+/// `value` as read back from JSON, cast to the type of `column`.
+fn cast_value<C: ColumnTrait>(column: C, value: &Value) -> Expr {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    };
+    let sql_type = match column.def().get_column_type() {
+        ColumnType::TinyInteger | ColumnType::SmallInteger | ColumnType::TinyUnsigned => "smallint",
+        ColumnType::Integer | ColumnType::SmallUnsigned => "integer",
+        ColumnType::BigInteger | ColumnType::Unsigned | ColumnType::BigUnsigned => "bigint",
+        ColumnType::Float => "real",
+        ColumnType::Double => "double precision",
+        ColumnType::Decimal(..) | ColumnType::Money(..) => "numeric",
+        ColumnType::DateTime | ColumnType::Timestamp => "timestamp",
+        ColumnType::TimestampWithTimeZone => "timestamptz",
+        ColumnType::Date => "date",
+        ColumnType::Time => "time",
+        ColumnType::Boolean => "boolean",
+        ColumnType::Uuid => "uuid",
+        _ => "text",
+    };
+    Expr::val(text).cast_as(Alias::new(sql_type))
+}
+
+fn deserialize_cursor<'de, D>(deserializer: D) -> Result<Option<Cursor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    match value.as_deref() {
+        None | Some("") => Ok(None),
+        Some(cursor) => Cursor::decode(cursor)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
 }
-
-impl<T: serde::de::DeserializeOwned> QueryResult<T> {
-    pub fn from_value(value: serde_json::Value) -> Result<Self, String> {
-        serde_json::from_value(value).map_err(|e| {
-            tracing::error!("Failed to deserialize QueryResult: {}", e);
-            e.to_string()
-        })
-    }
-}
 
 fn deserialize_page_size<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
 where
@@ -45,6 +235,15 @@ pub struct Pagination {
     )]
     #[param(value_type = Option<u64>)]
     pub page_size: Option<u64>,
+    /// `next_cursor` or `prev_cursor` of a previous page. The page is then read after or
+    /// before it in the sort of that page, ignoring `page` and the order
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<Cursor>,
+    /// `false` skips the count of `total_page`
+    #[serde(default)]
+    #[param(value_type = Option<bool>)]
+    pub with_total: Option<bool>,
 }
 
 fn default_page() -> Option<u64> {
@@ -60,6 +259,8 @@ impl Pagination {
         Self {
             page: default_page(),
             page_size: default_page_size(),
+            cursor: None,
+            with_total: None,
         }
     }
 
@@ -67,6 +268,13 @@ impl Pagination {
         Self {
             page: Some(page),
             page_size: Some(page_size),
+            cursor: None,
+            with_total: None,
         }
     }
+
+    /// Whether `total_page` is counted, unless `with_total=false`.
+    pub fn with_total(&self) -> bool {
+        self.with_total.unwrap_or(true)
+    }
 }
@@ -3,11 +3,26 @@ use sea_orm::DbErr;
 use uuid::Uuid;
 
 use crate::{
+    aggregate::{AggregateRow, Aggregation},
     filter::{FilterCondition, FilterEnum},
     order::Order,
     paging::{Pagination, QueryResult},
 };
 
+/// Start of the message of `invalid_column` errors.
+const INVALID_COLUMN: &str = "Invalid column";
+
+/// Error of a query on a column that clients can't use for `usage`, e.g. sorting on a column
+/// outside the `#[query_sort]` ones. Answered with a 400.
+pub fn invalid_column(usage: &str, name: &str) -> DbErr {
+    DbErr::Custom(format!("{INVALID_COLUMN} for {usage}: {name}"))
+}
+
+/// Whether `message`, of a `DbErr::Custom`, is an `invalid_column` error.
+pub fn is_invalid_column(message: &str) -> bool {
+    message.starts_with(INVALID_COLUMN)
+}
+
 pub trait QueryManager<AM, MD> {
     fn get_by_id_uuid(id: Uuid) -> impl std::future::Future<Output = Result<MD, DbErr>>;
 
@@ -20,6 +35,12 @@ pub trait QueryManager<AM, MD> {
         filter: &FilterCondition,
     ) -> impl std::future::Future<Output = Result<QueryResult<MD>, DbErr>>;
 
+    /// Count or sum of the rows matching `filter`, by group.
+    fn aggregate(
+        aggregation: &Aggregation,
+        filter: &FilterCondition,
+    ) -> impl std::future::Future<Output = Result<Vec<AggregateRow>, DbErr>>;
+
     fn filter_with_related_entities(
         pagination: &Pagination,
         order: &Order,
@@ -33,6 +33,14 @@ impl<T> RoundRobin<T> {
         value
     }
 
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
     /// Replaces all the values in the RoundRobin generator with a new set of values.
     /// The current index is reset to 0.
     pub fn replace_values(&mut self, new_values: Vec<T>) {
@@ -0,0 +1,23 @@
+use uuid::Uuid;
+
+use shared_shared_data_core::actor::{current_actor, with_actor};
+
+#[tokio::test]
+async fn actor_is_set_for_the_scope() {
+    let actor = Uuid::new_v4();
+    assert_eq!(current_actor(), None);
+    let seen = with_actor(Some(actor), async { current_actor() }).await;
+    assert_eq!(seen, Some(actor));
+    assert_eq!(current_actor(), None);
+}
+
+#[tokio::test]
+async fn nested_scope_wins() {
+    let outer = Uuid::new_v4();
+    let seen = with_actor(Some(outer), async {
+        let inner = with_actor(None, async { current_actor() }).await;
+        (inner, current_actor())
+    })
+    .await;
+    assert_eq!(seen, (None, Some(outer)));
+}
@@ -0,0 +1,37 @@
+use serde_json::json;
+use shared_shared_data_core::aggregate::*;
+
+#[test]
+fn aggregation_defaults_to_count() {
+    let json = r#"{"group_by": "status"}"#;
+    let a: Aggregation = serde_json::from_str(json).unwrap();
+    assert_eq!(a.group_by, "status");
+    assert_eq!(a.function, AggregateFunction::Count);
+    assert!(a.field.is_none());
+}
+
+#[test]
+fn aggregation_deserialize_sum() {
+    let json = r#"{"group_by": "bakery_id", "function": "sum", "field": "total"}"#;
+    let a: Aggregation = serde_json::from_str(json).unwrap();
+    assert_eq!(a.function, AggregateFunction::Sum);
+    assert_eq!(a.field.as_deref(), Some("total"));
+}
+
+#[test]
+fn aggregation_invalid_function() {
+    let json = r#"{"group_by": "status", "function": "avg"}"#;
+    assert!(serde_json::from_str::<Aggregation>(json).is_err());
+}
+
+#[test]
+fn aggregate_row_serialize() {
+    let row = AggregateRow {
+        key: json!("ACTIVE"),
+        value: json!(3),
+    };
+    assert_eq!(
+        serde_json::to_value(&row).unwrap(),
+        json!({ "key": "ACTIVE", "value": 3 })
+    );
+}
@@ -0,0 +1,74 @@
+use std::{net::IpAddr, str::FromStr};
+
+use shared_shared_data_core::cidr::Cidr;
+
+fn ip(s: &str) -> IpAddr {
+    IpAddr::from_str(s).unwrap()
+}
+
+#[test]
+fn parse_ipv4_network() {
+    let cidr = Cidr::from_str("10.1.0.0/16").unwrap();
+    assert_eq!(cidr.network(), ip("10.1.0.0"));
+    assert_eq!(cidr.prefix(), 16);
+}
+
+#[test]
+fn parse_clears_host_bits() {
+    let cidr = Cidr::from_str("192.168.1.77/24").unwrap();
+    assert_eq!(cidr.to_string(), "192.168.1.0/24");
+}
+
+#[test]
+fn parse_bare_address_is_single_host() {
+    assert_eq!(Cidr::from_str("203.0.113.5").unwrap().prefix(), 32);
+    assert_eq!(Cidr::from_str("2001:db8::1").unwrap().prefix(), 128);
+}
+
+#[test]
+fn parse_rejects_invalid_input() {
+    assert!(Cidr::from_str("10.0.0.0/33").is_err());
+    assert!(Cidr::from_str("2001:db8::/129").is_err());
+    assert!(Cidr::from_str("10.0.0/8").is_err());
+    assert!(Cidr::from_str("10.0.0.0/abc").is_err());
+    assert!(Cidr::from_str("").is_err());
+}
+
+#[test]
+fn contains_ipv4() {
+    let cidr = Cidr::from_str("10.0.0.0/8").unwrap();
+    assert!(cidr.contains(&ip("10.255.3.4")));
+    assert!(!cidr.contains(&ip("11.0.0.1")));
+}
+
+#[test]
+fn contains_ipv6() {
+    let cidr = Cidr::from_str("2001:db8::/32").unwrap();
+    assert!(cidr.contains(&ip("2001:db8:1::42")));
+    assert!(!cidr.contains(&ip("2001:db9::1")));
+    assert!(!cidr.contains(&ip("10.0.0.1")));
+}
+
+#[test]
+fn contains_ipv4_mapped_ipv6() {
+    let cidr = Cidr::from_str("10.0.0.0/8").unwrap();
+    assert!(cidr.contains(&ip("::ffff:10.1.2.3")));
+    assert!(!cidr.contains(&ip("::ffff:11.1.2.3")));
+}
+
+#[test]
+fn zero_prefix_contains_everything() {
+    let cidr = Cidr::from_str("0.0.0.0/0").unwrap();
+    assert!(cidr.contains(&ip("1.2.3.4")));
+    assert!(cidr.contains(&ip("255.255.255.255")));
+}
+
+#[test]
+fn serde_round_trip() {
+    let cidr = Cidr::from_str("172.16.0.0/12").unwrap();
+    let json = serde_json::to_string(&cidr).unwrap();
+    assert_eq!(json, r#""172.16.0.0/12""#);
+    let back: Cidr = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, cidr);
+    assert!(serde_json::from_str::<Cidr>(r#""not-a-cidr""#).is_err());
+}
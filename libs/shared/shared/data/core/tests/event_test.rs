@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use shared_shared_data_core::event::{EventContract, EventEnvelope, EventError};
+
+/// Version 2 renamed `name` to `full_name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct UserCreated {
+    user_id: Uuid,
+    full_name: String,
+}
+
+impl EventContract for UserCreated {
+    const EVENT_TYPE: &'static str = "test.user_created";
+    const VERSION: u32 = 2;
+
+    fn upcast(version: u32, mut data: Value) -> Result<Value, EventError> {
+        if version < 2 {
+            let object = data
+                .as_object_mut()
+                .ok_or(EventError::Upcast("not an object".to_string()))?;
+            let name = object.remove("name").unwrap_or_default();
+            object.insert("full_name".to_string(), name);
+        }
+        Ok(data)
+    }
+}
+
+fn event() -> UserCreated {
+    UserCreated {
+        user_id: Uuid::nil(),
+        full_name: "Ada".to_string(),
+    }
+}
+
+#[test]
+fn encoded_envelope_decodes() {
+    let envelope = EventEnvelope::wrap(&event()).with_tenant(Some("acme".to_string()));
+    let decoded = EventEnvelope::<UserCreated>::decode(&envelope.encode().unwrap()).unwrap();
+    assert_eq!(decoded.data, event());
+    assert_eq!(decoded.id, envelope.id);
+    assert_eq!(decoded.version, 2);
+    assert_eq!(decoded.tenant.as_deref(), Some("acme"));
+}
+
+#[test]
+fn legacy_payload_is_upcast_from_version_1() {
+    let payload = json!({ "user_id": Uuid::nil(), "name": "Ada" }).to_string();
+    let decoded = EventEnvelope::<UserCreated>::decode(&payload).unwrap();
+    assert_eq!(decoded.data, event());
+    assert_eq!(decoded.version, 1);
+    assert!(decoded.id.is_nil());
+}
+
+#[test]
+fn older_envelope_is_upcast() {
+    let payload = json!({
+        "type": "test.user_created",
+        "version": 1,
+        "id": Uuid::new_v4(),
+        "occurred_at": "2026-10-19T10:00:00Z",
+        "data": { "user_id": Uuid::nil(), "name": "Ada" }
+    })
+    .to_string();
+    assert_eq!(
+        EventEnvelope::<UserCreated>::decode(&payload).unwrap().data,
+        event()
+    );
+}
+
+#[test]
+fn newer_version_is_rejected() {
+    let mut envelope = EventEnvelope::new(event());
+    envelope.version = 3;
+    assert_eq!(
+        EventEnvelope::<UserCreated>::decode(&envelope.encode().unwrap()),
+        Err(EventError::UnsupportedVersion {
+            event_type: "test.user_created".to_string(),
+            version: 3,
+        })
+    );
+}
+
+#[test]
+fn other_event_type_is_rejected() {
+    let mut envelope = EventEnvelope::new(event());
+    envelope.event_type = "test.user_deleted".to_string();
+    assert!(matches!(
+        EventEnvelope::<UserCreated>::decode(&envelope.encode().unwrap()),
+        Err(EventError::WrongType { .. })
+    ));
+}
@@ -75,6 +75,24 @@ struct UuidFilter {
     field: Option<shared_shared_data_core::filter::FilterParam<uuid::Uuid>>,
 }
 
+#[derive(Deserialize)]
+struct DateTimeFilter {
+    #[serde(
+        default = "default_none_datetime",
+        deserialize_with = "deserialize_filter_from_datetime"
+    )]
+    field: Option<shared_shared_data_core::filter::FilterParam<chrono::NaiveDateTime>>,
+}
+
+#[derive(Deserialize)]
+struct JsonFilter {
+    #[serde(
+        default = "default_none_json",
+        deserialize_with = "deserialize_filter_from_json"
+    )]
+    field: Option<shared_shared_data_core::filter::FilterParam<serde_json::Value>>,
+}
+
 #[derive(Deserialize)]
 struct VecStringFilter {
     #[serde(
@@ -179,6 +197,64 @@ fn deserialize_vecstring_filter() {
     assert_eq!(param.value.unwrap(), vec!["in|a", "b", "c"]);
 }
 
+#[test]
+fn deserialize_i32_between_filter_keeps_range() {
+    let json = r#"{"field": "bt|18,30"}"#;
+    let f: I32Filter = serde_json::from_str(json).unwrap();
+    let param = f.field.unwrap();
+    assert_eq!(param.operator, FilterOperator::Between);
+    assert_eq!(param.raw_value, "18,30");
+}
+
+#[test]
+fn deserialize_string_null_filter() {
+    let json = r#"{"field": "nu|"}"#;
+    let f: StringFilter = serde_json::from_str(json).unwrap();
+    assert_eq!(f.field.unwrap().operator, FilterOperator::IsNull);
+}
+
+#[test]
+fn deserialize_datetime_between_filter() {
+    let json = r#"{"field": "bt|2024-01-01T00:00,2024-02-01T00:00"}"#;
+    let f: DateTimeFilter = serde_json::from_str(json).unwrap();
+    let param = f.field.unwrap();
+    assert_eq!(param.operator, FilterOperator::Between);
+    assert_eq!(param.raw_value, "2024-01-01T00:00,2024-02-01T00:00");
+}
+
+#[test]
+fn deserialize_json_contains_filter() {
+    let json = r#"{"field": "jc|{\"field1\":\"Value 1\"}"}"#;
+    let f: JsonFilter = serde_json::from_str(json).unwrap();
+    let param = f.field.unwrap();
+    assert_eq!(param.operator, FilterOperator::JsonContains);
+    assert_eq!(param.raw_value, r#"{"field1":"Value 1"}"#);
+    assert_eq!(
+        param.value.unwrap(),
+        serde_json::json!({ "field1": "Value 1" })
+    );
+}
+
+#[test]
+fn deserialize_json_path_filter() {
+    let json = r#"{"field": "jp|address.city=Hanoi"}"#;
+    let f: JsonFilter = serde_json::from_str(json).unwrap();
+    let param = f.field.unwrap();
+    assert_eq!(param.operator, FilterOperator::JsonPath);
+    assert_eq!(param.raw_value, "address.city=Hanoi");
+}
+
+#[test]
+fn parse_datetime_without_seconds() {
+    let expected = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(10, 30, 0)
+        .unwrap();
+    assert_eq!(parse_datetime("2024-01-01T10:30"), Some(expected));
+    assert_eq!(parse_datetime(" 2024-01-01T10:30:00"), Some(expected));
+    assert_eq!(parse_datetime("yesterday"), None);
+}
+
 #[test]
 fn default_none_functions_return_none() {
     assert!(default_none_string().is_none());
@@ -14,6 +14,13 @@ fn filter_operator_deserialize_all_variants() {
         ("\"in\"", FilterOperator::In),
         ("\"nin\"", FilterOperator::NotIn),
         ("\"sw\"", FilterOperator::StartWith),
+        ("\"ili\"", FilterOperator::ILike),
+        ("\"bt\"", FilterOperator::Between),
+        ("\"nu\"", FilterOperator::IsNull),
+        ("\"nnu\"", FilterOperator::IsNotNull),
+        ("\"jc\"", FilterOperator::JsonContains),
+        ("\"jp\"", FilterOperator::JsonPath),
+        ("\"ts\"", FilterOperator::Search),
     ];
     for (json, expected) in cases {
         let op: FilterOperator = serde_json::from_str(json).unwrap();
@@ -156,6 +163,13 @@ fn convert_filter_param_to_query_string_all_operators() {
         (FilterOperator::In, "in"),
         (FilterOperator::NotIn, "nin"),
         (FilterOperator::StartWith, "sw"),
+        (FilterOperator::ILike, "ili"),
+        (FilterOperator::Between, "bt"),
+        (FilterOperator::IsNull, "nu"),
+        (FilterOperator::IsNotNull, "nnu"),
+        (FilterOperator::JsonContains, "jc"),
+        (FilterOperator::JsonPath, "jp"),
+        (FilterOperator::Search, "ts"),
     ];
     for (op, expected_str) in cases {
         let param = FilterParam::<String> {
@@ -168,3 +182,35 @@ fn convert_filter_param_to_query_string_all_operators() {
         assert_eq!(qs, format!("field={}|val", expected_str));
     }
 }
+
+#[test]
+fn filter_enum_get_operator() {
+    let f = FilterEnum::I32(FilterParam {
+        name: "age".into(),
+        value: Some(0),
+        raw_value: "18,30".into(),
+        operator: FilterOperator::Between,
+    });
+    assert_eq!(f.get_operator(), &FilterOperator::Between);
+}
+
+#[test]
+fn filter_enum_search() {
+    let f = FilterEnum::search("chocolate cake");
+    assert_eq!(f.get_name(), SEARCH_FILTER_NAME);
+    assert_eq!(f.get_operator(), &FilterOperator::Search);
+}
+
+#[test]
+fn to_query_string_sends_search_as_is() {
+    let condition = FilterCondition::and(vec![
+        FilterCondition::leaf(FilterEnum::String(FilterParam {
+            name: "status".into(),
+            value: Some("active".into()),
+            raw_value: "active".into(),
+            operator: FilterOperator::Equal,
+        })),
+        FilterCondition::leaf(FilterEnum::search("rock")),
+    ]);
+    assert_eq!(condition.to_query_string(), "status=eq|active&_search=rock");
+}
@@ -40,3 +40,44 @@ fn order_deserialize_empty_defaults() {
     assert!(o.order_name.is_none());
     assert!(o.order_direction.is_none());
 }
+
+#[test]
+fn order_keys_from_sort() {
+    let json = r#"{"sort": "name, -created_at,"}"#;
+    let o: Order = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        o.keys(),
+        vec![
+            OrderKey {
+                name: "name".to_string(),
+                direction: OrderDirection::Asc,
+            },
+            OrderKey {
+                name: "created_at".to_string(),
+                direction: OrderDirection::Desc,
+            },
+        ]
+    );
+}
+
+#[test]
+fn order_keys_sort_takes_precedence() {
+    let json = r#"{"sort": "-name", "order_name": "created_at", "order_direction": 1}"#;
+    let o: Order = serde_json::from_str(json).unwrap();
+    assert_eq!(o.keys().len(), 1);
+    assert_eq!(o.keys()[0].name, "name");
+}
+
+#[test]
+fn order_keys_from_order_name() {
+    let json = r#"{"sort": " ", "order_name": "created_at", "order_direction": -1}"#;
+    let o: Order = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        o.keys(),
+        vec![OrderKey {
+            name: "created_at".to_string(),
+            direction: OrderDirection::Desc,
+        }]
+    );
+    assert!(Order::default().keys().is_empty());
+}
@@ -42,6 +42,8 @@ fn query_result_serialize() {
     let qr = QueryResult {
         total_page: 5,
         result: vec![1, 2, 3],
+        next_cursor: None,
+        prev_cursor: None,
     };
     let json = serde_json::to_value(&qr).unwrap();
     assert_eq!(json["total_page"], 5);
@@ -55,3 +57,149 @@ fn query_result_deserialize() {
     assert_eq!(qr.total_page, 2);
     assert_eq!(qr.result, vec!["a", "b"]);
 }
+
+mod item {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "items")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: Uuid,
+        pub name: Option<String>,
+        pub created_at: DateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+fn cursor() -> Cursor {
+    Cursor {
+        column: "created_at".to_string(),
+        desc: true,
+        value: serde_json::json!("2026-10-19T10:00:00"),
+        id: serde_json::json!(uuid::Uuid::nil()),
+        backward: false,
+    }
+}
+
+fn keyset_sql(column: item::Column, desc: bool, value: serde_json::Value) -> String {
+    use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+
+    let condition = keyset_condition(column, item::Column::Id, desc, &value, &cursor().id);
+    item::Entity::find()
+        .filter(condition)
+        .build(DbBackend::Postgres)
+        .to_string()
+}
+
+#[test]
+fn pagination_deserialize_cursor() {
+    let json = format!(
+        r#"{{"cursor": "{}", "with_total": false}}"#,
+        cursor().encode()
+    );
+    let p: Pagination = serde_json::from_str(&json).unwrap();
+    assert_eq!(p.cursor, Some(cursor()));
+    assert!(!p.with_total());
+    assert!(Pagination::default().with_total());
+}
+
+#[test]
+fn pagination_invalid_cursor() {
+    let json = r#"{"cursor": "not a cursor"}"#;
+    assert!(serde_json::from_str::<Pagination>(json).is_err());
+}
+
+#[test]
+fn query_result_omits_missing_cursors() {
+    let json = serde_json::to_value(QueryResult::<u32> {
+        total_page: 1,
+        result: vec![],
+        next_cursor: None,
+        prev_cursor: None,
+    })
+    .unwrap();
+    assert!(json.get("next_cursor").is_none());
+    assert!(json.get("prev_cursor").is_none());
+}
+
+#[test]
+fn query_result_map_keeps_cursors() {
+    let qr = QueryResult {
+        total_page: 3,
+        result: vec![1, 2],
+        next_cursor: Some("next".to_string()),
+        prev_cursor: Some("prev".to_string()),
+    }
+    .map(|n| n * 10);
+    assert_eq!(qr.total_page, 3);
+    assert_eq!(qr.result, vec![10, 20]);
+    assert_eq!(qr.next_cursor.as_deref(), Some("next"));
+    assert_eq!(qr.prev_cursor.as_deref(), Some("prev"));
+}
+
+#[test]
+fn cursor_from_column_values() {
+    let created_at = chrono::NaiveDate::from_ymd_opt(2026, 10, 19)
+        .and_then(|date| date.and_hms_opt(10, 0, 0))
+        .unwrap();
+    let from_values = Cursor::new(
+        "created_at",
+        true,
+        &sea_orm::Value::from(created_at),
+        &sea_orm::Value::from(uuid::Uuid::nil()),
+        false,
+    );
+    assert_eq!(from_values, cursor());
+    assert_eq!(Cursor::decode(&from_values.encode()), Ok(cursor()));
+}
+
+#[test]
+fn cursor_with_forged_value_is_rejected() {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+    let encoded = cursor().encode();
+    let (_, signature) = encoded.split_once('.').unwrap();
+    let mut forged = cursor();
+    forged.column = "password".to_string();
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&forged).unwrap());
+    assert!(Cursor::decode(&format!("{payload}.{signature}")).is_err());
+    assert!(Cursor::decode(&payload).is_err());
+}
+
+#[test]
+fn cursor_keeps_decimals_as_text() {
+    let price = sea_orm::prelude::Decimal::new(1999, 2);
+    let cursor = Cursor::new(
+        "price",
+        false,
+        &sea_orm::Value::from(price),
+        &sea_orm::Value::from(1),
+        false,
+    );
+    assert_eq!(cursor.value, serde_json::json!("19.99"));
+    assert_eq!(cursor.id, serde_json::json!(1));
+}
+
+#[test]
+fn keyset_condition_descending() {
+    let sql = keyset_sql(
+        item::Column::CreatedAt,
+        true,
+        serde_json::json!("2026-10-19T10:00:00"),
+    );
+    assert!(sql.contains(r#""items"."created_at" < CAST('2026-10-19T10:00:00' AS timestamp)"#));
+    assert!(sql.contains(r#""items"."id" < CAST('00000000-0000-0000-0000-000000000000' AS uuid)"#));
+    assert!(!sql.contains("IS NULL"));
+}
+
+#[test]
+fn keyset_condition_ascending_null_value() {
+    let sql = keyset_sql(item::Column::Name, false, serde_json::Value::Null);
+    assert!(sql.contains(r#""items"."name" IS NULL"#));
+    assert!(sql.contains(r#""items"."id" > CAST("#));
+}
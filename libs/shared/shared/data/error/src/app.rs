@@ -4,6 +4,8 @@ use std::{fmt::Debug, sync::Arc};
 use thiserror::Error;
 use tracing::debug;
 
+use shared_shared_data_core::query::is_invalid_column;
+
 use crate::{
     auth::{AuthError, TokenError},
     password::PasswordError,
@@ -23,12 +25,18 @@ pub enum AppError {
     DbErr(#[from] sea_orm::DbErr),
     #[error("Entity not found: {entity}")]
     EntityNotFound { entity: String },
+    /// The entity doesn't exist or is outside the caller's access policy
+    #[error("Not found: {0}")]
+    NotFound(String),
     #[error("JSON rejection error")]
     JsonRejection,
     #[error("Unknown error")]
     Unknown,
     #[error("Internal error {0}")]
     Internal(String),
+    /// The entity is in a state that doesn't allow the operation
+    #[error("Conflict: {0}")]
+    Conflict(String),
     #[error("Validation error: {0}")]
     Validation(validator::ValidationErrors),
 }
@@ -62,6 +70,16 @@ impl AppError {
                 },
             ),
             JsonRejection => (StatusCode::BAD_REQUEST, ClientError::JsonRejection),
+            // Optimistic locking: the row changed since the caller read it
+            DbErr(sea_orm::DbErr::RecordNotUpdated) => {
+                (StatusCode::CONFLICT, ClientError::Conflict)
+            }
+            DbErr(sea_orm::DbErr::Custom(message)) if is_invalid_column(message) => (
+                StatusCode::BAD_REQUEST,
+                ClientError::InvalidQuery(message.clone()),
+            ),
+            Conflict(_) => (StatusCode::CONFLICT, ClientError::Conflict),
+            NotFound(_) => (StatusCode::NOT_FOUND, ClientError::NotFound),
             EntityNotFound { entity } => (
                 StatusCode::FORBIDDEN,
                 ClientError::EntityNotFound {
@@ -81,7 +99,9 @@ impl AppError {
 #[serde(tag = "error_type", content = "details", rename_all = "snake_case")]
 pub enum ClientError {
     AuthError(AuthError),
+    Conflict,
     EntityNotFound { entity: String },
+    InvalidQuery(String),
     JsonRejection,
     NotFound,
     ServerError,
@@ -24,12 +24,32 @@ pub enum AuthError {
     ExistingUser,
     #[error("Unknow role")]
     UnknowRole,
+    #[error("A role can't inherit from itself or from one of its descendants")]
+    RoleCycle,
+    #[error("A role can only inherit from a role of its client")]
+    ParentRoleOfOtherClient,
     #[error("Insufficient permission")]
     InsufficientPermission,
     #[error("Password reset code not found")]
     PasswordResetCodeNotFound,
     #[error("Password reset code expired")]
     PasswordResetCodeExpired,
+    #[error("Unknown identity provider")]
+    UnknownProvider,
+    #[error("Identity provider request failed")]
+    ProviderFailed,
+    #[error("Identity provider did not return a verified email")]
+    UnverifiedEmail,
+    #[error("Social login state is invalid or was already used")]
+    InvalidOidcState,
+    #[error("Confirm the link of the social account with the code sent by email")]
+    LinkPending,
+    #[error("Link code not found")]
+    LinkCodeNotFound,
+    #[error("Link code expired")]
+    LinkCodeExpired,
+    #[error("Too many wrong link codes, log in again to receive a new one")]
+    TooManyLinkAttempts,
     #[error("Unknow error")]
     Unknow,
 }
@@ -39,11 +59,21 @@ impl AuthError {
         match self {
             AuthError::NotFoundUser => StatusCode::NOT_FOUND,
             AuthError::UnknowRole => StatusCode::NOT_FOUND,
+            AuthError::RoleCycle => StatusCode::BAD_REQUEST,
+            AuthError::ParentRoleOfOtherClient => StatusCode::BAD_REQUEST,
             AuthError::WrongPassword => StatusCode::UNAUTHORIZED,
             AuthError::InsufficientPermission => StatusCode::FORBIDDEN,
             AuthError::ExistingUser => StatusCode::CONFLICT,
             AuthError::PasswordResetCodeNotFound => StatusCode::NOT_FOUND,
             AuthError::PasswordResetCodeExpired => StatusCode::GONE,
+            AuthError::UnknownProvider => StatusCode::NOT_FOUND,
+            AuthError::ProviderFailed => StatusCode::BAD_GATEWAY,
+            AuthError::UnverifiedEmail => StatusCode::CONFLICT,
+            AuthError::InvalidOidcState => StatusCode::BAD_REQUEST,
+            AuthError::LinkPending => StatusCode::CONFLICT,
+            AuthError::LinkCodeNotFound => StatusCode::NOT_FOUND,
+            AuthError::LinkCodeExpired => StatusCode::GONE,
+            AuthError::TooManyLinkAttempts => StatusCode::TOO_MANY_REQUESTS,
             AuthError::Unknow => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -151,6 +151,8 @@ pub fn filter_macro_derive_impl(input: FilterInput) -> TokenStream {
         pub struct #param_filter_name {
             #[serde(default = "shared_shared_data_core::filter::default_filter_logic")]
             pub _condition: String,
+            #[serde(default)]
+            pub _search: Option<String>,
             #(#builder_fields,)*
         }
 
@@ -159,10 +161,18 @@ pub fn filter_macro_derive_impl(input: FilterInput) -> TokenStream {
                 use shared_shared_data_core::filter::FilterCondition;
                 let mut leaves: Vec<FilterEnum> = vec![];
                 #(#builder_all_filters)*
-                if self._condition == "or" {
+                let condition = if self._condition == "or" {
                     FilterCondition::Or(leaves.into_iter().map(FilterCondition::Leaf).collect())
                 } else {
                     FilterCondition::And(leaves.into_iter().map(FilterCondition::Leaf).collect())
+                };
+                // The search narrows the result whatever `_condition` is
+                match self._search.as_deref().map(str::trim) {
+                    Some(text) if !text.is_empty() => FilterCondition::And(vec![
+                        condition,
+                        FilterCondition::Leaf(FilterEnum::search(text)),
+                    ]),
+                    _ => condition,
                 }
             }
         }
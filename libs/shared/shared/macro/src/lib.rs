@@ -7,14 +7,17 @@ mod query;
 mod response;
 mod service;
 
-#[proc_macro_derive(Query, attributes(query_filter, query, query_related))]
+#[proc_macro_derive(
+    Query,
+    attributes(query_filter, query, query_related, query_search, query_sort, policy)
+)]
 pub fn query_derive(input: TokenStream) -> TokenStream {
     let derive_input = syn::parse_macro_input!(input as syn::DeriveInput);
     let query_input = query::QueryInput::parse_from(derive_input);
     query::query_impl(query_input)
 }
 
-#[proc_macro_derive(Mutation, attributes(mutation))]
+#[proc_macro_derive(Mutation, attributes(mutation, policy))]
 pub fn mutation_derive(input: TokenStream) -> TokenStream {
     let derive_input = syn::parse_macro_input!(input as syn::DeriveInput);
     let mutation_input = mutation::MutationInput::parse_from(derive_input);
@@ -1,16 +1,22 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     parse::{Parse, ParseStream},
-    DeriveInput, Ident,
+    DeriveInput, Ident, Token,
 };
 
 mod kw {
     syn::custom_keyword!(key_type);
+    syn::custom_keyword!(soft_delete);
+    syn::custom_keyword!(version_column);
+    syn::custom_keyword!(audit);
 }
 
 struct MutationAttr {
     key_type: proc_macro2::TokenStream,
+    soft_delete: bool,
+    version_column: Option<Ident>,
+    audit: bool,
 }
 
 impl Parse for MutationAttr {
@@ -19,435 +25,435 @@ impl Parse for MutationAttr {
         let content;
         syn::parenthesized!(content in input);
         let key_type: proc_macro2::TokenStream = content.parse()?;
-        Ok(MutationAttr { key_type })
+        let mut attr = MutationAttr {
+            key_type,
+            soft_delete: false,
+            version_column: None,
+            audit: false,
+        };
+        while input.parse::<Token![,]>().is_ok() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::soft_delete) {
+                input.parse::<kw::soft_delete>()?;
+                attr.soft_delete = true;
+            } else if lookahead.peek(kw::version_column) {
+                input.parse::<kw::version_column>()?;
+                input.parse::<Token![=]>()?;
+                attr.version_column = Some(input.parse()?);
+            } else if lookahead.peek(kw::audit) {
+                input.parse::<kw::audit>()?;
+                attr.audit = true;
+            } else {
+                return Err(lookahead.error());
+            }
+        }
+        Ok(attr)
     }
 }
 
 pub(crate) struct MutationInput {
     pub name: Ident,
     pub key_type_str: String,
+    /// Deletes set `deleted_at` instead of removing the row
+    pub soft_delete: bool,
+    /// Field bumped by every update, an update of a stale version fails
+    pub version_column: Option<Ident>,
+    /// Writes stamp `created_at`/`updated_at` and `created_by`/`updated_by`
+    pub audit: bool,
+    /// Expression evaluating to a `shared_shared_auth::policy::AccessPolicy`
+    pub policy: Option<proc_macro2::TokenStream>,
 }
 
 impl MutationInput {
     pub fn parse_from(input: DeriveInput) -> Self {
         let name = input.ident;
         let mut key_type_str = String::new();
+        let mut soft_delete = false;
+        let mut version_column = None;
+        let mut audit = false;
+        let mut policy = None;
         for attr in &input.attrs {
             if attr.path().is_ident("mutation") {
                 let parsed: MutationAttr = attr.parse_args().unwrap();
                 key_type_str = parsed.key_type.to_string();
+                soft_delete = parsed.soft_delete;
+                version_column = parsed.version_column;
+                audit = parsed.audit;
+            } else if attr.path().is_ident("policy") {
+                policy = Some(attr.parse_args::<proc_macro2::TokenStream>().unwrap());
             }
         }
-        MutationInput { name, key_type_str }
+        MutationInput {
+            name,
+            key_type_str,
+            soft_delete,
+            version_column,
+            audit,
+            policy,
+        }
     }
 }
 
+/// `Column` variant of a model field, `version` -> `Version`.
+fn column_ident(field: &Ident) -> Ident {
+    let column: String = field
+        .to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    format_ident!("{}", column)
+}
+
 pub fn mutation_impl(input: MutationInput) -> TokenStream {
-    let MutationInput { name, key_type_str } = input;
+    let MutationInput {
+        name,
+        key_type_str,
+        soft_delete,
+        version_column,
+        audit,
+        policy,
+    } = input;
+
+    let key_type: syn::Type = syn::parse_str(&key_type_str).expect("Invalid key_type");
+    // String keys are given by the caller and are not `Copy`
+    let (not_set_id, key_of_row, key_of_id) = match key_type_str.as_str() {
+        "Uuid" | "i32" => (
+            quote! { active_model.not_set(Column::Id); },
+            quote! { model.id },
+            quote! { id },
+        ),
+        "String" => (
+            quote! {},
+            quote! { model.id.clone() },
+            quote! { id.clone() },
+        ),
+        _ => panic!("Unsupported key type: {}", key_type_str),
+    };
 
-    let create_quote = match key_type_str.as_str() {
-        "Uuid" => {
+    let stamp_create = if audit {
+        quote! {
+            let now = shared_shared_data_core::actor::now();
+            let actor = shared_shared_data_core::actor::current_actor();
+            active_model.created_at = sea_orm::ActiveValue::Set(now);
+            active_model.updated_at = sea_orm::ActiveValue::Set(now);
+            active_model.created_by = sea_orm::ActiveValue::Set(actor);
+            active_model.updated_by = sea_orm::ActiveValue::Set(actor);
+        }
+    } else {
+        quote! {}
+    };
+    let stamp_update = if audit {
+        quote! {
+            let mut active_model = active_model;
+            active_model.updated_at = sea_orm::ActiveValue::Set(shared_shared_data_core::actor::now());
+            active_model.updated_by = sea_orm::ActiveValue::Set(shared_shared_data_core::actor::current_actor());
+        }
+    } else {
+        quote! {}
+    };
+    // The expected version is the one sent by the caller, or the one just read
+    let save = match &version_column {
+        Some(version_field) => {
+            let version_column = column_ident(version_field);
             quote! {
-                #[tracing::instrument]
-                async fn create_uuid(model: Model) -> Result<Uuid, DbErr> {
-                    let mut active_model: ActiveModel = model.into();
-                    active_model.not_set(Column::Id);
-                    let result_model = active_model.insert(Self::get_db()).await;
-                    let id = result_model?.id;
-                    Ok(id)
-                }
-
-                #[tracing::instrument]
-                async fn bulk_create_uuid(models: Vec<Model>) -> Result<Vec<Uuid>, DbErr> {
-                    let active_models: Vec<ActiveModel> = models.into_iter().map(|model| {
-                        let mut active_model: ActiveModel = model.into();
-                        active_model.not_set(Column::Id);
-                        active_model
-                    }).collect();
-
-                    let result_models = Entity::insert_many(active_models).exec_with_returning(Self::get_db()).await?;
-                    let ids = result_models.iter().map(|model| model.id).collect();
-                    Ok(ids)
-                }
-
-                async fn create_i32(model: Model) -> Result<i32, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-
-                async fn bulk_create_i32(models: Vec<Model>) -> Result<Vec<i32>, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-
-                async fn create_str(model: Model) -> Result<String, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-                async fn bulk_create_str(models: Vec<Model>) -> Result<Vec<String>, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-
+                use sea_orm::{ColumnTrait, QueryFilter};
+                let mut active_model = active_model;
+                let expected = active_model.#version_field.clone().unwrap();
+                active_model.#version_field = sea_orm::ActiveValue::Set(expected + 1);
+                // No row updated when the version changed meanwhile: `DbErr::RecordNotUpdated`
+                Entity::update(active_model)
+                    .validate()?
+                    .filter(Column::#version_column.eq(expected))
+                    .exec(db)
+                    .await
             }
         }
-        "i32" => quote! {
-            #[tracing::instrument]
-            async fn create_i32(model: Model) -> Result<i32, DbErr> {
-                let mut active_model: ActiveModel = model.into();
-                active_model.not_set(Column::Id);
-                let result_model = active_model.insert(Self::get_db()).await;
-                let id = result_model?.id;
-                Ok(id)
-            }
-
-            #[tracing::instrument]
-            async fn bulk_create_i32(models: Vec<Model>) -> Result<Vec<i32>, DbErr> {
-
-                let active_models: Vec<ActiveModel> = models.into_iter().map(|model| {
-                    let mut active_model: ActiveModel = model.into();
-                    active_model.not_set(Column::Id);
-                    active_model
-                }).collect();
-
-                let result_models = Entity::insert_many(active_models).exec_with_returning(Self::get_db()).await?;
-                let ids = result_models.iter().map(|model| model.id).collect();
-                Ok(ids)
+        None => quote! { active_model.update(db).await },
+    };
+    let (live_select, remove) = if soft_delete {
+        (
+            quote! {
+                use sea_orm::{ColumnTrait, QueryFilter};
+                Entity::find_by_id(id).filter(Column::DeletedAt.is_null())
+            },
+            quote! {
+                let mut active_model: ActiveModel = exists.into();
+                active_model.deleted_at = sea_orm::ActiveValue::Set(Some(shared_shared_data_core::actor::now()));
+                Self::save_with_conn(db, active_model).await?;
+            },
+        )
+    } else {
+        (
+            quote! { Entity::find_by_id(id) },
+            quote! {
+                let active_model: ActiveModel = exists.into();
+                active_model.delete(db).await?;
+            },
+        )
+    };
+    let restore_quote = if soft_delete {
+        quote! {
+            /// Undoes the soft delete of the row.
+            #[tracing::instrument(skip(db))]
+            pub async fn restore_by_id_with_conn<C: ConnectionTrait>(
+                db: &C,
+                id: #key_type,
+            ) -> Result<bool, DbErr> {
+                use sea_orm::{ColumnTrait, QueryFilter};
+                let exists = Entity::find_by_id(id)
+                    .filter(Column::DeletedAt.is_not_null())
+                    .one(db)
+                    .await?
+                    .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
+                let mut active_model: ActiveModel = exists.into();
+                active_model.deleted_at = sea_orm::ActiveValue::Set(None);
+                Self::save_with_conn(db, active_model).await?;
+                Ok(true)
             }
 
-            async fn create_uuid(model: Model) -> Result<Uuid, DbErr> {
-                unimplemented!("Not implemented")
+            pub async fn restore_by_id(id: #key_type) -> Result<bool, DbErr> {
+                Self::restore_by_id_with_conn(Self::get_db(), id).await
             }
+        }
+    } else {
+        quote! {}
+    };
 
-            async fn bulk_create_uuid(models: Vec<Model>) -> Result<Vec<Uuid>, DbErr> {
-                unimplemented!("Not implemented")
+    // Every write runs on the connection it is given, the `MutationManager` methods give
+    // `write_db()` and a unit of work gives its transaction.
+    let conn_quote = quote! {
+        impl #name {
+            /// Row of `id`, unless soft deleted.
+            fn find_live_by_id(id: #key_type) -> sea_orm::Select<Entity> {
+                #live_select
             }
 
-            async fn create_str(model: Model) -> Result<String, DbErr> {
-                unimplemented!("Not implemented")
+            async fn save_with_conn<C: ConnectionTrait>(
+                db: &C,
+                active_model: ActiveModel,
+            ) -> Result<Model, DbErr> {
+                #stamp_update
+                #save
             }
 
-            async fn bulk_create_str(models: Vec<Model>) -> Result<Vec<String>, DbErr> {
-                unimplemented!("Not implemented")
+            async fn remove_with_conn<C: ConnectionTrait>(db: &C, exists: Model) -> Result<(), DbErr> {
+                #remove
+                Ok(())
             }
 
-        },
-        "String" => quote! {
-            #[tracing::instrument]
-            async fn create_str(model: Model) -> Result<String, DbErr> {
+            #[tracing::instrument(skip(db))]
+            pub async fn create_with_conn<C: ConnectionTrait>(
+                db: &C,
+                model: Model,
+            ) -> Result<#key_type, DbErr> {
                 let mut active_model: ActiveModel = model.into();
-                let result_model = active_model.insert(Self::get_db()).await;
-                let id = result_model?.id;
-                Ok(id)
+                #not_set_id
+                #stamp_create
+                let result_model = active_model.insert(db).await?;
+                Ok(result_model.id)
             }
 
-            #[tracing::instrument]
-            async fn bulk_create_str(models: Vec<Model>) -> Result<Vec<String>, DbErr> {
+            #[tracing::instrument(skip(db))]
+            pub async fn bulk_create_with_conn<C: ConnectionTrait>(
+                db: &C,
+                models: Vec<Model>,
+            ) -> Result<Vec<#key_type>, DbErr> {
                 let active_models: Vec<ActiveModel> = models.into_iter().map(|model| {
                     let mut active_model: ActiveModel = model.into();
+                    #not_set_id
+                    #stamp_create
                     active_model
                 }).collect();
 
-                let result_models = Entity::insert_many(active_models).exec_with_returning(Self::get_db()).await?;
-                let ids = result_models.iter().map(|model| model.id.clone()).collect();
+                let result_models = Entity::insert_many(active_models).exec_with_returning(db).await?;
+                let ids = result_models.iter().map(|model| #key_of_row).collect();
                 Ok(ids)
             }
 
-
-            async fn create_uuid(model: Model) -> Result<Uuid, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn bulk_create_uuid(models: Vec<Model>) -> Result<Vec<Uuid>, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn create_i32(model: Model) -> Result<i32, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn bulk_create_i32(models: Vec<Model>) -> Result<Vec<i32>, DbErr> {
-                unimplemented!("Not implemented")
-            }
-        },
-        _ => panic!("Unsupported key type: {}", key_type_str),
-    };
-
-    let upate_quote = match key_type_str.as_str() {
-        "Uuid" => {
-            quote! {
-                #[tracing::instrument]
-                async fn update_by_id_uuid(
-                    id: Uuid,
-                    model_option: ModelOptionDto,
-                ) -> Result<bool, DbErr> {
-                    let exists = Entity::find_by_id(id)
-                        .one(Self::get_db())
-                        .await?
-                        .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
-
-                    let active_model = assign(exists.into(), model_option);
-                    active_model.update(Self::get_db()).await?;
-
-                    Ok(true)
-                }
-
-                #[tracing::instrument]
-                async fn bulk_update_by_id_uuid(
-                    data: Vec<(Uuid, ModelOptionDto)>,
-                ) -> Result<Vec<Uuid>, DbErr> {
-                    let mut ids = Vec::new();
-                    for (id, model_option) in data {
-                        let exists = Entity::find_by_id(id)
-                            .one(Self::get_db())
-                            .await?
-                            .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
-
-                        let active_model = assign(exists.into(), model_option);
-                        active_model.update(Self::get_db()).await?;
-                        ids.push(id);
-                    }
-                    Ok(ids)
-                }
-
-                async fn update_by_id_i32(
-                    id: i32,
-                    model_option: ModelOptionDto,
-                ) -> Result<bool, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-
-                async fn bulk_update_by_id_i32(
-                    data: Vec<(i32, ModelOptionDto)>,
-                ) -> Result<Vec<i32>, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-
-                async fn update_by_id_str(
-                    id: String,
-                    model_option: ModelOptionDto,
-                ) -> Result<bool, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-
-                async fn bulk_update_by_id_str(
-                    data: Vec<(String, ModelOptionDto)>,
-                ) -> Result<Vec<String>, DbErr> {
-                    unimplemented!("Not implemented")
-                }
-
-            }
-        }
-        "i32" => quote! {
-            #[tracing::instrument]
-            async fn update_by_id_i32(
-                id: i32,
+            #[tracing::instrument(skip(db))]
+            pub async fn update_by_id_with_conn<C: ConnectionTrait>(
+                db: &C,
+                id: #key_type,
                 model_option: ModelOptionDto,
             ) -> Result<bool, DbErr> {
-                let exists = Entity::find_by_id(id)
-                    .one(Self::get_db())
+                let exists = Self::find_live_by_id(id)
+                    .one(db)
                     .await?
                     .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
 
                 let active_model = assign(exists.into(), model_option);
-                active_model.update(Self::get_db()).await?;
+                Self::save_with_conn(db, active_model).await?;
 
                 Ok(true)
             }
 
-            #[tracing::instrument]
-            async fn bulk_update_by_id_i32(
-                    data: Vec<(i32, ModelOptionDto)>,
-                ) -> Result<Vec<i32>, DbErr> {
+            #[tracing::instrument(skip(db))]
+            pub async fn bulk_update_by_id_with_conn<C: ConnectionTrait>(
+                db: &C,
+                data: Vec<(#key_type, ModelOptionDto)>,
+            ) -> Result<Vec<#key_type>, DbErr> {
                 let mut ids = Vec::new();
                 for (id, model_option) in data {
-                    let exists = Entity::find_by_id(id)
-                        .one(Self::get_db())
-                        .await?
-                        .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
-
-                    let active_model = assign(exists.into(), model_option);
-                    active_model.update(Self::get_db()).await?;
+                    Self::update_by_id_with_conn(db, #key_of_id, model_option).await?;
                     ids.push(id);
                 }
                 Ok(ids)
             }
 
-            async fn update_by_id_uuid(
-                id: Uuid,
-                model_option: ModelOptionDto,
-            ) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn bulk_update_by_id_uuid(
-                    data: Vec<(Uuid, ModelOptionDto)>,
-                ) -> Result<Vec<Uuid>, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn update_by_id_str(
-                    id: String,
-                    model_option: ModelOptionDto,
-            ) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn bulk_update_by_id_str(
-                    data: Vec<(String, ModelOptionDto)>,
-                ) -> Result<Vec<String>, DbErr> {
-                unimplemented!("Not implemented")
-            }
-        },
-        "String" => quote! {
-            async fn update_by_id_str(
-                id: String,
-                model_option: ModelOptionDto,
+            #[tracing::instrument(skip(db))]
+            pub async fn delete_by_id_with_conn<C: ConnectionTrait>(
+                db: &C,
+                id: #key_type,
             ) -> Result<bool, DbErr> {
-                let exists = Entity::find_by_id(id)
-                    .one(Self::get_db())
+                let exists = Self::find_live_by_id(id)
+                    .one(db)
                     .await?
                     .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
 
-                let active_model = assign(exists.into(), model_option);
-                active_model.update(Self::get_db()).await?;
-                Ok(true)
-            }
-
-            async fn bulk_update_by_id_str(
-                    data: Vec<(String, ModelOptionDto)>,
-                ) -> Result<Vec<String>, DbErr> {
+                Self::remove_with_conn(db, exists).await?;
 
-                let mut ids = Vec::new();
-                for (id, model_option) in data {
-                    let exists = Entity::find_by_id(id.clone())
-                        .one(Self::get_db())
-                        .await?
-                        .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
-
-                    let active_model = assign(exists.into(), model_option);
-                    active_model.update(Self::get_db()).await?;
-                    ids.push(id);
-
-                }
-                Ok(ids)
-            }
-
-            async fn update_by_id_uuid(
-                id: Uuid,
-                model_option: ModelOptionDto,
-            ) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn bulk_update_by_id_uuid(
-                    data: Vec<(Uuid, ModelOptionDto)>,
-                ) -> Result<Vec<Uuid>, DbErr> {
-                unimplemented!("Not implemented")
-            }
-
-            async fn update_by_id_i32(
-                id: i32,
-                model_option: ModelOptionDto,
-            ) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
+                Ok(true)
             }
 
-            async fn bulk_update_by_id_i32(
-                    data: Vec<(i32, ModelOptionDto)>,
-                ) -> Result<Vec<i32>, DbErr> {
-                unimplemented!("Not implemented")
-            }
-        },
-        _ => panic!("Unsupported key type: {}", key_type_str),
+            #restore_quote
+        }
     };
 
-    let delete_quote = match key_type_str.as_str() {
-        "Uuid" => {
-            quote! {
-                #[tracing::instrument]
-                async fn delete_by_id_uuid(id: Uuid) -> Result<bool, DbErr> {
-                    let model: ActiveModel = Entity::find_by_id(id)
-                        .one(Self::get_db())
-                        .await?
-                        .ok_or(DbErr::RecordNotFound("Not found".to_string()))
-                        .map(Into::into)?;
+    let manager_quotes =
+        [("Uuid", "uuid"), ("i32", "i32"), ("String", "str")].map(|(ty_str, suffix)| {
+            let ty: syn::Type = syn::parse_str(ty_str).expect("Invalid key_type");
+            let create = format_ident!("create_{}", suffix);
+            let bulk_create = format_ident!("bulk_create_{}", suffix);
+            let update = format_ident!("update_by_id_{}", suffix);
+            let bulk_update = format_ident!("bulk_update_by_id_{}", suffix);
+            let delete = format_ident!("delete_by_id_{}", suffix);
+            if ty_str == key_type_str {
+                quote! {
+                    async fn #create(model: Model) -> Result<#ty, DbErr> {
+                        Self::create_with_conn(Self::get_db(), model).await
+                    }
 
-                    model.delete(Self::get_db()).await?;
+                    async fn #bulk_create(models: Vec<Model>) -> Result<Vec<#ty>, DbErr> {
+                        Self::bulk_create_with_conn(Self::get_db(), models).await
+                    }
 
-                    Ok(true)
-                }
+                    async fn #update(id: #ty, model_option: ModelOptionDto) -> Result<bool, DbErr> {
+                        Self::update_by_id_with_conn(Self::get_db(), id, model_option).await
+                    }
 
-                async fn delete_by_id_i32(id: i32) -> Result<bool, DbErr> {
-                    unimplemented!("Not implemented")
-                }
+                    async fn #bulk_update(
+                        data: Vec<(#ty, ModelOptionDto)>,
+                    ) -> Result<Vec<#ty>, DbErr> {
+                        Self::bulk_update_by_id_with_conn(Self::get_db(), data).await
+                    }
 
-                async fn delete_by_id_str(id: String) -> Result<bool, DbErr> {
-                    unimplemented!("Not implemented")
+                    async fn #delete(id: #ty) -> Result<bool, DbErr> {
+                        Self::delete_by_id_with_conn(Self::get_db(), id).await
+                    }
                 }
-            }
-        }
-        "i32" => quote! {
-            #[tracing::instrument]
-            async fn delete_by_id_i32(id: i32) -> Result<bool, DbErr> {
-                let model: ActiveModel = Entity::find_by_id(id)
-                    .one(Self::get_db())
-                    .await?
-                    .ok_or(DbErr::RecordNotFound("Not found".to_string()))
-                    .map(Into::into)?;
+            } else {
+                quote! {
+                    async fn #create(model: Model) -> Result<#ty, DbErr> {
+                        unimplemented!("Not implemented")
+                    }
 
-                model.delete(Self::get_db()).await?;
+                    async fn #bulk_create(models: Vec<Model>) -> Result<Vec<#ty>, DbErr> {
+                        unimplemented!("Not implemented")
+                    }
 
-                Ok(true)
-            }
+                    async fn #update(id: #ty, model_option: ModelOptionDto) -> Result<bool, DbErr> {
+                        unimplemented!("Not implemented")
+                    }
 
-            async fn delete_by_id_uuid(id: Uuid) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
-            }
+                    async fn #bulk_update(
+                        data: Vec<(#ty, ModelOptionDto)>,
+                    ) -> Result<Vec<#ty>, DbErr> {
+                        unimplemented!("Not implemented")
+                    }
 
-            async fn delete_by_id_str(id: String) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
+                    async fn #delete(id: #ty) -> Result<bool, DbErr> {
+                        unimplemented!("Not implemented")
+                    }
+                }
             }
-        },
-        "String" => quote! {
-            async fn delete_by_id_str(id: String) -> Result<bool, DbErr> {
-                let model: ActiveModel = Entity::find_by_id(id)
-                    .one(Self::get_db())
-                    .await?
-                    .ok_or(DbErr::RecordNotFound("Not found".to_string()))
-                    .map(Into::into)?;
-
-                model.delete(Self::get_db()).await?;
+        });
 
-                Ok(true)
-            }
+    // Generate subject-scoped update/delete when an access policy is declared with `#[policy(...)]`.
+    // The row is loaded with the policy conditions in its `WHERE` clause, like the `Query` path.
+    let policy_quote = match &policy {
+        Some(policy_expr) => {
+            quote! {
+                impl #name {
+                    async fn find_for_subject(
+                        id: #key_type,
+                        subject: &shared_shared_auth::policy::PolicySubject,
+                    ) -> Result<Model, DbErr> {
+                        use sea_orm::QueryFilter;
+                        let policy: shared_shared_auth::policy::AccessPolicy = #policy_expr;
+                        let condition = policy
+                            .to_condition::<Entity>(subject)
+                            .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
+                        Self::find_live_by_id(id)
+                            .filter(condition)
+                            .one(Self::get_db())
+                            .await?
+                            .ok_or(DbErr::RecordNotFound("Not found".to_string()))
+                    }
 
-            async fn delete_by_id_uuid(id: Uuid) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
-            }
+                    #[tracing::instrument]
+                    pub async fn update_by_id_for_subject(
+                        id: #key_type,
+                        model_option: ModelOptionDto,
+                        subject: &shared_shared_auth::policy::PolicySubject,
+                    ) -> Result<bool, DbErr> {
+                        let exists = Self::find_for_subject(id, subject).await?;
+                        let active_model = assign(exists.into(), model_option);
+                        Self::save_with_conn(Self::get_db(), active_model).await?;
+                        Ok(true)
+                    }
 
-            async fn delete_by_id_i32(id: i32) -> Result<bool, DbErr> {
-                unimplemented!("Not implemented")
+                    #[tracing::instrument]
+                    pub async fn delete_by_id_for_subject(
+                        id: #key_type,
+                        subject: &shared_shared_auth::policy::PolicySubject,
+                    ) -> Result<bool, DbErr> {
+                        let exists = Self::find_for_subject(id, subject).await?;
+                        Self::remove_with_conn(Self::get_db(), exists).await?;
+                        Ok(true)
+                    }
+                }
             }
-        },
-        _ => panic!("Unsupported key type: {}", key_type_str),
+        }
+        None => quote! {},
     };
 
     let expanded = quote! {
         use uuid::Uuid;
-        use sea_orm::{DbConn, DbErr};
+        use sea_orm::{ConnectionTrait, DbConn, DbErr};
         use sea_orm::{entity::ActiveModelTrait, EntityTrait};
         use shared_shared_data_core::mutation::MutationManager;
-        use shared_shared_config::db::DB_WRITE;
+        use shared_shared_config::db::write_db;
 
         impl #name {
             pub fn get_db<'a>() -> &'a DbConn {
-                let db: &DbConn = DB_WRITE.get().expect("DB_WRITE is not initialized");
+                let db: &DbConn = write_db().expect("DB_WRITE is not initialized");
                 db
             }
         }
 
+        #conn_quote
+
         impl MutationManager<ActiveModel, Model, ModelOptionDto> for #name {
-            #create_quote
-            #upate_quote
-            #delete_quote
+            #(#manager_quotes)*
         }
+
+        #policy_quote
     };
     expanded.into()
 }
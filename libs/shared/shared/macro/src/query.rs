@@ -12,10 +12,15 @@ mod kw {
     syn::custom_keyword!(column);
     syn::custom_keyword!(field);
     syn::custom_keyword!(name);
+    syn::custom_keyword!(prefix);
+    syn::custom_keyword!(columns);
+    syn::custom_keyword!(language);
+    syn::custom_keyword!(soft_delete);
 }
 
 struct QueryAttr {
     key_type: proc_macro2::TokenStream,
+    soft_delete: bool,
 }
 
 impl Parse for QueryAttr {
@@ -24,12 +29,22 @@ impl Parse for QueryAttr {
         let content;
         syn::parenthesized!(content in input);
         let key_type: proc_macro2::TokenStream = content.parse()?;
-        Ok(QueryAttr { key_type })
+        let mut soft_delete = false;
+        if input.parse::<Token![,]>().is_ok() {
+            input.parse::<kw::soft_delete>()?;
+            soft_delete = true;
+        }
+        Ok(QueryAttr {
+            key_type,
+            soft_delete,
+        })
     }
 }
 
 struct QueryFilterAttr {
     column_name: proc_macro2::TokenStream,
+    prefix: Option<String>,
+    entity_tokens: Option<proc_macro2::TokenStream>,
 }
 
 impl Parse for QueryFilterAttr {
@@ -38,7 +53,95 @@ impl Parse for QueryFilterAttr {
         let content;
         syn::parenthesized!(content in input);
         let column_name: proc_macro2::TokenStream = content.parse()?;
-        Ok(QueryFilterAttr { column_name })
+        let mut prefix = None;
+        let mut entity_tokens = None;
+
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::prefix) {
+                input.parse::<kw::prefix>()?;
+                let content;
+                syn::parenthesized!(content in input);
+                prefix = Some(content.parse::<LitStr>()?.value());
+            } else if lookahead.peek(kw::entity) {
+                input.parse::<kw::entity>()?;
+                let content;
+                syn::parenthesized!(content in input);
+                entity_tokens = Some(content.parse::<proc_macro2::TokenStream>()?);
+            } else {
+                return Err(lookahead.error());
+            }
+        }
+
+        Ok(QueryFilterAttr {
+            column_name,
+            prefix,
+            entity_tokens,
+        })
+    }
+}
+
+struct QuerySearchAttr {
+    columns: Vec<Ident>,
+    language: Option<String>,
+}
+
+impl Parse for QuerySearchAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut columns = Vec::new();
+        let mut language = None;
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::columns) {
+                input.parse::<kw::columns>()?;
+                let content;
+                syn::parenthesized!(content in input);
+                columns = content
+                    .parse_terminated(Ident::parse, Token![,])?
+                    .into_iter()
+                    .collect();
+            } else if lookahead.peek(kw::language) {
+                input.parse::<kw::language>()?;
+                let content;
+                syn::parenthesized!(content in input);
+                let lit = content.parse::<LitStr>()?;
+                // Written into the SQL, so that an expression index can match it
+                if !lit
+                    .value()
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    return Err(syn::Error::new(lit.span(), "invalid search language"));
+                }
+                language = Some(lit.value());
+            } else {
+                return Err(lookahead.error());
+            }
+            let _ = input.parse::<Token![,]>();
+        }
+
+        if columns.is_empty() {
+            return Err(input.error("missing `columns(...)` in query_search"));
+        }
+        Ok(QuerySearchAttr { columns, language })
+    }
+}
+
+struct QuerySortAttr {
+    columns: Vec<Ident>,
+}
+
+impl Parse for QuerySortAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<kw::columns>()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let columns = content
+            .parse_terminated(Ident::parse, Token![,])?
+            .into_iter()
+            .collect();
+        Ok(QuerySortAttr { columns })
     }
 }
 
@@ -102,28 +205,58 @@ pub(crate) struct RelatedEntityDef {
     pub include_name: String,
 }
 
+/// A `#[query_filter]` column. Filters named `<prefix>.<column>` go to a prefixed one, through
+/// a join with its entity when given.
+pub(crate) struct FilterColumnDef {
+    pub column_name: String,
+    pub prefix: Option<String>,
+    pub entity_tokens: Option<proc_macro2::TokenStream>,
+}
+
 pub(crate) struct QueryInput {
     pub name: Ident,
     pub key_type_str: String,
+    /// Rows with a `deleted_at` are left out
+    pub soft_delete: bool,
     pub filter_columns: Vec<String>,
-    pub user_filter_count: usize,
+    pub user_filters: Vec<FilterColumnDef>,
     pub related_entities: Vec<RelatedEntityDef>,
+    /// Columns of `Column` searched by `_search` and the `ts` operator
+    pub search_columns: Vec<Ident>,
+    /// Postgres text search configuration, `simple` by default
+    pub search_language: String,
+    /// Columns of `Column` clients may sort, page and aggregate on, besides `CreatedAt`
+    pub sort_columns: Vec<Ident>,
+    /// Expression evaluating to a `shared_shared_auth::policy::AccessPolicy`
+    pub policy: Option<proc_macro2::TokenStream>,
 }
 
 impl QueryInput {
     pub fn parse_from(input: DeriveInput) -> Self {
         let name = input.ident;
         let mut key_type_str = String::new();
+        let mut soft_delete = false;
         let mut filter_columns: Vec<String> = Vec::new();
+        let mut user_filters: Vec<FilterColumnDef> = Vec::new();
         let mut related_entities: Vec<RelatedEntityDef> = Vec::new();
+        let mut search_columns: Vec<Ident> = Vec::new();
+        let mut search_language = "simple".to_string();
+        let mut sort_columns: Vec<Ident> = Vec::new();
+        let mut policy = None;
 
         for attr in &input.attrs {
             if attr.path().is_ident("query") {
                 let parsed: QueryAttr = attr.parse_args().unwrap();
                 key_type_str = parsed.key_type.to_string();
+                soft_delete = parsed.soft_delete;
             } else if attr.path().is_ident("query_filter") {
                 let parsed: QueryFilterAttr = attr.parse_args().unwrap();
                 filter_columns.push(parsed.column_name.to_string());
+                user_filters.push(FilterColumnDef {
+                    column_name: parsed.column_name.to_string(),
+                    prefix: parsed.prefix,
+                    entity_tokens: parsed.entity_tokens,
+                });
             } else if attr.path().is_ident("query_related") {
                 let parsed: QueryRelatedAttr = attr.parse_args().unwrap();
                 let inc = parsed
@@ -135,11 +268,21 @@ impl QueryInput {
                     field_name: parsed.field_name,
                     include_name: inc,
                 });
+            } else if attr.path().is_ident("query_search") {
+                let parsed: QuerySearchAttr = attr.parse_args().unwrap();
+                search_columns = parsed.columns;
+                if let Some(language) = parsed.language {
+                    search_language = language;
+                }
+            } else if attr.path().is_ident("query_sort") {
+                let parsed: QuerySortAttr = attr.parse_args().unwrap();
+                sort_columns = parsed.columns;
+            } else if attr.path().is_ident("policy") {
+                policy = Some(attr.parse_args::<proc_macro2::TokenStream>().unwrap());
             }
         }
 
         // Also register related entity columns as filter columns
-        let user_filter_count = filter_columns.len();
         for rel in &related_entities {
             if let Some(col_tok) = &rel.column_tokens {
                 let col_str = col_tok.to_string();
@@ -152,9 +295,14 @@ impl QueryInput {
         QueryInput {
             name,
             key_type_str,
+            soft_delete,
             filter_columns,
-            user_filter_count,
+            user_filters,
             related_entities,
+            search_columns,
+            search_language,
+            sort_columns,
+            policy,
         }
     }
 }
@@ -163,13 +311,28 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
     let QueryInput {
         name,
         key_type_str,
+        soft_delete,
         filter_columns,
-        user_filter_count,
+        user_filters,
         related_entities,
+        search_columns,
+        search_language,
+        sort_columns,
+        policy,
     } = input;
 
     let function_quotes = filter_columns.iter().map(|column_name| {
         let fn_name = format_ident!("filter_condition_{}", column_name.to_lowercase());
+        // Full-text search is limited to the `#[query_search]` columns of the entity
+        let search_arm = if column_name == "Column" && !search_columns.is_empty() {
+            quote! {
+                FilterOperator::Search if Self::search_columns().iter().any(|c| c.to_string() == column.to_string()) => {
+                    Self::search_condition(&[column], &filter.raw_value)
+                }
+            }
+        } else {
+            quote! {}
+        };
         let column_name = format_ident!("{}", column_name);
         quote! {
             fn #fn_name (column: #column_name, filter_enum: &FilterEnum) -> Condition {
@@ -189,6 +352,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                             FilterOperator::NotEqual => Condition::any().add(column.ne($filter.value.clone())),
                             FilterOperator::In => Condition::any().add(column.is_in(values)),
                             FilterOperator::NotIn => Condition::any().add(column.is_not_in(values)),
+                            FilterOperator::Between if values.len() == 2 => Condition::any().add(column.between(values[0], values[1])),
                             _ => Condition::all(),
                         }
                     }};
@@ -211,6 +375,20 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                     }};
                 }
 
+                macro_rules! json_filter {
+                    ($sql:expr, $values:expr) => {{
+                        let mut exprs = vec![Expr::col(column.as_column_ref())];
+                        exprs.extend($values);
+                        Condition::any().add(Expr::cust_with_exprs($sql, exprs))
+                    }};
+                }
+
+                match filter_enum.get_operator() {
+                    FilterOperator::IsNull => return Condition::any().add(column.is_null()),
+                    FilterOperator::IsNotNull => return Condition::any().add(column.is_not_null()),
+                    _ => {}
+                }
+
                 match filter_enum {
                     FilterEnum::Bool(filter) => match filter.operator {
                         FilterOperator::Equal => Condition::any().add(column.eq(filter.value.clone())),
@@ -218,7 +396,9 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                         _ => Condition::all(),
                     },
                     FilterEnum::U32(filter) => numeric_filter!(filter, u32),
+                    FilterEnum::I8(filter) => numeric_filter!(filter, i8),
                     FilterEnum::I32(filter) => numeric_filter!(filter, i32),
+                    FilterEnum::I64(filter) => numeric_filter!(filter, i64),
                     FilterEnum::U64(filter) => numeric_filter!(filter, u64),
                     FilterEnum::F32(filter) => numeric_filter!(filter, f32),
                     FilterEnum::F64(filter) => numeric_filter!(filter, f64),
@@ -247,11 +427,58 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                             FilterOperator::NotEqual => Condition::any().add(column.ne(filter.value.clone())),
                             FilterOperator::StartWith => Condition::any().add(column.starts_with(filter.value.as_deref().unwrap_or_default())),
                             FilterOperator::Like => Condition::any().add(column.contains(filter.value.as_deref().unwrap_or_default())),
+                            FilterOperator::ILike => Condition::any().add(Expr::cust_with_exprs(
+                                "$1 ILIKE $2",
+                                vec![
+                                    Expr::col(column.as_column_ref()),
+                                    Expr::value(format!("%{}%", filter.value.as_deref().unwrap_or_default())),
+                                ],
+                            )),
                             FilterOperator::In => Condition::any().add(column.is_in(values)),
                             FilterOperator::NotIn => Condition::any().add(column.is_not_in(values)),
+                            #search_arm
                             _ => Condition::all(),
                         }
                     },
+                    FilterEnum::DateTime(filter) => {
+                        let values: Vec<_> = filter
+                            .raw_value
+                            .split(",")
+                            .filter_map(parse_datetime)
+                            .collect();
+                        match filter.operator {
+                            FilterOperator::Less => Condition::any().add(column.lt(filter.value.clone())),
+                            FilterOperator::LessEqual => Condition::any().add(column.lte(filter.value.clone())),
+                            FilterOperator::Greater => Condition::any().add(column.gt(filter.value.clone())),
+                            FilterOperator::GreaterEqual => Condition::any().add(column.gte(filter.value.clone())),
+                            FilterOperator::Equal => Condition::any().add(column.eq(filter.value.clone())),
+                            FilterOperator::NotEqual => Condition::any().add(column.ne(filter.value.clone())),
+                            FilterOperator::Between if values.len() == 2 => Condition::any().add(column.between(values[0], values[1])),
+                            _ => Condition::all(),
+                        }
+                    }
+                    FilterEnum::Json(filter) => match filter.operator {
+                        // Cast, so that json columns are supported as well as jsonb ones
+                        FilterOperator::JsonContains => json_filter!(
+                            "$1 ::jsonb @> $2 ::jsonb",
+                            [Expr::value(filter.raw_value.clone())]
+                        ),
+                        FilterOperator::JsonPath => {
+                            let (path, value) = match filter.raw_value.split_once('=') {
+                                Some((path, value)) => (path, Some(value)),
+                                None => (filter.raw_value.as_str(), None),
+                            };
+                            let path: Vec<String> = path.split('.').map(|s| s.trim().to_string()).collect();
+                            match value {
+                                Some(value) => json_filter!(
+                                    "$1 #>> $2 ::text[] = $3",
+                                    [Expr::value(path), Expr::value(value.to_string())]
+                                ),
+                                None => json_filter!("$1 #> $2 ::text[] IS NOT NULL", [Expr::value(path)]),
+                            }
+                        }
+                        _ => Condition::all(),
+                    },
                     FilterEnum::VecString(filter) => match filter.operator {
                         FilterOperator::In => vec_string_filter!(filter, "$1  && $2 ::varchar[]"),
                         FilterOperator::NotIn => vec_string_filter!(filter, "NOT ($1  @> $2 ::varchar[])"),
@@ -267,12 +494,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
         "Uuid" => quote! {
             #[tracing::instrument]
             async fn get_by_id_uuid(id: Uuid) -> Result<ModelOptionDto, DbErr> {
-                let exists = Entity::find_by_id(id)
-                    .one(Self::get_db())
-                    .await?
-                    .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
-                let model_option: ModelOptionDto = exists.into();
-                Ok(model_option)
+                Self::get_by_id_with_conn(Self::get_db(), id).await
             }
 
             async fn get_by_id_i32(id: i32) -> Result<ModelOptionDto, DbErr> {
@@ -285,13 +507,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
         "i32" => quote! {
             #[tracing::instrument]
             async fn get_by_id_i32(id: i32) -> Result<ModelOptionDto, DbErr> {
-                let exists = Entity::find_by_id(id)
-                    .one(Self::get_db())
-                    .await?
-                    .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
-                let model_option: ModelOptionDto = exists.into();
-
-                Ok(model_option)
+                Self::get_by_id_with_conn(Self::get_db(), id).await
             }
             async fn get_by_id_uuid(id: Uuid) -> Result<ModelOptionDto, DbErr> {
                 unimplemented!("Not implemented")
@@ -303,13 +519,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
         "String" => quote! {
             #[tracing::instrument]
             async fn get_by_id_str(id: String) -> Result<ModelOptionDto, DbErr> {
-                let exists = Entity::find_by_id(id)
-                    .one(Self::get_db())
-                    .await?
-                    .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
-                let model_option: ModelOptionDto = exists.into();
-
-                Ok(model_option)
+                Self::get_by_id_with_conn(Self::get_db(), id).await
             }
 
             async fn get_by_id_i32(id: i32) -> Result<ModelOptionDto, DbErr> {
@@ -458,6 +668,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                     related_filters: &Vec<FilterEnum>,
                 ) -> Result<ModelOptionDto, DbErr> {
                     let parent_model = Entity::find_by_id(id)
+                        .filter(Self::live_condition())
                         .one(Self::get_db())
                         .await?
                         .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
@@ -474,6 +685,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                     related_filters: &Vec<FilterEnum>,
                 ) -> Result<ModelOptionDto, DbErr> {
                     let parent_model = Entity::find_by_id(id)
+                        .filter(Self::live_condition())
                         .one(Self::get_db())
                         .await?
                         .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
@@ -490,6 +702,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                     related_filters: &Vec<FilterEnum>,
                 ) -> Result<ModelOptionDto, DbErr> {
                     let parent_model = Entity::find_by_id(id)
+                        .filter(Self::live_condition())
                         .one(Self::get_db())
                         .await?
                         .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
@@ -516,29 +729,14 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
 
                 // Step 1: Build base query from parent filters + related filters (JOIN-based)
                 let parent_condition = Self::build_filter_condition(filter);
-                let mut base_query = Entity::find().filter(parent_condition);
+                let mut base_query = Entity::find()
+                    .filter(Self::live_condition())
+                    .filter(parent_condition);
                 #(#related_subquery_blocks)*
 
-                // Step 2: Count total pages on the base query (no ordering)
-                let page_size = pagination.page_size.unwrap_or(1);
-                let page = pagination.page.unwrap_or(1).max(1);
-                let num_pages = base_query.clone().paginate(Self::get_db(), page_size).num_pages().await?;
-
-                // Step 3: Apply ordering, fetch page, and load related entities
-                let ordered_query = match (order.order_name.as_deref(), order.order_direction.as_ref()) {
-                    (Some(name), Some(direction)) => {
-                        if let Ok(column) = Column::from_str(name) {
-                            match direction {
-                                OrderDirection::Asc => base_query.order_by(column, SeaOrder::Asc),
-                                OrderDirection::Desc => base_query.order_by(column, SeaOrder::Desc),
-                            }
-                        } else {
-                            base_query.order_by(Column::CreatedAt, SeaOrder::Desc)
-                        }
-                    }
-                    _ => base_query.order_by(Column::CreatedAt, SeaOrder::Desc),
-                };
-                let parents = ordered_query.paginate(Self::get_db(), page_size).fetch_page(page - 1).await?;
+                // Step 2: Fetch the page of parents, then load related entities
+                let page = Self::paginate_select(Self::get_db(), base_query, pagination, order).await?;
+                let parents = page.result;
                 let parent_ids: Vec<_> = parents.iter().map(|p| p.id.clone()).collect();
                 let mut result_map: std::collections::HashMap<_, ModelOptionDto> = parents
                     .into_iter()
@@ -550,15 +748,17 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
 
                 #(#related_load_blocks)*
 
-                // Step 4: Map to DTOs preserving original order and return
+                // Step 3: Map to DTOs preserving original order and return
                 let result: Vec<ModelOptionDto> = parent_ids
                     .iter()
                     .filter_map(|id| result_map.remove(id))
                     .collect();
 
                 Ok(QueryResult {
-                    total_page: num_pages,
+                    total_page: page.total_page,
                     result,
+                    next_cursor: page.next_cursor,
+                    prev_cursor: page.prev_cursor,
                 })
             }
 
@@ -568,9 +768,43 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
         quote! {}
     };
 
-    // Generate build_filter_condition only for single user-specified filter column.
-    // Multi-column cases (e.g. UserQueryManager, BakerQueryManager) need custom dispatch.
-    let build_filter_condition_quote = if user_filter_count == 1 {
+    // Generate build_filter_condition when every user-specified filter column but `Column` has a
+    // prefix, other cases (e.g. UserQueryManager, BakerQueryManager before prefixes) need
+    // custom dispatch.
+    let prefixed_dispatch: Vec<_> = user_filters
+        .iter()
+        .filter_map(|filter| {
+            let prefix = format!("{}.", filter.prefix.as_ref()?);
+            let col_tok = format_ident!("{}", filter.column_name);
+            let filter_fn = format_ident!("filter_condition_{}", filter.column_name.to_lowercase());
+            let condition = match &filter.entity_tokens {
+                // Narrows the parents to those joined with a matching row
+                Some(entity_tok) => quote! {
+                    let sub = Entity::find()
+                        .select_only()
+                        .column(Column::Id)
+                        .inner_join(#entity_tok)
+                        .filter(Self::#filter_fn(column, filter_enum))
+                        .into_query();
+                    Condition::all().add(Column::Id.in_subquery(sub))
+                },
+                None => quote! { Self::#filter_fn(column, filter_enum) },
+            };
+            Some(quote! {
+                if let Some(column_name) = name.strip_prefix(#prefix) {
+                    return match #col_tok::from_str(column_name) {
+                        Ok(column) => { #condition }
+                        Err(_) => Condition::all(),
+                    };
+                }
+            })
+        })
+        .collect();
+    let unprefixed_count = user_filters.iter().filter(|f| f.prefix.is_none()).count();
+    if !search_columns.is_empty() && unprefixed_count != 1 {
+        panic!("query_search needs a generated build_filter_condition, give the other query_filter columns a prefix");
+    }
+    let build_filter_condition_quote = if unprefixed_count == 1 {
         quote! {
             impl #name {
                 fn build_filter_condition(filter_condition: &FilterCondition) -> Condition {
@@ -590,7 +824,17 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                             condition
                         }
                         FilterCondition::Leaf(filter_enum) => {
-                            if let Ok(column) = Column::from_str(filter_enum.get_name().as_str()) {
+                            let name = filter_enum.get_name();
+                            if name == SEARCH_FILTER_NAME {
+                                return match filter_enum {
+                                    FilterEnum::String(filter) => {
+                                        Self::search_condition(&Self::search_columns(), &filter.raw_value)
+                                    }
+                                    _ => Condition::all(),
+                                };
+                            }
+                            #(#prefixed_dispatch)*
+                            if let Ok(column) = Column::from_str(name.as_str()) {
                                 Self::filter_condition_column(column, filter_enum)
                             } else {
                                 Condition::all()
@@ -598,12 +842,137 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                         }
                     }
                 }
+
+                fn search_columns() -> Vec<Column> {
+                    vec![#(Column::#search_columns),*]
+                }
+
+                /// Full-text match of `text` on the concatenated `columns`, in web search syntax
+                /// (quotes, `or`, `-`). Always true without columns or text.
+                fn search_condition(columns: &[Column], text: &str) -> Condition {
+                    let text = text.trim();
+                    if columns.is_empty() || text.is_empty() {
+                        return Condition::all();
+                    }
+                    // Same expression as the one to index, e.g.
+                    // `to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(description, ''))`
+                    let document = (1..=columns.len())
+                        .map(|i| format!("coalesce(${}, '')", i))
+                        .collect::<Vec<_>>()
+                        .join(" || ' ' || ");
+                    let sql = format!(
+                        "to_tsvector('{language}', {}) @@ websearch_to_tsquery('{language}', ${})",
+                        document,
+                        columns.len() + 1,
+                        language = #search_language,
+                    );
+                    let mut exprs: Vec<Expr> = columns.iter().map(|c| Expr::col(c.as_column_ref())).collect();
+                    exprs.push(Expr::value(text.to_string()));
+                    Condition::all().add(Expr::cust_with_exprs(sql, exprs))
+                }
             }
         }
     } else {
         quote! {}
     };
 
+    let key_type: syn::Type = syn::parse_str(&key_type_str).expect("Invalid key_type");
+
+    let live_condition_quote = if soft_delete {
+        quote! {
+            /// Rows not soft deleted.
+            fn live_condition() -> Condition {
+                Condition::all().add(Column::DeletedAt.is_null())
+            }
+
+            /// `filter` over the soft deleted rows, to pick the ones to restore.
+            #[tracing::instrument]
+            pub async fn filter_deleted(
+                pagination: &Pagination,
+                order: &Order,
+                filter: &FilterCondition,
+            ) -> Result<QueryResult<ModelOptionDto>, DbErr> {
+                let select = Entity::find()
+                    .filter(Column::DeletedAt.is_not_null())
+                    .filter(Self::build_filter_condition(filter));
+                let result = Self::paginate_select(Self::get_db(), select, pagination, order).await?;
+                Ok(result.map(|m| m.into()))
+            }
+        }
+    } else {
+        quote! {
+            fn live_condition() -> Condition {
+                Condition::all()
+            }
+        }
+    };
+
+    // Generate subject-scoped variants when an access policy is declared with `#[policy(...)]`.
+    // The policy conditions are appended to the caller's filters as extra leaves.
+    let policy_quote = match &policy {
+        Some(policy_expr) => {
+            quote! {
+                impl #name {
+                    pub fn policy_filter(
+                        subject: &shared_shared_auth::policy::PolicySubject,
+                    ) -> Option<FilterCondition> {
+                        let policy: shared_shared_auth::policy::AccessPolicy = #policy_expr;
+                        policy.to_filter_condition(subject)
+                    }
+
+                    #[tracing::instrument]
+                    pub async fn filter_for_subject(
+                        pagination: &Pagination,
+                        order: &Order,
+                        filter: &FilterCondition,
+                        subject: &shared_shared_auth::policy::PolicySubject,
+                    ) -> Result<QueryResult<ModelOptionDto>, DbErr> {
+                        let Some(policy_filter) = Self::policy_filter(subject) else {
+                            return Ok(QueryResult {
+                                total_page: 0,
+                                result: vec![],
+                                next_cursor: None,
+                                prev_cursor: None,
+                            });
+                        };
+                        let filter = FilterCondition::and(vec![filter.clone(), policy_filter]);
+                        Self::filter(pagination, order, &filter).await
+                    }
+
+                    #[tracing::instrument]
+                    pub async fn aggregate_for_subject(
+                        aggregation: &shared_shared_data_core::aggregate::Aggregation,
+                        filter: &FilterCondition,
+                        subject: &shared_shared_auth::policy::PolicySubject,
+                    ) -> Result<Vec<shared_shared_data_core::aggregate::AggregateRow>, DbErr> {
+                        let Some(policy_filter) = Self::policy_filter(subject) else {
+                            return Ok(vec![]);
+                        };
+                        let filter = FilterCondition::and(vec![filter.clone(), policy_filter]);
+                        Self::aggregate(aggregation, &filter).await
+                    }
+
+                    #[tracing::instrument]
+                    pub async fn get_by_id_for_subject(
+                        id: #key_type,
+                        subject: &shared_shared_auth::policy::PolicySubject,
+                    ) -> Result<ModelOptionDto, DbErr> {
+                        let policy_filter = Self::policy_filter(subject)
+                            .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
+                        let exists = Entity::find_by_id(id)
+                            .filter(Self::live_condition())
+                            .filter(Self::build_filter_condition(&policy_filter))
+                            .one(Self::get_db())
+                            .await?
+                            .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
+                        Ok(exists.into())
+                    }
+                }
+            }
+        }
+        None => quote! {},
+    };
+
     let expanded = quote! {
 
         use std::str::FromStr;
@@ -611,11 +980,14 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
             ConnectionTrait, DbConn, DbErr,
             entity::ColumnTrait, Condition, EntityTrait, Order as SeaOrder, PaginatorTrait, QueryFilter,
             QueryOrder, Select, QuerySelect, QueryTrait,
-            sea_query::{Alias, SelectStatement, SimpleExpr, Expr},
+            sea_query::{Alias, SelectStatement, SimpleExpr, Expr, Func},
             prelude::*
         };
         use shared_shared_data_core::{query::QueryManager, filter::FilterOperator, filter::FilterCondition, order::OrderDirection};
-        use shared_shared_config::db::DB_READ;
+        use shared_shared_data_core::filter::SEARCH_FILTER_NAME;
+        use shared_shared_data_core::filter_deserialize::parse_datetime;
+        use shared_shared_data_core::paging::{keyset_condition, Cursor};
+        use shared_shared_config::db::read_db;
 
 
         impl #name {
@@ -623,40 +995,151 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                 (num_items / page_size) + (num_items % page_size > 0) as u64
             }
 
-            fn build_query(order: &Order, filters: &FilterCondition) -> Select<Entity> {
-                let default_order = Entity::find().order_by(Column::CreatedAt, SeaOrder::Desc);
+            /// `created_at` and the `#[query_sort]` columns.
+            fn sort_columns() -> Vec<Column> {
+                vec![Column::CreatedAt, #(Column::#sort_columns),*]
+            }
 
-                let select = match (order.order_name.as_deref(), order.order_direction.as_ref()) {
-                    (Some(name), Some(direction)) => {
-                        if let Ok(column) = Column::from_str(name) {
-                            match direction {
-                                OrderDirection::Asc => Entity::find().order_by(column, SeaOrder::Asc),
-                                OrderDirection::Desc => Entity::find().order_by(column, SeaOrder::Desc),
-                            }
-                        } else {
-                            default_order
-                        }
-                    }
-                    _ => default_order,
+            /// Column `name`, when clients may sort or aggregate on it. Fails for `usage`
+            /// otherwise.
+            fn sort_column(usage: &str, name: &str) -> Result<Column, DbErr> {
+                Column::from_str(name)
+                    .ok()
+                    .filter(|column| Self::sort_columns().iter().any(|c| c.to_string() == column.to_string()))
+                    .ok_or_else(|| shared_shared_data_core::query::invalid_column(usage, name))
+            }
+
+            /// Sort columns of `order` and whether they are descending, `created_at` descending
+            /// by default. Fails on a column outside `sort_columns`.
+            fn sorts_of(order: &Order) -> Result<Vec<(Column, bool)>, DbErr> {
+                let sorts = order
+                    .keys()
+                    .iter()
+                    .map(|key| {
+                        Self::sort_column("sort", &key.name)
+                            .map(|column| (column, key.direction == OrderDirection::Desc))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if sorts.is_empty() {
+                    Ok(vec![(Column::CreatedAt, true)])
+                } else {
+                    Ok(sorts)
+                }
+            }
+
+            // The id makes the order total, so rows of equal sort values keep their place between pages
+            fn order_select(mut select: Select<Entity>, sorts: &[(Column, bool)], reverse: bool) -> Select<Entity> {
+                let direction = |desc: bool| if desc != reverse { SeaOrder::Desc } else { SeaOrder::Asc };
+                for (column, desc) in sorts {
+                    select = select.order_by(*column, direction(*desc));
+                }
+                select.order_by(Column::Id, direction(sorts[0].1))
+            }
+
+            /// Page of `select`, at `pagination.page` or after/before `pagination.cursor`, with
+            /// the cursors of the pages around it. A cursor keeps the sort it was created with,
+            /// cursors are only given for a single sort column.
+            async fn paginate_select<C: ConnectionTrait>(
+                db: &C,
+                select: Select<Entity>,
+                pagination: &Pagination,
+                order: &Order,
+            ) -> Result<QueryResult<<Entity as EntityTrait>::Model>, DbErr> {
+                let page_size = pagination.page_size.unwrap_or(1).max(1);
+                let page = pagination.page.unwrap_or(1).max(1);
+                let total_page = if pagination.with_total() {
+                    select.clone().paginate(db, page_size).num_pages().await?
+                } else {
+                    0
                 };
 
-                let condition = Self::build_filter_condition(filters);
-                let select = select.filter(condition);
+                let cursor = pagination.cursor.as_ref();
+                let sorts = match cursor {
+                    Some(cursor) => vec![(Self::sort_column("sort", &cursor.column)?, cursor.desc)],
+                    None => Self::sorts_of(order)?,
+                };
+                let (column, desc) = sorts[0];
+                let backward = cursor.is_some_and(|cursor| cursor.backward);
+
+                // Backward pages are read in the reverse order from the cursor, then put back in order
+                let select = Self::order_select(select, &sorts, backward);
+                let select = match cursor {
+                    Some(cursor) => select.filter(keyset_condition(
+                        column,
+                        Column::Id,
+                        desc != backward,
+                        &cursor.value,
+                        &cursor.id,
+                    )),
+                    None => select.offset((page - 1) * page_size),
+                };
+                let mut result = select.limit(page_size + 1).all(db).await?;
+                let has_more = result.len() as u64 > page_size;
+                result.truncate(page_size as usize);
+                if backward {
+                    result.reverse();
+                }
 
-                select
+                let (has_next, has_prev) = if sorts.len() > 1 {
+                    (false, false)
+                } else if backward {
+                    (true, has_more)
+                } else {
+                    (has_more, cursor.is_some() || page > 1)
+                };
+                let column_name = column.to_string();
+                let cursor_of = |row: Option<&<Entity as EntityTrait>::Model>, backward: bool| {
+                    row.map(|row| {
+                        Cursor::new(&column_name, desc, &row.get(column), &row.get(Column::Id), backward)
+                            .encode()
+                    })
+                };
+                Ok(QueryResult {
+                    total_page,
+                    next_cursor: if has_next { cursor_of(result.last(), false) } else { None },
+                    prev_cursor: if has_prev { cursor_of(result.first(), true) } else { None },
+                    result,
+                })
             }
 
-            async fn paginate_query(
+            async fn paginate_query<C: ConnectionTrait>(
+                db: &C,
                 pagination: &Pagination,
                 order: &Order,
                 filters: &FilterCondition,
-            ) -> Result<(u64, Vec<<Entity as EntityTrait>::Model>), DbErr> {
-                let page_size = pagination.page_size.unwrap_or(1);
-                let page = pagination.page.unwrap_or(1).max(1);
-                let paginator = Self::build_query(order, filters).paginate(Self::get_db(), page_size);
-                let num_pages = paginator.num_pages().await?;
-                let result = paginator.fetch_page(page - 1).await?;
-                Ok((num_pages, result))
+            ) -> Result<QueryResult<<Entity as EntityTrait>::Model>, DbErr> {
+                let select = Entity::find()
+                    .filter(Self::live_condition())
+                    .filter(Self::build_filter_condition(filters));
+                Self::paginate_select(db, select, pagination, order).await
+            }
+
+            #live_condition_quote
+
+            /// `get_by_id` on `db`, a transaction sees the rows it wrote.
+            #[tracing::instrument(skip(db))]
+            pub async fn get_by_id_with_conn<C: ConnectionTrait>(
+                db: &C,
+                id: #key_type,
+            ) -> Result<ModelOptionDto, DbErr> {
+                let exists = Entity::find_by_id(id)
+                    .filter(Self::live_condition())
+                    .one(db)
+                    .await?
+                    .ok_or(DbErr::RecordNotFound("Not found".to_string()))?;
+                Ok(exists.into())
+            }
+
+            /// `filter` on `db`, a transaction sees the rows it wrote.
+            #[tracing::instrument(skip(db))]
+            pub async fn filter_with_conn<C: ConnectionTrait>(
+                db: &C,
+                pagination: &Pagination,
+                order: &Order,
+                filter: &FilterCondition,
+            ) -> Result<QueryResult<ModelOptionDto>, DbErr> {
+                let result = Self::paginate_query(db, pagination, order, filter).await?;
+                Ok(result.map(|m| m.into()))
             }
 
             #(#function_quotes)*
@@ -664,7 +1147,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
 
         impl #name {
             pub fn get_db<'a>() -> &'a DbConn {
-                let db: &DbConn = DB_READ.get().expect("DB_WRITE is not initialized");
+                let db: &DbConn = read_db().expect("DB_READ is not initialized");
                 db
             }
         }
@@ -679,13 +1162,45 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
                 order: &Order,
                 filter: &FilterCondition,
             ) -> Result<QueryResult<ModelOptionDto>, DbErr> {
-                let (num_pages, result) = Self::paginate_query(pagination, order, filter).await?;
-                let result: Vec<ModelOptionDto> = result.into_iter().map(|m| m.into()).collect();
+                Self::filter_with_conn(Self::get_db(), pagination, order, filter).await
+            }
 
-                Ok(QueryResult {
-                    total_page: num_pages,
-                    result,
-                })
+            #[tracing::instrument]
+            async fn aggregate(
+                aggregation: &shared_shared_data_core::aggregate::Aggregation,
+                filter: &FilterCondition,
+            ) -> Result<Vec<shared_shared_data_core::aggregate::AggregateRow>, DbErr> {
+                let group_by = Self::sort_column("group_by", &aggregation.group_by)?;
+                let value = match aggregation.function {
+                    shared_shared_data_core::aggregate::AggregateFunction::Count => {
+                        Func::count(Expr::col(Column::Id.as_column_ref()))
+                    }
+                    shared_shared_data_core::aggregate::AggregateFunction::Sum => {
+                        let field = aggregation.field.as_deref().unwrap_or_default();
+                        let field = Self::sort_column("sum", field)?;
+                        // As float8, integer sums are numeric or bigint and decimals are numeric
+                        Func::cast_as(Func::sum(Expr::col(field.as_column_ref())), Alias::new("float8"))
+                    }
+                };
+                let rows = Entity::find()
+                    .select_only()
+                    .column_as(group_by, "key")
+                    .column_as(SimpleExpr::from(value), "value")
+                    .filter(Self::live_condition())
+                    .filter(Self::build_filter_condition(filter))
+                    .group_by(group_by)
+                    .order_by(group_by, SeaOrder::Asc)
+                    .limit(shared_shared_data_core::aggregate::MAX_GROUPS)
+                    .into_json()
+                    .all(Self::get_db())
+                    .await?;
+                Ok(rows
+                    .into_iter()
+                    .map(|row| shared_shared_data_core::aggregate::AggregateRow {
+                        key: row["key"].clone(),
+                        value: row["value"].clone(),
+                    })
+                    .collect())
             }
 
             #related_entity_trait_quote
@@ -697,6 +1212,7 @@ pub fn query_impl(input: QueryInput) -> TokenStream {
     let final_output = quote! {
         #expanded
         #build_filter_condition_quote
+        #policy_quote
     };
 
     TokenStream::from(final_output)
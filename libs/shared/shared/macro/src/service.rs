@@ -50,14 +50,14 @@ pub fn remote_service(input: RemoteServiceInput) -> TokenStream {
     let gen = quote! {
         use dn_consul::{Consul, GetServiceNodesRequest};
         use opentelemetry::{baggage::BaggageExt, Context};
-        use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-        use reqwest::{Client, Method, header::{HeaderName, HeaderValue, HeaderMap}};
+        use reqwest::{Method, header::{HeaderName, HeaderValue, HeaderMap}};
         use serde_json::Value;
         use std::collections::HashMap;
-        use std::sync::{Arc, LazyLock, RwLock, Mutex};
+        use std::sync::{LazyLock, RwLock, Mutex};
         use tracing::{debug, error};
 
         use shared_shared_data_core::roundrobin::RoundRobin;
+        use shared_shared_middleware::remote::{self, RemoteError, DEFAULT_TENANT};
 
         type Host = (String, u16);
 
@@ -100,7 +100,7 @@ pub fn remote_service(input: RemoteServiceInput) -> TokenStream {
                         address
                     };
                     let port = service.service.port;
-                    let tenant = service.service.meta.get("tenant").cloned().unwrap_or("DEFAULT".to_string());
+                    let tenant = service.service.meta.get("tenant").cloned().unwrap_or(DEFAULT_TENANT.to_string());
                     (address, port, tenant)
                 })
                 .collect();
@@ -118,42 +118,25 @@ pub fn remote_service(input: RemoteServiceInput) -> TokenStream {
 
             }
 
+            /// Calls `endpoint` on an instance of the tenant of the current context with the
+            /// shared client, idempotent methods being retried on another instance.
             #[tracing::instrument(name = "call_api", skip(json_body, headers_hashmap), fields(service_name = %Self::service_name()))]
             async fn call_api(
                 endpoint: String,
                 method: Method,
                 json_body: Option<Value>,
                 headers_hashmap: HashMap<String, String>,
-            ) -> Result<Value, String>
+            ) -> Result<Value, RemoteError>
             {
                 debug!("Calling API: {} with method {} body: {:?} and headers: {:?}", endpoint, method, json_body, headers_hashmap);
 
-                let client: ClientWithMiddleware = ClientBuilder::new(Client::new())
-                    .with(shared_shared_middleware::RequestTracingMiddleware)
-                    .build();
-
                 let tenant = Context::current()
                     .baggage()
                     .get("tenant_id")
                     .map(|v| v.to_string())
-                    .unwrap_or_else(|| "DEFAULT".to_string());
+                    .unwrap_or_else(|| DEFAULT_TENANT.to_string());
                 debug!("Extracted tenant_id from baggage: {}", tenant);
 
-                let (ip, port) = Self::get_instance(tenant.as_str()).ok_or_else(|| {
-                    error!("No available service instances");
-                    "No available service instances".to_string()
-                })?;
-
-                let url = format!("{}://{}:{}{}", Self::http_protocol(), ip, port, endpoint);
-                debug!("Request URL: {}", url);
-
-                let builder = match method {
-                    Method::POST => client.post(&url),
-                    Method::PATCH => client.patch(&url),
-                    Method::GET => client.get(&url),
-                    _ => return Err(format!("Unsupported HTTP method: {}", method)),
-                };
-
                 let mut header_map = HeaderMap::new();
                 for (key, value) in headers_hashmap {
                     if let (Ok(name), Ok(val)) = (
@@ -164,39 +147,43 @@ pub fn remote_service(input: RemoteServiceInput) -> TokenStream {
                     }
                 }
 
-                let res = builder
-                    .header("Content-Type", "application/json")
-                    .headers(header_map)
-                    .json(&json_body)
-                    .send()
-                    .await
-                    .map_err(|e| format!("Failed to send request: {}", e))?;
-
-                if !res.status().is_success() {
-                    return Err(format!("Return failed status: {}", res.status()));
-                }
-
-                let body = res.text().await
-                    .map_err(|e| format!("Failed to read response body: {}", e))?;
-
-                let data: Value = serde_json::from_str(&body)
-                    .map_err(|e| format!("Failed to parse response body: {}", e))?;
-
-                data.get("data")
-                    .cloned()
-                    .ok_or_else(|| "Response missing 'data' field".to_string())
+                remote::call(
+                    &Self::http_protocol(),
+                    method,
+                    &endpoint,
+                    json_body.as_ref(),
+                    header_map,
+                    || Self::get_instance(tenant.as_str()),
+                )
+                .await
+                .inspect_err(|e| error!("Call to {} failed: {}", Self::service_name(), e))
             }
 
-            fn get_instance(tenant_id: &str) -> Option<Host> {
-                if let Ok(routing_table) = TENANT_ROUTING_TABLE.read() {
-                    if let Some(rr_mutex) = routing_table.get(tenant_id) {
-                        if let Ok(mut rr) = rr_mutex.lock() {
-                            let result = rr.next_value();
-                            return Some(result.clone());
-                        }
+            /// Next instance of the tenant, or of `DEFAULT_TENANT` when it has none, skipping
+            /// the instances whose circuit is open.
+            fn get_instance(tenant_id: &str) -> Result<Host, RemoteError> {
+                let no_instance = || RemoteError::NoInstance {
+                    service: Self::service_name().to_string(),
+                    tenant: tenant_id.to_string(),
+                };
+                let routing_table = TENANT_ROUTING_TABLE.read().map_err(|_| no_instance())?;
+                let rr_mutex = routing_table
+                    .get(tenant_id)
+                    .or_else(|| routing_table.get(DEFAULT_TENANT))
+                    .ok_or_else(no_instance)?;
+                let mut rr = rr_mutex.lock().map_err(|_| no_instance())?;
+                if rr.is_empty() {
+                    return Err(no_instance());
+                }
+                for _ in 0..rr.len() {
+                    let (ip, port) = rr.next_value().clone();
+                    if remote::circuit_breaker().allow(&format!("{}:{}", ip, port)) {
+                        return Ok((ip, port));
                     }
                 }
-                None
+                Err(RemoteError::CircuitOpen {
+                    service: Self::service_name().to_string(),
+                })
             }
         }
     };
@@ -0,0 +1,16 @@
+use axum::{extract::Request, middleware::Next, response::Response};
+
+use shared_shared_auth::claim::AccessTokenStruct;
+use shared_shared_data_core::actor::with_actor;
+
+/// Runs the request on behalf of the user of its `baggage` header, the `audit` columns of the
+/// rows it writes are stamped with this user.
+pub async fn actor_middleware(req: Request, next: Next) -> Response {
+    let actor = req
+        .headers()
+        .get("baggage")
+        .and_then(|v| v.to_str().ok())
+        .and_then(AccessTokenStruct::from_string)
+        .map(|access_token| access_token.user_id);
+    with_actor(actor, next.run(req)).await
+}
@@ -94,7 +94,11 @@ fn filter_response(selection: &FieldSelection, value: Value) -> Value {
     match &value {
         Value::Object(map) if map.contains_key("result") && map.contains_key("total_page") => {
             let mut out = serde_json::Map::new();
-            out.insert("total_page".to_string(), map["total_page"].clone());
+            for key in ["total_page", "next_cursor", "prev_cursor"] {
+                if let Some(v) = map.get(key) {
+                    out.insert(key.to_string(), v.clone());
+                }
+            }
             if let Some(Value::Array(arr)) = map.get("result") {
                 let filtered: Vec<Value> = arr.iter().map(|v| filter_object(selection, v.clone())).collect();
                 out.insert("result".to_string(), Value::Array(filtered));
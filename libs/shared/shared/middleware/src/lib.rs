@@ -1,8 +1,11 @@
+mod actor;
 mod deprecated;
 mod field_access;
 mod field_filter;
+pub mod remote;
 mod request;
 
+pub use actor::actor_middleware;
 pub use deprecated::{deprecation_endpoint, DeprecationConfig};
 pub use field_access::{field_access_middleware, field_update_guard};
 pub use field_filter::field_filter_middleware;
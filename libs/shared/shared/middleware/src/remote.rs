@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use reqwest::{header::HeaderMap, Client, Method, StatusCode};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use serde_json::Value;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::RequestTracingMiddleware;
+
+/// Tenant whose instances serve the tenants without their own.
+pub const DEFAULT_TENANT: &str = "DEFAULT";
+
+type Host = (String, u16);
+
+/// Settings of the calls between services, read once from the environment.
+#[derive(Clone, Debug)]
+pub struct RemoteConfig {
+    /// `REMOTE_CONNECT_TIMEOUT_MS`
+    pub connect_timeout: Duration,
+    /// `REMOTE_REQUEST_TIMEOUT_MS`, of each attempt
+    pub request_timeout: Duration,
+    /// `REMOTE_POOL_MAX_IDLE_PER_HOST`
+    pub pool_max_idle_per_host: usize,
+    /// `REMOTE_MAX_RETRIES`, of idempotent calls
+    pub max_retries: u32,
+    /// `REMOTE_RETRY_BASE_DELAY_MS`, doubled on every retry
+    pub retry_base_delay: Duration,
+    /// `REMOTE_RETRY_MAX_DELAY_MS`
+    pub retry_max_delay: Duration,
+    /// `REMOTE_BREAKER_FAILURES`, consecutive failures opening the circuit of an instance
+    pub breaker_failures: u32,
+    /// `REMOTE_BREAKER_OPEN_MS`, before an open circuit lets a trial call through
+    pub breaker_open_for: Duration,
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(2),
+            request_timeout: Duration::from_secs(10),
+            pool_max_idle_per_host: 32,
+            max_retries: 2,
+            retry_base_delay: Duration::from_millis(100),
+            retry_max_delay: Duration::from_secs(2),
+            breaker_failures: 5,
+            breaker_open_for: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RemoteConfig {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let millis = |name: &str, default: Duration| {
+            env_parse(name)
+                .map(Duration::from_millis)
+                .unwrap_or(default)
+        };
+        Self {
+            connect_timeout: millis("REMOTE_CONNECT_TIMEOUT_MS", default.connect_timeout),
+            request_timeout: millis("REMOTE_REQUEST_TIMEOUT_MS", default.request_timeout),
+            pool_max_idle_per_host: env_parse("REMOTE_POOL_MAX_IDLE_PER_HOST")
+                .unwrap_or(default.pool_max_idle_per_host),
+            max_retries: env_parse("REMOTE_MAX_RETRIES").unwrap_or(default.max_retries),
+            retry_base_delay: millis("REMOTE_RETRY_BASE_DELAY_MS", default.retry_base_delay),
+            retry_max_delay: millis("REMOTE_RETRY_MAX_DELAY_MS", default.retry_max_delay),
+            breaker_failures: env_parse("REMOTE_BREAKER_FAILURES")
+                .unwrap_or(default.breaker_failures),
+            breaker_open_for: millis("REMOTE_BREAKER_OPEN_MS", default.breaker_open_for),
+        }
+    }
+
+    /// Delay before the retry following `attempt` (0 for the first call): exponential with
+    /// full jitter, so the callers of a failing instance don't retry all at once.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let cap = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.retry_max_delay);
+        let cap_ms = cap.as_millis() as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=cap_ms))
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+}
+
+static REMOTE_CONFIG: LazyLock<RemoteConfig> = LazyLock::new(RemoteConfig::from_env);
+
+// One pool of connections for every remote service of the process
+static REMOTE_CLIENT: LazyLock<ClientWithMiddleware> = LazyLock::new(|| {
+    let client = Client::builder()
+        .connect_timeout(REMOTE_CONFIG.connect_timeout)
+        .timeout(REMOTE_CONFIG.request_timeout)
+        .pool_max_idle_per_host(REMOTE_CONFIG.pool_max_idle_per_host)
+        .build()
+        .expect("Failed to build remote service client");
+    ClientBuilder::new(client)
+        .with(RequestTracingMiddleware)
+        .build()
+});
+
+static CIRCUIT_BREAKER: LazyLock<CircuitBreaker> = LazyLock::new(|| {
+    CircuitBreaker::new(
+        REMOTE_CONFIG.breaker_failures,
+        REMOTE_CONFIG.breaker_open_for,
+    )
+});
+
+pub fn remote_config() -> &'static RemoteConfig {
+    &REMOTE_CONFIG
+}
+
+/// Circuit breaker of the instances of the remote services.
+pub fn circuit_breaker() -> &'static CircuitBreaker {
+    &CIRCUIT_BREAKER
+}
+
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    #[error("No available instance of {service} for tenant {tenant}")]
+    NoInstance { service: String, tenant: String },
+    #[error("Circuit open for every instance of {service}")]
+    CircuitOpen { service: String },
+    #[error("Unsupported HTTP method: {0}")]
+    UnsupportedMethod(Method),
+    /// The request never reached the instance
+    #[error("Failed to connect: {0}")]
+    Connect(String),
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    #[error("Failed to send request: {0}")]
+    Request(String),
+    /// The service answered with an error, `body` as it sent it
+    #[error("Return failed status: {status} {body}")]
+    Status { status: StatusCode, body: String },
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl RemoteError {
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Error body of the service, as JSON when it is.
+    pub fn body_json(&self) -> Option<Value> {
+        match self {
+            Self::Status { body, .. } => serde_json::from_str(body).ok(),
+            _ => None,
+        }
+    }
+
+    /// Whether calling again may succeed. A non idempotent call is only sent again when it
+    /// never reached an instance.
+    pub fn is_retryable(&self, idempotent: bool) -> bool {
+        match self {
+            Self::Connect(_) => true,
+            Self::Timeout(_) | Self::Request(_) => idempotent,
+            Self::Status { status, .. } => {
+                idempotent
+                    && matches!(
+                        *status,
+                        StatusCode::TOO_MANY_REQUESTS
+                            | StatusCode::BAD_GATEWAY
+                            | StatusCode::SERVICE_UNAVAILABLE
+                            | StatusCode::GATEWAY_TIMEOUT
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the instance itself failed, counted by its circuit breaker. An error status
+    /// under 500 is the service answering.
+    fn is_instance_failure(&self) -> bool {
+        match self {
+            Self::Connect(_) | Self::Timeout(_) | Self::Request(_) => true,
+            Self::Status { status, .. } => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
+impl From<reqwest_middleware::Error> for RemoteError {
+    fn from(e: reqwest_middleware::Error) -> Self {
+        match e {
+            reqwest_middleware::Error::Reqwest(e) if e.is_connect() => Self::Connect(e.to_string()),
+            reqwest_middleware::Error::Reqwest(e) if e.is_timeout() => Self::Timeout(e.to_string()),
+            e => Self::Request(e.to_string()),
+        }
+    }
+}
+
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Circuit {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A trial call is in flight, another one is let through after `until` in case it was
+    /// dropped
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+/// Per instance circuit breaker: `failures` consecutive failures open the circuit of an
+/// instance for `open_for`, then one trial call closes it again or keeps it open.
+pub struct CircuitBreaker {
+    failures: u32,
+    open_for: Duration,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failures: u32, open_for: Duration) -> Self {
+        Self {
+            failures: failures.max(1),
+            open_for,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `instance` can be called, the caller reporting the outcome with `record`.
+    pub fn allow(&self, instance: &str) -> bool {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match circuits.get(instance).copied() {
+            None | Some(Circuit::Closed { .. }) => true,
+            Some(Circuit::Open { until }) | Some(Circuit::HalfOpen { until }) if now < until => {
+                false
+            }
+            Some(_) => {
+                circuits.insert(
+                    instance.to_string(),
+                    Circuit::HalfOpen {
+                        until: now + self.open_for,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    pub fn record(&self, instance: &str, success: bool) {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        if success {
+            circuits.remove(instance);
+            return;
+        }
+        let failures = match circuits.get(instance) {
+            Some(Circuit::Closed { failures }) => failures + 1,
+            None => 1,
+            // The trial call failed
+            Some(_) => self.failures,
+        };
+        let circuit = if failures >= self.failures {
+            warn!("Opening circuit of {} for {:?}", instance, self.open_for);
+            Circuit::Open {
+                until: Instant::now() + self.open_for,
+            }
+        } else {
+            Circuit::Closed { failures }
+        };
+        circuits.insert(instance.to_string(), circuit);
+    }
+}
+
+/// Calls `endpoint` on the instances picked by `instance` with the shared client, retrying
+/// with backoff what `RemoteError::is_retryable` allows, and returns the `data` field of the
+/// response. Every attempt picks an instance again, so a retry usually goes to another one.
+pub async fn call<F>(
+    protocol: &str,
+    method: Method,
+    endpoint: &str,
+    json_body: Option<&Value>,
+    headers: HeaderMap,
+    mut instance: F,
+) -> Result<Value, RemoteError>
+where
+    F: FnMut() -> Result<Host, RemoteError>,
+{
+    if !matches!(
+        method,
+        Method::GET | Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    ) {
+        return Err(RemoteError::UnsupportedMethod(method));
+    }
+    let idempotent = is_idempotent(&method);
+    let config = remote_config();
+    let mut attempt = 0;
+    loop {
+        let result = match instance() {
+            Ok((ip, port)) => {
+                let url = format!("{}://{}:{}{}", protocol, ip, port, endpoint);
+                debug!("Request URL: {}", url);
+                let result = send(method.clone(), &url, json_body, headers.clone()).await;
+                circuit_breaker().record(
+                    &format!("{}:{}", ip, port),
+                    !result.as_ref().is_err_and(RemoteError::is_instance_failure),
+                );
+                result
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Err(e) if attempt < config.max_retries && e.is_retryable(idempotent) => {
+                let delay = config.backoff(attempt);
+                warn!("Retrying {} {} in {:?}: {}", method, endpoint, delay, e);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn send(
+    method: Method,
+    url: &str,
+    json_body: Option<&Value>,
+    headers: HeaderMap,
+) -> Result<Value, RemoteError> {
+    let mut builder = REMOTE_CLIENT
+        .request(method, url)
+        .header("Content-Type", "application/json")
+        .headers(headers);
+    if let Some(json_body) = json_body {
+        builder = builder.json(json_body);
+    }
+    let res = builder.send().await?;
+
+    let status = res.status();
+    let body = res
+        .text()
+        .await
+        .map_err(|e| RemoteError::Request(format!("Failed to read response body: {}", e)))?;
+    if !status.is_success() {
+        return Err(RemoteError::Status { status, body });
+    }
+
+    let data: Value = serde_json::from_str(&body).map_err(|e| {
+        RemoteError::InvalidResponse(format!("Failed to parse response body: {}", e))
+    })?;
+    data.get("data")
+        .cloned()
+        .ok_or_else(|| RemoteError::InvalidResponse("Response missing 'data' field".to_string()))
+}
@@ -0,0 +1,41 @@
+use axum::{
+    http::{HeaderName, HeaderValue},
+    middleware,
+    routing::get,
+    Router,
+};
+use axum_test::TestServer;
+
+use shared_shared_data_core::actor::current_actor;
+use shared_shared_middleware::actor_middleware;
+
+async fn actor_handler() -> String {
+    current_actor()
+        .map(|actor| actor.to_string())
+        .unwrap_or_default()
+}
+
+fn server() -> TestServer {
+    let app = Router::new()
+        .route("/actor", get(actor_handler))
+        .layer(middleware::from_fn(actor_middleware));
+    TestServer::new(app).unwrap()
+}
+
+#[tokio::test]
+async fn actor_is_the_user_of_the_baggage() {
+    let response = server()
+        .get("/actor")
+        .add_header(
+            HeaderName::from_static("baggage"),
+            HeaderValue::from_static("accesses=ADMIN_ALL*,user_id=066df7b0-dcd1-4e7c-94a1-9b5f68794ca7,client_id=123e4567-e89b-12d3-a456-426614174000"),
+        )
+        .await;
+    assert_eq!(response.text(), "066df7b0-dcd1-4e7c-94a1-9b5f68794ca7");
+}
+
+#[tokio::test]
+async fn no_actor_without_baggage() {
+    let response = server().get("/actor").await;
+    assert_eq!(response.text(), "");
+}
@@ -0,0 +1,125 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use reqwest::{header::HeaderMap, Method};
+use serde_json::{json, Value};
+
+use shared_shared_middleware::remote::{self, CircuitBreaker, RemoteConfig, RemoteError};
+
+/// Serves `/flaky`, failing with 503 the first `failures` calls, and returns its address.
+async fn serve(failures: usize) -> ((String, u16), Arc<AtomicUsize>) {
+    async fn flaky(
+        State((failures, calls)): State<(usize, Arc<AtomicUsize>)>,
+    ) -> (StatusCode, Json<Value>) {
+        if calls.fetch_add(1, Ordering::SeqCst) < failures {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "message": "unavailable" })),
+            )
+        } else {
+            (StatusCode::OK, Json(json!({ "data": { "ok": true } })))
+        }
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let app = Router::new()
+        .route("/flaky", get(flaky).post(flaky).put(flaky).delete(flaky))
+        .with_state((failures, calls.clone()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (("127.0.0.1".to_string(), port), calls)
+}
+
+async fn call(host: &(String, u16), method: Method) -> Result<Value, RemoteError> {
+    remote::call("http", method, "/flaky", None, HeaderMap::new(), || {
+        Ok(host.clone())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn idempotent_call_is_retried() {
+    let (host, calls) = serve(2).await;
+    assert_eq!(
+        call(&host, Method::GET).await.unwrap(),
+        json!({ "ok": true })
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn non_idempotent_call_keeps_the_service_error() {
+    let (host, calls) = serve(1).await;
+    let err = call(&host, Method::POST).await.unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(err.body_json(), Some(json!({ "message": "unavailable" })));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn put_and_delete_are_supported() {
+    let (host, _) = serve(0).await;
+    assert!(call(&host, Method::PUT).await.is_ok());
+    assert!(call(&host, Method::DELETE).await.is_ok());
+    assert!(matches!(
+        call(&host, Method::TRACE).await,
+        Err(RemoteError::UnsupportedMethod(_))
+    ));
+}
+
+#[test]
+fn connect_errors_are_retried_for_every_method() {
+    let err = RemoteError::Connect("refused".to_string());
+    assert!(err.is_retryable(false));
+    let err = RemoteError::Timeout("slow".to_string());
+    assert!(err.is_retryable(true));
+    assert!(!err.is_retryable(false));
+    let err = RemoteError::Status {
+        status: StatusCode::NOT_FOUND,
+        body: String::new(),
+    };
+    assert!(!err.is_retryable(true));
+}
+
+#[test]
+fn backoff_stays_under_the_cap() {
+    let config = RemoteConfig {
+        retry_base_delay: Duration::from_millis(100),
+        retry_max_delay: Duration::from_millis(300),
+        ..Default::default()
+    };
+    for _ in 0..50 {
+        assert!(config.backoff(0) <= Duration::from_millis(100));
+        assert!(config.backoff(1) <= Duration::from_millis(200));
+        assert!(config.backoff(10) <= Duration::from_millis(300));
+    }
+}
+
+#[test]
+fn breaker_opens_after_consecutive_failures() {
+    let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+    breaker.record("a:80", false);
+    breaker.record("a:80", true);
+    breaker.record("a:80", false);
+    assert!(breaker.allow("a:80"));
+    breaker.record("a:80", false);
+    assert!(!breaker.allow("a:80"));
+    assert!(breaker.allow("b:80"));
+}
+
+#[test]
+fn breaker_lets_one_trial_call_through() {
+    let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+    breaker.record("a:80", false);
+    assert!(!breaker.allow("a:80"));
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(breaker.allow("a:80"));
+    assert!(!breaker.allow("a:80"));
+    breaker.record("a:80", true);
+    assert!(breaker.allow("a:80"));
+}
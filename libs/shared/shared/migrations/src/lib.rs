@@ -0,0 +1,9 @@
+//! Migrations of tables owned by the shared libraries, added to the migrator of every service
+//! using them.
+
+/// `outbox_messages` of the transactional outbox (`shared_shared_app::event_task::outbox`)
+pub mod m20261019_000001_create_outbox_messages;
+/// `dlq_messages` of the dead letter queue (`shared_shared_app::dlq`)
+pub mod m20261019_000002_create_dlq_messages;
+/// Claim and parking of outbox messages by the relay
+pub mod m20261019_000003_add_relay_columns_to_outbox_messages;
@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000001_create_outbox_messages"
+    }
+}
+
+#[derive(DeriveIden)]
+enum OutboxMessages {
+    Table,
+    Id,
+    Topic,
+    Key,
+    Payload,
+    Headers,
+    Attempts,
+    LastError,
+    CreatedAt,
+    PublishedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OutboxMessages::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OutboxMessages::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxMessages::Topic)
+                            .string()
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxMessages::Key)
+                            .string()
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(OutboxMessages::Payload).text().not_null())
+                    .col(
+                        ColumnDef::new(OutboxMessages::Headers)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxMessages::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(OutboxMessages::LastError).text().null())
+                    .col(
+                        ColumnDef::new(OutboxMessages::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OutboxMessages::PublishedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Pending messages in order, and published ones to clean up
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_outbox_messages_published_at_created_at")
+                    .table(OutboxMessages::Table)
+                    .col(OutboxMessages::PublishedAt)
+                    .col(OutboxMessages::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OutboxMessages::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000002_create_dlq_messages"
+    }
+}
+
+#[derive(DeriveIden)]
+enum DlqMessages {
+    Table,
+    Id,
+    SourceTopic,
+    ConsumerGroup,
+    SourceKey,
+    Headers,
+    Payload,
+    Error,
+    Status,
+    ReplayOf,
+    ReplayCount,
+    LastReplayedAt,
+    LastReplayedBy,
+    LastReplayError,
+    FailedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DlqMessages::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DlqMessages::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DlqMessages::SourceTopic)
+                            .string()
+                            .string_len(255)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DlqMessages::ConsumerGroup)
+                            .string()
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(DlqMessages::SourceKey)
+                            .string()
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(DlqMessages::Headers)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DlqMessages::Payload).text().not_null())
+                    .col(ColumnDef::new(DlqMessages::Error).text().not_null())
+                    .col(
+                        ColumnDef::new(DlqMessages::Status)
+                            .string()
+                            .string_len(32)
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(ColumnDef::new(DlqMessages::ReplayOf).uuid().null())
+                    .col(
+                        ColumnDef::new(DlqMessages::ReplayCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(DlqMessages::LastReplayedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(DlqMessages::LastReplayedBy).uuid().null())
+                    .col(ColumnDef::new(DlqMessages::LastReplayError).text().null())
+                    .col(ColumnDef::new(DlqMessages::FailedAt).timestamp().not_null())
+                    .col(
+                        ColumnDef::new(DlqMessages::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DlqMessages::UpdatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Listing by status, most recent failures first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_dlq_messages_status_failed_at")
+                    .table(DlqMessages::Table)
+                    .col(DlqMessages::Status)
+                    .col(DlqMessages::FailedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DlqMessages::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20261019_000003_add_relay_columns_to_outbox_messages"
+    }
+}
+
+#[derive(DeriveIden)]
+enum OutboxMessages {
+    Table,
+    ClaimedUntil,
+    ParkedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OutboxMessages::Table)
+                    .add_column(
+                        ColumnDef::new(OutboxMessages::ClaimedUntil)
+                            .timestamp()
+                            .null(),
+                    )
+                    .add_column(ColumnDef::new(OutboxMessages::ParkedAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OutboxMessages::Table)
+                    .drop_column(OutboxMessages::ClaimedUntil)
+                    .drop_column(OutboxMessages::ParkedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
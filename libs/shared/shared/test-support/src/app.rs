@@ -0,0 +1,87 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use sea_orm::DatabaseConnection;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use shared_shared_app::{start_app::app_router, state::AppState};
+use shared_shared_config::db::with_db;
+use shared_shared_data_cache::cache::Cache;
+
+use crate::database::TestDatabase;
+
+/// Router of a service run in-process: the layers of `start_app` on the schema of a
+/// [`TestDatabase`], an in-memory cache and no discovery.
+///
+/// Requests run in [`with_db`] scope, the `Mutation` and `Query` derives use the schema of the
+/// test instead of `DB_READ` and `DB_WRITE`.
+pub struct TestApp {
+    router: Router,
+    db: &'static DatabaseConnection,
+}
+
+impl TestApp {
+    /// State of the routes of the service, with an in-memory cache.
+    pub fn app_state<T, C>(db: &TestDatabase, state: Option<T>) -> AppState<T, C>
+    where
+        C: Clone + Serialize + DeserializeOwned + Default + Sync,
+        T: Clone,
+    {
+        AppState::new(db.connection(), Cache::in_memory("test"), state)
+    }
+
+    /// `routes` behind the layers of `start_app`, `public_paths` being the ones of the service.
+    pub fn new(db: &TestDatabase, routes: Router, public_paths: &'static [&'static str]) -> Self {
+        TestApp {
+            router: app_router(routes, public_paths),
+            db: db.connection(),
+        }
+    }
+
+    pub async fn call(&self, request: Request<Body>) -> Response {
+        let router = self.router.clone();
+        with_db(self.db, async move { router.oneshot(request).await })
+            .await
+            .expect("Router is infallible")
+    }
+
+    /// Sends `body` as JSON with the `baggage` header when given, returns the status and the
+    /// JSON of the response (`Value::Null` when empty).
+    pub async fn send_json(
+        &self,
+        method: Method,
+        uri: &str,
+        baggage: Option<&str>,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(baggage) = baggage {
+            builder = builder.header("baggage", baggage);
+        }
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("Invalid request");
+
+        let response = self.call(request).await;
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read response body");
+        let json = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+        };
+        (status, json)
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use sea_orm::{ConnectOptions, ConnectionTrait, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+use uuid::Uuid;
+
+/// Environment variable with the URL of the Postgres server the tests create their schemas on.
+pub const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+
+/// Schema of one test on the Postgres of `TEST_DATABASE_URL`, migrated by the `Migrator` of a
+/// feature. Tests using it are marked `#[ignore = "needs TEST_DATABASE_URL"]` and run with
+/// `cargo test -- --ignored`.
+///
+/// The schema is dropped by [`TestDatabase::cleanup`]. A test that panics before leaves it
+/// behind, all of them are named `test_<uuid>`.
+pub struct TestDatabase {
+    admin: DatabaseConnection,
+    connection: &'static DatabaseConnection,
+    schema: String,
+}
+
+impl TestDatabase {
+    /// Creates a schema and runs the migrations of `M` in it. Panics when `TEST_DATABASE_URL`
+    /// is not set: a test run without a database fails instead of passing without checking.
+    pub async fn new<M: MigratorTrait>() -> Self {
+        let url = std::env::var(TEST_DATABASE_URL)
+            .unwrap_or_else(|_| panic!("{} is not set", TEST_DATABASE_URL));
+        let schema = format!("test_{}", Uuid::new_v4().simple());
+
+        let admin = sea_orm::Database::connect(url.as_str())
+            .await
+            .unwrap_or_else(|e| panic!("Failed to connect to {}: {}", TEST_DATABASE_URL, e));
+        admin
+            .execute_unprepared(&format!("CREATE SCHEMA \"{}\"", schema))
+            .await
+            .unwrap_or_else(|e| panic!("Failed to create schema {}: {}", schema, e));
+
+        let mut opt = ConnectOptions::new(url);
+        opt.max_connections(5)
+            .connect_timeout(Duration::from_secs(8))
+            .sqlx_logging(false);
+        opt.set_schema_search_path(schema.clone());
+        let connection = sea_orm::Database::connect(opt)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to connect to schema {}: {}", schema, e));
+        // `with_db` needs a `'static` connection, one per test is leaked
+        let connection: &'static DatabaseConnection = Box::leak(Box::new(connection));
+
+        M::up(connection, None)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to migrate schema {}: {}", schema, e));
+
+        TestDatabase {
+            admin,
+            connection,
+            schema,
+        }
+    }
+
+    pub fn connection(&self) -> &'static DatabaseConnection {
+        self.connection
+    }
+
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Closes the connection and drops the schema with everything in it.
+    pub async fn cleanup(self) {
+        self.connection.close_by_ref().await.unwrap_or_else(|e| {
+            panic!(
+                "Failed to close connection to schema {}: {}",
+                self.schema, e
+            )
+        });
+        self.admin
+            .execute_unprepared(&format!(
+                "DROP SCHEMA IF EXISTS \"{}\" CASCADE",
+                self.schema
+            ))
+            .await
+            .unwrap_or_else(|e| panic!("Failed to drop schema {}: {}", self.schema, e));
+        let _ = self.admin.close().await;
+    }
+}
@@ -0,0 +1,5 @@
+pub mod app;
+pub mod database;
+
+pub use app::TestApp;
+pub use database::TestDatabase;